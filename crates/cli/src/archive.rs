@@ -0,0 +1,122 @@
+// Copyright (C) 2024 Melody Madeline Lyons
+//
+// This file is part of Luminol.
+//
+// Luminol is free software: you can redistribute it and/or modify
+// it under the terms of the GNU General Public License as published by
+// the Free Software Foundation, either version 3 of the License, or
+// (at your option) any later version.
+//
+// Luminol is distributed in the hope that it will be useful,
+// but WITHOUT ANY WARRANTY; without even the implied warranty of
+// MERCHANTABILITY or FITNESS FOR A PARTICULAR PURPOSE.  See the
+// GNU General Public License for more details.
+//
+// You should have received a copy of the GNU General Public License
+// along with Luminol.  If not, see <http://www.gnu.org/licenses/>.
+//
+//     Additional permission under GNU GPL version 3 section 7
+//
+// If you modify this Program, or any covered work, by linking or combining
+// it with Steamworks API by Valve Corporation, containing parts covered by
+// terms of the Steamworks API by Valve Corporation, the licensors of this
+// Program grant you additional permission to convey the resulting work.
+
+use color_eyre::eyre::{bail, WrapErr};
+use luminol_filesystem::{File, FileSystem, OpenFlags, ReadDir};
+
+pub fn pack(
+    source: &camino::Utf8Path,
+    archive: &camino::Utf8Path,
+    version: Option<u8>,
+) -> color_eyre::Result<()> {
+    let version = match version {
+        Some(version) => version,
+        None => match archive.extension().map(str::to_lowercase).as_deref() {
+            Some("rgssad" | "rgss2a") => 1,
+            Some("rgss3a") => 3,
+            _ => bail!("Could not infer the archive version of {archive}, pass --version"),
+        },
+    };
+
+    let source_fs = luminol_filesystem::host::FileSystem::new(source);
+    let mut file_paths = Vec::new();
+    find_files_recurse(&mut file_paths, &source_fs, "".into(), false)
+        .wrap_err_with(|| format!("While enumerating the files in {source}"))?;
+
+    let (archive_dir, archive_name) = split_path(archive)?;
+    let mut file = luminol_filesystem::host::FileSystem::new(archive_dir).open_file(
+        archive_name,
+        OpenFlags::Create | OpenFlags::Write | OpenFlags::Truncate,
+    )?;
+
+    futures_lite::future::block_on(
+        luminol_filesystem::archiver::FileSystem::from_buffer_and_files(
+            &mut file,
+            // VX archives have the same layout as XP archives
+            if version == 2 { 1 } else { version },
+            file_paths.iter().map(|path| {
+                let file = source_fs.open_file(path, OpenFlags::Read)?;
+                let size = file.metadata()?.size as u32;
+                Ok((path, size, file))
+            }),
+        ),
+    )
+    .wrap_err_with(|| format!("While creating {archive}"))?;
+
+    eprintln!("Packed {} files into {archive}", file_paths.len());
+    Ok(())
+}
+
+pub fn unpack(
+    archive: &camino::Utf8Path,
+    destination: &camino::Utf8Path,
+) -> color_eyre::Result<()> {
+    let (archive_dir, archive_name) = split_path(archive)?;
+    let file = luminol_filesystem::host::FileSystem::new(archive_dir)
+        .open_file(archive_name, OpenFlags::Read)?;
+    let archive_fs = luminol_filesystem::archiver::FileSystem::new(file)
+        .wrap_err_with(|| format!("While reading {archive}"))?;
+
+    let mut file_paths = Vec::new();
+    find_files_recurse(&mut file_paths, &archive_fs, "".into(), false)
+        .wrap_err_with(|| format!("While enumerating the files in {archive}"))?;
+
+    let dest_fs = luminol_filesystem::host::FileSystem::new(destination);
+    dest_fs.create_dir("")?;
+    for path in file_paths.iter() {
+        if let Some(parent) = path.parent() {
+            dest_fs.create_dir(parent)?;
+        }
+        let data = archive_fs.read(path)?;
+        dest_fs
+            .write(path, data)
+            .wrap_err_with(|| format!("While extracting {path}"))?;
+    }
+
+    eprintln!("Unpacked {} files into {destination}", file_paths.len());
+    Ok(())
+}
+
+fn split_path(path: &camino::Utf8Path) -> color_eyre::Result<(&camino::Utf8Path, &str)> {
+    match (path.parent(), path.file_name()) {
+        (Some(parent), Some(file_name)) => Ok((parent, file_name)),
+        _ => bail!("{path} is not a path to a file"),
+    }
+}
+
+fn find_files_recurse(
+    vec: &mut Vec<camino::Utf8PathBuf>,
+    src_fs: &impl ReadDir,
+    path: &camino::Utf8Path,
+    is_file: bool,
+) -> luminol_filesystem::Result<()> {
+    if is_file {
+        vec.push(path.to_owned());
+    } else {
+        for entry in src_fs.read_dir(path)? {
+            find_files_recurse(vec, src_fs, &entry.path, entry.metadata.is_file)?;
+        }
+    }
+    Ok(())
+}
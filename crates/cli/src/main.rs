@@ -0,0 +1,196 @@
+// Copyright (C) 2024 Melody Madeline Lyons
+//
+// This file is part of Luminol.
+//
+// Luminol is free software: you can redistribute it and/or modify
+// it under the terms of the GNU General Public License as published by
+// the Free Software Foundation, either version 3 of the License, or
+// (at your option) any later version.
+//
+// Luminol is distributed in the hope that it will be useful,
+// but WITHOUT ANY WARRANTY; without even the implied warranty of
+// MERCHANTABILITY or FITNESS FOR A PARTICULAR PURPOSE.  See the
+// GNU General Public License for more details.
+//
+// You should have received a copy of the GNU General Public License
+// along with Luminol.  If not, see <http://www.gnu.org/licenses/>.
+//
+//     Additional permission under GNU GPL version 3 section 7
+//
+// If you modify this Program, or any covered work, by linking or combining
+// it with Steamworks API by Valve Corporation, containing parts covered by
+// terms of the Steamworks API by Valve Corporation, the licensors of this
+// Program grant you additional permission to convey the resulting work.
+
+//! Headless command line interface for Luminol.
+//!
+//! This exposes project operations that would otherwise require the editor (data format
+//! conversion, RGSSAD archives, script extraction and validation) so they can be scripted
+//! in build pipelines and CI.
+
+#[cfg(not(target_arch = "wasm32"))]
+mod archive;
+#[cfg(not(target_arch = "wasm32"))]
+mod project;
+#[cfg(not(target_arch = "wasm32"))]
+mod scripts;
+
+/// Data formats that a project can be converted to.
+#[derive(Clone, Copy, Debug, PartialEq, Eq, clap::ValueEnum)]
+pub enum Format {
+    /// Ruby Marshal, the format RPG Maker itself uses.
+    Marshal,
+    /// Rusty Object Notation.
+    Ron,
+    /// JavaScript Object Notation.
+    Json,
+    /// YAML Ain't Markup Language.
+    Yaml,
+    /// Pretty-printed RON with one file per database entry, map info and script.
+    Split,
+}
+
+impl Format {
+    pub fn data_format(self, pretty: bool) -> luminol_config::DataFormat {
+        match self {
+            Self::Marshal => luminol_config::DataFormat::Marshal,
+            Self::Ron => luminol_config::DataFormat::Ron { pretty },
+            Self::Json => luminol_config::DataFormat::Json { pretty },
+            Self::Yaml => luminol_config::DataFormat::Yaml,
+            Self::Split => luminol_config::DataFormat::Split,
+        }
+    }
+}
+
+/// What to do with a project's data files in the old format after converting it.
+#[derive(Clone, Copy, Debug, PartialEq, Eq, clap::ValueEnum)]
+pub enum OldFiles {
+    /// Leave them alongside the converted files.
+    Keep,
+    /// Delete them.
+    Delete,
+    /// Move them into the `OldData` folder of the project.
+    Archive,
+}
+
+impl From<OldFiles> for luminol_core::convert::OldFiles {
+    fn from(old_files: OldFiles) -> Self {
+        match old_files {
+            OldFiles::Keep => Self::Keep,
+            OldFiles::Delete => Self::Delete,
+            OldFiles::Archive => Self::Archive,
+        }
+    }
+}
+
+#[derive(Debug, clap::Parser)]
+#[command(name = "luminol-cli", version, about)]
+pub struct Cli {
+    #[command(subcommand)]
+    command: Command,
+}
+
+#[derive(Debug, clap::Subcommand)]
+pub enum Command {
+    /// Convert every data file of a project to another data format.
+    ///
+    /// Every file is converted and checked before the project is modified,
+    /// so a failed conversion leaves the project untouched.
+    Convert {
+        /// Path to the project folder.
+        project: camino::Utf8PathBuf,
+        /// The data format to convert to.
+        #[arg(long, value_enum)]
+        to: Format,
+        /// Pretty-print the output (only applies to RON and JSON.)
+        #[arg(long)]
+        pretty: bool,
+        /// What to do with the data files in the old format after converting.
+        #[arg(long, value_enum, default_value_t = OldFiles::Keep)]
+        old_files: OldFiles,
+        /// Store text in Marshal data as UTF-8, for projects that use a legacy encoding like
+        /// Shift-JIS. Other formats always store text as UTF-8.
+        #[arg(long)]
+        utf8: bool,
+    },
+    /// Pack every file in a folder into an RGSSAD archive.
+    Pack {
+        /// The folder to pack. Paths inside the archive are relative to this folder.
+        source: camino::Utf8PathBuf,
+        /// Path of the archive to create.
+        archive: camino::Utf8PathBuf,
+        /// Archive version: 1 for XP and VX, 3 for VX Ace.
+        /// Inferred from the archive's extension if not given.
+        #[arg(long, value_parser = clap::value_parser!(u8).range(1..=3))]
+        version: Option<u8>,
+    },
+    /// Unpack every file in an RGSSAD archive into a folder.
+    Unpack {
+        /// Path of the archive to unpack.
+        archive: camino::Utf8PathBuf,
+        /// The folder to unpack into. Created if it doesn't exist.
+        destination: camino::Utf8PathBuf,
+    },
+    /// Extract a project's scripts into a folder of .rb files.
+    ///
+    /// The order, IDs and names of the scripts are recorded in `_scripts.ron` in the folder.
+    ExtractScripts {
+        /// Path to the project folder.
+        project: camino::Utf8PathBuf,
+        /// The folder to extract into. Created if it doesn't exist.
+        destination: camino::Utf8PathBuf,
+    },
+    /// Replace a project's scripts with a folder of .rb files.
+    ///
+    /// Scripts are ordered according to `_scripts.ron` in the folder if it exists;
+    /// any remaining scripts are appended in alphabetical order.
+    BuildScripts {
+        /// Path to the project folder.
+        project: camino::Utf8PathBuf,
+        /// The folder to read scripts from.
+        source: camino::Utf8PathBuf,
+    },
+    /// Check that every data file and map of a project can be loaded.
+    Validate {
+        /// Path to the project folder.
+        project: camino::Utf8PathBuf,
+    },
+}
+
+#[cfg(not(target_arch = "wasm32"))]
+fn main() -> color_eyre::Result<()> {
+    use clap::Parser;
+
+    color_eyre::install()?;
+    tracing_subscriber::fmt()
+        .with_writer(std::io::stderr)
+        .init();
+
+    match Cli::parse().command {
+        Command::Convert {
+            project,
+            to,
+            pretty,
+            old_files,
+            utf8,
+        } => project::convert(&project, to.data_format(pretty), old_files.into(), utf8),
+        Command::Pack {
+            source,
+            archive,
+            version,
+        } => archive::pack(&source, &archive, version),
+        Command::Unpack {
+            archive,
+            destination,
+        } => archive::unpack(&archive, &destination),
+        Command::ExtractScripts {
+            project,
+            destination,
+        } => scripts::extract(&project, &destination),
+        Command::BuildScripts { project, source } => scripts::build(&project, &source),
+        Command::Validate { project } => project::validate(&project),
+    }
+}
+
+#[cfg(target_arch = "wasm32")]
+fn main() {}
@@ -0,0 +1,150 @@
+// Copyright (C) 2024 Melody Madeline Lyons
+//
+// This file is part of Luminol.
+//
+// Luminol is free software: you can redistribute it and/or modify
+// it under the terms of the GNU General Public License as published by
+// the Free Software Foundation, either version 3 of the License, or
+// (at your option) any later version.
+//
+// Luminol is distributed in the hope that it will be useful,
+// but WITHOUT ANY WARRANTY; without even the implied warranty of
+// MERCHANTABILITY or FITNESS FOR A PARTICULAR PURPOSE.  See the
+// GNU General Public License for more details.
+//
+// You should have received a copy of the GNU General Public License
+// along with Luminol.  If not, see <http://www.gnu.org/licenses/>.
+//
+//     Additional permission under GNU GPL version 3 section 7
+//
+// If you modify this Program, or any covered work, by linking or combining
+// it with Steamworks API by Valve Corporation, containing parts covered by
+// terms of the Steamworks API by Valve Corporation, the licensors of this
+// Program grant you additional permission to convey the resulting work.
+
+use color_eyre::eyre::{bail, eyre, WrapErr};
+
+/// A project opened without the editor.
+pub struct Project {
+    pub filesystem: luminol_filesystem::project::FileSystem,
+    pub config: luminol_config::project::Config,
+    pub data: luminol_core::Data,
+}
+
+impl Project {
+    /// Open the project at `path`, loading its configuration and all of its data except maps.
+    pub fn open(path: &camino::Utf8Path) -> color_eyre::Result<Self> {
+        let mut project = Self::open_config(path)?;
+
+        let mut toasts = luminol_core::Toasts::default();
+        project
+            .data
+            .load(&project.filesystem, &mut toasts, &mut project.config)
+            .wrap_err_with(|| format!("While loading the data of {path}"))?;
+
+        Ok(project)
+    }
+
+    /// Open the project at `path`, loading only its configuration.
+    pub fn open_config(path: &camino::Utf8Path) -> color_eyre::Result<Self> {
+        let mut filesystem = luminol_filesystem::project::FileSystem::new();
+        let mut config = None;
+        let mut global_config = luminol_config::global::Config::new();
+
+        let result = filesystem
+            .load_project_from_path(&mut config, &mut global_config, path)
+            .wrap_err_with(|| format!("While opening the project at {path}"))?;
+        for rtp in result.missing_rtps {
+            eprintln!("warning: could not find the RTP \"{rtp}\"");
+        }
+
+        let config = config.ok_or_else(|| eyre!("The project at {path} has no configuration"))?;
+
+        Ok(Self {
+            filesystem,
+            config,
+            data: luminol_core::Data::Unloaded,
+        })
+    }
+
+    /// The IDs of every map listed in the project's MapInfos, in ascending order.
+    pub fn map_ids(&self) -> Vec<usize> {
+        let mut ids: Vec<_> = self.data.map_infos().data.keys().copied().collect();
+        ids.sort_unstable();
+        ids
+    }
+}
+
+pub fn convert(
+    path: &camino::Utf8Path,
+    data_format: luminol_config::DataFormat,
+    old_files: luminol_core::convert::OldFiles,
+    utf8: bool,
+) -> color_eyre::Result<()> {
+    let mut project = Project::open_config(path)?;
+
+    let old_format = project.config.project.data_format;
+    if old_format == luminol_config::DataFormat::Marshal
+        && project.config.project.encoding == luminol_config::StringEncoding::Utf8
+    {
+        if let Some(encoding) = luminol_core::data_formats::detect_encoding(
+            &project.filesystem,
+            project.config.project.editor_ver,
+        ) {
+            eprintln!("Detected that the project's text is in {encoding}");
+            project.config.project.encoding = encoding;
+        }
+    }
+    let old_encoding = project.config.project.encoding;
+
+    let converts_encoding = utf8
+        && data_format == luminol_config::DataFormat::Marshal
+        && old_encoding != luminol_config::StringEncoding::Utf8;
+    if old_format == data_format && !converts_encoding {
+        bail!("The project is already in the {data_format} format");
+    }
+
+    let mut conversion =
+        luminol_core::convert::Conversion::new(&project.filesystem, &project.config, data_format)?;
+    if utf8 {
+        conversion = conversion.with_encoding(luminol_config::StringEncoding::Utf8);
+    }
+    while let Some(file) = conversion.current_file() {
+        let (staged, total) = conversion.progress();
+        eprintln!("[{}/{total}] {file}", staged + 1);
+        if let Err(error) = conversion.step(&project.filesystem) {
+            conversion.rollback(&project.filesystem)?;
+            return Err(error.wrap_err("The conversion was rolled back"));
+        }
+    }
+    conversion.commit(&project.filesystem, &mut project.config, old_files)?;
+
+    if old_format == data_format {
+        eprintln!("Converted the text of {path} from {old_encoding} to UTF-8");
+    } else {
+        eprintln!("Converted {path} from {old_format} to {data_format}");
+    }
+    Ok(())
+}
+
+pub fn validate(path: &camino::Utf8Path) -> color_eyre::Result<()> {
+    let project = Project::open(path)?;
+
+    let mut failures = 0;
+    for id in project.map_ids() {
+        if let Err(e) = project
+            .data
+            .load_map(id, &project.filesystem, &project.config)
+        {
+            eprintln!("error: {e:?}");
+            failures += 1;
+        }
+    }
+
+    if failures > 0 {
+        bail!("{failures} map(s) of {path} failed to load");
+    }
+
+    eprintln!("{path} is valid");
+    Ok(())
+}
@@ -0,0 +1,139 @@
+// Copyright (C) 2024 Melody Madeline Lyons
+//
+// This file is part of Luminol.
+//
+// Luminol is free software: you can redistribute it and/or modify
+// it under the terms of the GNU General Public License as published by
+// the Free Software Foundation, either version 3 of the License, or
+// (at your option) any later version.
+//
+// Luminol is distributed in the hope that it will be useful,
+// but WITHOUT ANY WARRANTY; without even the implied warranty of
+// MERCHANTABILITY or FITNESS FOR A PARTICULAR PURPOSE.  See the
+// GNU General Public License for more details.
+//
+// You should have received a copy of the GNU General Public License
+// along with Luminol.  If not, see <http://www.gnu.org/licenses/>.
+//
+//     Additional permission under GNU GPL version 3 section 7
+//
+// If you modify this Program, or any covered work, by linking or combining
+// it with Steamworks API by Valve Corporation, containing parts covered by
+// terms of the Steamworks API by Valve Corporation, the licensors of this
+// Program grant you additional permission to convey the resulting work.
+
+use color_eyre::eyre::WrapErr;
+use luminol_core::data_formats::{split_entry_filename, ScriptIndexEntry, SCRIPTS_INDEX};
+use luminol_filesystem::{FileSystem, ReadDir};
+
+use crate::project::Project;
+
+pub fn extract(
+    project_path: &camino::Utf8Path,
+    destination: &camino::Utf8Path,
+) -> color_eyre::Result<()> {
+    let project = Project::open(project_path)?;
+    let scripts = project.data.scripts();
+
+    let dest_fs = luminol_filesystem::host::FileSystem::new(destination);
+    dest_fs.create_dir("")?;
+
+    // every script gets its own file, numbered so that scripts with the same name don't collide
+    let mut index = Vec::with_capacity(scripts.data.len());
+    for (number, script) in scripts.data.iter().enumerate() {
+        let file = split_entry_filename(number + 1, &script.name, "rb");
+        dest_fs
+            .write(&file, &script.script_text)
+            .wrap_err_with(|| format!("While extracting {}", script.name))?;
+        index.push(ScriptIndexEntry {
+            id: script.id,
+            name: script.name.clone(),
+            file,
+        });
+    }
+
+    let index = ron::ser::to_string_pretty(&index, ron::ser::PrettyConfig::new())?;
+    dest_fs.write(SCRIPTS_INDEX, index)?;
+
+    eprintln!(
+        "Extracted {} scripts into {destination}",
+        scripts.data.len()
+    );
+    Ok(())
+}
+
+pub fn build(project_path: &camino::Utf8Path, source: &camino::Utf8Path) -> color_eyre::Result<()> {
+    let project = Project::open(project_path)?;
+
+    let source_fs = luminol_filesystem::host::FileSystem::new(source);
+    let mut remaining = Vec::new();
+    find_scripts_recurse(&mut remaining, &source_fs, "".into())
+        .wrap_err_with(|| format!("While enumerating the scripts in {source}"))?;
+    remaining.sort_unstable();
+
+    let mut scripts = Vec::with_capacity(remaining.len());
+    if source_fs.exists(SCRIPTS_INDEX)? {
+        let index = source_fs.read(SCRIPTS_INDEX)?;
+        let index: Vec<ScriptIndexEntry> = ron::de::from_bytes(&index)
+            .wrap_err_with(|| format!("While reading {source}/{SCRIPTS_INDEX}"))?;
+        for entry in index {
+            let Some(position) = remaining.iter().position(|file| *file == entry.file) else {
+                eprintln!(
+                    "warning: {SCRIPTS_INDEX} lists \"{}\", which doesn't exist",
+                    entry.file
+                );
+                continue;
+            };
+            let file = remaining.remove(position);
+            let script_text = source_fs
+                .read_to_string(&file)
+                .wrap_err_with(|| format!("While reading {file}"))?;
+            scripts.push(luminol_data::rpg::Script {
+                id: entry.id,
+                name: entry.name,
+                script_text,
+            });
+        }
+    }
+    for file in remaining {
+        let script_text = source_fs
+            .read_to_string(&file)
+            .wrap_err_with(|| format!("While reading {file}"))?;
+        scripts.push(luminol_data::rpg::Script::new(
+            file.with_extension("").into_string(),
+            script_text,
+        ));
+    }
+
+    // only the scripts are written, the rest of the project's data is left as it is
+    luminol_core::data_formats::Handler::for_project(&project.config.project)
+        .write_scripts(
+            &scripts,
+            &project.filesystem,
+            &project.config.project.scripts_path,
+        )
+        .wrap_err("While writing the scripts")?;
+
+    eprintln!("Built {} scripts into {project_path}", scripts.len());
+    Ok(())
+}
+
+/// Collects the paths of every .rb file in a folder.
+fn find_scripts_recurse(
+    vec: &mut Vec<camino::Utf8PathBuf>,
+    src_fs: &impl ReadDir,
+    path: &camino::Utf8Path,
+) -> luminol_filesystem::Result<()> {
+    for entry in src_fs.read_dir(path)? {
+        if !entry.metadata.is_file {
+            find_scripts_recurse(vec, src_fs, &entry.path)?;
+        } else if entry
+            .path
+            .extension()
+            .is_some_and(|ext| ext.eq_ignore_ascii_case("rb"))
+        {
+            vec.push(entry.path);
+        }
+    }
+    Ok(())
+}
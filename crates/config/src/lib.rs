@@ -20,6 +20,7 @@ pub mod global;
 pub mod project;
 #[cfg(not(target_arch = "wasm32"))]
 pub mod terminal;
+pub mod terrain;
 
 #[derive(Clone, Copy, Hash, PartialEq, Debug, Default)]
 #[derive(serde::Deserialize, serde::Serialize)]
@@ -32,14 +33,25 @@ pub enum DataFormat {
     Ron { pretty: bool },
     #[strum(to_string = "JSON")]
     Json { pretty: bool },
+    #[strum(to_string = "YAML")]
+    Yaml,
+    /// Pretty-printed RON, with every database entry, map info and script in its own file.
+    /// Meant for projects kept under version control.
+    #[strum(to_string = "Split RON (one file per entry)")]
+    Split,
 }
 
 impl DataFormat {
-    pub fn extension(self) -> &'static str {
+    pub fn extension(self, rm_ver: RMVer) -> &'static str {
         match self {
-            Self::Marshal => "rxdata",
-            Self::Ron { .. } => "ron",
+            Self::Marshal => match rm_ver {
+                RMVer::XP => "rxdata",
+                RMVer::VX => "rvdata",
+                RMVer::Ace => "rvdata2",
+            },
+            Self::Ron { .. } | Self::Split => "ron",
             Self::Json { .. } => "json",
+            Self::Yaml => "yaml",
         }
     }
 }
@@ -80,6 +92,57 @@ pub enum RMVer {
     Ace = 3,
 }
 
+/// The text encoding of strings in a project's Marshal data.
+///
+/// RPG Maker XP and VX store text in whatever encoding the system used when the project was
+/// edited, so a Japanese project will usually be in Shift-JIS. Luminol decodes strings to UTF-8
+/// when loading and encodes them back when saving.
+#[derive(Default, Clone, Copy, PartialEq, Eq, Hash, Debug)]
+#[derive(serde::Serialize, serde::Deserialize)]
+#[derive(strum::EnumIter, strum::Display)]
+#[allow(missing_docs)]
+pub enum StringEncoding {
+    #[default]
+    #[strum(to_string = "UTF-8")]
+    Utf8,
+    #[strum(to_string = "Shift-JIS (Japanese)")]
+    ShiftJis,
+    #[strum(to_string = "GBK (Simplified Chinese)")]
+    Gbk,
+    #[strum(to_string = "Big5 (Traditional Chinese)")]
+    Big5,
+    #[strum(to_string = "EUC-KR (Korean)")]
+    EucKr,
+    #[strum(to_string = "Windows-1251 (Cyrillic)")]
+    Windows1251,
+    #[strum(to_string = "Windows-1252 (Western European)")]
+    Windows1252,
+}
+
+impl StringEncoding {
+    /// The legacy encodings to try, in order, when detecting the encoding of a project.
+    pub const LEGACY: [Self; 6] = [
+        Self::ShiftJis,
+        Self::Gbk,
+        Self::Big5,
+        Self::EucKr,
+        Self::Windows1251,
+        Self::Windows1252,
+    ];
+
+    pub fn encoding(self) -> &'static encoding_rs::Encoding {
+        match self {
+            Self::Utf8 => encoding_rs::UTF_8,
+            Self::ShiftJis => encoding_rs::SHIFT_JIS,
+            Self::Gbk => encoding_rs::GBK,
+            Self::Big5 => encoding_rs::BIG5,
+            Self::EucKr => encoding_rs::EUC_KR,
+            Self::Windows1251 => encoding_rs::WINDOWS_1251,
+            Self::Windows1252 => encoding_rs::WINDOWS_1252,
+        }
+    }
+}
+
 #[derive(Default, Clone, Copy, PartialEq, Eq, Hash, Debug)]
 #[derive(serde::Serialize, serde::Deserialize)]
 #[derive(strum::EnumIter, strum::Display)]
@@ -23,13 +23,14 @@
 // Program grant you additional permission to convey the resulting work.
 use serde::{Deserialize, Serialize};
 
-use super::{command_db, DataFormat, RGSSVer, RMVer, VolumeScale};
+use super::{command_db, terrain, DataFormat, RGSSVer, RMVer, StringEncoding, VolumeScale};
 
 #[derive(Debug, Clone)]
 #[allow(clippy::large_enum_variant)]
 pub struct Config {
     pub project: Project,
     pub command_db: command_db::CommandDB,
+    pub terrains: terrain::Terrains,
     pub game_ini: ini::Ini,
 }
 
@@ -47,6 +48,12 @@ pub struct Project {
     pub playtest_exe: String,
     pub prefer_rgssad: bool,
     pub persistence_id: u64,
+    /// How many backups of the project's data to keep in `.luminol/backups`. 0 disables backups.
+    pub backup_count: usize,
+    /// How often unsaved changes are autosaved for crash recovery, in seconds. 0 disables autosaving.
+    pub autosave_interval: u64,
+    /// The encoding of strings in Marshal data. Only used when `data_format` is Marshal.
+    pub encoding: StringEncoding,
 }
 
 impl Default for Project {
@@ -61,6 +68,9 @@ impl Default for Project {
             playtest_exe: "game".to_string(),
             prefer_rgssad: false,
             persistence_id: 0,
+            backup_count: 5,
+            autosave_interval: 60,
+            encoding: StringEncoding::Utf8,
         }
     }
 }
@@ -82,6 +92,7 @@ impl Config {
         Self {
             project,
             command_db,
+            terrains: Default::default(),
             game_ini,
         }
     }
@@ -0,0 +1,182 @@
+// Copyright (C) 2024 Melody Madeline Lyons
+//
+// This file is part of Luminol.
+//
+// Luminol is free software: you can redistribute it and/or modify
+// it under the terms of the GNU General Public License as published by
+// the Free Software Foundation, either version 3 of the License, or
+// (at your option) any later version.
+//
+// Luminol is distributed in the hope that it will be useful,
+// but WITHOUT ANY WARRANTY; without even the implied warranty of
+// MERCHANTABILITY or FITNESS FOR A PARTICULAR PURPOSE.  See the
+// GNU General Public License for more details.
+//
+// You should have received a copy of the GNU General Public License
+// along with Luminol.  If not, see <http://www.gnu.org/licenses/>.
+//
+//     Additional permission under GNU GPL version 3 section 7
+//
+// If you modify this Program, or any covered work, by linking or combining
+// it with Steamworks API by Valve Corporation, containing parts covered by
+// terms of the Steamworks API by Valve Corporation, the licensors of this
+// Program grant you additional permission to convey the resulting work.
+
+//! Terrains made of regular tileset tiles, which the terrain brush borders automatically like
+//! autotiles.
+
+use serde::{Deserialize, Serialize};
+
+#[derive(Deserialize, Serialize, Debug, Clone, Default)]
+pub struct Terrains {
+    pub terrains: Vec<Terrain>,
+}
+
+#[derive(Deserialize, Serialize, Debug, Clone, PartialEq, Eq)]
+pub struct Terrain {
+    pub name: String,
+    /// The tileset the tiles of this terrain are from.
+    pub tileset_id: usize,
+    pub tiles: TerrainTiles,
+}
+
+/// The tile IDs that make up a terrain. Edges and outer corners are named after the side of the
+/// terrain they're on, and inner corners after the side the terrain is missing from.
+#[derive(Deserialize, Serialize, Debug, Clone, Copy, PartialEq, Eq)]
+pub struct TerrainTiles {
+    pub center: i16,
+    pub top: i16,
+    pub bottom: i16,
+    pub left: i16,
+    pub right: i16,
+    pub top_left: i16,
+    pub top_right: i16,
+    pub bottom_left: i16,
+    pub bottom_right: i16,
+    pub inner_top_left: i16,
+    pub inner_top_right: i16,
+    pub inner_bottom_left: i16,
+    pub inner_bottom_right: i16,
+}
+
+/// Which of the tiles around a tile are part of the same terrain.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct Neighbors {
+    pub top: bool,
+    pub bottom: bool,
+    pub left: bool,
+    pub right: bool,
+    pub top_left: bool,
+    pub top_right: bool,
+    pub bottom_left: bool,
+    pub bottom_right: bool,
+}
+
+impl Terrains {
+    /// Iterates over the indices and terrains made for the tileset `tileset_id`.
+    pub fn for_tileset(&self, tileset_id: usize) -> impl Iterator<Item = (usize, &Terrain)> {
+        self.terrains
+            .iter()
+            .enumerate()
+            .filter(move |(_, terrain)| terrain.tileset_id == tileset_id)
+    }
+}
+
+impl Terrain {
+    /// Returns true if `tile_id` is one of the tiles of this terrain.
+    pub fn contains(&self, tile_id: i16) -> bool {
+        self.tiles.iter().any(|id| id == tile_id)
+    }
+
+    /// Picks the tile of this terrain that fits with the tiles around it.
+    ///
+    /// Terrains narrower than two tiles don't have tiles of their own, so they're drawn with
+    /// whichever corner or edge fits best.
+    pub fn tile_for(&self, neighbors: Neighbors) -> i16 {
+        let tiles = &self.tiles;
+        match neighbors {
+            Neighbors {
+                top: false,
+                left: false,
+                ..
+            } => tiles.top_left,
+            Neighbors {
+                top: false,
+                right: false,
+                ..
+            } => tiles.top_right,
+            Neighbors {
+                bottom: false,
+                left: false,
+                ..
+            } => tiles.bottom_left,
+            Neighbors {
+                bottom: false,
+                right: false,
+                ..
+            } => tiles.bottom_right,
+            Neighbors { top: false, .. } => tiles.top,
+            Neighbors { bottom: false, .. } => tiles.bottom,
+            Neighbors { left: false, .. } => tiles.left,
+            Neighbors { right: false, .. } => tiles.right,
+            Neighbors {
+                top_left: false, ..
+            } => tiles.inner_top_left,
+            Neighbors {
+                top_right: false, ..
+            } => tiles.inner_top_right,
+            Neighbors {
+                bottom_left: false, ..
+            } => tiles.inner_bottom_left,
+            Neighbors {
+                bottom_right: false,
+                ..
+            } => tiles.inner_bottom_right,
+            _ => tiles.center,
+        }
+    }
+}
+
+impl TerrainTiles {
+    /// Reads the tiles of a terrain from a 5x3 block of tile IDs, given row by row. The first three
+    /// columns hold the outer corners and edges around the center tile, as they'd be laid out on a
+    /// map, and the top two rows of the last two columns hold the inner corners, each placed in
+    /// the corner the terrain is missing from. The bottom two tiles of the last two columns are
+    /// unused.
+    pub fn from_block(block: &[[i16; 5]; 3]) -> Self {
+        Self {
+            top_left: block[0][0],
+            top: block[0][1],
+            top_right: block[0][2],
+            left: block[1][0],
+            center: block[1][1],
+            right: block[1][2],
+            bottom_left: block[2][0],
+            bottom: block[2][1],
+            bottom_right: block[2][2],
+            inner_top_left: block[0][3],
+            inner_top_right: block[0][4],
+            inner_bottom_left: block[1][3],
+            inner_bottom_right: block[1][4],
+        }
+    }
+
+    pub fn iter(&self) -> impl Iterator<Item = i16> {
+        [
+            self.center,
+            self.top,
+            self.bottom,
+            self.left,
+            self.right,
+            self.top_left,
+            self.top_right,
+            self.bottom_left,
+            self.bottom_right,
+            self.inner_top_left,
+            self.inner_top_right,
+            self.inner_bottom_left,
+            self.inner_bottom_right,
+        ]
+        .into_iter()
+    }
+}
@@ -23,15 +23,22 @@
 // Program grant you additional permission to convey the resulting work.
 
 use color_eyre::eyre::WrapErr;
-use luminol_data::rpg;
+use luminol_data::{rmvx, rmvxace, rpg};
 use std::{
     cell::{RefCell, RefMut},
-    collections::HashMap,
+    collections::{BTreeMap, BTreeSet, HashMap},
 };
 
 use crate::error;
 
+pub mod backups;
+pub mod convert;
 pub mod data_formats;
+pub mod recovery;
+pub mod transaction;
+
+#[cfg(test)]
+mod test_fixtures;
 
 // TODO convert this to an option like project config?
 #[allow(clippy::large_enum_variant)]
@@ -57,18 +64,107 @@ pub enum Data {
         weapons: RefCell<rpg::Weapons>,
 
         maps: RefCell<HashMap<usize, rpg::Map>>,
+        /// Maps that were deleted since the last save, whose files are removed when saving.
+        deleted_maps: RefCell<BTreeSet<usize>>,
+
+        /// Databases that couldn't be read, see [`Data::load_partial`].
+        load_errors: BTreeMap<String, color_eyre::Report>,
+    },
+    LoadedVX {
+        actors: RefCell<rmvx::Actors>,
+        animations: RefCell<rmvx::Animations>,
+        areas: RefCell<rmvx::Areas>,
+        armors: RefCell<rmvx::Armors>,
+        classes: RefCell<rmvx::Classes>,
+        common_events: RefCell<rpg::CommonEvents>,
+        enemies: RefCell<rmvx::Enemies>,
+        items: RefCell<rmvx::Items>,
+        map_infos: RefCell<rpg::MapInfos>,
+        scripts: RefCell<rpg::Scripts>,
+        skills: RefCell<rmvx::Skills>,
+        states: RefCell<rmvx::States>,
+        system: RefCell<rmvx::System>,
+        troops: RefCell<rmvx::Troops>,
+        weapons: RefCell<rmvx::Weapons>,
+
+        maps: RefCell<HashMap<usize, rmvx::Map>>,
+        /// Maps that were deleted since the last save, whose files are removed when saving.
+        deleted_maps: RefCell<BTreeSet<usize>>,
+
+        /// Databases that couldn't be read, see [`Data::load_partial`].
+        load_errors: BTreeMap<String, color_eyre::Report>,
     },
+    LoadedAce {
+        actors: RefCell<rmvxace::Actors>,
+        animations: RefCell<rmvxace::Animations>,
+        armors: RefCell<rmvxace::Armors>,
+        classes: RefCell<rmvxace::Classes>,
+        common_events: RefCell<rpg::CommonEvents>,
+        enemies: RefCell<rmvxace::Enemies>,
+        items: RefCell<rmvxace::Items>,
+        map_infos: RefCell<rpg::MapInfos>,
+        scripts: RefCell<rpg::Scripts>,
+        skills: RefCell<rmvxace::Skills>,
+        states: RefCell<rmvxace::States>,
+        system: RefCell<rmvxace::System>,
+        tilesets: RefCell<rmvxace::Tilesets>,
+        troops: RefCell<rmvxace::Troops>,
+        weapons: RefCell<rmvxace::Weapons>,
+
+        maps: RefCell<HashMap<usize, rmvxace::Map>>,
+        /// Maps that were deleted since the last save, whose files are removed when saving.
+        deleted_maps: RefCell<BTreeSet<usize>>,
+
+        /// Databases that couldn't be read, see [`Data::load_partial`].
+        load_errors: BTreeMap<String, color_eyre::Report>,
+    },
+}
+
+/// A database or map in the data cache.
+#[derive(Clone, Debug, PartialEq, Eq)]
+#[derive(serde::Serialize, serde::Deserialize)]
+pub enum Entry {
+    Database(String),
+    Map(usize),
+}
+
+impl std::fmt::Display for Entry {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        match self {
+            Self::Database(name) => write!(f, "{name}"),
+            Self::Map(id) => write!(f, "Map {id:0>3}"),
+        }
+    }
+}
+
+impl Entry {
+    pub fn map_id(&self) -> Option<usize> {
+        match self {
+            Self::Map(id) => Some(*id),
+            Self::Database(_) => None,
+        }
+    }
 }
 
 macro_rules! load {
-    ($fs:ident, $type:ident, $format_handler:ident) => {
-        RefCell::new(rpg::$type {
-            data: $format_handler
+    ($fs:ident, $module:ident::$type:ident, $format_handler:ident, $load_errors:ident) => {
+        RefCell::new($module::$type {
+            data: match $format_handler
                 .read_nil_padded($fs, format!("{}", stringify!($type)))
-                .wrap_err_with(|| format!("While reading {}", stringify!($type)))?,
+                .wrap_err_with(|| format!("While reading {}", stringify!($type)))
+            {
+                Ok(data) => data,
+                Err(error) => {
+                    $load_errors.insert(stringify!($type).to_string(), error);
+                    Vec::new()
+                }
+            },
             ..Default::default()
         })
     };
+    ($fs:ident, $type:ident, $format_handler:ident, $load_errors:ident) => {
+        load!($fs, rpg::$type, $format_handler, $load_errors)
+    };
 }
 macro_rules! from_defaults {
     ($parent:ident, $child:ident) => {
@@ -79,18 +175,89 @@ macro_rules! from_defaults {
     };
 }
 
-macro_rules! save {
-    ($fs:ident, $type:ident, $field:ident, $format_handler:ident) => {{
-        let borrowed = $field.get_mut();
-        if borrowed.modified {
-            $format_handler
-                .write_nil_padded(&borrowed.data, $fs, format!("{}", stringify!($type)))
-                .wrap_err_with(|| format!("While saving {}", stringify!($type)))?;
+/// A nil padded database, like `Actors`, that [`Data::stage`] writes when it's modified.
+trait Database<F> {
+    fn modified(&mut self) -> &mut bool;
+
+    fn write(
+        &self,
+        handler: data_formats::Handler,
+        filesystem: &F,
+        filename: &str,
+    ) -> color_eyre::Result<()>;
+}
+
+macro_rules! impl_database {
+    ($($type:ty),* $(,)?) => {
+        $(
+            impl<F: luminol_filesystem::FileSystem> Database<F> for $type {
+                fn modified(&mut self) -> &mut bool {
+                    &mut self.modified
+                }
+
+                fn write(
+                    &self,
+                    handler: data_formats::Handler,
+                    filesystem: &F,
+                    filename: &str,
+                ) -> color_eyre::Result<()> {
+                    handler.write_nil_padded(&self.data, filesystem, filename)
+                }
+            }
+        )*
+    };
+}
+
+impl_database! {
+    rpg::Actors, rpg::Animations, rpg::Armors, rpg::Classes, rpg::CommonEvents, rpg::Enemies,
+    rpg::Items, rpg::Skills, rpg::States, rpg::Tilesets, rpg::Troops, rpg::Weapons,
+    rmvx::Actors, rmvx::Animations, rmvx::Areas, rmvx::Armors, rmvx::Classes, rmvx::Enemies,
+    rmvx::Items, rmvx::Skills, rmvx::States, rmvx::Troops, rmvx::Weapons,
+    rmvxace::Actors, rmvxace::Animations, rmvxace::Armors, rmvxace::Classes, rmvxace::Enemies,
+    rmvxace::Items, rmvxace::Skills, rmvxace::States, rmvxace::Tilesets, rmvxace::Troops,
+    rmvxace::Weapons,
+}
+
+macro_rules! save_maps {
+    ($fs:ident, $maps:ident, $deleted_maps:ident, $format_handler:ident, $transaction:ident, $flags:ident) => {{
+        let mut modified = false;
+        for id in $deleted_maps.get_mut().iter() {
+            modified = true;
+            $transaction.stage_removal($format_handler.path_for(format!("Map{id:0>3}")));
+        }
+        for (id, map) in $maps.get_mut().iter_mut() {
+            if map.modified {
+                modified = true;
+                let filename = format!("Map{id:0>3}");
+                $format_handler
+                    .with_data_dir(SAVE_STAGING_DATA_DIR)
+                    .write_data(map, $fs, &filename)
+                    .wrap_err_with(|| format!("While saving map {id:0>3}"))?;
+                $transaction.stage($format_handler.path_for(filename));
+                $flags.push(&mut map.modified);
+            }
         }
-        borrowed.modified
+        modified
     }};
 }
 
+macro_rules! reload {
+    ($fs:ident, $name:ident, $type:ident, $field:ident, $format_handler:ident) => {
+        if $name == stringify!($type) {
+            let field = $field.get_mut();
+            field.data = $format_handler
+                .read_nil_padded($fs, stringify!($type))
+                .wrap_err_with(|| format!("While reloading {}", stringify!($type)))?;
+            field.modified = false;
+            return Ok(());
+        }
+    };
+}
+
+/// Where [`Data::save`] writes files before swapping them into place.
+const SAVE_STAGING_DIR: &str = ".luminol/save";
+const SAVE_STAGING_DATA_DIR: &str = ".luminol/save/Data";
+
 impl Data {
     /// Load all data required when opening a project.
     /// Does not load config. That is expected to have been loaded beforehand.
@@ -100,23 +267,149 @@ impl Data {
         toasts: &mut crate::Toasts,
         config: &mut luminol_config::project::Config,
     ) -> color_eyre::Result<()> {
-        let handler = data_formats::Handler::new(config.project.data_format);
+        let mut data = Self::Unloaded;
+        data.load_partial(filesystem, toasts, config)?;
+        if let Some((_, error)) = data.load_errors_mut().and_then(BTreeMap::pop_first) {
+            return Err(error);
+        }
+        *self = data;
+        Ok(())
+    }
+
+    /// Like [`Data::load`], but databases that can't be read don't stop the project from being
+    /// opened. They are left empty and recorded in [`Data::load_errors`] instead, and aren't saved
+    /// until they have been reloaded successfully.
+    ///
+    /// MapInfos, Scripts and System are still required, since the editor can't work without them.
+    pub fn load_partial(
+        &mut self,
+        filesystem: &impl luminol_filesystem::FileSystem,
+        toasts: &mut crate::Toasts,
+        config: &mut luminol_config::project::Config,
+    ) -> color_eyre::Result<()> {
+        // older projects store text in the system's encoding, which the project config doesn't know
+        // about the first time they're opened
+        if config.project.data_format == luminol_config::DataFormat::Marshal
+            && config.project.encoding == luminol_config::StringEncoding::Utf8
+        {
+            if let Some(encoding) =
+                data_formats::detect_encoding(filesystem, config.project.editor_ver)
+            {
+                config.project.encoding = encoding;
+                crate::info!(
+                    *toasts,
+                    format!(
+                        "Detected that this project's text is in {encoding}. \
+                        This can be changed in the project config."
+                    )
+                );
+            }
+        }
+
+        let handler = data_formats::Handler::for_project(&config.project);
+        let mut load_errors = BTreeMap::new();
 
         let map_infos = RefCell::new(rpg::MapInfos {
             data: handler
-                .read_data(filesystem, "MapInfos")
+                .read_map_infos(filesystem)
                 .wrap_err("While reading MapInfos")?,
             ..Default::default()
         });
+        let scripts = RefCell::new(Self::load_scripts(handler, filesystem, toasts, config)?);
 
-        let mut system = handler
-            .read_data::<rpg::System>(filesystem, "System")
-            .wrap_err("While reading System")?;
-        system.magic_number = rand::random();
+        *self = match config.project.editor_ver {
+            luminol_config::RMVer::XP => {
+                let mut system = handler
+                    .read_data::<rpg::System>(filesystem, "System")
+                    .wrap_err("While reading System")?;
+                system.magic_number = rand::random();
 
-        let system = RefCell::new(system);
+                Self::Loaded {
+                    actors: load!(filesystem, Actors, handler, load_errors),
+                    animations: load!(filesystem, Animations, handler, load_errors),
+                    armors: load!(filesystem, Armors, handler, load_errors),
+                    classes: load!(filesystem, Classes, handler, load_errors),
+                    common_events: load!(filesystem, CommonEvents, handler, load_errors),
+                    enemies: load!(filesystem, Enemies, handler, load_errors),
+                    items: load!(filesystem, Items, handler, load_errors),
+                    skills: load!(filesystem, Skills, handler, load_errors),
+                    states: load!(filesystem, States, handler, load_errors),
+                    tilesets: load!(filesystem, Tilesets, handler, load_errors),
+                    troops: load!(filesystem, Troops, handler, load_errors),
+                    weapons: load!(filesystem, Weapons, handler, load_errors),
+                    map_infos,
+                    system: RefCell::new(system),
+                    scripts,
+                    maps: RefCell::new(std::collections::HashMap::with_capacity(32)),
+                    deleted_maps: RefCell::default(),
+                    load_errors,
+                }
+            }
+            luminol_config::RMVer::VX => {
+                let mut system = handler
+                    .read_data::<rmvx::System>(filesystem, "System")
+                    .wrap_err("While reading System")?;
+                system.version_id = rand::random();
 
-        let mut scripts = None;
+                Self::LoadedVX {
+                    actors: load!(filesystem, rmvx::Actors, handler, load_errors),
+                    animations: load!(filesystem, rmvx::Animations, handler, load_errors),
+                    areas: load!(filesystem, rmvx::Areas, handler, load_errors),
+                    armors: load!(filesystem, rmvx::Armors, handler, load_errors),
+                    classes: load!(filesystem, rmvx::Classes, handler, load_errors),
+                    common_events: load!(filesystem, CommonEvents, handler, load_errors),
+                    enemies: load!(filesystem, rmvx::Enemies, handler, load_errors),
+                    items: load!(filesystem, rmvx::Items, handler, load_errors),
+                    skills: load!(filesystem, rmvx::Skills, handler, load_errors),
+                    states: load!(filesystem, rmvx::States, handler, load_errors),
+                    troops: load!(filesystem, rmvx::Troops, handler, load_errors),
+                    weapons: load!(filesystem, rmvx::Weapons, handler, load_errors),
+                    map_infos,
+                    system: RefCell::new(system),
+                    scripts,
+                    maps: RefCell::new(std::collections::HashMap::with_capacity(32)),
+                    deleted_maps: RefCell::default(),
+                    load_errors,
+                }
+            }
+            luminol_config::RMVer::Ace => {
+                let mut system = handler
+                    .read_data::<rmvxace::System>(filesystem, "System")
+                    .wrap_err("While reading System")?;
+                system.version_id = rand::random();
+
+                Self::LoadedAce {
+                    actors: load!(filesystem, rmvxace::Actors, handler, load_errors),
+                    animations: load!(filesystem, rmvxace::Animations, handler, load_errors),
+                    armors: load!(filesystem, rmvxace::Armors, handler, load_errors),
+                    classes: load!(filesystem, rmvxace::Classes, handler, load_errors),
+                    common_events: load!(filesystem, CommonEvents, handler, load_errors),
+                    enemies: load!(filesystem, rmvxace::Enemies, handler, load_errors),
+                    items: load!(filesystem, rmvxace::Items, handler, load_errors),
+                    skills: load!(filesystem, rmvxace::Skills, handler, load_errors),
+                    states: load!(filesystem, rmvxace::States, handler, load_errors),
+                    tilesets: load!(filesystem, rmvxace::Tilesets, handler, load_errors),
+                    troops: load!(filesystem, rmvxace::Troops, handler, load_errors),
+                    weapons: load!(filesystem, rmvxace::Weapons, handler, load_errors),
+                    map_infos,
+                    system: RefCell::new(system),
+                    scripts,
+                    maps: RefCell::new(std::collections::HashMap::with_capacity(32)),
+                    deleted_maps: RefCell::default(),
+                    load_errors,
+                }
+            }
+        };
+
+        Ok(())
+    }
+
+    fn load_scripts(
+        handler: data_formats::Handler,
+        filesystem: &impl luminol_filesystem::FileSystem,
+        toasts: &mut crate::Toasts,
+        config: &mut luminol_config::project::Config,
+    ) -> color_eyre::Result<rpg::Scripts> {
         let scripts_paths = [
             config.project.scripts_path.clone(),
             "xScripts".to_string(),
@@ -124,14 +417,13 @@ impl Data {
         ];
 
         for script_path in scripts_paths {
-            match handler.read_data(filesystem, format!("{script_path}")) {
+            match handler.read_scripts(filesystem, &script_path) {
                 Ok(s) => {
                     config.project.scripts_path = script_path;
-                    scripts = Some(rpg::Scripts {
+                    return Ok(rpg::Scripts {
                         data: s,
                         ..Default::default()
                     });
-                    break;
                 }
                 Err(e) => {
                     error!(
@@ -143,42 +435,55 @@ impl Data {
                 }
             }
         }
-        let Some(scripts) = scripts else {
-            color_eyre::eyre::bail!(
-                "Unable to load scripts (tried {}, xScripts, and Scripts first)",
-                config.project.scripts_path
-            );
-        };
-        let scripts = RefCell::new(scripts);
-
-        let maps = RefCell::new(std::collections::HashMap::with_capacity(32));
-
-        *self = Self::Loaded {
-            actors: load!(filesystem, Actors, handler),
-            animations: load!(filesystem, Animations, handler),
-            armors: load!(filesystem, Armors, handler),
-            classes: load!(filesystem, Classes, handler),
-            common_events: load!(filesystem, CommonEvents, handler),
-            enemies: load!(filesystem, Enemies, handler),
-            items: load!(filesystem, Items, handler),
-            skills: load!(filesystem, Skills, handler),
-            states: load!(filesystem, States, handler),
-            tilesets: load!(filesystem, Tilesets, handler),
-            troops: load!(filesystem, Troops, handler),
-            weapons: load!(filesystem, Weapons, handler),
-            map_infos,
-            system,
-            scripts,
-            maps,
-        };
 
-        Ok(())
+        color_eyre::eyre::bail!(
+            "Unable to load scripts (tried {}, xScripts, and Scripts first)",
+            config.project.scripts_path
+        )
     }
 
     pub fn unload(&mut self) {
         *self = Self::Unloaded;
     }
 
+    /// The editor version of the loaded project data, if any is loaded.
+    pub fn editor_ver(&self) -> Option<luminol_config::RMVer> {
+        match self {
+            Self::Unloaded => None,
+            Self::Loaded { .. } => Some(luminol_config::RMVer::XP),
+            Self::LoadedVX { .. } => Some(luminol_config::RMVer::VX),
+            Self::LoadedAce { .. } => Some(luminol_config::RMVer::Ace),
+        }
+    }
+
+    /// The databases that couldn't be read when the project was opened, and why.
+    pub fn load_errors(&self) -> impl Iterator<Item = (&str, &color_eyre::Report)> {
+        match self {
+            Self::Unloaded => None,
+            Self::Loaded { load_errors, .. }
+            | Self::LoadedVX { load_errors, .. }
+            | Self::LoadedAce { load_errors, .. } => Some(load_errors),
+        }
+        .into_iter()
+        .flatten()
+        .map(|(name, error)| (name.as_str(), error))
+    }
+
+    fn load_errors_mut(&mut self) -> Option<&mut BTreeMap<String, color_eyre::Report>> {
+        match self {
+            Self::Unloaded => None,
+            Self::Loaded { load_errors, .. }
+            | Self::LoadedVX { load_errors, .. }
+            | Self::LoadedAce { load_errors, .. } => Some(load_errors),
+        }
+    }
+
+    /// Whether a database could be read when the project was opened.
+    /// Databases that couldn't be are empty, and shouldn't be edited.
+    pub fn is_available(&self, name: &str) -> bool {
+        !self.load_errors().any(|(failed, _)| failed == name)
+    }
+
     pub fn from_defaults() -> Self {
         let mut map_infos = std::collections::HashMap::with_capacity(16);
         map_infos.insert(1, rpg::MapInfo::default());
@@ -220,99 +525,263 @@ impl Data {
             system,
             scripts,
             maps,
+            deleted_maps: RefCell::default(),
+            load_errors: BTreeMap::new(),
         }
     }
 
-    pub fn rxdata_ext(&self) -> &'static str {
-        todo!()
-    }
-
     /// Save all cached data to disk.
+    ///
+    /// Every file is written to `.luminol/save` first, and only swapped into place once all of
+    /// them have been written successfully. If anything was modified, a backup of the project's
+    /// data as it was before saving is taken beforehand (see [`backups`]).
     // we take an &mut self to ensure no outsanding borrows of the cache exist.
     pub fn save(
         &mut self,
         filesystem: &impl luminol_filesystem::FileSystem,
         config: &luminol_config::project::Config,
     ) -> color_eyre::Result<()> {
-        let handler = data_formats::Handler::new(config.project.data_format);
-
-        let Self::Loaded {
-            actors,
-            animations,
-            armors,
-            classes,
-            common_events,
-            enemies,
-            items,
-            map_infos,
-            scripts,
-            skills,
-            states,
-            tilesets,
-            troops,
-            weapons,
-            system,
-            maps,
-        } = self
-        else {
-            panic!("project not loaded")
+        let mut transaction = transaction::Transaction::new(filesystem, SAVE_STAGING_DIR)
+            .wrap_err("While preparing to save")?;
+
+        let (modified, modified_flags) = match self.stage(filesystem, config, &mut transaction) {
+            Ok(staged) => staged,
+            Err(error) => {
+                transaction.rollback(filesystem)?;
+                return Err(error);
+            }
         };
 
-        let mut modified = false;
+        if modified && config.project.backup_count > 0 {
+            if let Err(error) = backups::create(filesystem, config.project.backup_count) {
+                transaction.rollback(filesystem)?;
+                return Err(error.wrap_err("Not saving, since a backup could not be created"));
+            }
+        }
 
-        modified |= save!(filesystem, Actors, actors, handler);
-        modified |= save!(filesystem, Animations, animations, handler);
-        modified |= save!(filesystem, Armors, armors, handler);
-        modified |= save!(filesystem, Classes, classes, handler);
-        modified |= save!(filesystem, CommonEvents, common_events, handler);
-        modified |= save!(filesystem, Enemies, enemies, handler);
-        modified |= save!(filesystem, Items, items, handler);
-        modified |= save!(filesystem, Skills, skills, handler);
-        modified |= save!(filesystem, States, states, handler);
-        modified |= save!(filesystem, Tilesets, tilesets, handler);
-        modified |= save!(filesystem, Troops, troops, handler);
-        modified |= save!(filesystem, Weapons, weapons, handler);
+        transaction.commit(filesystem)?;
 
-        {
-            let map_infos = map_infos.get_mut();
-            if map_infos.modified {
-                modified = true;
-                handler
-                    .write_data(&map_infos.data, filesystem, "MapInfos")
-                    .wrap_err("While saving MapInfos")?;
-            }
+        // only now that everything is on disk can the data be considered unmodified
+        for flag in modified_flags {
+            *flag = false;
+        }
+        match self {
+            Self::Unloaded => {}
+            Self::Loaded { deleted_maps, .. }
+            | Self::LoadedVX { deleted_maps, .. }
+            | Self::LoadedAce { deleted_maps, .. } => deleted_maps.get_mut().clear(),
         }
 
-        {
-            let scripts = scripts.get_mut();
-            if scripts.modified {
-                modified = true;
-                handler.write_data(&scripts.data, filesystem, &config.project.scripts_path)?;
+        recovery::discard(filesystem, config)
+            .wrap_err("The project was saved, but its autosaved data could not be removed")
+    }
+
+    /// Writes everything that needs saving to the staging folder of `transaction`.
+    /// Returns whether any data was modified, and the modified flags to reset once the
+    /// transaction has been committed.
+    fn stage<'a, F: luminol_filesystem::FileSystem + 'a>(
+        &'a mut self,
+        filesystem: &F,
+        config: &luminol_config::project::Config,
+        transaction: &mut transaction::Transaction,
+    ) -> color_eyre::Result<(bool, Vec<&'a mut bool>)> {
+        let handler = data_formats::Handler::for_project(&config.project);
+        let mut flags = Vec::new();
+        let mut modified = false;
+
+        match self {
+            Self::Unloaded => panic!("project not loaded"),
+            Self::Loaded {
+                actors,
+                animations,
+                armors,
+                classes,
+                common_events,
+                enemies,
+                items,
+                map_infos,
+                scripts,
+                skills,
+                states,
+                tilesets,
+                troops,
+                weapons,
+                system,
+                maps,
+                deleted_maps,
+                load_errors,
+            } => {
+                let databases: Vec<(&str, &mut dyn Database<F>)> = vec![
+                    ("Actors", actors.get_mut()),
+                    ("Animations", animations.get_mut()),
+                    ("Armors", armors.get_mut()),
+                    ("Classes", classes.get_mut()),
+                    ("CommonEvents", common_events.get_mut()),
+                    ("Enemies", enemies.get_mut()),
+                    ("Items", items.get_mut()),
+                    ("Skills", skills.get_mut()),
+                    ("States", states.get_mut()),
+                    ("Tilesets", tilesets.get_mut()),
+                    ("Troops", troops.get_mut()),
+                    ("Weapons", weapons.get_mut()),
+                ];
+                modified |= Self::save_databases(
+                    filesystem,
+                    handler,
+                    transaction,
+                    &mut flags,
+                    load_errors,
+                    databases,
+                )?;
+                modified |= Self::save_shared(
+                    filesystem,
+                    config,
+                    handler,
+                    transaction,
+                    &mut flags,
+                    map_infos,
+                    scripts,
+                )?;
+                modified |= save_maps!(filesystem, maps, deleted_maps, handler, transaction, flags);
+
+                let system = system.get_mut();
+                if system.modified || modified {
+                    system.magic_number = rand::random();
+                    handler
+                        .with_data_dir(SAVE_STAGING_DATA_DIR)
+                        .write_data(system, filesystem, "System")
+                        .wrap_err("While saving System")?;
+                    transaction.stage(handler.path_for("System"));
+                    flags.push(&mut system.modified);
+                }
             }
-        }
+            Self::LoadedVX {
+                actors,
+                animations,
+                areas,
+                armors,
+                classes,
+                common_events,
+                enemies,
+                items,
+                map_infos,
+                scripts,
+                skills,
+                states,
+                system,
+                troops,
+                weapons,
+                maps,
+                deleted_maps,
+                load_errors,
+            } => {
+                let databases: Vec<(&str, &mut dyn Database<F>)> = vec![
+                    ("Actors", actors.get_mut()),
+                    ("Animations", animations.get_mut()),
+                    ("Areas", areas.get_mut()),
+                    ("Armors", armors.get_mut()),
+                    ("Classes", classes.get_mut()),
+                    ("CommonEvents", common_events.get_mut()),
+                    ("Enemies", enemies.get_mut()),
+                    ("Items", items.get_mut()),
+                    ("Skills", skills.get_mut()),
+                    ("States", states.get_mut()),
+                    ("Troops", troops.get_mut()),
+                    ("Weapons", weapons.get_mut()),
+                ];
+                modified |= Self::save_databases(
+                    filesystem,
+                    handler,
+                    transaction,
+                    &mut flags,
+                    load_errors,
+                    databases,
+                )?;
+                modified |= Self::save_shared(
+                    filesystem,
+                    config,
+                    handler,
+                    transaction,
+                    &mut flags,
+                    map_infos,
+                    scripts,
+                )?;
+                modified |= save_maps!(filesystem, maps, deleted_maps, handler, transaction, flags);
 
-        {
-            let maps = maps.get_mut();
-            maps.iter().try_for_each(|(id, map)| {
-                if map.modified {
-                    modified = true;
+                let system = system.get_mut();
+                if system.modified || modified {
+                    system.version_id = rand::random();
                     handler
-                        .write_data(map, filesystem, format!("Map{id:0>3}"))
-                        .wrap_err_with(|| format!("While saving map {id:0>3}"))
-                } else {
-                    Ok(())
+                        .with_data_dir(SAVE_STAGING_DATA_DIR)
+                        .write_data(system, filesystem, "System")
+                        .wrap_err("While saving System")?;
+                    transaction.stage(handler.path_for("System"));
+                    flags.push(&mut system.modified);
                 }
-            })?
-        }
+            }
+            Self::LoadedAce {
+                actors,
+                animations,
+                armors,
+                classes,
+                common_events,
+                enemies,
+                items,
+                map_infos,
+                scripts,
+                skills,
+                states,
+                system,
+                tilesets,
+                troops,
+                weapons,
+                maps,
+                deleted_maps,
+                load_errors,
+            } => {
+                let databases: Vec<(&str, &mut dyn Database<F>)> = vec![
+                    ("Actors", actors.get_mut()),
+                    ("Animations", animations.get_mut()),
+                    ("Armors", armors.get_mut()),
+                    ("Classes", classes.get_mut()),
+                    ("CommonEvents", common_events.get_mut()),
+                    ("Enemies", enemies.get_mut()),
+                    ("Items", items.get_mut()),
+                    ("Skills", skills.get_mut()),
+                    ("States", states.get_mut()),
+                    ("Tilesets", tilesets.get_mut()),
+                    ("Troops", troops.get_mut()),
+                    ("Weapons", weapons.get_mut()),
+                ];
+                modified |= Self::save_databases(
+                    filesystem,
+                    handler,
+                    transaction,
+                    &mut flags,
+                    load_errors,
+                    databases,
+                )?;
+                modified |= Self::save_shared(
+                    filesystem,
+                    config,
+                    handler,
+                    transaction,
+                    &mut flags,
+                    map_infos,
+                    scripts,
+                )?;
+                modified |= save_maps!(filesystem, maps, deleted_maps, handler, transaction, flags);
 
-        {
-            let system = system.get_mut();
-            if system.modified || modified {
-                system.magic_number = rand::random();
-                handler
-                    .write_data(system, filesystem, "System")
-                    .wrap_err("While saving System")?;
-                system.modified = false;
+                let system = system.get_mut();
+                if system.modified || modified {
+                    system.version_id = rand::random();
+                    handler
+                        .with_data_dir(SAVE_STAGING_DATA_DIR)
+                        .write_data(system, filesystem, "System")
+                        .wrap_err("While saving System")?;
+                    transaction.stage(handler.path_for("System"));
+                    flags.push(&mut system.modified);
+                }
             }
         }
 
@@ -322,49 +791,97 @@ impl Data {
 
         let project_config = ron::ser::to_string_pretty(&config.project, pretty_config.clone())
             .wrap_err("While serializing .luminol/config")?;
-        filesystem
-            .write(".luminol/config", project_config)
-            .wrap_err("While writing .luminol/config")?;
+        transaction.write(filesystem, ".luminol/config", project_config)?;
 
         let command_db = ron::ser::to_string_pretty(&config.command_db, pretty_config.clone())
             .wrap_err("While serializing .luminol/commands")?;
-        filesystem
-            .write(".luminol/commands", command_db)
-            .wrap_err("While writing .luminol/commands")?;
-
-        // even though Ini uses fmt::write internally, it provides no easy way to write to a string.
-        // so we need to open a file instead
-        let mut ini_file = filesystem
-            .open_file(
-                "Game.ini",
-                luminol_filesystem::OpenFlags::Create
-                    | luminol_filesystem::OpenFlags::Write
-                    | luminol_filesystem::OpenFlags::Truncate,
-            )
-            .wrap_err("While opening Game.ini")?;
+        transaction.write(filesystem, ".luminol/commands", command_db)?;
+
+        // Terrains are optional, so there's nothing to write if the project doesn't have any
+        if !config.terrains.terrains.is_empty() || filesystem.exists(".luminol/terrains")? {
+            let terrains = ron::ser::to_string_pretty(&config.terrains, pretty_config.clone())
+                .wrap_err("While serializing .luminol/terrains")?;
+            transaction.write(filesystem, ".luminol/terrains", terrains)?;
+        }
+
+        let mut game_ini = Vec::new();
         config
             .game_ini
-            .write_to(&mut ini_file)
+            .write_to(&mut game_ini)
             .wrap_err("While serializing Game.ini")?;
+        transaction.write(filesystem, "Game.ini", game_ini)?;
 
-        actors.borrow_mut().modified = false;
-        animations.borrow_mut().modified = false;
-        armors.borrow_mut().modified = false;
-        classes.borrow_mut().modified = false;
-        common_events.borrow_mut().modified = false;
-        enemies.borrow_mut().modified = false;
-        items.borrow_mut().modified = false;
-        skills.borrow_mut().modified = false;
-        states.borrow_mut().modified = false;
-        tilesets.borrow_mut().modified = false;
-        troops.borrow_mut().modified = false;
-        weapons.borrow_mut().modified = false;
-        map_infos.borrow_mut().modified = false;
-        scripts.borrow_mut().modified = false;
-        for (_, map) in maps.borrow_mut().iter_mut() {
-            map.modified = false;
+        Ok((modified, flags))
+    }
+
+    /// Stages every modified database in `databases`, which are listed along with their filenames.
+    /// Returns true if anything was written.
+    fn save_databases<'a, F: luminol_filesystem::FileSystem>(
+        filesystem: &F,
+        handler: data_formats::Handler,
+        transaction: &mut transaction::Transaction,
+        flags: &mut Vec<&'a mut bool>,
+        load_errors: &BTreeMap<String, color_eyre::Report>,
+        databases: Vec<(&str, &'a mut dyn Database<F>)>,
+    ) -> color_eyre::Result<bool> {
+        let mut modified = false;
+        for (name, database) in databases {
+            // databases that couldn't be loaded are empty, so saving them would lose their data
+            if !*database.modified() || load_errors.contains_key(name) {
+                continue;
+            }
+            modified = true;
+            database
+                .write(
+                    handler.with_data_dir(SAVE_STAGING_DATA_DIR),
+                    filesystem,
+                    name,
+                )
+                .wrap_err_with(|| format!("While saving {name}"))?;
+            transaction.stage(handler.collection_path_for(name));
+            flags.push(database.modified());
         }
-        Ok(())
+        Ok(modified)
+    }
+
+    /// Stages the data files that have the same layout in every RPG Maker version.
+    /// Returns true if anything was written.
+    #[allow(clippy::too_many_arguments)]
+    fn save_shared<'a>(
+        filesystem: &impl luminol_filesystem::FileSystem,
+        config: &luminol_config::project::Config,
+        handler: data_formats::Handler,
+        transaction: &mut transaction::Transaction,
+        flags: &mut Vec<&'a mut bool>,
+        map_infos: &'a mut RefCell<rpg::MapInfos>,
+        scripts: &'a mut RefCell<rpg::Scripts>,
+    ) -> color_eyre::Result<bool> {
+        let staging_handler = handler.with_data_dir(SAVE_STAGING_DATA_DIR);
+        let mut modified = false;
+
+        let map_infos = map_infos.get_mut();
+        if map_infos.modified {
+            modified = true;
+            staging_handler
+                .write_map_infos(&map_infos.data, filesystem)
+                .wrap_err("While saving MapInfos")?;
+            transaction.stage(handler.collection_path_for("MapInfos"));
+            flags.push(&mut map_infos.modified);
+        }
+
+        let scripts = scripts.get_mut();
+        if scripts.modified {
+            modified = true;
+            staging_handler.write_scripts(
+                &scripts.data,
+                filesystem,
+                &config.project.scripts_path,
+            )?;
+            transaction.stage(handler.collection_path_for(&config.project.scripts_path));
+            flags.push(&mut scripts.modified);
+        }
+
+        Ok(modified)
     }
 }
 
@@ -376,11 +893,58 @@ macro_rules! nested_ref_getter {
                 match self {
                     Self::Unloaded => panic!("data cache unloaded"),
                     Self::Loaded { $name, ..} => $name.borrow_mut(),
+                    _ => panic!("project is not an RPG Maker XP project"),
+                }
+            }
+        )+
+    };
+
+}
+
+macro_rules! shared_ref_getter {
+    ($($typ:ty, $name:ident),* $(,)?) => {
+        $(
+            #[allow(unsafe_code, dead_code)]
+            pub fn $name(&self) -> RefMut<'_, $typ> {
+                match self {
+                    Self::Unloaded => panic!("data cache unloaded"),
+                    Self::Loaded { $name, .. }
+                    | Self::LoadedVX { $name, .. }
+                    | Self::LoadedAce { $name, .. } => $name.borrow_mut(),
+                }
+            }
+        )+
+    };
+}
+
+macro_rules! vx_ref_getter {
+    ($($typ:ty, $field:ident, $name:ident),* $(,)?) => {
+        $(
+            #[allow(unsafe_code, dead_code)]
+            pub fn $name(&self) -> RefMut<'_, $typ> {
+                match self {
+                    Self::Unloaded => panic!("data cache unloaded"),
+                    Self::LoadedVX { $field, ..} => $field.borrow_mut(),
+                    _ => panic!("project is not an RPG Maker VX project"),
                 }
             }
         )+
     };
+}
 
+macro_rules! ace_ref_getter {
+    ($($typ:ty, $field:ident, $name:ident),* $(,)?) => {
+        $(
+            #[allow(unsafe_code, dead_code)]
+            pub fn $name(&self) -> RefMut<'_, $typ> {
+                match self {
+                    Self::Unloaded => panic!("data cache unloaded"),
+                    Self::LoadedAce { $field, ..} => $field.borrow_mut(),
+                    _ => panic!("project is not an RPG Maker VX Ace project"),
+                }
+            }
+        )+
+    };
 }
 
 impl Data {
@@ -389,11 +953,8 @@ impl Data {
         rpg::Animations, animations,
         rpg::Armors, armors,
         rpg::Classes, classes,
-        rpg::CommonEvents, common_events,
         rpg::Enemies, enemies,
         rpg::Items, items,
-        rpg::MapInfos, map_infos,
-        rpg::Scripts, scripts,
         rpg::Skills, skills,
         rpg::States, states,
         rpg::System, system,
@@ -402,34 +963,435 @@ impl Data {
         rpg::Weapons, weapons,
     }
 
-    /// Load a map.
-    #[allow(clippy::panic)]
+    shared_ref_getter! {
+        rpg::CommonEvents, common_events,
+        rpg::MapInfos, map_infos,
+        rpg::Scripts, scripts,
+    }
+
+    vx_ref_getter! {
+        rmvx::Actors, actors, vx_actors,
+        rmvx::Animations, animations, vx_animations,
+        rmvx::Areas, areas, vx_areas,
+        rmvx::Armors, armors, vx_armors,
+        rmvx::Classes, classes, vx_classes,
+        rmvx::Enemies, enemies, vx_enemies,
+        rmvx::Items, items, vx_items,
+        rmvx::Skills, skills, vx_skills,
+        rmvx::States, states, vx_states,
+        rmvx::System, system, vx_system,
+        rmvx::Troops, troops, vx_troops,
+        rmvx::Weapons, weapons, vx_weapons,
+    }
+
+    ace_ref_getter! {
+        rmvxace::Actors, actors, ace_actors,
+        rmvxace::Animations, animations, ace_animations,
+        rmvxace::Armors, armors, ace_armors,
+        rmvxace::Classes, classes, ace_classes,
+        rmvxace::Enemies, enemies, ace_enemies,
+        rmvxace::Items, items, ace_items,
+        rmvxace::Skills, skills, ace_skills,
+        rmvxace::States, states, ace_states,
+        rmvxace::System, system, ace_system,
+        rmvxace::Tilesets, tilesets, ace_tilesets,
+        rmvxace::Troops, troops, ace_troops,
+        rmvxace::Weapons, weapons, ace_weapons,
+    }
+
+    /// Load a map if it isn't already loaded, and get it.
     pub fn get_or_load_map(
         &self,
         id: usize,
         filesystem: &impl luminol_filesystem::FileSystem,
         config: &luminol_config::project::Config,
-    ) -> RefMut<'_, rpg::Map> {
+    ) -> color_eyre::Result<RefMut<'_, rpg::Map>> {
+        self.load_map(id, filesystem, config)?;
+        Ok(self.get_map(id))
+    }
+
+    pub fn get_map(&self, id: usize) -> RefMut<'_, rpg::Map> {
         let maps_ref = match self {
             Self::Loaded { maps, .. } => maps.borrow_mut(),
             Self::Unloaded => panic!("project not loaded"),
+            _ => panic!("project is not an RPG Maker XP project"),
         };
-        RefMut::map(maps_ref, |maps| {
-            // FIXME
-            maps.entry(id).or_insert_with(|| {
-                let handler = data_formats::Handler::new(config.project.data_format);
-                handler
+        RefMut::map(maps_ref, |maps| maps.get_mut(&id).expect("map not loaded"))
+    }
+
+    /// Remove a map from the cache, discarding any unsaved changes to it.
+    /// This does not touch the map's file.
+    pub fn remove_map(&self, id: usize) -> Option<rpg::Map> {
+        match self {
+            Self::Loaded { maps, .. } => maps.borrow_mut().remove(&id),
+            Self::Unloaded => panic!("project not loaded"),
+            _ => panic!("project is not an RPG Maker XP project"),
+        }
+    }
+
+    /// Add a new map to the cache. It is written to disk the next time the project is saved.
+    pub fn insert_map(&self, id: usize, mut map: rpg::Map) {
+        match self {
+            Self::Loaded {
+                maps, deleted_maps, ..
+            } => {
+                map.modified = true;
+                deleted_maps.borrow_mut().remove(&id);
+                maps.borrow_mut().insert(id, map);
+            }
+            Self::Unloaded => panic!("project not loaded"),
+            _ => panic!("project is not an RPG Maker XP project"),
+        }
+    }
+
+    /// Remove a map from the cache and delete its file the next time the project is saved.
+    pub fn delete_map(&self, id: usize) {
+        match self {
+            Self::Loaded {
+                maps, deleted_maps, ..
+            } => {
+                maps.borrow_mut().remove(&id);
+                deleted_maps.borrow_mut().insert(id);
+            }
+            Self::Unloaded => panic!("project not loaded"),
+            _ => panic!("project is not an RPG Maker XP project"),
+        }
+    }
+
+    /// Load a map into the cache if it isn't already loaded, regardless of the project's
+    /// RPG Maker version.
+    pub fn load_map(
+        &self,
+        id: usize,
+        filesystem: &impl luminol_filesystem::FileSystem,
+        config: &luminol_config::project::Config,
+    ) -> color_eyre::Result<()> {
+        fn load_into<T>(
+            maps: &RefCell<HashMap<usize, T>>,
+            id: usize,
+            filesystem: &impl luminol_filesystem::FileSystem,
+            config: &luminol_config::project::Config,
+        ) -> color_eyre::Result<()>
+        where
+            T: for<'de> alox_48::Deserialize<'de> + alox_48::Serialize,
+            T: ::serde::de::DeserializeOwned,
+            T: luminol_data::HasExtraIvars,
+        {
+            let mut maps = maps.borrow_mut();
+            if let std::collections::hash_map::Entry::Vacant(e) = maps.entry(id) {
+                let handler = data_formats::Handler::for_project(&config.project);
+                let map = handler
                     .read_data(filesystem, format!("Map{id:0>3}"))
-                    .expect("failed to load map")
-            })
-        })
+                    .wrap_err_with(|| format!("While loading map {id:0>3}"))?;
+                e.insert(map);
+            }
+            Ok(())
+        }
+
+        match self {
+            Self::Unloaded => panic!("project not loaded"),
+            Self::Loaded { maps, .. } => load_into(maps, id, filesystem, config),
+            Self::LoadedVX { maps, .. } => load_into(maps, id, filesystem, config),
+            Self::LoadedAce { maps, .. } => load_into(maps, id, filesystem, config),
+        }
     }
 
-    pub fn get_map(&self, id: usize) -> RefMut<'_, rpg::Map> {
-        let maps_ref = match self {
-            Self::Loaded { maps, .. } => maps.borrow_mut(),
+    /// Works out which databases and maps are stored at `paths`.
+    pub fn entries_at(
+        &mut self,
+        config: &luminol_config::project::Config,
+        paths: &[camino::Utf8PathBuf],
+    ) -> Vec<Entry> {
+        let handler = data_formats::Handler::for_project(&config.project);
+        fn lowercase(path: &camino::Utf8Path) -> String {
+            path.as_str().to_lowercase()
+        }
+
+        let mut found = Vec::new();
+        for (entry, _) in recovery::entries(self, Some(&[] as &[usize]), false) {
+            let Entry::Database(name) = &entry else {
+                continue;
+            };
+            let filename = if name == "Scripts" {
+                config.project.scripts_path.as_str()
+            } else {
+                name.as_str()
+            };
+            let collection_path = lowercase(&handler.collection_path_for(filename));
+            if paths.iter().any(|path| {
+                let path = lowercase(path);
+                // in the split data format, databases are folders
+                path == collection_path || path.starts_with(&format!("{collection_path}/"))
+            }) {
+                found.push(entry);
+            }
+        }
+
+        for path in paths {
+            let map_id = path.file_stem().and_then(|stem| {
+                let id = stem
+                    .strip_prefix("Map")
+                    .or_else(|| stem.strip_prefix("map"))?;
+                let id = id.parse().ok()?;
+                (lowercase(path) == lowercase(&handler.path_for(stem))).then_some(id)
+            });
+            if let Some(id) = map_id {
+                let entry = Entry::Map(id);
+                if !found.contains(&entry) {
+                    found.push(entry);
+                }
+            }
+        }
+
+        found
+    }
+
+    /// Returns true if a database or map has unsaved changes.
+    /// Maps that aren't in the cache have no unsaved changes.
+    pub fn is_modified(&mut self, entry: &Entry) -> bool {
+        let map_ids: Vec<_> = entry.map_id().into_iter().collect();
+        recovery::entries(self, Some(&map_ids), false)
+            .into_iter()
+            .find(|(e, _)| e == entry)
+            .is_some_and(|(_, mut recoverable)| recoverable.is_modified())
+    }
+
+    /// Reads a database or map from disk again, replacing what is in the cache.
+    /// Maps are only read again if they are in the cache.
+    pub fn reload(
+        &mut self,
+        entry: &Entry,
+        filesystem: &impl luminol_filesystem::FileSystem,
+        config: &luminol_config::project::Config,
+    ) -> color_eyre::Result<()> {
+        let handler = data_formats::Handler::for_project(&config.project);
+
+        let name = match entry {
+            Entry::Database(name) => name.as_str(),
+            Entry::Map(id) => {
+                let was_loaded = match self {
+                    Self::Unloaded => panic!("project not loaded"),
+                    Self::Loaded { maps, .. } => maps.get_mut().remove(id).is_some(),
+                    Self::LoadedVX { maps, .. } => maps.get_mut().remove(id).is_some(),
+                    Self::LoadedAce { maps, .. } => maps.get_mut().remove(id).is_some(),
+                };
+                if was_loaded && filesystem.exists(handler.path_for(format!("Map{id:0>3}")))? {
+                    self.load_map(*id, filesystem, config)?;
+                }
+                return Ok(());
+            }
+        };
+
+        self.reload_database(name, filesystem, config)?;
+        if let Some(load_errors) = self.load_errors_mut() {
+            load_errors.remove(name);
+        }
+        Ok(())
+    }
+
+    fn reload_database(
+        &mut self,
+        name: &str,
+        filesystem: &impl luminol_filesystem::FileSystem,
+        config: &luminol_config::project::Config,
+    ) -> color_eyre::Result<()> {
+        let handler = data_formats::Handler::for_project(&config.project);
+
+        match self {
             Self::Unloaded => panic!("project not loaded"),
+            Self::Loaded {
+                actors,
+                animations,
+                armors,
+                classes,
+                common_events,
+                enemies,
+                items,
+                map_infos,
+                scripts,
+                skills,
+                states,
+                system,
+                tilesets,
+                troops,
+                weapons,
+                ..
+            } => {
+                reload!(filesystem, name, Actors, actors, handler);
+                reload!(filesystem, name, Animations, animations, handler);
+                reload!(filesystem, name, Armors, armors, handler);
+                reload!(filesystem, name, Classes, classes, handler);
+                reload!(filesystem, name, CommonEvents, common_events, handler);
+                reload!(filesystem, name, Enemies, enemies, handler);
+                reload!(filesystem, name, Items, items, handler);
+                reload!(filesystem, name, Skills, skills, handler);
+                reload!(filesystem, name, States, states, handler);
+                reload!(filesystem, name, Tilesets, tilesets, handler);
+                reload!(filesystem, name, Troops, troops, handler);
+                reload!(filesystem, name, Weapons, weapons, handler);
+                if name == "System" {
+                    *system.get_mut() = handler
+                        .read_data(filesystem, "System")
+                        .wrap_err("While reloading System")?;
+                    return Ok(());
+                }
+                Self::reload_shared(name, filesystem, config, handler, map_infos, scripts)
+            }
+            Self::LoadedVX {
+                actors,
+                animations,
+                areas,
+                armors,
+                classes,
+                common_events,
+                enemies,
+                items,
+                map_infos,
+                scripts,
+                skills,
+                states,
+                system,
+                troops,
+                weapons,
+                ..
+            } => {
+                reload!(filesystem, name, Actors, actors, handler);
+                reload!(filesystem, name, Animations, animations, handler);
+                reload!(filesystem, name, Areas, areas, handler);
+                reload!(filesystem, name, Armors, armors, handler);
+                reload!(filesystem, name, Classes, classes, handler);
+                reload!(filesystem, name, CommonEvents, common_events, handler);
+                reload!(filesystem, name, Enemies, enemies, handler);
+                reload!(filesystem, name, Items, items, handler);
+                reload!(filesystem, name, Skills, skills, handler);
+                reload!(filesystem, name, States, states, handler);
+                reload!(filesystem, name, Troops, troops, handler);
+                reload!(filesystem, name, Weapons, weapons, handler);
+                if name == "System" {
+                    *system.get_mut() = handler
+                        .read_data(filesystem, "System")
+                        .wrap_err("While reloading System")?;
+                    return Ok(());
+                }
+                Self::reload_shared(name, filesystem, config, handler, map_infos, scripts)
+            }
+            Self::LoadedAce {
+                actors,
+                animations,
+                armors,
+                classes,
+                common_events,
+                enemies,
+                items,
+                map_infos,
+                scripts,
+                skills,
+                states,
+                system,
+                tilesets,
+                troops,
+                weapons,
+                ..
+            } => {
+                reload!(filesystem, name, Actors, actors, handler);
+                reload!(filesystem, name, Animations, animations, handler);
+                reload!(filesystem, name, Armors, armors, handler);
+                reload!(filesystem, name, Classes, classes, handler);
+                reload!(filesystem, name, CommonEvents, common_events, handler);
+                reload!(filesystem, name, Enemies, enemies, handler);
+                reload!(filesystem, name, Items, items, handler);
+                reload!(filesystem, name, Skills, skills, handler);
+                reload!(filesystem, name, States, states, handler);
+                reload!(filesystem, name, Tilesets, tilesets, handler);
+                reload!(filesystem, name, Troops, troops, handler);
+                reload!(filesystem, name, Weapons, weapons, handler);
+                if name == "System" {
+                    *system.get_mut() = handler
+                        .read_data(filesystem, "System")
+                        .wrap_err("While reloading System")?;
+                    return Ok(());
+                }
+                Self::reload_shared(name, filesystem, config, handler, map_infos, scripts)
+            }
+        }
+    }
+
+    /// Reloads the databases that have the same layout in every RPG Maker version.
+    fn reload_shared(
+        name: &str,
+        filesystem: &impl luminol_filesystem::FileSystem,
+        config: &luminol_config::project::Config,
+        handler: data_formats::Handler,
+        map_infos: &mut RefCell<rpg::MapInfos>,
+        scripts: &mut RefCell<rpg::Scripts>,
+    ) -> color_eyre::Result<()> {
+        match name {
+            "MapInfos" => {
+                *map_infos.get_mut() = rpg::MapInfos {
+                    data: handler
+                        .read_map_infos(filesystem)
+                        .wrap_err("While reloading MapInfos")?,
+                    modified: false,
+                };
+            }
+            "Scripts" => {
+                *scripts.get_mut() = rpg::Scripts {
+                    data: handler
+                        .read_scripts(filesystem, &config.project.scripts_path)
+                        .wrap_err("While reloading Scripts")?,
+                    modified: false,
+                };
+            }
+            _ => color_eyre::eyre::bail!("{name} is not a database"),
+        }
+        Ok(())
+    }
+
+    /// Load a map from an RPG Maker VX project.
+    pub fn get_or_load_vx_map(
+        &self,
+        id: usize,
+        filesystem: &impl luminol_filesystem::FileSystem,
+        config: &luminol_config::project::Config,
+    ) -> color_eyre::Result<RefMut<'_, rmvx::Map>> {
+        let mut maps_ref = match self {
+            Self::LoadedVX { maps, .. } => maps.borrow_mut(),
+            Self::Unloaded => panic!("project not loaded"),
+            _ => panic!("project is not an RPG Maker VX project"),
         };
-        RefMut::map(maps_ref, |maps| maps.get_mut(&id).expect("map not loaded"))
+        if let std::collections::hash_map::Entry::Vacant(e) = maps_ref.entry(id) {
+            let handler = data_formats::Handler::for_project(&config.project);
+            let map = handler
+                .read_data(filesystem, format!("Map{id:0>3}"))
+                .wrap_err_with(|| format!("While loading map {id:0>3}"))?;
+            e.insert(map);
+        }
+        Ok(RefMut::map(maps_ref, |maps| {
+            maps.get_mut(&id).expect("map not loaded")
+        }))
+    }
+
+    /// Load a map from an RPG Maker VX Ace project.
+    pub fn get_or_load_ace_map(
+        &self,
+        id: usize,
+        filesystem: &impl luminol_filesystem::FileSystem,
+        config: &luminol_config::project::Config,
+    ) -> color_eyre::Result<RefMut<'_, rmvxace::Map>> {
+        let mut maps_ref = match self {
+            Self::LoadedAce { maps, .. } => maps.borrow_mut(),
+            Self::Unloaded => panic!("project not loaded"),
+            _ => panic!("project is not an RPG Maker VX Ace project"),
+        };
+        if let std::collections::hash_map::Entry::Vacant(e) = maps_ref.entry(id) {
+            let handler = data_formats::Handler::for_project(&config.project);
+            let map = handler
+                .read_data(filesystem, format!("Map{id:0>3}"))
+                .wrap_err_with(|| format!("While loading map {id:0>3}"))?;
+            e.insert(map);
+        }
+        Ok(RefMut::map(maps_ref, |maps| {
+            maps.get_mut(&id).expect("map not loaded")
+        }))
     }
 }
@@ -0,0 +1,214 @@
+// Copyright (C) 2024 Melody Madeline Lyons
+//
+// This file is part of Luminol.
+//
+// Luminol is free software: you can redistribute it and/or modify
+// it under the terms of the GNU General Public License as published by
+// the Free Software Foundation, either version 3 of the License, or
+// (at your option) any later version.
+//
+// Luminol is distributed in the hope that it will be useful,
+// but WITHOUT ANY WARRANTY; without even the implied warranty of
+// MERCHANTABILITY or FITNESS FOR A PARTICULAR PURPOSE.  See the
+// GNU General Public License for more details.
+//
+// You should have received a copy of the GNU General Public License
+// along with Luminol.  If not, see <http://www.gnu.org/licenses/>.
+//
+//     Additional permission under GNU GPL version 3 section 7
+//
+// If you modify this Program, or any covered work, by linking or combining
+// it with Steamworks API by Valve Corporation, containing parts covered by
+// terms of the Steamworks API by Valve Corporation, the licensors of this
+// Program grant you additional permission to convey the resulting work.
+
+//! Rotating, timestamped snapshots of a project's data, kept in `.luminol/backups`.
+//!
+//! A snapshot is taken before every save that changes something, containing the `Data` folder
+//! and project config as they were before the save.
+
+use color_eyre::eyre::{bail, WrapErr};
+use luminol_filesystem::FileSystem;
+
+pub const BACKUP_DIR: &str = ".luminol/backups";
+/// Where a backup's data is copied to before being swapped in when restoring it.
+const RESTORE_STAGING_DIR: &str = ".luminol/restore";
+
+/// A snapshot of a project's data.
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub struct Backup {
+    /// The name of the backup's folder, which is the UTC time it was taken at.
+    pub name: String,
+}
+
+impl Backup {
+    pub fn path(&self) -> camino::Utf8PathBuf {
+        camino::Utf8Path::new(BACKUP_DIR).join(&self.name)
+    }
+}
+
+/// Lists every backup of the project, newest first.
+pub fn list(filesystem: &impl FileSystem) -> color_eyre::Result<Vec<Backup>> {
+    if !filesystem.exists(BACKUP_DIR)? {
+        return Ok(Vec::new());
+    }
+
+    let mut backups: Vec<_> = filesystem
+        .read_dir(BACKUP_DIR)?
+        .into_iter()
+        .filter(|entry| !entry.metadata.is_file)
+        .filter_map(|entry| {
+            entry.path.file_name().map(|name| Backup {
+                name: name.to_string(),
+            })
+        })
+        .collect();
+    // the names are timestamps, so sorting them sorts the backups by age
+    backups.sort_unstable_by(|a, b| b.name.cmp(&a.name));
+    Ok(backups)
+}
+
+/// Takes a snapshot of the project's current data, then deletes the oldest backups so that at most
+/// `keep` remain.
+pub fn create(filesystem: &impl FileSystem, keep: usize) -> color_eyre::Result<Backup> {
+    let backup = snapshot(filesystem)?;
+
+    for old_backup in list(filesystem)?.into_iter().skip(keep) {
+        delete(filesystem, &old_backup)?;
+    }
+
+    Ok(backup)
+}
+
+fn snapshot(filesystem: &impl FileSystem) -> color_eyre::Result<Backup> {
+    let timestamp = timestamp();
+    let mut name = timestamp.clone();
+    let mut suffix = 1;
+    while filesystem.exists(camino::Utf8Path::new(BACKUP_DIR).join(&name))? {
+        suffix += 1;
+        name = format!("{timestamp} ({suffix})");
+    }
+    let backup = Backup { name };
+    let path = backup.path();
+
+    let result = (|| -> color_eyre::Result<()> {
+        copy_recursive(filesystem, "Data", path.join("Data"))?;
+        if filesystem.exists(".luminol/config")? {
+            copy_recursive(filesystem, ".luminol/config", path.join("config"))?;
+        }
+        Ok(())
+    })();
+    if let Err(error) = result {
+        // don't leave an incomplete backup around where it could be restored
+        let _ = filesystem.remove_dir(&path);
+        return Err(error.wrap_err("While creating a backup"));
+    }
+
+    Ok(backup)
+}
+
+/// Replaces the project's `Data` folder with the one in `backup`.
+///
+/// The current data is backed up first so that restoring can be undone. Backups made while the
+/// project was in a different data format can't be restored, since the project config isn't
+/// restored with them.
+pub fn restore(
+    filesystem: &impl FileSystem,
+    config: &luminol_config::project::Config,
+    backup: &Backup,
+) -> color_eyre::Result<()> {
+    let path = backup.path();
+    let c = format!("While restoring the backup from {}", backup.name);
+
+    let backup_config: luminol_config::project::Project = ron::de::from_bytes(
+        &filesystem
+            .read(path.join("config"))
+            .wrap_err_with(|| c.clone())?,
+    )
+    .wrap_err_with(|| c.clone())?;
+    if backup_config.data_format != config.project.data_format {
+        bail!(
+            "The backup from {} is in the {} data format, but the project is in {}",
+            backup.name,
+            backup_config.data_format,
+            config.project.data_format
+        );
+    }
+    // the data would be decoded with the wrong encoding after restoring it
+    if config.project.data_format == luminol_config::DataFormat::Marshal
+        && backup_config.encoding != config.project.encoding
+    {
+        bail!(
+            "The backup from {} has text encoded as {}, but the project uses {}",
+            backup.name,
+            backup_config.encoding,
+            config.project.encoding
+        );
+    }
+
+    // the backups aren't rotated here, since that could delete the one being restored
+    snapshot(filesystem).wrap_err_with(|| c.clone())?;
+
+    let mut transaction = super::transaction::Transaction::new(filesystem, RESTORE_STAGING_DIR)
+        .wrap_err_with(|| c.clone())?;
+    copy_recursive(
+        filesystem,
+        path.join("Data"),
+        transaction.staging_path("Data"),
+    )
+    .wrap_err_with(|| c.clone())?;
+    transaction.stage("Data");
+    transaction.commit(filesystem).wrap_err(c)
+}
+
+/// Deletes a backup.
+pub fn delete(filesystem: &impl FileSystem, backup: &Backup) -> color_eyre::Result<()> {
+    filesystem
+        .remove_dir(backup.path())
+        .wrap_err_with(|| format!("While deleting the backup from {}", backup.name))
+}
+
+fn copy_recursive(
+    filesystem: &impl FileSystem,
+    from: impl AsRef<camino::Utf8Path>,
+    to: impl AsRef<camino::Utf8Path>,
+) -> color_eyre::Result<()> {
+    let (from, to) = (from.as_ref(), to.as_ref());
+
+    if filesystem.metadata(from)?.is_file {
+        if let Some(parent) = to.parent() {
+            filesystem.create_dir(parent)?;
+        }
+        filesystem
+            .write(to, filesystem.read(from)?)
+            .wrap_err_with(|| format!("While copying {from}"))
+    } else {
+        filesystem.create_dir(to)?;
+        for entry in filesystem.read_dir(from)? {
+            let Some(name) = entry.path.file_name() else {
+                continue;
+            };
+            copy_recursive(filesystem, &entry.path, to.join(name))?;
+        }
+        Ok(())
+    }
+}
+
+/// The current UTC time, formatted so that it sorts chronologically and is a valid filename.
+fn timestamp() -> String {
+    let seconds = web_time::SystemTime::now()
+        .duration_since(web_time::UNIX_EPOCH)
+        .map_or(0, |duration| duration.as_secs());
+
+    let time = time::OffsetDateTime::from_unix_timestamp(seconds as i64)
+        .unwrap_or(time::OffsetDateTime::UNIX_EPOCH);
+    format!(
+        "{:04}-{:02}-{:02} {:02}-{:02}-{:02}",
+        time.year(),
+        time.month() as u8,
+        time.day(),
+        time.hour(),
+        time.minute(),
+        time.second()
+    )
+}
@@ -0,0 +1,625 @@
+// Copyright (C) 2024 Melody Madeline Lyons
+//
+// This file is part of Luminol.
+//
+// Luminol is free software: you can redistribute it and/or modify
+// it under the terms of the GNU General Public License as published by
+// the Free Software Foundation, either version 3 of the License, or
+// (at your option) any later version.
+//
+// Luminol is distributed in the hope that it will be useful,
+// but WITHOUT ANY WARRANTY; without even the implied warranty of
+// MERCHANTABILITY or FITNESS FOR A PARTICULAR PURPOSE.  See the
+// GNU General Public License for more details.
+//
+// You should have received a copy of the GNU General Public License
+// along with Luminol.  If not, see <http://www.gnu.org/licenses/>.
+//
+//     Additional permission under GNU GPL version 3 section 7
+//
+// If you modify this Program, or any covered work, by linking or combining
+// it with Steamworks API by Valve Corporation, containing parts covered by
+// terms of the Steamworks API by Valve Corporation, the licensors of this
+// Program grant you additional permission to convey the resulting work.
+
+//! Converting every data file of a project from one [`luminol_config::DataFormat`] to another.
+//!
+//! A conversion happens in two phases. While staging, each data file is read in the old format,
+//! written to a staging folder in the new format and read back to check that nothing was lost.
+//! Nothing in the project is touched until every file has been staged, at which point
+//! [`Conversion::commit`] moves the staged files and the new project config into place together.
+//! If anything fails (or the conversion is cancelled) the project is left exactly as it was.
+
+use color_eyre::eyre::{eyre, WrapErr};
+use luminol_data::{rmvx, rmvxace, rpg};
+use luminol_filesystem::FileSystem;
+
+use super::data_formats::Handler;
+
+/// Where staged files are written while converting.
+const STAGING_DIR: &str = ".luminol/convert";
+/// The data folder inside of the staging folder.
+const STAGING_DATA_DIR: &str = ".luminol/convert/Data";
+/// Where files that would be overwritten are moved while committing.
+const BACKUP_DIR: &str = ".luminol/convert/backup";
+/// Where old-format files are moved to when they are archived.
+pub const ARCHIVE_DIR: &str = "OldData";
+
+/// What to do with the data files in the old format once a conversion has been committed.
+#[derive(Clone, Copy, Debug, Default, PartialEq, Eq)]
+#[derive(strum::EnumIter, strum::Display)]
+pub enum OldFiles {
+    /// Leave them alongside the converted files.
+    #[default]
+    #[strum(to_string = "Keep")]
+    Keep,
+    /// Delete them.
+    #[strum(to_string = "Delete")]
+    Delete,
+    /// Move them into the `OldData` folder.
+    #[strum(to_string = "Move to OldData")]
+    Archive,
+}
+
+type ConvertFn<F> = fn(Handler, Handler, &F, &str) -> color_eyre::Result<()>;
+
+struct Entry<F> {
+    filename: String,
+    /// Whether this is a collection, which the split data format stores as a directory.
+    collection: bool,
+    convert: ConvertFn<F>,
+}
+
+/// An in-progress conversion of a project's data files.
+pub struct Conversion<F> {
+    from: Handler,
+    to: Handler,
+    data_format: luminol_config::DataFormat,
+    entries: Vec<Entry<F>>,
+    staged: usize,
+}
+
+impl<F> Conversion<F>
+where
+    F: FileSystem,
+{
+    /// Prepare a conversion of the project to `data_format`.
+    /// This reads MapInfos to find out which maps need converting but doesn't write anything.
+    pub fn new(
+        filesystem: &F,
+        config: &luminol_config::project::Config,
+        data_format: luminol_config::DataFormat,
+    ) -> color_eyre::Result<Self> {
+        let from = Handler::for_project(&config.project);
+        let to = Handler::new(data_format, config.project.editor_ver)
+            .with_encoding(config.project.encoding);
+
+        let map_infos = from
+            .read_map_infos(filesystem)
+            .wrap_err("While reading MapInfos")?;
+        let mut map_ids: Vec<_> = map_infos.keys().copied().collect();
+        map_ids.sort_unstable();
+
+        macro_rules! entries {
+            ($($filename:expr => $convert:expr),* $(,)?) => {
+                vec![$(Entry {
+                    filename: $filename.to_string(),
+                    collection: true,
+                    convert: $convert,
+                }),*]
+            };
+        }
+
+        let (mut entries, convert_system, convert_map): (_, ConvertFn<F>, ConvertFn<F>) =
+            match config.project.editor_ver {
+                luminol_config::RMVer::XP => (
+                    entries! {
+                        "Actors" => convert_nil_padded::<F, rpg::Actor>,
+                        "Animations" => convert_nil_padded::<F, rpg::Animation>,
+                        "Armors" => convert_nil_padded::<F, rpg::Armor>,
+                        "Classes" => convert_nil_padded::<F, rpg::Class>,
+                        "CommonEvents" => convert_nil_padded::<F, rpg::CommonEvent>,
+                        "Enemies" => convert_nil_padded::<F, rpg::Enemy>,
+                        "Items" => convert_nil_padded::<F, rpg::Item>,
+                        "Skills" => convert_nil_padded::<F, rpg::Skill>,
+                        "States" => convert_nil_padded::<F, rpg::State>,
+                        "Tilesets" => convert_nil_padded::<F, rpg::Tileset>,
+                        "Troops" => convert_nil_padded::<F, rpg::Troop>,
+                        "Weapons" => convert_nil_padded::<F, rpg::Weapon>,
+                    },
+                    convert_regular::<F, rpg::System>,
+                    convert_regular::<F, rpg::Map>,
+                ),
+                luminol_config::RMVer::VX => (
+                    entries! {
+                        "Actors" => convert_nil_padded::<F, rmvx::Actor>,
+                        "Animations" => convert_nil_padded::<F, rmvx::Animation>,
+                        "Areas" => convert_nil_padded::<F, rmvx::Area>,
+                        "Armors" => convert_nil_padded::<F, rmvx::Armor>,
+                        "Classes" => convert_nil_padded::<F, rmvx::Class>,
+                        "CommonEvents" => convert_nil_padded::<F, rpg::CommonEvent>,
+                        "Enemies" => convert_nil_padded::<F, rmvx::Enemy>,
+                        "Items" => convert_nil_padded::<F, rmvx::Item>,
+                        "Skills" => convert_nil_padded::<F, rmvx::Skill>,
+                        "States" => convert_nil_padded::<F, rmvx::State>,
+                        "Troops" => convert_nil_padded::<F, rmvx::Troop>,
+                        "Weapons" => convert_nil_padded::<F, rmvx::Weapon>,
+                    },
+                    convert_regular::<F, rmvx::System>,
+                    convert_regular::<F, rmvx::Map>,
+                ),
+                luminol_config::RMVer::Ace => (
+                    entries! {
+                        "Actors" => convert_nil_padded::<F, rmvxace::Actor>,
+                        "Animations" => convert_nil_padded::<F, rmvxace::Animation>,
+                        "Armors" => convert_nil_padded::<F, rmvxace::Armor>,
+                        "Classes" => convert_nil_padded::<F, rmvxace::Class>,
+                        "CommonEvents" => convert_nil_padded::<F, rpg::CommonEvent>,
+                        "Enemies" => convert_nil_padded::<F, rmvxace::Enemy>,
+                        "Items" => convert_nil_padded::<F, rmvxace::Item>,
+                        "Skills" => convert_nil_padded::<F, rmvxace::Skill>,
+                        "States" => convert_nil_padded::<F, rmvxace::State>,
+                        "Tilesets" => convert_nil_padded::<F, rmvxace::Tileset>,
+                        "Troops" => convert_nil_padded::<F, rmvxace::Troop>,
+                        "Weapons" => convert_nil_padded::<F, rmvxace::Weapon>,
+                    },
+                    convert_regular::<F, rmvxace::System>,
+                    convert_regular::<F, rmvxace::Map>,
+                ),
+            };
+        entries.extend(entries! {
+            config.project.scripts_path => convert_scripts::<F>,
+            "MapInfos" => convert_map_infos::<F>,
+        });
+        entries.push(Entry {
+            filename: "System".to_string(),
+            collection: false,
+            convert: convert_system,
+        });
+        entries.extend(map_ids.into_iter().map(|id| Entry {
+            filename: format!("Map{id:0>3}"),
+            collection: false,
+            convert: convert_map,
+        }));
+
+        Ok(Self {
+            from,
+            to,
+            data_format,
+            entries,
+            staged: 0,
+        })
+    }
+
+    /// Write strings in Marshal data in `encoding` instead of the project's current encoding.
+    /// This allows converting a project to UTF-8 (or back) without changing its data format.
+    pub fn with_encoding(mut self, encoding: luminol_config::StringEncoding) -> Self {
+        self.to = self.to.with_encoding(encoding);
+        self
+    }
+
+    /// The number of files staged so far and the total number of files to convert.
+    pub fn progress(&self) -> (usize, usize) {
+        (self.staged, self.entries.len())
+    }
+
+    /// The path of the file that the next call to [`Conversion::step`] will convert.
+    pub fn current_file(&self) -> Option<camino::Utf8PathBuf> {
+        self.entries
+            .get(self.staged)
+            .map(|entry| Self::path(self.from, entry))
+    }
+
+    /// Returns true once every file has been staged.
+    pub fn is_staged(&self) -> bool {
+        self.staged == self.entries.len()
+    }
+
+    /// Convert and verify the next file, writing it into the staging folder.
+    /// Returns true once every file has been staged.
+    pub fn step(&mut self, filesystem: &F) -> color_eyre::Result<bool> {
+        let Some(entry) = self.entries.get(self.staged) else {
+            return Ok(true);
+        };
+
+        let c = format!("While converting {}", Self::path(self.from, entry));
+
+        if self.staged == 0 {
+            filesystem
+                .create_dir(STAGING_DATA_DIR)
+                .wrap_err_with(|| c.clone())?;
+        }
+
+        (entry.convert)(self.from, self.staging(), filesystem, &entry.filename)
+            .wrap_err_with(|| c.clone())?;
+
+        self.staged += 1;
+        Ok(self.is_staged())
+    }
+
+    /// Stage every remaining file at once.
+    pub fn stage_all(&mut self, filesystem: &F) -> color_eyre::Result<()> {
+        while !self.step(filesystem)? {}
+        Ok(())
+    }
+
+    /// Discard the conversion, removing anything that was staged.
+    /// The project itself is never modified before [`Conversion::commit`], so this is all that's
+    /// needed to cancel a conversion or clean up after a failed one.
+    pub fn rollback(self, filesystem: &F) -> color_eyre::Result<()> {
+        if filesystem.exists(STAGING_DIR)? {
+            filesystem
+                .remove_dir(STAGING_DIR)
+                .wrap_err("While removing staged files")?;
+        }
+        Ok(())
+    }
+
+    /// Move every staged file into place and switch the project config over to the new format.
+    ///
+    /// If anything fails, the files that were already moved are put back and the error is returned.
+    /// Either way, the staging folder is removed.
+    pub fn commit(
+        self,
+        filesystem: &F,
+        config: &mut luminol_config::project::Config,
+        old_files: OldFiles,
+    ) -> color_eyre::Result<()> {
+        if !self.is_staged() {
+            let result = Err(eyre!("Not every file has been converted yet"));
+            self.rollback(filesystem)?;
+            return result;
+        }
+
+        let mut project = config.project.clone();
+        project.data_format = self.data_format;
+        project.encoding = self.to.encoding();
+
+        let mut journal = Vec::new();
+        let result = self.commit_inner(filesystem, &project, &mut journal);
+        if let Err(error) = result {
+            let undo_result = Self::undo(filesystem, journal);
+            self.rollback(filesystem)?;
+            return match undo_result {
+                Ok(()) => Err(error),
+                Err(undo_error) => Err(error.wrap_err(format!(
+                    "Additionally, rolling back the conversion failed: {undo_error:?}"
+                ))),
+            };
+        }
+
+        config.project = project;
+
+        // the conversion has been committed at this point, so errors past here can't be rolled
+        // back. they're still reported, but the project is in the new format regardless.
+        let result = self.dispose_old_files(filesystem, old_files);
+        self.rollback(filesystem)?;
+        result
+    }
+
+    fn commit_inner(
+        &self,
+        filesystem: &F,
+        project: &luminol_config::project::Project,
+        journal: &mut Vec<Step>,
+    ) -> color_eyre::Result<()> {
+        filesystem.create_dir(BACKUP_DIR)?;
+
+        for entry in self.entries.iter() {
+            let path = Self::path(self.to, entry);
+
+            // converting between two formats with the same extension (like pretty and compact
+            // RON) overwrites the old file, so it needs to be kept around until we're done.
+            if filesystem.exists(&path)? {
+                let backup_path = self.backup_path(entry);
+                if let Some(parent) = backup_path.parent() {
+                    filesystem.create_dir(parent)?;
+                }
+                filesystem
+                    .rename(&path, &backup_path)
+                    .wrap_err_with(|| format!("While backing up {path}"))?;
+                journal.push(Step::BackedUp {
+                    path: path.clone(),
+                    backup_path,
+                });
+            }
+
+            filesystem
+                .rename(Self::path(self.staging(), entry), &path)
+                .wrap_err_with(|| format!("While moving {path} into place"))?;
+            journal.push(Step::Moved(path));
+        }
+
+        let pretty_config = ron::ser::PrettyConfig::new()
+            .struct_names(true)
+            .enumerate_arrays(true);
+        let project_config = ron::ser::to_string_pretty(project, pretty_config)
+            .wrap_err("While serializing .luminol/config")?;
+        let old_project_config = filesystem
+            .read(".luminol/config")
+            .wrap_err("While reading .luminol/config")?;
+        journal.push(Step::WroteConfig(old_project_config));
+        filesystem
+            .write(".luminol/config", project_config)
+            .wrap_err("While writing .luminol/config")?;
+
+        Ok(())
+    }
+
+    fn undo(filesystem: &F, journal: Vec<Step>) -> color_eyre::Result<()> {
+        for step in journal.into_iter().rev() {
+            match step {
+                Step::BackedUp { path, backup_path } => filesystem.rename(backup_path, path)?,
+                Step::Moved(path) => remove_path(filesystem, &path)?,
+                Step::WroteConfig(old_project_config) => {
+                    filesystem.write(".luminol/config", old_project_config)?
+                }
+            }
+        }
+        Ok(())
+    }
+
+    fn dispose_old_files(&self, filesystem: &F, old_files: OldFiles) -> color_eyre::Result<()> {
+        if old_files == OldFiles::Keep {
+            return Ok(());
+        }
+        if old_files == OldFiles::Archive {
+            filesystem.create_dir(camino::Utf8Path::new(ARCHIVE_DIR).join("Data"))?;
+        }
+
+        for entry in self.entries.iter() {
+            let old_path = Self::path(self.from, entry);
+            let path = if old_path == Self::path(self.to, entry) {
+                self.backup_path(entry)
+            } else {
+                old_path.clone()
+            };
+            if !filesystem.exists(&path)? {
+                continue;
+            }
+
+            match old_files {
+                OldFiles::Keep => unreachable!(),
+                OldFiles::Delete => remove_path(filesystem, &path)
+                    .wrap_err_with(|| format!("While deleting {old_path}"))?,
+                OldFiles::Archive => filesystem
+                    .rename(&path, camino::Utf8Path::new(ARCHIVE_DIR).join(&old_path))
+                    .wrap_err_with(|| format!("While archiving {old_path}"))?,
+            }
+        }
+
+        Ok(())
+    }
+
+    /// The handler that writes into the staging folder.
+    fn staging(&self) -> Handler {
+        self.to.with_data_dir(STAGING_DATA_DIR)
+    }
+
+    fn path(handler: Handler, entry: &Entry<F>) -> camino::Utf8PathBuf {
+        if entry.collection {
+            handler.collection_path_for(&entry.filename)
+        } else {
+            handler.path_for(&entry.filename)
+        }
+    }
+
+    fn backup_path(&self, entry: &Entry<F>) -> camino::Utf8PathBuf {
+        camino::Utf8Path::new(BACKUP_DIR).join(Self::path(self.from, entry))
+    }
+}
+
+/// Something [`Conversion::commit`] did that has to be undone if a later step fails.
+enum Step {
+    BackedUp {
+        path: camino::Utf8PathBuf,
+        backup_path: camino::Utf8PathBuf,
+    },
+    Moved(camino::Utf8PathBuf),
+    WroteConfig(Vec<u8>),
+}
+
+/// Removes a file, or a directory in the case of split collections.
+fn remove_path(filesystem: &impl FileSystem, path: &camino::Utf8Path) -> color_eyre::Result<()> {
+    if filesystem.metadata(path)?.is_file {
+        filesystem.remove_file(path)
+    } else {
+        filesystem.remove_dir(path)
+    }
+}
+
+fn convert_nil_padded<F, T>(
+    from: Handler,
+    to: Handler,
+    filesystem: &F,
+    filename: &str,
+) -> color_eyre::Result<()>
+where
+    F: FileSystem,
+    T: ::serde::de::DeserializeOwned + ::serde::Serialize,
+    T: for<'de> alox_48::Deserialize<'de> + alox_48::Serialize,
+    T: luminol_data::HasExtraIvars,
+{
+    let data = from.read_nil_padded::<T>(filesystem, filename)?;
+    to.write_nil_padded(&data, filesystem, filename)?;
+
+    let round_tripped = to
+        .read_nil_padded::<T>(filesystem, filename)
+        .wrap_err("While reading back the converted data")?;
+    verify(&data, &round_tripped)
+}
+
+fn convert_regular<F, T>(
+    from: Handler,
+    to: Handler,
+    filesystem: &F,
+    filename: &str,
+) -> color_eyre::Result<()>
+where
+    F: FileSystem,
+    T: ::serde::de::DeserializeOwned + ::serde::Serialize,
+    T: for<'de> alox_48::Deserialize<'de> + alox_48::Serialize,
+    T: luminol_data::HasExtraIvars,
+{
+    let data = from.read_data::<T>(filesystem, filename)?;
+    to.write_data(&data, filesystem, filename)?;
+
+    let round_tripped = to
+        .read_data::<T>(filesystem, filename)
+        .wrap_err("While reading back the converted data")?;
+    verify(&data, &round_tripped)
+}
+
+fn convert_map_infos<F>(
+    from: Handler,
+    to: Handler,
+    filesystem: &F,
+    _filename: &str,
+) -> color_eyre::Result<()>
+where
+    F: FileSystem,
+{
+    let data = from.read_map_infos(filesystem)?;
+    to.write_map_infos(&data, filesystem)?;
+
+    let round_tripped = to
+        .read_map_infos(filesystem)
+        .wrap_err("While reading back the converted data")?;
+    verify(&data, &round_tripped)
+}
+
+fn convert_scripts<F>(
+    from: Handler,
+    to: Handler,
+    filesystem: &F,
+    filename: &str,
+) -> color_eyre::Result<()>
+where
+    F: FileSystem,
+{
+    let data = from.read_scripts(filesystem, filename)?;
+    to.write_scripts(&data, filesystem, filename)?;
+
+    let round_tripped = to
+        .read_scripts(filesystem, filename)
+        .wrap_err("While reading back the converted data")?;
+    verify(&data, &round_tripped)
+}
+
+/// Check that converted data is equal to the original.
+// Most data types don't implement PartialEq, so we compare them through serde_json's value type
+// instead. Its maps are ordered, so this also doesn't depend on HashMap iteration order.
+fn verify<T>(original: &T, round_tripped: &T) -> color_eyre::Result<()>
+where
+    T: ::serde::Serialize + ?Sized,
+{
+    let original = serde_json::to_value(original)?;
+    let round_tripped = serde_json::to_value(round_tripped)?;
+    if original != round_tripped {
+        color_eyre::eyre::bail!("The converted data is not the same as the original");
+    }
+    Ok(())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    /// An RPG Maker XP project in the Ruby Marshal format, with one entry in every database and a
+    /// single map.
+    fn project() -> (
+        tempfile::TempDir,
+        luminol_filesystem::host::FileSystem,
+        luminol_config::project::Config,
+    ) {
+        let (dir, filesystem) = crate::data_cache::test_fixtures::filesystem();
+        filesystem.create_dir("Data").unwrap();
+        filesystem.create_dir(".luminol").unwrap();
+
+        let config = luminol_config::project::Config::from_project(Default::default());
+        let project_config = ron::ser::to_string(&config.project).unwrap();
+        filesystem.write(".luminol/config", project_config).unwrap();
+
+        let handler = Handler::for_project(&config.project);
+        macro_rules! write_databases {
+            ($($filename:literal => $type:ty),* $(,)?) => {
+                $(handler
+                    .write_nil_padded(&[<$type>::default()], &filesystem, $filename)
+                    .unwrap();)*
+            };
+        }
+        write_databases! {
+            "Actors" => rpg::Actor,
+            "Animations" => rpg::Animation,
+            "Armors" => rpg::Armor,
+            "Classes" => rpg::Class,
+            "CommonEvents" => rpg::CommonEvent,
+            "Enemies" => rpg::Enemy,
+            "Items" => rpg::Item,
+            "Skills" => rpg::Skill,
+            "States" => rpg::State,
+            "Tilesets" => rpg::Tileset,
+            "Troops" => rpg::Troop,
+            "Weapons" => rpg::Weapon,
+        }
+        handler
+            .write_data(&rpg::System::default(), &filesystem, "System")
+            .unwrap();
+        handler
+            .write_data(&rpg::Map::default(), &filesystem, "Map001")
+            .unwrap();
+        let map_infos: std::collections::HashMap<_, _> =
+            [(1, rpg::MapInfo::default())].into_iter().collect();
+        handler
+            .write_data(&map_infos, &filesystem, "MapInfos")
+            .unwrap();
+        let scripts = vec![rpg::Script::new("Main", "p 'Hello'\n")];
+        handler
+            .write_data(&scripts, &filesystem, "Scripts")
+            .unwrap();
+
+        (dir, filesystem, config)
+    }
+
+    #[test]
+    fn conversion_replaces_old_files() {
+        let (_dir, filesystem, mut config) = project();
+        let data_format = luminol_config::DataFormat::Ron { pretty: true };
+
+        let mut conversion = Conversion::new(&filesystem, &config, data_format).unwrap();
+        conversion.stage_all(&filesystem).unwrap();
+        conversion
+            .commit(&filesystem, &mut config, OldFiles::Delete)
+            .unwrap();
+
+        assert_eq!(config.project.data_format, data_format);
+        let project_config = filesystem.read_to_string(".luminol/config").unwrap();
+        let project: luminol_config::project::Project = ron::from_str(&project_config).unwrap();
+        assert_eq!(project.data_format, data_format);
+
+        assert!(!filesystem.exists("Data/Actors.rxdata").unwrap());
+        assert!(filesystem.exists("Data/Actors.ron").unwrap());
+        assert!(!filesystem.exists(STAGING_DIR).unwrap());
+        let handler = Handler::for_project(&config.project);
+        let actors: Vec<rpg::Actor> = handler.read_nil_padded(&filesystem, "Actors").unwrap();
+        assert_eq!(actors.len(), 1);
+        let scripts: Vec<rpg::Script> = handler.read_data(&filesystem, "Scripts").unwrap();
+        assert_eq!(scripts[0].script_text, "p 'Hello'\n");
+    }
+
+    #[test]
+    fn failed_conversion_leaves_project_untouched() {
+        let (_dir, filesystem, config) = project();
+        filesystem.remove_file("Data/Tilesets.rxdata").unwrap();
+        let actors = filesystem.read("Data/Actors.rxdata").unwrap();
+
+        let data_format = luminol_config::DataFormat::Ron { pretty: true };
+        let mut conversion = Conversion::new(&filesystem, &config, data_format).unwrap();
+        assert!(conversion.stage_all(&filesystem).is_err());
+        conversion.rollback(&filesystem).unwrap();
+
+        assert_eq!(filesystem.read("Data/Actors.rxdata").unwrap(), actors);
+        assert!(!filesystem.exists("Data/Actors.ron").unwrap());
+        assert!(!filesystem.exists(STAGING_DIR).unwrap());
+    }
+}
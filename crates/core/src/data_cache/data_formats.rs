@@ -22,24 +22,72 @@
 // terms of the Steamworks API by Valve Corporation, the licensors of this
 // Program grant you additional permission to convey the resulting work.
 
-use std::io::Write;
+use color_eyre::eyre::WrapErr;
+use luminol_config::{DataFormat, StringEncoding};
+use luminol_data::rpg;
+
+/// The name of the file listing the scripts of a project stored in the split data format.
+pub const SCRIPTS_INDEX: &str = "_scripts.ron";
 
-use luminol_config::DataFormat;
-use luminol_filesystem::OpenFlags;
 #[derive(Clone, Copy)]
 pub struct Handler {
     format: luminol_config::DataFormat,
+    rm_ver: luminol_config::RMVer,
+    data_dir: &'static str,
+    encoding: StringEncoding,
 }
 
 impl Handler {
-    pub fn new(format: luminol_config::DataFormat) -> Self {
-        Self { format }
+    pub fn new(format: luminol_config::DataFormat, rm_ver: luminol_config::RMVer) -> Self {
+        Self {
+            format,
+            rm_ver,
+            data_dir: "Data",
+            encoding: StringEncoding::Utf8,
+        }
+    }
+
+    /// Creates a handler for the data format, editor version and string encoding of a project.
+    pub fn for_project(project: &luminol_config::project::Project) -> Self {
+        Self::new(project.data_format, project.editor_ver).with_encoding(project.encoding)
+    }
+
+    /// Returns a handler that converts strings in Marshal data from and to `encoding`.
+    /// Other formats always store strings as UTF-8.
+    pub fn with_encoding(self, encoding: StringEncoding) -> Self {
+        Self { encoding, ..self }
+    }
+
+    /// Returns a handler that reads and writes data in `data_dir` instead of `Data`.
+    pub fn with_data_dir(self, data_dir: &'static str) -> Self {
+        Self { data_dir, ..self }
     }
 
     pub fn path_for(self, filename: impl AsRef<camino::Utf8Path>) -> camino::Utf8PathBuf {
-        camino::Utf8Path::new("Data")
+        camino::Utf8Path::new(self.data_dir)
             .join(filename)
-            .with_extension(self.format.extension())
+            .with_extension(self.format.extension(self.rm_ver))
+    }
+
+    /// The path that a collection of entries (a database like `Actors`, `MapInfos` or the scripts)
+    /// is stored at. In the split data format this is a directory with one file per entry.
+    pub fn collection_path_for(
+        self,
+        filename: impl AsRef<camino::Utf8Path>,
+    ) -> camino::Utf8PathBuf {
+        match self.format {
+            DataFormat::Split => camino::Utf8Path::new(self.data_dir).join(filename),
+            _ => self.path_for(filename),
+        }
+    }
+
+    /// The format that individual files are written in.
+    /// Split data is stored as pretty-printed RON, one file per entry.
+    fn file_format(self) -> DataFormat {
+        match self.format {
+            DataFormat::Split => DataFormat::Ron { pretty: true },
+            format => format,
+        }
     }
 
     pub fn read_data<T>(
@@ -48,40 +96,33 @@ impl Handler {
         filename: impl AsRef<camino::Utf8Path>,
     ) -> color_eyre::Result<T>
     where
-        T: for<'de> alox_48::Deserialize<'de>,
+        T: for<'de> alox_48::Deserialize<'de> + alox_48::Serialize,
         T: ::serde::de::DeserializeOwned,
+        T: luminol_data::HasExtraIvars,
     {
         let data = filesystem.read(self.path_for(filename))?;
-
-        match self.format {
-            DataFormat::Marshal => {
-                let mut de = alox_48::Deserializer::new(&data)?;
-                let result = alox_48::path_to_error::deserialize(&mut de);
-
-                result.map_err(|(error, trace)| format_traced_error(error, trace))
-            }
-            DataFormat::Ron { .. } => {
-                let mut de = ron::de::Deserializer::from_bytes(&data)?;
-                serde_path_to_error::deserialize(&mut de).map_err(format_path_to_error)
-            }
-            DataFormat::Json { .. } => {
-                let mut de = serde_json::de::Deserializer::from_slice(&data);
-                serde_path_to_error::deserialize(&mut de).map_err(format_path_to_error)
-            }
-        }
+        self.read_data_from(&data)
     }
 
     pub fn read_data_from<T>(self, data: &[u8]) -> color_eyre::Result<T>
     where
-        T: for<'de> alox_48::Deserialize<'de>,
+        T: for<'de> alox_48::Deserialize<'de> + alox_48::Serialize,
         T: ::serde::de::DeserializeOwned,
+        T: luminol_data::HasExtraIvars,
     {
-        match self.format {
+        match self.file_format() {
             DataFormat::Marshal => {
-                let mut de = alox_48::Deserializer::new(data)?;
-                let result = alox_48::path_to_error::deserialize(&mut de);
+                if !T::KEEPS_EXTRA_IVARS && self.encoding == StringEncoding::Utf8 {
+                    // nothing needs the data as plain Ruby values, so it can be read directly
+                    let mut de = alox_48::Deserializer::new(data)?;
+                    return alox_48::path_to_error::deserialize(&mut de)
+                        .map_err(|(error, trace)| format_traced_error(error, trace));
+                }
 
-                result.map_err(|(error, trace)| format_traced_error(error, trace))
+                let value = self.read_marshal_value(data, false)?;
+                let mut result = deserialize_value(&value)?;
+                capture_extra_ivars(&mut result, &value)?;
+                Ok(result)
             }
             DataFormat::Ron { .. } => {
                 let mut de = ron::de::Deserializer::from_bytes(data)?;
@@ -91,6 +132,11 @@ impl Handler {
                 let mut de = serde_json::de::Deserializer::from_slice(data);
                 serde_path_to_error::deserialize(&mut de).map_err(format_path_to_error)
             }
+            DataFormat::Yaml => {
+                let de = serde_yml::Deserializer::from_slice(data);
+                serde_path_to_error::deserialize(de).map_err(format_path_to_error)
+            }
+            DataFormat::Split => unreachable!(),
         }
     }
 
@@ -103,48 +149,25 @@ impl Handler {
     where
         T: ::serde::Serialize,
         T: alox_48::Serialize,
+        T: luminol_data::HasExtraIvars,
     {
-        let mut file = filesystem.open_file(
-            self.path_for(filename),
-            OpenFlags::Create | OpenFlags::Truncate | OpenFlags::Write,
-        )?;
-
-        match self.format {
-            DataFormat::Marshal => {
-                let mut serializer = alox_48::Serializer::new();
-                alox_48::path_to_error::serialize(data, &mut serializer)
-                    .map_err(|(error, trace)| format_traced_error(error, trace))?;
-                file.write_all(&serializer.output)?;
-            }
-            DataFormat::Ron { pretty } => {
-                let config = pretty.then(|| ron::ser::PrettyConfig::new().struct_names(true));
-                let mut ser =
-                    ron::Serializer::with_options(&mut file, config, ron::Options::default())?;
-                serde_path_to_error::serialize(data, &mut ser)?;
-            }
-            DataFormat::Json { pretty } => {
-                if pretty {
-                    let mut ser = serde_json::Serializer::pretty(&mut file);
-                    serde_path_to_error::serialize(data, &mut ser)?;
-                } else {
-                    let mut ser = serde_json::Serializer::new(&mut file);
-                    serde_path_to_error::serialize(data, &mut ser)?;
-                }
-            }
-        };
-
-        Ok(())
+        let mut buffer = Vec::new();
+        self.write_data_to(data, &mut buffer)?;
+        filesystem.write(self.path_for(filename), buffer)
     }
 
     pub fn write_data_to<T>(self, data: &T, buffer: &mut Vec<u8>) -> color_eyre::Result<()>
     where
         T: ::serde::Serialize,
         T: alox_48::Serialize,
+        T: luminol_data::HasExtraIvars,
     {
-        match self.format {
+        match self.file_format() {
             DataFormat::Marshal => {
+                let mut value = luminol_data::extra_ivars::to_value(data)?;
+                self.encode_marshal_value(&mut value);
                 let mut serializer = alox_48::Serializer::new();
-                alox_48::path_to_error::serialize(data, &mut serializer)
+                alox_48::path_to_error::serialize(&value, &mut serializer)
                     .map_err(|(error, trace)| format_traced_error(error, trace))?;
                 buffer.extend_from_slice(&serializer.output);
             }
@@ -163,6 +186,11 @@ impl Handler {
                     serde_path_to_error::serialize(data, &mut ser)?;
                 }
             }
+            DataFormat::Yaml => {
+                let mut ser = serde_yml::Serializer::new(buffer);
+                serde_path_to_error::serialize(data, &mut ser)?;
+            }
+            DataFormat::Split => unreachable!(),
         };
 
         Ok(())
@@ -174,56 +202,53 @@ impl Handler {
         filename: impl AsRef<camino::Utf8Path>,
     ) -> color_eyre::Result<Vec<T>>
     where
-        T: for<'de> alox_48::Deserialize<'de>,
+        T: for<'de> alox_48::Deserialize<'de> + alox_48::Serialize,
         T: ::serde::de::DeserializeOwned,
+        T: luminol_data::HasExtraIvars,
     {
-        let data = filesystem.read(self.path_for(filename))?;
-
-        match self.format {
-            DataFormat::Marshal => {
-                let mut de = alox_48::Deserializer::new(&data)?;
-                let mut trace = alox_48::path_to_error::Trace::default();
-                let de = alox_48::path_to_error::Deserializer::new(&mut de, &mut trace);
-
-                luminol_data::helpers::nil_padded_alox::deserialize_with(de)
-                    .map_err(|error| format_traced_error(error, trace))
-            }
-            DataFormat::Ron { .. } => {
-                let mut de = ron::de::Deserializer::from_bytes(&data)?;
-                let mut track = serde_path_to_error::Track::new();
-                let de = serde_path_to_error::Deserializer::new(&mut de, &mut track);
-
-                luminol_data::helpers::nil_padded_serde::deserialize(de).map_err(|inner| {
-                    let error = serde_path_to_error::Error::new(track.path(), inner);
-                    format_path_to_error(error)
+        if self.format == DataFormat::Split {
+            let dir = self.collection_path_for(filename);
+            return read_split_entries(filesystem, &dir, "ron")?
+                .into_iter()
+                .enumerate()
+                .map(|(index, (number, path))| {
+                    if number != index + 1 {
+                        color_eyre::eyre::bail!("Missing entry {} in {dir}", index + 1);
+                    }
+                    let data = filesystem.read(&path)?;
+                    self.read_data_from(&data)
+                        .wrap_err_with(|| format!("While reading {path}"))
                 })
-            }
-            DataFormat::Json { .. } => {
-                let mut de = serde_json::de::Deserializer::from_slice(&data);
-                let mut track = serde_path_to_error::Track::new();
-                let de = serde_path_to_error::Deserializer::new(&mut de, &mut track);
-
-                luminol_data::helpers::nil_padded_serde::deserialize(de).map_err(|inner| {
-                    let error = serde_path_to_error::Error::new(track.path(), inner);
-                    format_path_to_error(error)
-                })
-            }
+                .collect();
         }
+
+        let data = filesystem.read(self.path_for(filename))?;
+        self.read_nil_padded_from(&data)
     }
 
     pub fn read_nil_padded_from<T>(self, data: &[u8]) -> color_eyre::Result<Vec<T>>
     where
-        T: for<'de> alox_48::Deserialize<'de>,
+        T: for<'de> alox_48::Deserialize<'de> + alox_48::Serialize,
         T: ::serde::de::DeserializeOwned,
+        T: luminol_data::HasExtraIvars,
     {
-        match self.format {
+        match self.file_format() {
             DataFormat::Marshal => {
-                let mut de = alox_48::Deserializer::new(data)?;
-                let mut trace = alox_48::path_to_error::Trace::default();
-                let de = alox_48::path_to_error::Deserializer::new(&mut de, &mut trace);
+                if !T::KEEPS_EXTRA_IVARS && self.encoding == StringEncoding::Utf8 {
+                    // nothing needs the data as plain Ruby values, so it can be read directly
+                    let mut de = alox_48::Deserializer::new(data)?;
+                    let mut trace = alox_48::path_to_error::Trace::default();
+                    let de = alox_48::path_to_error::Deserializer::new(&mut de, &mut trace);
+
+                    return luminol_data::helpers::nil_padded_alox::deserialize_with(de)
+                        .map_err(|error| format_traced_error(error, trace));
+                }
 
-                luminol_data::helpers::nil_padded_alox::deserialize_with(de)
-                    .map_err(|error| format_traced_error(error, trace))
+                // the nil padding has already been removed from the value
+                let value = self.read_marshal_value(data, true)?;
+                let mut result = deserialize_value(&value)?;
+                capture_extra_ivars(&mut result, &value)?;
+                Ok(result)
             }
             DataFormat::Ron { .. } => {
                 let mut de = ron::de::Deserializer::from_bytes(data)?;
@@ -245,6 +270,17 @@ impl Handler {
                     format_path_to_error(error)
                 })
             }
+            DataFormat::Yaml => {
+                let de = serde_yml::Deserializer::from_slice(data);
+                let mut track = serde_path_to_error::Track::new();
+                let de = serde_path_to_error::Deserializer::new(de, &mut track);
+
+                luminol_data::helpers::nil_padded_serde::deserialize(de).map_err(|inner| {
+                    let error = serde_path_to_error::Error::new(track.path(), inner);
+                    format_path_to_error(error)
+                })
+            }
+            DataFormat::Split => unreachable!(),
         }
     }
 
@@ -257,77 +293,46 @@ impl Handler {
     where
         T: ::serde::Serialize,
         T: alox_48::Serialize,
+        T: luminol_data::HasExtraIvars,
     {
-        let mut file = filesystem.open_file(
-            self.path_for(filename),
-            OpenFlags::Create | OpenFlags::Truncate | OpenFlags::Write,
-        )?;
-
-        match self.format {
-            DataFormat::Marshal => {
-                let mut trace = alox_48::path_to_error::Trace::new();
-                let mut ser = alox_48::Serializer::new();
-                let trace_ser = alox_48::path_to_error::Serializer::new(&mut ser, &mut trace);
-
-                luminol_data::helpers::nil_padded_alox::serialize_with(data, trace_ser)
-                    .map_err(|error| format_traced_error(error, trace))?;
-                file.write_all(&ser.output)?;
-            }
-            DataFormat::Json { pretty } => {
-                let mut track = serde_path_to_error::Track::new();
-                if pretty {
-                    let mut ser = serde_json::Serializer::pretty(&mut file);
-                    let ser = serde_path_to_error::Serializer::new(&mut ser, &mut track);
-
-                    luminol_data::helpers::nil_padded_serde::serialize(data, ser).map_err(
-                        |inner| {
-                            let error = serde_path_to_error::Error::new(track.path(), inner);
-                            format_path_to_error(error)
-                        },
-                    )?;
-                } else {
-                    let mut ser = serde_json::Serializer::new(&mut file);
-                    let ser = serde_path_to_error::Serializer::new(&mut ser, &mut track);
-
-                    luminol_data::helpers::nil_padded_serde::serialize(data, ser).map_err(
-                        |inner| {
-                            let error = serde_path_to_error::Error::new(track.path(), inner);
-                            format_path_to_error(error)
-                        },
-                    )?;
-                }
-            }
-            DataFormat::Ron { pretty } => {
-                let mut track = serde_path_to_error::Track::new();
-                let config = pretty.then(|| ron::ser::PrettyConfig::new().struct_names(true));
-                let mut ser =
-                    ron::Serializer::with_options(&mut file, config, ron::Options::default())?;
-                let ser = serde_path_to_error::Serializer::new(&mut ser, &mut track);
-
-                luminol_data::helpers::nil_padded_serde::serialize(data, ser).map_err(|inner| {
-                    let error = serde_path_to_error::Error::new(track.path(), inner);
-                    format_path_to_error(error)
-                })?;
+        if self.format == DataFormat::Split {
+            let dir = self.collection_path_for(filename);
+            let mut entries = Vec::with_capacity(data.len());
+            for (index, entry) in data.iter().enumerate() {
+                let name = entry_name(entry).unwrap_or_default();
+                let mut buffer = Vec::new();
+                self.write_data_to(entry, &mut buffer)?;
+                entries.push((split_entry_filename(index + 1, &name, "ron"), buffer));
             }
+            return write_split_entries(filesystem, &dir, entries);
         }
 
-        file.flush()?;
-
-        Ok(())
+        let mut buffer = Vec::new();
+        self.write_nil_padded_to(data, &mut buffer)?;
+        filesystem.write(self.path_for(filename), buffer)
     }
 
     pub fn write_nil_padded_to<T>(self, data: &[T], buffer: &mut Vec<u8>) -> color_eyre::Result<()>
     where
         T: ::serde::Serialize,
         T: alox_48::Serialize,
+        T: luminol_data::HasExtraIvars,
     {
-        match self.format {
+        match self.file_format() {
             DataFormat::Marshal => {
+                let mut values = data
+                    .iter()
+                    .map(luminol_data::extra_ivars::to_value)
+                    .collect::<Result<Vec<_>, _>>()?;
+                values
+                    .iter_mut()
+                    .for_each(|value| self.encode_marshal_value(value));
+
                 let mut trace = alox_48::path_to_error::Trace::new();
                 let mut ser = alox_48::Serializer::new();
                 let trace_ser = alox_48::path_to_error::Serializer::new(&mut ser, &mut trace);
 
-                luminol_data::helpers::nil_padded_alox::serialize_with(data, trace_ser)
+                luminol_data::helpers::nil_padded_alox::serialize_with(&values, trace_ser)
                     .map_err(|error| format_traced_error(error, trace))?;
                 buffer.extend_from_slice(&ser.output);
             }
@@ -367,23 +372,349 @@ impl Handler {
                     format_path_to_error(error)
                 })?;
             }
+            DataFormat::Yaml => {
+                let mut track = serde_path_to_error::Track::new();
+                let mut ser = serde_yml::Serializer::new(buffer);
+                let ser = serde_path_to_error::Serializer::new(&mut ser, &mut track);
+
+                luminol_data::helpers::nil_padded_serde::serialize(data, ser).map_err(|inner| {
+                    let error = serde_path_to_error::Error::new(track.path(), inner);
+                    format_path_to_error(error)
+                })?;
+            }
+            DataFormat::Split => unreachable!(),
         }
 
         Ok(())
     }
 
+    /// Reads `MapInfos`. The split data format stores each map info in its own file, named after
+    /// the map's ID.
+    pub fn read_map_infos(
+        self,
+        filesystem: &impl luminol_filesystem::FileSystem,
+    ) -> color_eyre::Result<std::collections::HashMap<usize, rpg::MapInfo>> {
+        if self.format != DataFormat::Split {
+            return self.read_data(filesystem, "MapInfos");
+        }
+
+        let dir = self.collection_path_for("MapInfos");
+        read_split_entries(filesystem, &dir, "ron")?
+            .into_iter()
+            .map(|(id, path)| {
+                let data = filesystem.read(&path)?;
+                let map_info = self
+                    .read_data_from(&data)
+                    .wrap_err_with(|| format!("While reading {path}"))?;
+                Ok((id, map_info))
+            })
+            .collect()
+    }
+
+    pub fn write_map_infos(
+        self,
+        map_infos: &std::collections::HashMap<usize, rpg::MapInfo>,
+        filesystem: &impl luminol_filesystem::FileSystem,
+    ) -> color_eyre::Result<()> {
+        if self.format != DataFormat::Split {
+            return self.write_data(map_infos, filesystem, "MapInfos");
+        }
+
+        let dir = self.collection_path_for("MapInfos");
+        let mut entries = Vec::with_capacity(map_infos.len());
+        for (&id, map_info) in map_infos.iter() {
+            let mut buffer = Vec::new();
+            self.write_data_to(map_info, &mut buffer)?;
+            entries.push((split_entry_filename(id, &map_info.name, "ron"), buffer));
+        }
+        write_split_entries(filesystem, &dir, entries)
+    }
+
+    /// Reads the scripts from `filename`. The split data format stores each script as a plain
+    /// Ruby file, alongside an index file recording the scripts' order, IDs and names.
+    pub fn read_scripts(
+        self,
+        filesystem: &impl luminol_filesystem::FileSystem,
+        filename: impl AsRef<camino::Utf8Path>,
+    ) -> color_eyre::Result<Vec<rpg::Script>> {
+        if self.format == DataFormat::Marshal && self.encoding != StringEncoding::Utf8 {
+            // the script text is compressed, so it can't be decoded like the rest of the strings
+            let path = self.path_for(filename);
+            let data = filesystem.read(&path)?;
+            let mut de = alox_48::Deserializer::new(&data)?;
+            let mut value = <alox_48::Value as alox_48::Deserialize>::deserialize(&mut de)?;
+            luminol_data::encoding::decode_scripts(&mut value, self.encoding.encoding())
+                .wrap_err_with(|| format!("While decompressing the scripts in {path}"))?;
+            return deserialize_value(&value)
+                .wrap_err_with(|| format!("While reading the scripts in {path}"));
+        }
+        if self.format != DataFormat::Split {
+            return self.read_data(filesystem, filename);
+        }
+
+        let dir = self.collection_path_for(filename);
+        let index = filesystem.read(dir.join(SCRIPTS_INDEX))?;
+        let index: Vec<ScriptIndexEntry> = ron::de::from_bytes(&index)
+            .wrap_err_with(|| format!("While reading {dir}/{SCRIPTS_INDEX}"))?;
+
+        index
+            .into_iter()
+            .map(|entry| {
+                let path = dir.join(&entry.file);
+                let script_text = filesystem
+                    .read_to_string(&path)
+                    .wrap_err_with(|| format!("While reading {path}"))?;
+                Ok(rpg::Script {
+                    id: entry.id,
+                    name: entry.name,
+                    script_text,
+                })
+            })
+            .collect()
+    }
+
+    // the non-split formats serialize the Vec directly, which needs a sized type
+    #[allow(clippy::ptr_arg)]
+    pub fn write_scripts(
+        self,
+        scripts: &Vec<rpg::Script>,
+        filesystem: &impl luminol_filesystem::FileSystem,
+        filename: impl AsRef<camino::Utf8Path>,
+    ) -> color_eyre::Result<()> {
+        if self.format == DataFormat::Marshal && self.encoding != StringEncoding::Utf8 {
+            let mut value = alox_48::to_value(scripts)?;
+            let unencodable =
+                luminol_data::encoding::encode_scripts(&mut value, self.encoding.encoding())?;
+            if unencodable > 0 {
+                tracing::warn!(
+                    "{unencodable} scripts have text that can't be written as {}, they were saved as UTF-8",
+                    self.encoding
+                );
+            }
+            let mut serializer = alox_48::Serializer::new();
+            alox_48::path_to_error::serialize(&value, &mut serializer)
+                .map_err(|(error, trace)| format_traced_error(error, trace))?;
+            return filesystem.write(self.path_for(filename), serializer.output);
+        }
+        if self.format != DataFormat::Split {
+            return self.write_data(scripts, filesystem, filename);
+        }
+
+        let dir = self.collection_path_for(filename);
+        let mut index = Vec::with_capacity(scripts.len());
+        let mut entries = Vec::with_capacity(scripts.len() + 1);
+        for (number, script) in scripts.iter().enumerate() {
+            let file = split_entry_filename(number + 1, &script.name, "rb");
+            entries.push((file.clone(), script.script_text.clone().into_bytes()));
+            index.push(ScriptIndexEntry {
+                id: script.id,
+                name: script.name.clone(),
+                file,
+            });
+        }
+        let index = ron::ser::to_string_pretty(&index, ron::ser::PrettyConfig::new())?;
+        entries.push((SCRIPTS_INDEX.to_string(), index.into_bytes()));
+
+        write_split_entries(filesystem, &dir, entries)
+    }
+
+    /// Reads Marshal `data` as plain Ruby values, with its strings decoded to UTF-8.
+    /// If `nil_padded` is true, the `nil` at the start of nil padded data is removed.
+    fn read_marshal_value(
+        self,
+        data: &[u8],
+        nil_padded: bool,
+    ) -> color_eyre::Result<alox_48::Value> {
+        let mut de = alox_48::Deserializer::new(data)?;
+        let mut value = <alox_48::Value as alox_48::Deserialize>::deserialize(&mut de)?;
+        if let (true, alox_48::Value::Array(values)) = (nil_padded, &mut value) {
+            if !values.is_empty() && values.remove(0) != alox_48::Value::Nil {
+                color_eyre::eyre::bail!("the first element was not nil");
+            }
+        }
+
+        luminol_data::encoding::decode_strings(&mut value, self.encoding.encoding());
+        Ok(value)
+    }
+
+    /// Encodes the strings of a Marshal value that's about to be written.
+    fn encode_marshal_value(self, value: &mut alox_48::Value) {
+        let unencodable = luminol_data::encoding::encode_strings(value, self.encoding.encoding());
+        if unencodable > 0 {
+            tracing::warn!(
+                "{unencodable} strings can't be written as {}, they were saved as UTF-8",
+                self.encoding
+            );
+        }
+    }
+
+    /// The encoding of strings in Marshal data read and written by this handler.
+    pub fn encoding(self) -> StringEncoding {
+        self.encoding
+    }
+
     pub fn remove_file(
         self,
         filesystem: &impl luminol_filesystem::FileSystem,
         filename: impl AsRef<camino::Utf8Path>,
     ) -> color_eyre::Result<()> {
-        let path = camino::Utf8Path::new("Data")
-            .join(filename)
-            .with_extension(self.format.extension());
-        filesystem.remove_file(path)
+        filesystem.remove_file(self.path_for(filename))
+    }
+}
+
+/// Guesses the encoding of strings in a project's Marshal data from `MapInfos` and `System`,
+/// which hold the map names and most of the game's terms.
+///
+/// Returns `None` if their strings are valid UTF-8 (which plain ASCII always is), or if no
+/// encoding fits. Files that can't be read are ignored, since loading them will report the error.
+pub fn detect_encoding(
+    filesystem: &impl luminol_filesystem::FileSystem,
+    rm_ver: luminol_config::RMVer,
+) -> Option<StringEncoding> {
+    let handler = Handler::new(DataFormat::Marshal, rm_ver);
+    let values: Vec<_> = ["MapInfos", "System"]
+        .into_iter()
+        .filter_map(|filename| {
+            let data = filesystem.read(handler.path_for(filename)).ok()?;
+            let mut de = alox_48::Deserializer::new(&data).ok()?;
+            <alox_48::Value as alox_48::Deserialize>::deserialize(&mut de).ok()
+        })
+        .collect();
+
+    if values.iter().all(luminol_data::encoding::is_utf8) {
+        return None;
+    }
+    StringEncoding::LEGACY.into_iter().find(|encoding| {
+        values
+            .iter()
+            .all(|value| luminol_data::encoding::decodes_as(value, encoding.encoding()))
+    })
+}
+
+/// An entry of [`SCRIPTS_INDEX`], naming the file that a script's text is stored in.
+#[derive(serde::Serialize, serde::Deserialize)]
+pub struct ScriptIndexEntry {
+    pub id: u32,
+    pub name: String,
+    pub file: String,
+}
+
+/// Gets the name of a database entry from its `name` field, if it has one.
+// Database entries don't share a trait for this, so we go through serde instead.
+fn entry_name(entry: &impl ::serde::Serialize) -> Option<String> {
+    match serde_json::to_value(entry).ok()? {
+        serde_json::Value::Object(mut fields) => match fields.remove("name")? {
+            serde_json::Value::String(name) => Some(name),
+            _ => None,
+        },
+        _ => None,
     }
 }
 
+/// The filename of an entry in a split collection, like `001-Aluxes.ron`.
+/// Names are only there to make the files easier to find, so they are reduced to characters that
+/// are safe on every filesystem.
+pub fn split_entry_filename(number: usize, name: &str, extension: &str) -> String {
+    let name: String = name
+        .chars()
+        .map(|c| {
+            if c.is_alphanumeric() || matches!(c, ' ' | '-' | '_') {
+                c
+            } else {
+                '_'
+            }
+        })
+        .take(40)
+        .collect();
+    let name = name.trim();
+
+    if name.is_empty() {
+        format!("{number:0>3}.{extension}")
+    } else {
+        format!("{number:0>3}-{name}.{extension}")
+    }
+}
+
+/// Lists the entries of a split collection with the given extension, sorted by their number.
+fn read_split_entries(
+    filesystem: &impl luminol_filesystem::FileSystem,
+    dir: &camino::Utf8Path,
+    extension: &str,
+) -> color_eyre::Result<Vec<(usize, camino::Utf8PathBuf)>> {
+    let mut entries = Vec::new();
+    for entry in filesystem.read_dir(dir)? {
+        if !entry.metadata.is_file || entry.path.extension() != Some(extension) {
+            continue;
+        }
+        let Some(number) = entry.path.file_stem().and_then(|stem| {
+            let digits = stem.split_once('-').map_or(stem, |(digits, _)| digits);
+            digits.parse::<usize>().ok()
+        }) else {
+            continue;
+        };
+        entries.push((number, entry.path));
+    }
+    entries.sort_unstable_by_key(|(number, _)| *number);
+
+    if let Some(window) = entries.windows(2).find(|w| w[0].0 == w[1].0) {
+        color_eyre::eyre::bail!(
+            "{} and {} have the same number in {dir}",
+            window[0].1,
+            window[1].1
+        );
+    }
+
+    Ok(entries)
+}
+
+/// Writes every entry of a split collection into `dir`, then removes any files left over from
+/// entries that no longer exist (or have been renamed.)
+fn write_split_entries(
+    filesystem: &impl luminol_filesystem::FileSystem,
+    dir: &camino::Utf8Path,
+    entries: Vec<(String, Vec<u8>)>,
+) -> color_eyre::Result<()> {
+    filesystem.create_dir(dir)?;
+
+    let mut written = std::collections::HashSet::with_capacity(entries.len());
+    for (file, data) in entries {
+        let path = dir.join(&file);
+        filesystem
+            .write(&path, data)
+            .wrap_err_with(|| format!("While writing {path}"))?;
+        written.insert(file);
+    }
+
+    for entry in filesystem.read_dir(dir)? {
+        let is_stale = entry.metadata.is_file
+            && entry
+                .path
+                .file_name()
+                .is_some_and(|name| !written.contains(name));
+        if is_stale {
+            filesystem
+                .remove_file(&entry.path)
+                .wrap_err_with(|| format!("While removing {}", entry.path))?;
+        }
+    }
+
+    Ok(())
+}
+
+/// Keeps the instance variables of `value` that `result` (which was read from the same data) has
+/// no fields for.
+fn capture_extra_ivars<T>(result: &mut T, value: &alox_48::Value) -> color_eyre::Result<()>
+where
+    T: alox_48::Serialize + luminol_data::HasExtraIvars,
+{
+    if !T::KEEPS_EXTRA_IVARS {
+        return Ok(());
+    }
+    let known = alox_48::to_value(&*result)?;
+    result.capture_extra_ivars(value, &known);
+    Ok(())
+}
+
 pub fn format_path_to_error<E>(error: serde_path_to_error::Error<E>) -> color_eyre::Report
 where
     E: serde::de::Error + Send + Sync + 'static,
@@ -391,6 +722,15 @@ where
     error.into() // TODO
 }
 
+/// Deserializes a Marshal value, with the same path-traced errors as Marshal data read directly.
+fn deserialize_value<T>(value: &alox_48::Value) -> color_eyre::Result<T>
+where
+    T: for<'de> alox_48::Deserialize<'de>,
+{
+    alox_48::path_to_error::deserialize(value)
+        .map_err(|(error, trace)| format_traced_error(error, trace))
+}
+
 pub fn format_traced_error(
     error: impl Into<color_eyre::Report>,
     trace: alox_48::path_to_error::Trace,
@@ -401,3 +741,178 @@ pub fn format_traced_error(
     }
     error
 }
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use luminol_config::RMVer;
+    use luminol_filesystem::FileSystem;
+
+    /// A filesystem in a temporary folder with an empty `Data` folder.
+    fn filesystem() -> (tempfile::TempDir, luminol_filesystem::host::FileSystem) {
+        let (dir, filesystem) = crate::data_cache::test_fixtures::filesystem();
+        filesystem.create_dir("Data").unwrap();
+        (dir, filesystem)
+    }
+
+    fn actors() -> Vec<rpg::Actor> {
+        ["アルシェス", "Basil"]
+            .into_iter()
+            .enumerate()
+            .map(|(index, name)| rpg::Actor {
+                id: index,
+                name: name.to_string(),
+                initial_level: index as i32 + 1,
+                ..Default::default()
+            })
+            .collect()
+    }
+
+    fn file_names(
+        filesystem: &impl luminol_filesystem::FileSystem,
+        dir: &str,
+    ) -> std::collections::BTreeSet<String> {
+        filesystem
+            .read_dir(dir)
+            .unwrap()
+            .into_iter()
+            .map(|entry| entry.path.file_name().unwrap().to_string())
+            .collect()
+    }
+
+    #[test]
+    fn split_data_stores_one_file_per_entry() {
+        let (_dir, filesystem) = filesystem();
+        let handler = Handler::new(DataFormat::Split, RMVer::XP);
+
+        handler
+            .write_nil_padded(&actors(), &filesystem, "Actors")
+            .unwrap();
+        assert_eq!(
+            file_names(&filesystem, "Data/Actors"),
+            ["001-アルシェス.ron", "002-Basil.ron"]
+                .map(String::from)
+                .into()
+        );
+        let read: Vec<rpg::Actor> = handler.read_nil_padded(&filesystem, "Actors").unwrap();
+        assert_eq!(read[1].name, "Basil");
+        assert_eq!(read[1].initial_level, 2);
+
+        let map_infos: std::collections::HashMap<_, _> = [(1, "Town"), (3, "Inn: 1F")]
+            .into_iter()
+            .map(|(id, name)| {
+                let map_info = rpg::MapInfo {
+                    name: name.to_string(),
+                    order: id as i32,
+                    ..Default::default()
+                };
+                (id, map_info)
+            })
+            .collect();
+        handler.write_map_infos(&map_infos, &filesystem).unwrap();
+        // entries are numbered by map ID, and names are made safe to use as file names
+        assert_eq!(
+            file_names(&filesystem, "Data/MapInfos"),
+            ["001-Town.ron", "003-Inn_ 1F.ron"].map(String::from).into()
+        );
+        assert_eq!(handler.read_map_infos(&filesystem).unwrap(), map_infos);
+    }
+
+    #[test]
+    fn split_scripts_are_plain_ruby_files() {
+        let (_dir, filesystem) = filesystem();
+        let handler = Handler::new(DataFormat::Split, RMVer::XP);
+
+        let scripts = vec![
+            rpg::Script::new("Game_Temp", "class Game_Temp\nend\n"),
+            rpg::Script::new("Main", "begin\n  $scene = Scene_Title.new\nend\n"),
+        ];
+        handler
+            .write_scripts(&scripts, &filesystem, "Scripts")
+            .unwrap();
+
+        assert_eq!(
+            file_names(&filesystem, "Data/Scripts"),
+            [SCRIPTS_INDEX, "001-Game_Temp.rb", "002-Main.rb"]
+                .map(String::from)
+                .into()
+        );
+        assert_eq!(
+            filesystem
+                .read_to_string("Data/Scripts/001-Game_Temp.rb")
+                .unwrap(),
+            "class Game_Temp\nend\n"
+        );
+
+        let read = handler.read_scripts(&filesystem, "Scripts").unwrap();
+        assert_eq!(read.len(), scripts.len());
+        for (read, script) in read.iter().zip(scripts.iter()) {
+            assert_eq!(read.id, script.id);
+            assert_eq!(read.name, script.name);
+            assert_eq!(read.script_text, script.script_text);
+        }
+    }
+
+    #[test]
+    fn split_data_removes_stale_entries() {
+        let (_dir, filesystem) = filesystem();
+        let handler = Handler::new(DataFormat::Split, RMVer::XP);
+
+        let mut actors = actors();
+        handler
+            .write_nil_padded(&actors, &filesystem, "Actors")
+            .unwrap();
+        actors.pop();
+        actors[0].name = "Aluxes".to_string();
+        handler
+            .write_nil_padded(&actors, &filesystem, "Actors")
+            .unwrap();
+
+        assert_eq!(
+            file_names(&filesystem, "Data/Actors"),
+            ["001-Aluxes.ron".to_string()].into()
+        );
+        let read: Vec<rpg::Actor> = handler.read_nil_padded(&filesystem, "Actors").unwrap();
+        assert_eq!(read.len(), 1);
+        assert_eq!(read[0].name, "Aluxes");
+    }
+
+    #[test]
+    fn yaml_data_is_readable_text() {
+        let (_dir, filesystem) = filesystem();
+        let handler = Handler::new(DataFormat::Yaml, RMVer::XP);
+
+        handler
+            .write_nil_padded(&actors(), &filesystem, "Actors")
+            .unwrap();
+        let text = filesystem.read_to_string("Data/Actors.yaml").unwrap();
+        // the padding entry is written as null, and names aren't escaped
+        assert!(text.starts_with("- null\n- id: 1\n  name: アルシェス\n"));
+        assert!(text.contains("\n- id: 2\n  name: Basil\n"));
+
+        let read: Vec<rpg::Actor> = handler.read_nil_padded(&filesystem, "Actors").unwrap();
+        assert_eq!(read.len(), 2);
+        assert_eq!(read[0].name, "アルシェス");
+        assert_eq!(read[1].initial_level, 2);
+    }
+
+    #[test]
+    fn shift_jis_marshal_data_is_encoded_on_disk() {
+        let (_dir, filesystem) = filesystem();
+        let handler =
+            Handler::new(DataFormat::Marshal, RMVer::XP).with_encoding(StringEncoding::ShiftJis);
+
+        handler
+            .write_nil_padded(&actors(), &filesystem, "Actors")
+            .unwrap();
+        // "アルシェス" in Shift-JIS
+        let name = [0x83, 0x41, 0x83, 0x8b, 0x83, 0x56, 0x83, 0x46, 0x83, 0x58];
+        let data = filesystem.read("Data/Actors.rxdata").unwrap();
+        assert!(data.windows(name.len()).any(|window| window == name));
+        assert!(std::str::from_utf8(&data).is_err());
+
+        let read: Vec<rpg::Actor> = handler.read_nil_padded(&filesystem, "Actors").unwrap();
+        assert_eq!(read[0].name, "アルシェス");
+        assert_eq!(read[1].name, "Basil");
+    }
+}
@@ -0,0 +1,612 @@
+// Copyright (C) 2024 Melody Madeline Lyons
+//
+// This file is part of Luminol.
+//
+// Luminol is free software: you can redistribute it and/or modify
+// it under the terms of the GNU General Public License as published by
+// the Free Software Foundation, either version 3 of the License, or
+// (at your option) any later version.
+//
+// Luminol is distributed in the hope that it will be useful,
+// but WITHOUT ANY WARRANTY; without even the implied warranty of
+// MERCHANTABILITY or FITNESS FOR A PARTICULAR PURPOSE.  See the
+// GNU General Public License for more details.
+//
+// You should have received a copy of the GNU General Public License
+// along with Luminol.  If not, see <http://www.gnu.org/licenses/>.
+//
+//     Additional permission under GNU GPL version 3 section 7
+//
+// If you modify this Program, or any covered work, by linking or combining
+// it with Steamworks API by Valve Corporation, containing parts covered by
+// terms of the Steamworks API by Valve Corporation, the licensors of this
+// Program grant you additional permission to convey the resulting work.
+
+//! Crash recovery for unsaved changes.
+//!
+//! Modified data is periodically autosaved to `.luminol/recovery/<persistence id>`, never to the
+//! project's real `Data` folder. When the project is next opened, anything in there that differs
+//! from the data on disk can be restored or discarded.
+
+use color_eyre::eyre::WrapErr;
+use luminol_data::{rmvx, rmvxace, rpg};
+use luminol_filesystem::FileSystem;
+use std::collections::{BTreeSet, HashMap};
+
+use super::{data_formats::Handler, transaction::Transaction, Data, Entry};
+
+pub const RECOVERY_DIR: &str = ".luminol/recovery";
+const STAGING_DIR: &str = ".luminol/recovery-staging";
+/// Lists the autosaved files. Written last, so files that aren't in it are ignored.
+const MANIFEST: &str = "manifest.ron";
+
+impl Entry {
+    fn path(&self, project: &luminol_config::project::Project) -> camino::Utf8PathBuf {
+        let filename = match self {
+            Self::Database(name) => name.clone(),
+            Self::Map(id) => format!("Map{id:0>3}"),
+        };
+        dir(project)
+            .join(filename)
+            .with_extension(luminol_config::DataFormat::Marshal.extension(project.editor_ver))
+    }
+}
+
+/// What an autosave holds.
+#[derive(Default, serde::Serialize, serde::Deserialize)]
+struct Manifest {
+    entries: Vec<Entry>,
+    /// Maps whose files are removed the next time the project is saved.
+    deleted_maps: Vec<usize>,
+}
+
+/// Unsaved changes from an earlier session that differ from the project's data.
+#[derive(Clone, Debug)]
+pub struct Recovered {
+    pub entries: Vec<Entry>,
+    /// Maps that were deleted, but whose files are still in the project.
+    pub deleted_maps: Vec<usize>,
+}
+
+/// The folder that a project's autosaves are kept in.
+pub fn dir(project: &luminol_config::project::Project) -> camino::Utf8PathBuf {
+    camino::Utf8Path::new(RECOVERY_DIR).join(format!("{:016x}", project.persistence_id))
+}
+
+/// Autosaved data is always stored as Marshal, since every type supports it.
+fn handler(project: &luminol_config::project::Project) -> Handler {
+    Handler::new(luminol_config::DataFormat::Marshal, project.editor_ver)
+}
+
+/// Writes every modified database and map to the recovery folder, along with which maps were
+/// deleted, replacing the previous autosave. If nothing is modified, the recovery folder is
+/// removed instead.
+///
+/// The new autosave is written to a staging folder and swapped in once it's complete, so the
+/// previous autosave survives if writing the new one fails.
+pub fn autosave(
+    data: &mut Data,
+    filesystem: &impl FileSystem,
+    config: &luminol_config::project::Config,
+) -> color_eyre::Result<()> {
+    let c = "While autosaving";
+    let dir = dir(&config.project);
+    let handler = handler(&config.project);
+
+    let mut transaction = Transaction::new(filesystem, STAGING_DIR).wrap_err(c)?;
+    filesystem
+        .create_dir(transaction.staging_path(&dir))
+        .wrap_err(c)?;
+
+    let mut manifest = Manifest {
+        entries: Vec::new(),
+        deleted_maps: deleted_maps(data).iter().copied().collect(),
+    };
+    for (entry, mut recoverable) in entries(data, None, false) {
+        if !recoverable.is_modified() {
+            continue;
+        }
+
+        let mut buffer = Vec::new();
+        recoverable
+            .write(handler, &mut buffer)
+            .wrap_err_with(|| format!("While autosaving {entry}"))?;
+        filesystem
+            .write(
+                transaction.staging_path(entry.path(&config.project)),
+                buffer,
+            )
+            .wrap_err_with(|| format!("While autosaving {entry}"))?;
+        manifest.entries.push(entry);
+    }
+
+    if manifest.entries.is_empty() && manifest.deleted_maps.is_empty() {
+        transaction.rollback(filesystem).wrap_err(c)?;
+        return discard(filesystem, config);
+    }
+
+    let manifest =
+        ron::ser::to_string_pretty(&manifest, ron::ser::PrettyConfig::new()).wrap_err(c)?;
+    filesystem
+        .write(transaction.staging_path(dir.join(MANIFEST)), manifest)
+        .wrap_err(c)?;
+
+    transaction.stage(dir);
+    transaction.commit(filesystem).wrap_err(c)
+}
+
+/// Looks for autosaved data from an earlier session that was never saved.
+///
+/// Autosaves that don't differ from the data on disk are discarded. Should be called right after
+/// loading the project, since it compares against the loaded data.
+pub fn check(
+    data: &mut Data,
+    filesystem: &impl FileSystem,
+    config: &luminol_config::project::Config,
+) -> color_eyre::Result<Option<Recovered>> {
+    let c = "While checking for autosaved data";
+    let manifest_path = dir(&config.project).join(MANIFEST);
+    if !filesystem.exists(&manifest_path).wrap_err(c)? {
+        return Ok(None);
+    }
+
+    let manifest: Manifest =
+        ron::de::from_bytes(&filesystem.read(&manifest_path).wrap_err(c)?).wrap_err(c)?;
+    let handler = handler(&config.project);
+
+    let map_ids: Vec<_> = manifest.entries.iter().filter_map(Entry::map_id).collect();
+    for &id in map_ids.iter() {
+        // maps that can't be loaded are treated as differing from their autosave below
+        let _ = data.load_map(id, filesystem, config);
+    }
+
+    let mut current = entries(data, Some(&map_ids), false);
+    let mut differing = Vec::new();
+    for entry in manifest.entries {
+        let autosaved = filesystem
+            .read(entry.path(&config.project))
+            .wrap_err_with(|| format!("While reading the autosave of {entry}"))?;
+        let differs = match current.iter_mut().find(|(e, _)| *e == entry) {
+            Some((_, recoverable)) => recoverable
+                .differs(handler, &autosaved)
+                .wrap_err_with(|| format!("While reading the autosave of {entry}"))?,
+            None => true,
+        };
+        if differs {
+            differing.push(entry);
+        }
+    }
+    drop(current);
+
+    // maps whose files were removed since don't need to be deleted again
+    let project_handler = Handler::for_project(&config.project);
+    let mut deleted_maps = Vec::new();
+    for id in manifest.deleted_maps {
+        let path = project_handler.path_for(format!("Map{id:0>3}"));
+        if filesystem.exists(path).wrap_err(c)? {
+            deleted_maps.push(id);
+        }
+    }
+
+    if differing.is_empty() && deleted_maps.is_empty() {
+        discard(filesystem, config)?;
+        return Ok(None);
+    }
+    Ok(Some(Recovered {
+        entries: differing,
+        deleted_maps,
+    }))
+}
+
+/// Replaces the loaded data with the autosaved data, and marks it as modified.
+/// The autosave is kept until the project is saved.
+pub fn restore(
+    data: &mut Data,
+    filesystem: &impl FileSystem,
+    config: &luminol_config::project::Config,
+    recovered: &Recovered,
+) -> color_eyre::Result<()> {
+    let handler = handler(&config.project);
+    let map_ids: Vec<_> = recovered.entries.iter().filter_map(Entry::map_id).collect();
+
+    let mut current = entries(data, Some(&map_ids), true);
+    for entry in recovered.entries.iter() {
+        let c = || format!("While restoring the autosave of {entry}");
+        let Some((_, recoverable)) = current.iter_mut().find(|(e, _)| e == entry) else {
+            color_eyre::eyre::bail!("{entry} does not exist in this project");
+        };
+        let autosaved = filesystem
+            .read(entry.path(&config.project))
+            .wrap_err_with(c)?;
+        recoverable.recover(handler, &autosaved).wrap_err_with(c)?;
+    }
+    drop(current);
+
+    match data {
+        Data::Unloaded => panic!("project not loaded"),
+        Data::Loaded {
+            maps, deleted_maps, ..
+        } => {
+            maps.get_mut()
+                .retain(|id, _| !recovered.deleted_maps.contains(id));
+            deleted_maps
+                .get_mut()
+                .extend(recovered.deleted_maps.iter().copied());
+        }
+        Data::LoadedVX {
+            maps, deleted_maps, ..
+        } => {
+            maps.get_mut()
+                .retain(|id, _| !recovered.deleted_maps.contains(id));
+            deleted_maps
+                .get_mut()
+                .extend(recovered.deleted_maps.iter().copied());
+        }
+        Data::LoadedAce {
+            maps, deleted_maps, ..
+        } => {
+            maps.get_mut()
+                .retain(|id, _| !recovered.deleted_maps.contains(id));
+            deleted_maps
+                .get_mut()
+                .extend(recovered.deleted_maps.iter().copied());
+        }
+    }
+
+    Ok(())
+}
+
+/// The maps that will be deleted the next time the project is saved.
+fn deleted_maps(data: &mut Data) -> &mut BTreeSet<usize> {
+    match data {
+        Data::Unloaded => panic!("project not loaded"),
+        Data::Loaded { deleted_maps, .. }
+        | Data::LoadedVX { deleted_maps, .. }
+        | Data::LoadedAce { deleted_maps, .. } => deleted_maps.get_mut(),
+    }
+}
+
+/// Removes a project's autosaved data.
+pub fn discard(
+    filesystem: &impl FileSystem,
+    config: &luminol_config::project::Config,
+) -> color_eyre::Result<()> {
+    let dir = dir(&config.project);
+    if filesystem.exists(&dir)? {
+        filesystem
+            .remove_dir(&dir)
+            .wrap_err("While removing autosaved data")?;
+    }
+    Ok(())
+}
+
+/// Something in the data cache that can be autosaved.
+pub(super) trait Recoverable {
+    fn is_modified(&mut self) -> bool;
+
+    fn write(&mut self, handler: Handler, buffer: &mut Vec<u8>) -> color_eyre::Result<()>;
+
+    /// Returns true if `data` holds something other than what is currently loaded.
+    fn differs(&mut self, handler: Handler, data: &[u8]) -> color_eyre::Result<bool>;
+
+    /// Replaces what is currently loaded with `data` and marks it as modified.
+    fn recover(&mut self, handler: Handler, data: &[u8]) -> color_eyre::Result<()>;
+}
+
+pub(super) type Entries<'a> = Vec<(Entry, Box<dyn Recoverable + 'a>)>;
+
+/// A nil padded database, like `Actors`.
+struct NilPadded<'a, T> {
+    data: &'a mut Vec<T>,
+    modified: &'a mut bool,
+}
+
+/// A database that isn't nil padded, like `MapInfos`.
+struct Plain<'a, T> {
+    data: &'a mut T,
+    modified: &'a mut bool,
+}
+
+/// A single object that tracks whether it is modified itself, like a map.
+struct Whole<'a, T> {
+    data: &'a mut T,
+}
+
+trait Modified {
+    fn modified(&mut self) -> &mut bool;
+}
+
+macro_rules! impl_modified {
+    ($($type:ty),* $(,)?) => {
+        $(
+            impl Modified for $type {
+                fn modified(&mut self) -> &mut bool {
+                    &mut self.modified
+                }
+            }
+        )*
+    };
+}
+
+impl_modified! {
+    rpg::System,
+    rpg::Map,
+    rmvx::System,
+    rmvx::Map,
+    rmvxace::System,
+    rmvxace::Map,
+}
+
+impl<T> Recoverable for NilPadded<'_, T>
+where
+    T: ::serde::Serialize + ::serde::de::DeserializeOwned,
+    T: alox_48::Serialize + for<'de> alox_48::Deserialize<'de>,
+    T: luminol_data::HasExtraIvars,
+{
+    fn is_modified(&mut self) -> bool {
+        *self.modified
+    }
+
+    fn write(&mut self, handler: Handler, buffer: &mut Vec<u8>) -> color_eyre::Result<()> {
+        handler.write_nil_padded_to(self.data, buffer)
+    }
+
+    fn differs(&mut self, handler: Handler, data: &[u8]) -> color_eyre::Result<bool> {
+        let autosaved: Vec<T> = handler.read_nil_padded_from(data)?;
+        Ok(serde_json::to_value(&autosaved)? != serde_json::to_value(&*self.data)?)
+    }
+
+    fn recover(&mut self, handler: Handler, data: &[u8]) -> color_eyre::Result<()> {
+        *self.data = handler.read_nil_padded_from(data)?;
+        *self.modified = true;
+        Ok(())
+    }
+}
+
+impl<T> Recoverable for Plain<'_, T>
+where
+    T: ::serde::Serialize + ::serde::de::DeserializeOwned,
+    T: alox_48::Serialize + for<'de> alox_48::Deserialize<'de>,
+    T: luminol_data::HasExtraIvars,
+{
+    fn is_modified(&mut self) -> bool {
+        *self.modified
+    }
+
+    fn write(&mut self, handler: Handler, buffer: &mut Vec<u8>) -> color_eyre::Result<()> {
+        handler.write_data_to(self.data, buffer)
+    }
+
+    fn differs(&mut self, handler: Handler, data: &[u8]) -> color_eyre::Result<bool> {
+        let autosaved: T = handler.read_data_from(data)?;
+        Ok(serde_json::to_value(&autosaved)? != serde_json::to_value(&*self.data)?)
+    }
+
+    fn recover(&mut self, handler: Handler, data: &[u8]) -> color_eyre::Result<()> {
+        *self.data = handler.read_data_from(data)?;
+        *self.modified = true;
+        Ok(())
+    }
+}
+
+impl<T> Recoverable for Whole<'_, T>
+where
+    T: ::serde::Serialize + ::serde::de::DeserializeOwned,
+    T: alox_48::Serialize + for<'de> alox_48::Deserialize<'de>,
+    T: luminol_data::HasExtraIvars,
+    T: Modified,
+{
+    fn is_modified(&mut self) -> bool {
+        *self.data.modified()
+    }
+
+    fn write(&mut self, handler: Handler, buffer: &mut Vec<u8>) -> color_eyre::Result<()> {
+        handler.write_data_to(self.data, buffer)
+    }
+
+    fn differs(&mut self, handler: Handler, data: &[u8]) -> color_eyre::Result<bool> {
+        let autosaved: T = handler.read_data_from(data)?;
+        Ok(serde_json::to_value(&autosaved)? != serde_json::to_value(&*self.data)?)
+    }
+
+    fn recover(&mut self, handler: Handler, data: &[u8]) -> color_eyre::Result<()> {
+        *self.data = handler.read_data_from(data)?;
+        *self.data.modified() = true;
+        Ok(())
+    }
+}
+
+macro_rules! nil_padded {
+    ($type:ident, $field:ident) => {{
+        let field = $field.get_mut();
+        (
+            Entry::Database(stringify!($type).to_string()),
+            Box::new(NilPadded {
+                data: &mut field.data,
+                modified: &mut field.modified,
+            }) as Box<dyn Recoverable + '_>,
+        )
+    }};
+}
+
+macro_rules! plain {
+    ($type:ident, $field:ident) => {{
+        let field = $field.get_mut();
+        (
+            Entry::Database(stringify!($type).to_string()),
+            Box::new(Plain {
+                data: &mut field.data,
+                modified: &mut field.modified,
+            }) as Box<dyn Recoverable + '_>,
+        )
+    }};
+}
+
+macro_rules! whole {
+    ($type:ident, $field:ident) => {
+        (
+            Entry::Database(stringify!($type).to_string()),
+            Box::new(Whole {
+                data: $field.get_mut(),
+            }) as Box<dyn Recoverable + '_>,
+        )
+    };
+}
+
+/// Lists every database, and the maps in the cache.
+///
+/// If `map_ids` is given, only those maps are listed. Maps that aren't in the cache are then
+/// skipped, or inserted as empty maps if `insert_maps` is true.
+pub(super) fn entries<'a>(
+    data: &'a mut Data,
+    map_ids: Option<&[usize]>,
+    insert_maps: bool,
+) -> Entries<'a> {
+    fn map_entries<'a, T>(
+        maps: &'a mut HashMap<usize, T>,
+        map_ids: Option<&[usize]>,
+        insert_maps: bool,
+    ) -> Entries<'a>
+    where
+        T: ::serde::Serialize + ::serde::de::DeserializeOwned,
+        T: alox_48::Serialize + for<'de> alox_48::Deserialize<'de>,
+        T: luminol_data::HasExtraIvars,
+        T: Modified + Default + 'a,
+    {
+        if let Some(map_ids) = map_ids.filter(|_| insert_maps) {
+            for &id in map_ids {
+                maps.entry(id).or_default();
+            }
+        }
+        maps.iter_mut()
+            .filter(|(id, _)| map_ids.map_or(true, |map_ids| map_ids.contains(id)))
+            .map(|(&id, map)| {
+                (
+                    Entry::Map(id),
+                    Box::new(Whole { data: map }) as Box<dyn Recoverable + 'a>,
+                )
+            })
+            .collect()
+    }
+
+    match data {
+        Data::Unloaded => panic!("project not loaded"),
+        Data::Loaded {
+            actors,
+            animations,
+            armors,
+            classes,
+            common_events,
+            enemies,
+            items,
+            map_infos,
+            scripts,
+            skills,
+            states,
+            system,
+            tilesets,
+            troops,
+            weapons,
+            maps,
+            ..
+        } => {
+            let mut entries = vec![
+                nil_padded!(Actors, actors),
+                nil_padded!(Animations, animations),
+                nil_padded!(Armors, armors),
+                nil_padded!(Classes, classes),
+                nil_padded!(CommonEvents, common_events),
+                nil_padded!(Enemies, enemies),
+                nil_padded!(Items, items),
+                plain!(MapInfos, map_infos),
+                plain!(Scripts, scripts),
+                nil_padded!(Skills, skills),
+                nil_padded!(States, states),
+                whole!(System, system),
+                nil_padded!(Tilesets, tilesets),
+                nil_padded!(Troops, troops),
+                nil_padded!(Weapons, weapons),
+            ];
+            entries.extend(map_entries(maps.get_mut(), map_ids, insert_maps));
+            entries
+        }
+        Data::LoadedVX {
+            actors,
+            animations,
+            areas,
+            armors,
+            classes,
+            common_events,
+            enemies,
+            items,
+            map_infos,
+            scripts,
+            skills,
+            states,
+            system,
+            troops,
+            weapons,
+            maps,
+            ..
+        } => {
+            let mut entries = vec![
+                nil_padded!(Actors, actors),
+                nil_padded!(Animations, animations),
+                nil_padded!(Areas, areas),
+                nil_padded!(Armors, armors),
+                nil_padded!(Classes, classes),
+                nil_padded!(CommonEvents, common_events),
+                nil_padded!(Enemies, enemies),
+                nil_padded!(Items, items),
+                plain!(MapInfos, map_infos),
+                plain!(Scripts, scripts),
+                nil_padded!(Skills, skills),
+                nil_padded!(States, states),
+                whole!(System, system),
+                nil_padded!(Troops, troops),
+                nil_padded!(Weapons, weapons),
+            ];
+            entries.extend(map_entries(maps.get_mut(), map_ids, insert_maps));
+            entries
+        }
+        Data::LoadedAce {
+            actors,
+            animations,
+            armors,
+            classes,
+            common_events,
+            enemies,
+            items,
+            map_infos,
+            scripts,
+            skills,
+            states,
+            system,
+            tilesets,
+            troops,
+            weapons,
+            maps,
+            ..
+        } => {
+            let mut entries = vec![
+                nil_padded!(Actors, actors),
+                nil_padded!(Animations, animations),
+                nil_padded!(Armors, armors),
+                nil_padded!(Classes, classes),
+                nil_padded!(CommonEvents, common_events),
+                nil_padded!(Enemies, enemies),
+                nil_padded!(Items, items),
+                plain!(MapInfos, map_infos),
+                plain!(Scripts, scripts),
+                nil_padded!(Skills, skills),
+                nil_padded!(States, states),
+                whole!(System, system),
+                nil_padded!(Tilesets, tilesets),
+                nil_padded!(Troops, troops),
+                nil_padded!(Weapons, weapons),
+            ];
+            entries.extend(map_entries(maps.get_mut(), map_ids, insert_maps));
+            entries
+        }
+    }
+}
@@ -0,0 +1,220 @@
+// Copyright (C) 2024 Melody Madeline Lyons
+//
+// This file is part of Luminol.
+//
+// Luminol is free software: you can redistribute it and/or modify
+// it under the terms of the GNU General Public License as published by
+// the Free Software Foundation, either version 3 of the License, or
+// (at your option) any later version.
+//
+// Luminol is distributed in the hope that it will be useful,
+// but WITHOUT ANY WARRANTY; without even the implied warranty of
+// MERCHANTABILITY or FITNESS FOR A PARTICULAR PURPOSE.  See the
+// GNU General Public License for more details.
+//
+// You should have received a copy of the GNU General Public License
+// along with Luminol.  If not, see <http://www.gnu.org/licenses/>.
+//
+//     Additional permission under GNU GPL version 3 section 7
+//
+// If you modify this Program, or any covered work, by linking or combining
+// it with Steamworks API by Valve Corporation, containing parts covered by
+// terms of the Steamworks API by Valve Corporation, the licensors of this
+// Program grant you additional permission to convey the resulting work.
+
+use color_eyre::eyre::WrapErr;
+use luminol_filesystem::FileSystem;
+
+/// A set of files that are written to a staging folder first and then swapped into place together,
+/// so that a failure partway through never leaves the project with half-written files.
+pub struct Transaction {
+    staging_dir: &'static str,
+    staged: Vec<camino::Utf8PathBuf>,
+    removed: Vec<camino::Utf8PathBuf>,
+}
+
+impl Transaction {
+    /// Starts a transaction that stages files in `staging_dir`.
+    /// Anything left over in `staging_dir` from an earlier, interrupted transaction is removed.
+    pub fn new(
+        filesystem: &impl FileSystem,
+        staging_dir: &'static str,
+    ) -> color_eyre::Result<Self> {
+        if filesystem.exists(staging_dir)? {
+            filesystem.remove_dir(staging_dir)?;
+        }
+        filesystem.create_dir(staging_dir)?;
+
+        Ok(Self {
+            staging_dir,
+            staged: Vec::new(),
+            removed: Vec::new(),
+        })
+    }
+
+    /// The path that a file or directory at `path` should be staged at.
+    pub fn staging_path(&self, path: impl AsRef<camino::Utf8Path>) -> camino::Utf8PathBuf {
+        camino::Utf8Path::new(self.staging_dir).join(path)
+    }
+
+    /// Records that `path` has been written to its staging path and should be swapped in on commit.
+    pub fn stage(&mut self, path: impl Into<camino::Utf8PathBuf>) {
+        self.staged.push(path.into());
+    }
+
+    /// Records that the file at `path` should be removed on commit.
+    pub fn stage_removal(&mut self, path: impl Into<camino::Utf8PathBuf>) {
+        self.removed.push(path.into());
+    }
+
+    /// Writes `data` to the staging path of `path` and stages it.
+    pub fn write(
+        &mut self,
+        filesystem: &impl FileSystem,
+        path: impl Into<camino::Utf8PathBuf>,
+        data: impl AsRef<[u8]>,
+    ) -> color_eyre::Result<()> {
+        let path = path.into();
+        let staging_path = self.staging_path(&path);
+        if let Some(parent) = staging_path.parent() {
+            filesystem.create_dir(parent)?;
+        }
+        filesystem
+            .write(&staging_path, data)
+            .wrap_err_with(|| format!("While writing {path}"))?;
+        self.stage(path);
+        Ok(())
+    }
+
+    /// Swaps every staged file into place and removes the files staged for removal.
+    ///
+    /// Existing files are moved aside first and only deleted once everything has been swapped in.
+    /// If anything fails, the files that were already swapped are put back and the error is returned.
+    pub fn commit(self, filesystem: &impl FileSystem) -> color_eyre::Result<()> {
+        let aside_dir = self.staging_path(".old");
+
+        let mut journal = Vec::with_capacity(self.staged.len() * 2 + self.removed.len());
+        let result = (|| -> color_eyre::Result<()> {
+            for path in self.staged.iter() {
+                if filesystem.exists(path)? {
+                    let aside_path = aside_dir.join(path);
+                    if let Some(parent) = aside_path.parent() {
+                        filesystem.create_dir(parent)?;
+                    }
+                    filesystem
+                        .rename(path, &aside_path)
+                        .wrap_err_with(|| format!("While moving {path} aside"))?;
+                    journal.push((aside_path, path.clone()));
+                }
+
+                if let Some(parent) = path.parent() {
+                    filesystem.create_dir(parent)?;
+                }
+                filesystem
+                    .rename(self.staging_path(path), path)
+                    .wrap_err_with(|| format!("While moving {path} into place"))?;
+                journal.push((path.clone(), self.staging_path(path)));
+            }
+
+            for path in self.removed.iter() {
+                if filesystem.exists(path)? {
+                    let aside_path = aside_dir.join(path);
+                    if let Some(parent) = aside_path.parent() {
+                        filesystem.create_dir(parent)?;
+                    }
+                    filesystem
+                        .rename(path, &aside_path)
+                        .wrap_err_with(|| format!("While removing {path}"))?;
+                    journal.push((aside_path, path.clone()));
+                }
+            }
+            Ok(())
+        })();
+
+        if let Err(error) = result {
+            // undo in reverse order, so that files moved aside are only restored once whatever
+            // replaced them has been moved out of the way again
+            for (from, to) in journal.into_iter().rev() {
+                if let Err(undo_error) = filesystem.rename(&from, &to) {
+                    return Err(error.wrap_err(format!(
+                        "Additionally, restoring {to} failed: {undo_error:?}"
+                    )));
+                }
+            }
+            self.rollback(filesystem)?;
+            return Err(error);
+        }
+
+        self.rollback(filesystem)
+    }
+
+    /// Discards everything that was staged.
+    pub fn rollback(self, filesystem: &impl FileSystem) -> color_eyre::Result<()> {
+        if filesystem.exists(self.staging_dir)? {
+            filesystem
+                .remove_dir(self.staging_dir)
+                .wrap_err("While removing staged files")?;
+        }
+        Ok(())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    const STAGING_DIR: &str = ".luminol/staging";
+
+    /// A filesystem in a temporary folder with the files `a.txt` and `b.txt`.
+    fn filesystem() -> (tempfile::TempDir, luminol_filesystem::host::FileSystem) {
+        let (dir, filesystem) = crate::data_cache::test_fixtures::filesystem();
+        filesystem.write("a.txt", "old a").unwrap();
+        filesystem.write("b.txt", "old b").unwrap();
+        (dir, filesystem)
+    }
+
+    #[test]
+    fn commit_replaces_and_removes_files() {
+        let (_dir, filesystem) = filesystem();
+
+        let mut transaction = Transaction::new(&filesystem, STAGING_DIR).unwrap();
+        transaction.write(&filesystem, "a.txt", "new a").unwrap();
+        transaction.write(&filesystem, "c/c.txt", "new c").unwrap();
+        transaction.stage_removal("b.txt");
+        transaction.commit(&filesystem).unwrap();
+
+        assert_eq!(filesystem.read_to_string("a.txt").unwrap(), "new a");
+        assert_eq!(filesystem.read_to_string("c/c.txt").unwrap(), "new c");
+        assert!(!filesystem.exists("b.txt").unwrap());
+        assert!(!filesystem.exists(STAGING_DIR).unwrap());
+    }
+
+    #[test]
+    fn rollback_leaves_files_untouched() {
+        let (_dir, filesystem) = filesystem();
+
+        let mut transaction = Transaction::new(&filesystem, STAGING_DIR).unwrap();
+        transaction.write(&filesystem, "a.txt", "new a").unwrap();
+        transaction.stage_removal("b.txt");
+        transaction.rollback(&filesystem).unwrap();
+
+        assert_eq!(filesystem.read_to_string("a.txt").unwrap(), "old a");
+        assert_eq!(filesystem.read_to_string("b.txt").unwrap(), "old b");
+        assert!(!filesystem.exists(STAGING_DIR).unwrap());
+    }
+
+    #[test]
+    fn failed_commit_restores_swapped_files() {
+        let (_dir, filesystem) = filesystem();
+
+        let mut transaction = Transaction::new(&filesystem, STAGING_DIR).unwrap();
+        transaction.write(&filesystem, "a.txt", "new a").unwrap();
+        // never written to the staging folder, so swapping it in fails after a.txt was swapped
+        transaction.stage("b.txt");
+        assert!(transaction.commit(&filesystem).is_err());
+
+        assert_eq!(filesystem.read_to_string("a.txt").unwrap(), "old a");
+        assert_eq!(filesystem.read_to_string("b.txt").unwrap(), "old b");
+        assert!(!filesystem.exists(STAGING_DIR).unwrap());
+    }
+}
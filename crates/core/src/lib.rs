@@ -35,7 +35,7 @@ pub mod modal;
 pub use modal::Modal;
 
 mod data_cache;
-pub use data_cache::Data;
+pub use data_cache::{Data, Entry};
 
 /// Toasts to be displayed for errors, information, etc.
 mod toasts;
@@ -46,7 +46,10 @@ pub use project_manager::spawn_future;
 pub use project_manager::ProjectManager;
 
 pub use alox_48;
+pub use data_cache::backups;
+pub use data_cache::convert;
 pub use data_cache::data_formats::{self, format_traced_error};
+pub use data_cache::recovery;
 
 pub mod prelude {
     pub use crate::{Modal, Tab, UpdateState, Window};
@@ -179,9 +182,22 @@ pub struct ToolbarState {
 pub enum Pencil {
     #[default]
     Pen,
+    Line,
+    /// Draws the outline of an ellipse.
     Circle,
+    #[strum(to_string = "Filled Circle")]
+    FilledCircle,
+    /// Draws the outline of a rectangle.
     Rectangle,
+    #[strum(to_string = "Filled Rectangle")]
+    FilledRectangle,
     Fill,
+    /// Picks the tile under the cursor into the tilepicker.
+    Eyedropper,
+    /// Paints the selected terrain, picking its edge and corner tiles based on the tiles around it.
+    Terrain,
+    /// Selects a rectangle of the map to copy, cut or paste.
+    Select,
 }
 
 impl Default for ToolbarState {
@@ -246,6 +262,7 @@ impl<'res> UpdateState<'res> {
     pub fn manage_projects(&mut self, show_modal: bool) {
         let mut should_close = false;
         let mut should_save = false;
+        let mut should_discard = false;
         let mut should_run_closure = false;
         let mut should_focus_save_button = false;
 
@@ -277,6 +294,7 @@ impl<'res> UpdateState<'res> {
                         should_close = true;
                     } else if discard_button.clicked() {
                         should_close = true;
+                        should_discard = true;
                         should_run_closure = true;
                     } else if save_button.clicked() {
                         should_close = true;
@@ -311,6 +329,14 @@ impl<'res> UpdateState<'res> {
                 }
             }
 
+            if should_discard {
+                if let Err(e) =
+                    recovery::discard(self.filesystem, self.project_config.as_ref().unwrap())
+                {
+                    error!(self.toasts, e.wrap_err("Error removing autosaved data"));
+                }
+            }
+
             if should_run_closure {
                 if let Some(closure) = self.project_manager.closure.take() {
                     closure(self);
@@ -320,9 +346,233 @@ impl<'res> UpdateState<'res> {
             self.project_manager.closure = None;
         }
 
+        if show_modal {
+            self.show_recovery_modal();
+            self.show_conflict_modal();
+        }
+
         self.handle_project_loading();
     }
 
+    /// Asks the user whether to restore autosaved changes found when opening the project.
+    fn show_recovery_modal(&mut self) {
+        let Some(recovered) = self.project_manager.recovered.as_ref() else {
+            return;
+        };
+        let mut should_restore = false;
+        let mut should_close = false;
+
+        self.project_manager.recovery_modal.open();
+        self.project_manager.recovery_modal.show(|ui| {
+            let modal = &self.project_manager.recovery_modal;
+            modal.title(ui, "Recover Unsaved Changes");
+            modal.frame(ui, |ui| {
+                modal.body(
+                    ui,
+                    "Luminol closed without saving this project. Do you want to restore the unsaved changes to these files?",
+                );
+                for entry in recovered.entries.iter() {
+                    ui.label(format!("• {entry}"));
+                }
+                for id in recovered.deleted_maps.iter() {
+                    ui.label(format!("• Map {id:0>3} (deleted)"));
+                }
+            });
+            modal.buttons(ui, |ui| {
+                if modal.suggested_button(ui, "Restore").clicked() {
+                    should_restore = true;
+                    should_close = true;
+                } else if modal.caution_button(ui, "Discard").clicked() {
+                    should_close = true;
+                }
+            });
+        });
+
+        if !should_close {
+            return;
+        }
+        self.project_manager.recovery_modal.close();
+        let recovered = self.project_manager.recovered.take().unwrap();
+        let config = self.project_config.as_ref().unwrap();
+
+        if should_restore {
+            match recovery::restore(self.data, self.filesystem, config, &recovered) {
+                Ok(()) => {
+                    self.modified.set(true);
+                    info!(self.toasts, "Restored unsaved changes");
+                }
+                Err(e) => error!(self.toasts, e.wrap_err("Error restoring unsaved changes")),
+            }
+        } else if let Err(e) = recovery::discard(self.filesystem, config) {
+            error!(self.toasts, e.wrap_err("Error removing autosaved data"));
+        }
+    }
+
+    /// Reloads data and graphics that were changed by other programs.
+    ///
+    /// Databases and maps with unsaved changes aren't reloaded straight away, the user is asked
+    /// whether to reload them or keep their changes instead.
+    pub fn handle_file_changes(&mut self) {
+        let changes = self.filesystem.changes();
+        if changes.is_empty() || self.data.editor_ver().is_none() {
+            return;
+        }
+        let Some(config) = self.project_config.as_ref() else {
+            return;
+        };
+
+        let is_in = |dir: &str| {
+            changes
+                .iter()
+                .any(|path| path.as_str().to_lowercase().starts_with(dir))
+        };
+        for path in changes.iter() {
+            self.graphics.texture_loader.invalidate(path);
+            self.ctx.forget_image(&format!(
+                "{}{path}",
+                luminol_filesystem::egui_bytes_loader::PROTOCOL
+            ));
+        }
+        if is_in("graphics/animations/") {
+            self.graphics.atlas_loader.clear_animation_atlases();
+        }
+        if (is_in("graphics/tilesets/") || is_in("graphics/autotiles/"))
+            && self.data.editor_ver() == Some(luminol_config::RMVer::XP)
+        {
+            let tilesets = self.data.tilesets();
+            for id in self.graphics.atlas_loader.loaded_atlas_ids() {
+                if let Some(tileset) = tilesets.data.get(id) {
+                    self.graphics.atlas_loader.reload_atlas(
+                        &self.graphics,
+                        self.filesystem,
+                        tileset,
+                    );
+                }
+            }
+        }
+
+        let mut reloaded = Vec::new();
+        let mut failed = Vec::new();
+        for entry in self.data.entries_at(config, &changes) {
+            if self.data.is_modified(&entry) {
+                if !self
+                    .project_manager
+                    .conflicts
+                    .iter()
+                    .any(|(e, _)| *e == entry)
+                {
+                    self.project_manager.conflicts.push((entry, false));
+                }
+                continue;
+            }
+            match self.data.reload(&entry, self.filesystem, config) {
+                Ok(()) => reloaded.push(entry),
+                Err(e) => {
+                    error!(
+                        self.toasts,
+                        e.wrap_err(format!(
+                            "Error reloading {entry}, which was changed by another program"
+                        ))
+                    );
+                    failed.push(entry);
+                }
+            }
+        }
+
+        self.close_map_tabs(&failed);
+        if !reloaded.is_empty() {
+            self.close_map_tabs(&reloaded);
+            info!(
+                self.toasts,
+                format!(
+                    "Reloaded {} because another program changed them",
+                    itertools::join(reloaded.iter(), ", ")
+                )
+            );
+        }
+    }
+
+    /// Closes the tabs of maps that were reloaded or failed to reload, since map tabs only read
+    /// the map when they're opened.
+    fn close_map_tabs(&mut self, entries: &[Entry]) {
+        for id in entries.iter().filter_map(Entry::map_id) {
+            // the map tab's ID, see luminol_ui::tabs::map::Tab
+            self.edit_tabs
+                .remove_tab_by_id(egui::Id::new("luminol_map").with(id));
+        }
+    }
+
+    /// Asks the user whether to reload files with unsaved changes that another program changed.
+    fn show_conflict_modal(&mut self) {
+        if self.project_manager.conflicts.is_empty() {
+            return;
+        }
+        let mut should_apply = false;
+
+        self.project_manager.conflict_modal.open();
+        self.project_manager.conflict_modal.show(|ui| {
+            let modal = &self.project_manager.conflict_modal;
+            modal.title(ui, "Files Changed by Another Program");
+            modal.frame(ui, |ui| {
+                modal.body(
+                    ui,
+                    "Another program changed these files, but you have unsaved changes to them. Choose which ones to reload. Your changes to the others are kept, and will replace the other program's changes when you save.",
+                );
+                for (entry, reload) in self.project_manager.conflicts.iter_mut() {
+                    ui.checkbox(reload, format!("Reload {entry}"));
+                }
+            });
+            modal.buttons(ui, |ui| {
+                should_apply = modal.suggested_button(ui, "OK").clicked();
+            });
+        });
+
+        if !should_apply {
+            return;
+        }
+        self.project_manager.conflict_modal.close();
+        let config = self.project_config.as_ref().unwrap();
+
+        let mut reloaded = Vec::new();
+        for (entry, reload) in std::mem::take(&mut self.project_manager.conflicts) {
+            if !reload {
+                continue;
+            }
+            if let Err(e) = self.data.reload(&entry, self.filesystem, config) {
+                error!(self.toasts, e.wrap_err(format!("Error reloading {entry}")));
+            }
+            reloaded.push(entry);
+        }
+        self.close_map_tabs(&reloaded);
+    }
+
+    /// Autosaves modified data for crash recovery once the project's autosave interval has passed.
+    pub fn autosave(&mut self) {
+        if self.modified.get_this_frame() {
+            self.project_manager.autosave_pending = true;
+        }
+
+        let Some(config) = self.project_config.as_ref() else {
+            return;
+        };
+        let interval = std::time::Duration::from_secs(config.project.autosave_interval);
+        if interval.is_zero()
+            || !self.project_manager.autosave_pending
+            || self.project_manager.last_autosave.elapsed() < interval
+            // don't overwrite recovered changes before the user has decided what to do with them
+            || self.project_manager.recovered.is_some()
+            || self.data.editor_ver().is_none()
+        {
+            return;
+        }
+
+        if let Err(e) = recovery::autosave(self.data, self.filesystem, config) {
+            error!(self.toasts, e.wrap_err("Error autosaving unsaved changes"));
+        }
+        self.project_manager.last_autosave = web_time::Instant::now();
+        self.project_manager.autosave_pending = false;
+    }
+
     fn handle_project_loading(&mut self) {
         let mut filesystem_open_result = None;
 
@@ -370,7 +620,7 @@ impl<'res> UpdateState<'res> {
                     info!(self.toasts, format!("Please place the {missing_rtp} RTP in the 'RTP/{missing_rtp}' subdirectory in your project directory"));
                 }
 
-                if let Err(error) = self.data.load(
+                if let Err(error) = self.data.load_partial(
                     self.filesystem,
                     // TODO code jank
                     self.toasts,
@@ -389,6 +639,28 @@ impl<'res> UpdateState<'res> {
                             self.filesystem.project_path().expect("project not open")
                         )
                     );
+
+                    let failed =
+                        itertools::join(self.data.load_errors().map(|(name, _)| name), ", ");
+                    if !failed.is_empty() {
+                        warn!(
+                            self.toasts,
+                            format!("{failed} could not be loaded, and can't be edited until they are fixed")
+                        );
+                        self.project_manager.show_load_errors = true;
+                    }
+
+                    match recovery::check(
+                        self.data,
+                        self.filesystem,
+                        self.project_config.as_ref().unwrap(),
+                    ) {
+                        Ok(recovered) => self.project_manager.recovered = recovered,
+                        Err(e) => error!(
+                            self.toasts,
+                            e.wrap_err("Error checking for unsaved changes from the last session")
+                        ),
+                    }
                 }
             }
             Some(Err(error)) => {
@@ -441,6 +713,10 @@ impl<'res> UpdateState<'res> {
         *self.project_config = None;
         self.data.unload();
         self.modified.set(false);
+        self.project_manager.recovered = None;
+        self.project_manager.autosave_pending = false;
+        self.project_manager.conflicts.clear();
+        self.project_manager.show_load_errors = false;
     }
 }
 
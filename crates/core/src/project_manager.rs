@@ -29,6 +29,23 @@ pub struct ProjectManager {
     pub create_project_promise: Option<poll_promise::Promise<CreateProjectPromiseResult>>,
     pub load_filesystem_promise: Option<poll_promise::Promise<FileSystemPromiseResult>>,
     pub filesystem_open_result: Option<FileSystemOpenResult>,
+
+    pub(crate) recovery_modal: egui_modal::Modal,
+    /// Autosaved changes found when opening the project, waiting for the user to restore or
+    /// discard them.
+    pub(crate) recovered: Option<crate::recovery::Recovered>,
+    pub(crate) last_autosave: web_time::Instant,
+    /// Whether anything has been modified since the last autosave.
+    pub(crate) autosave_pending: bool,
+
+    pub(crate) conflict_modal: egui_modal::Modal,
+    /// Databases and maps that were changed by another program while they had unsaved changes,
+    /// and whether the user wants to reload them.
+    pub(crate) conflicts: Vec<(crate::Entry, bool)>,
+
+    /// Set when a project was opened even though some of its databases couldn't be loaded, so
+    /// that the frontend can show the user what went wrong.
+    pub show_load_errors: bool,
 }
 
 pub struct CreateProjectResult {
@@ -68,12 +85,19 @@ impl ProjectManager {
             create_project_promise: None,
             load_filesystem_promise: None,
             filesystem_open_result: None,
+            recovery_modal: egui_modal::Modal::new(ctx, "luminol_recovery_modal"),
+            recovered: None,
+            last_autosave: web_time::Instant::now(),
+            autosave_pending: false,
+            conflict_modal: egui_modal::Modal::new(ctx, "luminol_conflict_modal"),
+            conflicts: Vec::new(),
+            show_load_errors: false,
         }
     }
 
     /// Returns whether or not the unsaved changes modal is currently open.
     pub fn is_modal_open(&self) -> bool {
-        self.modal.is_open()
+        self.modal.is_open() || self.recovery_modal.is_open() || self.conflict_modal.is_open()
     }
 
     /// Returns whether or not a file or filder picker is currently open.
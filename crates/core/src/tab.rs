@@ -77,6 +77,10 @@ impl Tabs {
         if let Some(f) = edit_tabs.clean_fn.take() {
             self.clean_tabs(f);
         }
+        if !edit_tabs.removed.is_empty() {
+            let removed = std::mem::take(&mut edit_tabs.removed);
+            self.clean_tabs(|tab| !removed.contains(&tab.id()));
+        }
     }
 
     pub fn ui_without_edit(
@@ -224,6 +224,11 @@ pub enum ParameterKind {
     Int,
     /// Parameter is a bool stored as an integer
     IntBool,
+    /// Parameter is a bool
+    Bool,
+
+    /// Parameter is a move route
+    MoveRoute,
 
     /// Parameter is a choice between a set of enums
     ///
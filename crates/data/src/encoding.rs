@@ -0,0 +1,332 @@
+// Copyright (C) 2024 Melody Madeline Lyons
+//
+// This file is part of Luminol.
+//
+// Luminol is free software: you can redistribute it and/or modify
+// it under the terms of the GNU General Public License as published by
+// the Free Software Foundation, either version 3 of the License, or
+// (at your option) any later version.
+//
+// Luminol is distributed in the hope that it will be useful,
+// but WITHOUT ANY WARRANTY; without even the implied warranty of
+// MERCHANTABILITY or FITNESS FOR A PARTICULAR PURPOSE.  See the
+// GNU General Public License for more details.
+//
+// You should have received a copy of the GNU General Public License
+// along with Luminol.  If not, see <http://www.gnu.org/licenses/>.
+
+//! Conversion of the strings in Marshal data between UTF-8 and legacy encodings like Shift-JIS.
+//!
+//! Ruby strings are just bytes, and RPG Maker XP and VX write them in whatever encoding the system
+//! used. Luminol works with UTF-8, so strings are decoded after reading Marshal data and encoded
+//! again before writing it.
+
+use alox_48::Value;
+use encoding_rs::Encoding;
+
+/// Calls `f` with the bytes of every string in `value`, including hash keys and strings nested
+/// in objects.
+fn for_each_string(value: &Value, f: &mut impl FnMut(&[u8])) {
+    match value {
+        Value::String(string) => f(&string.data),
+        Value::Array(values) => values.iter().for_each(|v| for_each_string(v, f)),
+        Value::Hash(hash) => hash.iter().for_each(|(key, value)| {
+            for_each_string(key, f);
+            for_each_string(value, f);
+        }),
+        Value::Object(object) => object.fields.values().for_each(|v| for_each_string(v, f)),
+        Value::Instance(instance) => for_each_string(&instance.value, f),
+        _ => {}
+    }
+}
+
+/// Calls `f` with the bytes of every string in `value`, allowing them to be replaced.
+fn for_each_string_mut(value: &mut Value, f: &mut impl FnMut(&mut Vec<u8>)) {
+    match value {
+        Value::String(string) => f(&mut string.data),
+        Value::Array(values) => values.iter_mut().for_each(|v| for_each_string_mut(v, f)),
+        Value::Hash(hash) => {
+            // keys can't be modified in place, so the hash has to be rebuilt
+            *hash = std::mem::take(hash)
+                .into_iter()
+                .map(|(mut key, mut value)| {
+                    for_each_string_mut(&mut key, f);
+                    for_each_string_mut(&mut value, f);
+                    (key, value)
+                })
+                .collect();
+        }
+        Value::Object(object) => object
+            .fields
+            .values_mut()
+            .for_each(|v| for_each_string_mut(v, f)),
+        Value::Instance(instance) => for_each_string_mut(&mut instance.value, f),
+        _ => {}
+    }
+}
+
+/// Decodes `bytes` from `encoding`, returning `None` if they aren't valid in it.
+fn decode(bytes: &[u8], encoding: &'static Encoding) -> Option<String> {
+    encoding
+        .decode_without_bom_handling_and_without_replacement(bytes)
+        .map(|string| string.into_owned())
+}
+
+/// Replaces `bytes` with their UTF-8 decoding from `encoding`, unless they're already valid UTF-8
+/// or aren't valid in `encoding`.
+///
+/// Legacy encodings like Windows-1252 accept almost any bytes, so text that was already written as
+/// UTF-8 by a newer editor would otherwise be decoded a second time.
+fn decode_in_place(bytes: &mut Vec<u8>, encoding: &'static Encoding) {
+    if std::str::from_utf8(bytes).is_ok() {
+        return;
+    }
+    if let Some(string) = decode(bytes, encoding) {
+        *bytes = string.into_bytes();
+    }
+}
+
+/// Encodes `string` into `encoding`, returning `None` if it has characters `encoding` can't
+/// represent.
+fn encode(string: &str, encoding: &'static Encoding) -> Option<Vec<u8>> {
+    let (bytes, _, had_unmappable) = encoding.encode(string);
+    (!had_unmappable).then(|| bytes.into_owned())
+}
+
+/// Returns true if every string in `value` is valid UTF-8.
+pub fn is_utf8(value: &Value) -> bool {
+    decodes_as(value, encoding_rs::UTF_8)
+}
+
+/// Returns true if every string in `value` is valid in `encoding`.
+pub fn decodes_as(value: &Value, encoding: &'static Encoding) -> bool {
+    let mut valid = true;
+    for_each_string(value, &mut |bytes| {
+        valid &= encoding
+            .decode_without_bom_handling_and_without_replacement(bytes)
+            .is_some();
+    });
+    valid
+}
+
+/// Converts every string in `value` from `encoding` to UTF-8.
+///
+/// Strings that are already valid UTF-8 or aren't valid in `encoding` are left as they are, since
+/// they were most likely written as UTF-8 by a newer editor.
+pub fn decode_strings(value: &mut Value, encoding: &'static Encoding) {
+    if encoding == encoding_rs::UTF_8 {
+        return;
+    }
+    for_each_string_mut(value, &mut |bytes| decode_in_place(bytes, encoding));
+}
+
+/// Converts every string in `value` from UTF-8 to `encoding`.
+///
+/// Strings with characters `encoding` can't represent are left as UTF-8 so that no text is lost.
+/// Returns how many strings that happened to.
+pub fn encode_strings(value: &mut Value, encoding: &'static Encoding) -> usize {
+    if encoding == encoding_rs::UTF_8 {
+        return 0;
+    }
+    let mut unencodable = 0;
+    for_each_string_mut(value, &mut |bytes| {
+        let Ok(string) = std::str::from_utf8(bytes) else {
+            return;
+        };
+        match encode(string, encoding) {
+            Some(encoded) => *bytes = encoded,
+            None => unencodable += 1,
+        }
+    });
+    unencodable
+}
+
+/// Runs `f` on the name and the uncompressed text of every script in `value`, which should hold
+/// the contents of a `Scripts` file: an array of `[id, name, zlib compressed text]` arrays.
+fn transcode_scripts(value: &mut Value, f: &mut impl FnMut(&mut Vec<u8>)) -> std::io::Result<()> {
+    use std::io::{Read, Write};
+
+    let Value::Array(scripts) = value else {
+        return Ok(());
+    };
+
+    for script in scripts {
+        let Value::Array(fields) = script else {
+            continue;
+        };
+        if let Some(name) = fields.get_mut(1) {
+            for_each_string_mut(name, f);
+        }
+        let Some(Value::String(data)) = fields.get_mut(2) else {
+            continue;
+        };
+
+        let mut text = Vec::new();
+        flate2::bufread::ZlibDecoder::new(data.data.as_slice()).read_to_end(&mut text)?;
+        f(&mut text);
+
+        let mut encoder = flate2::write::ZlibEncoder::new(Vec::new(), Default::default());
+        encoder.write_all(&text)?;
+        data.data = encoder.finish()?;
+    }
+
+    Ok(())
+}
+
+/// Like [`decode_strings`], for the contents of a `Scripts` file, whose script text is compressed.
+pub fn decode_scripts(value: &mut Value, encoding: &'static Encoding) -> std::io::Result<()> {
+    if encoding == encoding_rs::UTF_8 {
+        return Ok(());
+    }
+    transcode_scripts(value, &mut |bytes| decode_in_place(bytes, encoding))
+}
+
+/// Like [`encode_strings`], for the contents of a `Scripts` file, whose script text is compressed.
+pub fn encode_scripts(value: &mut Value, encoding: &'static Encoding) -> std::io::Result<usize> {
+    if encoding == encoding_rs::UTF_8 {
+        return Ok(0);
+    }
+    let mut unencodable = 0;
+    transcode_scripts(value, &mut |bytes| {
+        let Ok(string) = std::str::from_utf8(bytes) else {
+            return;
+        };
+        match encode(string, encoding) {
+            Some(encoded) => *bytes = encoded,
+            None => unencodable += 1,
+        }
+    })?;
+    Ok(unencodable)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    /// "テスト" in Shift-JIS.
+    const SHIFT_JIS_BYTES: &[u8] = &[0x83, 0x65, 0x83, 0x58, 0x83, 0x67];
+
+    fn string(bytes: &[u8]) -> Value {
+        Value::String(alox_48::RbString {
+            data: bytes.to_vec(),
+        })
+    }
+
+    fn compress(bytes: &[u8]) -> Vec<u8> {
+        use std::io::Write;
+
+        let mut encoder = flate2::write::ZlibEncoder::new(Vec::new(), Default::default());
+        encoder.write_all(bytes).unwrap();
+        encoder.finish().unwrap()
+    }
+
+    fn decompress(bytes: &[u8]) -> Vec<u8> {
+        use std::io::Read;
+
+        let mut text = Vec::new();
+        flate2::bufread::ZlibDecoder::new(bytes)
+            .read_to_end(&mut text)
+            .unwrap();
+        text
+    }
+
+    #[test]
+    fn shift_jis_strings_round_trip() {
+        let mut value = Value::Array(vec![string(SHIFT_JIS_BYTES), string(b"plain")]);
+        assert!(!is_utf8(&value));
+        assert!(decodes_as(&value, encoding_rs::SHIFT_JIS));
+
+        decode_strings(&mut value, encoding_rs::SHIFT_JIS);
+        assert_eq!(
+            value,
+            Value::Array(vec![string("テスト".as_bytes()), string(b"plain")])
+        );
+
+        assert_eq!(encode_strings(&mut value, encoding_rs::SHIFT_JIS), 0);
+        assert_eq!(
+            value,
+            Value::Array(vec![string(SHIFT_JIS_BYTES), string(b"plain")])
+        );
+    }
+
+    #[test]
+    fn utf8_strings_are_not_decoded_again() {
+        let utf8 = Value::Array(vec![string("テスト".as_bytes()), string("café".as_bytes())]);
+
+        let mut value = utf8.clone();
+        decode_strings(&mut value, encoding_rs::SHIFT_JIS);
+        assert_eq!(value, utf8);
+
+        // "é" is valid Windows-1252 too, as almost any bytes are
+        decode_strings(&mut value, encoding_rs::WINDOWS_1252);
+        assert_eq!(value, utf8);
+
+        let script = Value::Array(vec![Value::Array(vec![
+            Value::Integer(1),
+            string("テスト".as_bytes()),
+            string(&compress("テスト".as_bytes())),
+        ])]);
+        let mut value = script.clone();
+        decode_scripts(&mut value, encoding_rs::SHIFT_JIS).unwrap();
+        let Value::Array(scripts) = &value else {
+            panic!("scripts are not an array");
+        };
+        let Value::Array(fields) = &scripts[0] else {
+            panic!("script is not an array");
+        };
+        assert_eq!(fields[1], string("テスト".as_bytes()));
+        let Value::String(text) = &fields[2] else {
+            panic!("script text is not a string");
+        };
+        assert_eq!(decompress(&text.data), "テスト".as_bytes());
+    }
+
+    #[test]
+    fn unencodable_strings_are_kept_as_utf8() {
+        let mut value = Value::Array(vec![string("テスト".as_bytes()), string("✓".as_bytes())]);
+
+        assert_eq!(encode_strings(&mut value, encoding_rs::SHIFT_JIS), 1);
+        assert_eq!(
+            value,
+            Value::Array(vec![string(SHIFT_JIS_BYTES), string("✓".as_bytes())])
+        );
+    }
+
+    #[test]
+    fn shift_jis_scripts_round_trip() {
+        let mut value = Value::Array(vec![Value::Array(vec![
+            Value::Integer(1),
+            string(SHIFT_JIS_BYTES),
+            string(&compress(SHIFT_JIS_BYTES)),
+        ])]);
+
+        decode_scripts(&mut value, encoding_rs::SHIFT_JIS).unwrap();
+        let Value::Array(scripts) = &value else {
+            panic!("scripts are not an array");
+        };
+        let Value::Array(fields) = &scripts[0] else {
+            panic!("script is not an array");
+        };
+        assert_eq!(fields[1], string("テスト".as_bytes()));
+        let Value::String(text) = &fields[2] else {
+            panic!("script text is not a string");
+        };
+        assert_eq!(decompress(&text.data), "テスト".as_bytes());
+
+        assert_eq!(
+            encode_scripts(&mut value, encoding_rs::SHIFT_JIS).unwrap(),
+            0
+        );
+        let Value::Array(scripts) = &value else {
+            panic!("scripts are not an array");
+        };
+        let Value::Array(fields) = &scripts[0] else {
+            panic!("script is not an array");
+        };
+        assert_eq!(fields[1], string(SHIFT_JIS_BYTES));
+        let Value::String(text) = &fields[2] else {
+            panic!("script text is not a string");
+        };
+        assert_eq!(decompress(&text.data), SHIFT_JIS_BYTES);
+    }
+}
@@ -0,0 +1,412 @@
+// Copyright (C) 2024 Melody Madeline Lyons
+//
+// This file is part of Luminol.
+//
+// Luminol is free software: you can redistribute it and/or modify
+// it under the terms of the GNU General Public License as published by
+// the Free Software Foundation, either version 3 of the License, or
+// (at your option) any later version.
+//
+// Luminol is distributed in the hope that it will be useful,
+// but WITHOUT ANY WARRANTY; without even the implied warranty of
+// MERCHANTABILITY or FITNESS FOR A PARTICULAR PURPOSE.  See the
+// GNU General Public License for more details.
+//
+// You should have received a copy of the GNU General Public License
+// along with Luminol.  If not, see <http://www.gnu.org/licenses/>.
+
+use alox_48::Value;
+use base64::Engine;
+
+/// Instance variables of an object that Luminol has no field for, usually added by scripts.
+/// They're kept around so that saving doesn't throw them away.
+///
+/// Marshal data stores them as regular instance variables of the object. Other formats store
+/// each of them as base64 encoded Marshal data, since they can hold any Ruby object.
+#[derive(Debug, Default, Clone, PartialEq)]
+pub struct ExtraIvars(Vec<(String, Value)>);
+
+// Some of the structs holding these implement Eq. Values can only hold floats that aren't equal
+// to themselves if a script stored a NaN, which we can live with.
+impl Eq for ExtraIvars {}
+
+impl ExtraIvars {
+    pub fn is_empty(&self) -> bool {
+        self.0.is_empty()
+    }
+
+    pub fn len(&self) -> usize {
+        self.0.len()
+    }
+
+    /// Iterates over the names (including the leading `@`) and values of the instance variables.
+    pub fn iter(&self) -> impl Iterator<Item = (&str, &Value)> {
+        self.0.iter().map(|(name, value)| (name.as_str(), value))
+    }
+
+    pub fn get(&self, name: &str) -> Option<&Value> {
+        self.iter()
+            .find_map(|(n, value)| (n == name).then_some(value))
+    }
+
+    /// Sets an instance variable, replacing the previous value if there was one.
+    pub fn insert(&mut self, name: impl Into<String>, value: Value) {
+        let name = name.into();
+        match self.0.iter_mut().find(|(n, _)| *n == name) {
+            Some((_, v)) => *v = value,
+            None => self.0.push((name, value)),
+        }
+    }
+
+    pub fn remove(&mut self, name: &str) -> Option<Value> {
+        let index = self.0.iter().position(|(n, _)| n == name)?;
+        Some(self.0.remove(index).1)
+    }
+
+    /// Replaces these instance variables with the ones in `value` that aren't in `known`, which
+    /// should be what Luminol wrote out for the same object.
+    fn capture(&mut self, value: &Value, known: &Value) {
+        let (Value::Object(object), Value::Object(known)) = (value, known) else {
+            return;
+        };
+        self.0 = object
+            .fields
+            .iter()
+            .filter(|(name, _)| !known.fields.contains_key(*name))
+            .map(|(name, value)| (name.as_str().to_string(), value.clone()))
+            .collect();
+    }
+
+    /// Adds these instance variables to `value`.
+    fn emit(&self, value: &mut Value) {
+        let Value::Object(object) = value else {
+            return;
+        };
+        for (name, value) in self.iter() {
+            object.fields.insert(name.into(), value.clone());
+        }
+    }
+}
+
+/// Implemented by everything Luminol reads from or writes to a data file, so that instance
+/// variables it doesn't know about can be found in nested objects.
+///
+/// Both methods do nothing by default, for types that can't contain any RPG objects.
+pub trait HasExtraIvars {
+    /// Whether this type can hold instance variables it has no field for, so that reading it
+    /// needs to look for them.
+    const KEEPS_EXTRA_IVARS: bool = false;
+
+    /// Stores the instance variables in `value` that are missing from `known`.
+    /// `value` is the data this was read from, and `known` is what this serializes to.
+    fn capture_extra_ivars(&mut self, value: &Value, known: &Value) {
+        let _ = (value, known);
+    }
+
+    /// Adds the stored instance variables to `value`, which should be what this serializes to.
+    fn emit_extra_ivars(&self, value: &mut Value) {
+        let _ = value;
+    }
+}
+
+/// Converts a Marshal value into `T`, keeping any instance variables `T` has no field for.
+pub fn from_value<T>(value: &Value) -> Result<T, alox_48::DeError>
+where
+    T: HasExtraIvars + for<'de> alox_48::Deserialize<'de> + alox_48::Serialize,
+{
+    let mut data: T = alox_48::from_value(value)?;
+    if !T::KEEPS_EXTRA_IVARS {
+        return Ok(data);
+    }
+    let known = alox_48::to_value(&data).map_err(alox_48::DeError::custom)?;
+    data.capture_extra_ivars(value, &known);
+    Ok(data)
+}
+
+/// Converts `data` into a Marshal value, including any instance variables `T` has no field for.
+pub fn to_value<T>(data: &T) -> Result<Value, alox_48::SerError>
+where
+    T: HasExtraIvars + alox_48::Serialize,
+{
+    let mut value = alox_48::to_value(data)?;
+    data.emit_extra_ivars(&mut value);
+    Ok(value)
+}
+
+fn ivar<'a>(value: &'a Value, name: &str) -> Option<&'a Value> {
+    match value {
+        Value::Object(object) => object.fields.get(&alox_48::Symbol::from(name)),
+        _ => None,
+    }
+}
+
+fn ivar_mut<'a>(value: &'a mut Value, name: &str) -> Option<&'a mut Value> {
+    match value {
+        Value::Object(object) => object.fields.get_mut(&alox_48::Symbol::from(name)),
+        _ => None,
+    }
+}
+
+/// Implements [`HasExtraIvars`] for types with an `extra_ivars` field.
+/// The fields listed in braces hold other RPG objects, and are searched for instance variables too.
+macro_rules! has_extra_ivars {
+    ($($type:ty $({ $($field:ident),* $(,)? })?),* $(,)?) => {
+        $(
+            impl $crate::HasExtraIvars for $type {
+                const KEEPS_EXTRA_IVARS: bool = true;
+
+                fn capture_extra_ivars(&mut self, value: &alox_48::Value, known: &alox_48::Value) {
+                    self.extra_ivars.capture(value, known);
+                    $($(
+                        let name = concat!("@", stringify!($field));
+                        if let (Some(value), Some(known)) = (ivar(value, name), ivar(known, name)) {
+                            self.$field.capture_extra_ivars(value, known);
+                        }
+                    )*)?
+                }
+
+                fn emit_extra_ivars(&self, value: &mut alox_48::Value) {
+                    $($(
+                        if let Some(value) = ivar_mut(value, concat!("@", stringify!($field))) {
+                            self.$field.emit_extra_ivars(value);
+                        }
+                    )*)?
+                    self.extra_ivars.emit(value);
+                }
+            }
+        )*
+    };
+}
+
+/// Implements [`HasExtraIvars`] for types that never hold unknown instance variables.
+macro_rules! no_extra_ivars {
+    ($($type:ty),* $(,)?) => {
+        $(
+            impl $crate::HasExtraIvars for $type {}
+        )*
+    };
+}
+
+use crate::{rmvx, rmvxace, rpg, OptionVec, ParameterType};
+
+has_extra_ivars! {
+    rpg::Actor,
+    rpg::Animation { frames, timings },
+    rpg::animation::Timing { se },
+    rpg::animation::Frame,
+    rpg::Armor,
+    rpg::Class { learnings },
+    rpg::class::Learning,
+    rpg::Enemy { actions },
+    rpg::enemy::Action,
+    rpg::Item { menu_se },
+    rpg::Map { bgm, bgs, events },
+    rpg::Skill { menu_se },
+    rpg::State,
+    rpg::System {
+        title_bgm, battle_bgm, battle_end_me, gameover_me, cursor_se, decision_se, cancel_se,
+        buzzer_se, equip_se, shop_se, save_se, load_se, battle_start_se, escape_se,
+        actor_collapse_se, enemy_collapse_se, words, test_battlers,
+    },
+    rpg::system::Words,
+    rpg::system::TestBattler,
+    rpg::Tileset,
+    rpg::Troop { members, pages },
+    rpg::troop::Member,
+    rpg::troop::Page { condition, list },
+    rpg::troop::Condition,
+    rpg::Weapon,
+    rpg::AudioFile,
+    rpg::BGM,
+    rpg::BGS,
+    rpg::ME,
+    rpg::SE,
+    rpg::Event { pages },
+    rpg::CommonEvent { list },
+    rpg::EventPage { condition, graphic, move_route, list },
+    rpg::Graphic,
+    rpg::EventCondition,
+    rpg::EventCommand { parameters },
+    rpg::MapInfo,
+    rpg::MoveRoute { list },
+    rpg::MoveCommand { parameters },
+}
+
+// Only RPG Maker XP's classes keep their unknown instance variables so far.
+no_extra_ivars! {
+    rpg::Script,
+    rmvx::Actor, rmvx::Animation, rmvx::Area, rmvx::Armor, rmvx::Class, rmvx::Enemy, rmvx::Item,
+    rmvx::Map, rmvx::Skill, rmvx::State, rmvx::System, rmvx::Troop, rmvx::Weapon,
+    rmvxace::Actor, rmvxace::Armor, rmvxace::Class, rmvxace::Enemy, rmvxace::Item, rmvxace::Map,
+    rmvxace::Skill, rmvxace::State, rmvxace::System, rmvxace::Tileset, rmvxace::Weapon,
+}
+
+impl<T> HasExtraIvars for Vec<T>
+where
+    T: HasExtraIvars,
+{
+    const KEEPS_EXTRA_IVARS: bool = T::KEEPS_EXTRA_IVARS;
+
+    fn capture_extra_ivars(&mut self, value: &Value, known: &Value) {
+        let (Value::Array(values), Value::Array(known)) = (value, known) else {
+            return;
+        };
+        for ((element, value), known) in self.iter_mut().zip(values).zip(known) {
+            element.capture_extra_ivars(value, known);
+        }
+    }
+
+    fn emit_extra_ivars(&self, value: &mut Value) {
+        let Value::Array(values) = value else {
+            return;
+        };
+        for (element, value) in self.iter().zip(values) {
+            element.emit_extra_ivars(value);
+        }
+    }
+}
+
+fn hash_entry(hash: &Value, index: usize) -> Option<&Value> {
+    let Value::Hash(hash) = hash else {
+        return None;
+    };
+    hash.get(&Value::Integer(index as i32))
+}
+
+fn hash_entry_mut(hash: &mut Value, index: usize) -> Option<&mut Value> {
+    let Value::Hash(hash) = hash else {
+        return None;
+    };
+    hash.get_mut(&Value::Integer(index as i32))
+}
+
+impl<T> HasExtraIvars for OptionVec<T>
+where
+    T: HasExtraIvars,
+{
+    const KEEPS_EXTRA_IVARS: bool = T::KEEPS_EXTRA_IVARS;
+
+    fn capture_extra_ivars(&mut self, value: &Value, known: &Value) {
+        for (index, element) in self.iter_mut() {
+            if let (Some(value), Some(known)) = (hash_entry(value, index), hash_entry(known, index))
+            {
+                element.capture_extra_ivars(value, known);
+            }
+        }
+    }
+
+    fn emit_extra_ivars(&self, value: &mut Value) {
+        for (index, element) in self.iter() {
+            if let Some(value) = hash_entry_mut(value, index) {
+                element.emit_extra_ivars(value);
+            }
+        }
+    }
+}
+
+impl<T> HasExtraIvars for std::collections::HashMap<usize, T>
+where
+    T: HasExtraIvars,
+{
+    const KEEPS_EXTRA_IVARS: bool = T::KEEPS_EXTRA_IVARS;
+
+    fn capture_extra_ivars(&mut self, value: &Value, known: &Value) {
+        for (&index, element) in self.iter_mut() {
+            if let (Some(value), Some(known)) = (hash_entry(value, index), hash_entry(known, index))
+            {
+                element.capture_extra_ivars(value, known);
+            }
+        }
+    }
+
+    fn emit_extra_ivars(&self, value: &mut Value) {
+        for (&index, element) in self.iter() {
+            if let Some(value) = hash_entry_mut(value, index) {
+                element.emit_extra_ivars(value);
+            }
+        }
+    }
+}
+
+impl HasExtraIvars for ParameterType {
+    const KEEPS_EXTRA_IVARS: bool = true;
+
+    fn capture_extra_ivars(&mut self, value: &Value, known: &Value) {
+        match self {
+            Self::AudioFile(v) => v.capture_extra_ivars(value, known),
+            Self::MoveRoute(v) => v.capture_extra_ivars(value, known),
+            Self::MoveCommand(v) => v.capture_extra_ivars(value, known),
+            Self::Array(v) => v.capture_extra_ivars(value, known),
+            _ => {}
+        }
+    }
+
+    fn emit_extra_ivars(&self, value: &mut Value) {
+        match self {
+            Self::AudioFile(v) => v.emit_extra_ivars(value),
+            Self::MoveRoute(v) => v.emit_extra_ivars(value),
+            Self::MoveCommand(v) => v.emit_extra_ivars(value),
+            Self::Array(v) => v.emit_extra_ivars(value),
+            _ => {}
+        }
+    }
+}
+
+impl serde::Serialize for ExtraIvars {
+    fn serialize<S>(&self, serializer: S) -> Result<S::Ok, S::Error>
+    where
+        S: serde::Serializer,
+    {
+        use serde::ser::{Error, SerializeMap};
+
+        let mut map = serializer.serialize_map(Some(self.len()))?;
+        for (name, value) in self.iter() {
+            let mut ser = alox_48::Serializer::new();
+            alox_48::Serialize::serialize(value, &mut ser).map_err(S::Error::custom)?;
+            map.serialize_entry(
+                name,
+                &base64::engine::general_purpose::STANDARD.encode(ser.output),
+            )?;
+        }
+        map.end()
+    }
+}
+
+impl<'de> serde::Deserialize<'de> for ExtraIvars {
+    fn deserialize<D>(deserializer: D) -> Result<Self, D::Error>
+    where
+        D: serde::Deserializer<'de>,
+    {
+        struct Visitor;
+
+        impl<'de> serde::de::Visitor<'de> for Visitor {
+            type Value = ExtraIvars;
+
+            fn expecting(&self, formatter: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+                formatter
+                    .write_str("a map of instance variable names to base64 encoded Marshal data")
+            }
+
+            fn visit_map<A>(self, mut map: A) -> Result<Self::Value, A::Error>
+            where
+                A: serde::de::MapAccess<'de>,
+            {
+                use serde::de::Error;
+
+                let mut ivars = ExtraIvars::default();
+                while let Some((name, data)) = map.next_entry::<String, String>()? {
+                    let data = base64::engine::general_purpose::STANDARD
+                        .decode(data)
+                        .map_err(A::Error::custom)?;
+                    let mut de = alox_48::Deserializer::new(&data).map_err(A::Error::custom)?;
+                    let value = <Value as alox_48::Deserialize>::deserialize(&mut de)
+                        .map_err(A::Error::custom)?;
+                    ivars.insert(name, value);
+                }
+                Ok(ivars)
+            }
+        }
+
+        deserializer.deserialize_map(Visitor)
+    }
+}
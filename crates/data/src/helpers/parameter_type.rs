@@ -25,7 +25,7 @@
 use alox_48::Value;
 
 use crate::rgss_structs::{Color, Tone};
-use crate::shared::{AudioFile, MoveCommand, MoveRoute};
+use crate::shared::{AudioFile, AudioKind, MoveCommand, MoveRoute, BGM, BGS, ME, SE};
 
 #[derive(Debug, Clone, PartialEq, Default)]
 #[derive(serde::Deserialize, serde::Serialize)]
@@ -43,6 +43,10 @@ pub enum ParameterType {
     MoveCommand(MoveCommand),
     Array(Vec<ParameterType>),
     Bool(bool),
+    /// A string that isn't valid UTF-8, kept as the bytes it was read as so that saving doesn't
+    /// change it. Strings in legacy encodings are decoded before they get here, so this only holds
+    /// strings that aren't valid in the project's encoding either.
+    RawString(Vec<u8>),
 
     #[default]
     None,
@@ -55,7 +59,10 @@ impl From<alox_48::Value> for ParameterType {
             Value::Nil => Self::None,
             Value::Integer(v) => Self::Integer(v),
             Value::Float(v) => Self::Float(v),
-            Value::String(v) => Self::String(String::from_utf8(v.data).unwrap()),
+            Value::String(v) => match String::from_utf8(v.data) {
+                Ok(string) => Self::String(string),
+                Err(e) => Self::RawString(e.into_bytes()),
+            },
             Value::Array(v) => Self::Array(v.into_iter().map(|v| v.into()).collect()),
             Value::Bool(v) => Self::Bool(v),
             Value::Userdata(userdata) => match userdata.class.as_str() {
@@ -64,7 +71,12 @@ impl From<alox_48::Value> for ParameterType {
                 _ => panic!("Unsupported userdata type: {:#?}", userdata),
             },
             Value::Object(alox_48::Object { ref class, .. }) => match class.as_str() {
-                "RPG::AudioFile" => Self::AudioFile(alox_48::from_value(&value).unwrap()),
+                "RPG::AudioFile" | "RPG::BGM" | "RPG::BGS" | "RPG::ME" | "RPG::SE" => {
+                    Self::AudioFile(AudioFile {
+                        kind: AudioKind::from_class(class.as_str()).unwrap(),
+                        ..alox_48::from_value(&value).unwrap()
+                    })
+                }
                 "RPG::MoveRoute" => Self::MoveRoute(alox_48::from_value(&value).unwrap()),
                 "RPG::MoveCommand" => Self::MoveCommand(alox_48::from_value(&value).unwrap()),
                 _ => panic!("Unsupported object type: {:#?}", value),
@@ -82,11 +94,18 @@ impl From<ParameterType> for alox_48::Value {
             ParameterType::Integer(v) => Value::Integer(v),
             ParameterType::Float(v) => Value::Float(v),
             ParameterType::String(v) => Value::String(v.into()),
+            ParameterType::RawString(data) => Value::String(alox_48::RbString { data }),
             ParameterType::Array(v) => Value::Array(v.into_iter().map(|v| v.into()).collect()),
             ParameterType::Bool(v) => Value::Bool(v),
             ParameterType::Color(v) => Value::Userdata(v.into()),
             ParameterType::Tone(v) => Value::Userdata(v.into()),
-            ParameterType::AudioFile(v) => alox_48::to_value(v).unwrap(),
+            ParameterType::AudioFile(v) => match v.kind {
+                AudioKind::AudioFile => alox_48::to_value(v).unwrap(),
+                AudioKind::BGM => alox_48::to_value(BGM::from(v)).unwrap(),
+                AudioKind::BGS => alox_48::to_value(BGS::from(v)).unwrap(),
+                AudioKind::ME => alox_48::to_value(ME::from(v)).unwrap(),
+                AudioKind::SE => alox_48::to_value(SE::from(v)).unwrap(),
+            },
             ParameterType::MoveRoute(v) => alox_48::to_value(v).unwrap(),
             ParameterType::MoveCommand(v) => alox_48::to_value(v).unwrap(),
         }
@@ -1,6 +1,8 @@
 #![allow(non_upper_case_globals)]
 
 // Editor specific types
+pub mod rmvx;
+pub mod rmvxace;
 pub mod rmxp;
 
 // Shared structs with the same layout
@@ -10,13 +12,17 @@ mod option_vec;
 
 mod rgss_structs;
 
+pub mod encoding;
+pub mod extra_ivars;
+
 pub mod helpers;
 
 pub mod commands;
 
+pub use extra_ivars::{ExtraIvars, HasExtraIvars};
 pub use helpers::*;
 pub use option_vec::OptionVec;
-pub use rgss_structs::{Color, Table1, Table2, Table3, Tone};
+pub use rgss_structs::{Color, Rect, Table1, Table2, Table3, Tone};
 
 pub mod rpg {
     pub use crate::rmxp::*;
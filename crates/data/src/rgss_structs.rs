@@ -86,6 +86,43 @@ impl From<Tone> for alox_48::Value {
     }
 }
 
+/// **A struct representing a rectangle.**
+///
+/// RGSS1 does not store these in its data files, but RGSS2 does (for `RPG::Area`).
+#[derive(Default, Debug, Clone, Copy, PartialEq, Eq)]
+#[derive(serde::Serialize, serde::Deserialize)]
+#[derive(alox_48::Deserialize, alox_48::Serialize)]
+#[marshal(from = "alox_48::Userdata", into = "alox_48::Userdata")]
+#[derive(bytemuck::Pod, bytemuck::Zeroable)]
+#[repr(C)]
+pub struct Rect {
+    pub x: i32,
+    pub y: i32,
+    pub width: i32,
+    pub height: i32,
+}
+
+impl From<alox_48::Userdata> for Rect {
+    fn from(value: alox_48::Userdata) -> Self {
+        *bytemuck::from_bytes(&value.data)
+    }
+}
+
+impl From<Rect> for alox_48::Userdata {
+    fn from(value: Rect) -> Self {
+        alox_48::Userdata {
+            class: "Rect".into(),
+            data: bytemuck::bytes_of(&value).to_vec(),
+        }
+    }
+}
+
+impl From<Rect> for alox_48::Value {
+    fn from(value: Rect) -> Self {
+        Self::Userdata(value.into())
+    }
+}
+
 use std::ops::{Index, IndexMut};
 
 /// Normal RGSS has dynamically dimensioned arrays, but in practice that does not map well to Rust.
@@ -438,13 +475,32 @@ impl Table3 {
     }
 
     pub fn resize(&mut self, xsize: usize, ysize: usize, zsize: usize) {
-        let mut new_data = vec![0; xsize * ysize];
+        self.resize_with_offset(xsize, ysize, zsize, 0, 0);
+    }
+
+    /// Resizes the table, moving every element `x_offset` elements along the x axis and
+    /// `y_offset` elements along the y axis.
+    /// Elements that end up out of bounds are discarded and new elements are set to 0.
+    pub fn resize_with_offset(
+        &mut self,
+        xsize: usize,
+        ysize: usize,
+        zsize: usize,
+        x_offset: isize,
+        y_offset: isize,
+    ) {
+        let mut new_data = vec![0; xsize * ysize * zsize];
 
-        // A naive for loop like this is optimized to a handful of memcpys.
         for z in 0..self.zsize.min(zsize) {
-            for y in 0..self.ysize.min(ysize) {
-                for x in 0..self.xsize.min(xsize) {
-                    new_data[(xsize * ysize * z) + (xsize * y) + x] = self[(x, y, z)]
+            for y in 0..self.ysize {
+                let Some(new_y) = y.checked_add_signed(y_offset).filter(|&y| y < ysize) else {
+                    continue;
+                };
+                for x in 0..self.xsize {
+                    let Some(new_x) = x.checked_add_signed(x_offset).filter(|&x| x < xsize) else {
+                        continue;
+                    };
+                    new_data[(xsize * ysize * z) + (xsize * new_y) + new_x] = self[(x, y, z)]
                 }
             }
         }
@@ -0,0 +1,65 @@
+// Copyright (C) 2024 Melody Madeline Lyons
+//
+// This file is part of Luminol.
+//
+// Luminol is free software: you can redistribute it and/or modify
+// it under the terms of the GNU General Public License as published by
+// the Free Software Foundation, either version 3 of the License, or
+// (at your option) any later version.
+//
+// Luminol is distributed in the hope that it will be useful,
+// but WITHOUT ANY WARRANTY; without even the implied warranty of
+// MERCHANTABILITY or FITNESS FOR A PARTICULAR PURPOSE.  See the
+// GNU General Public License for more details.
+//
+// You should have received a copy of the GNU General Public License
+// along with Luminol.  If not, see <http://www.gnu.org/licenses/>.
+use crate::{id_alox, id_serde, optional_path_alox, optional_path_serde, rpg::SE, Color, Path};
+
+pub use crate::rmxp::animation::{Frame, Position, Scope};
+
+#[derive(Default, Debug, serde::Deserialize, serde::Serialize)]
+#[derive(alox_48::Deserialize, alox_48::Serialize)]
+#[marshal(class = "RPG::Animation")]
+pub struct Animation {
+    #[serde(with = "id_serde")]
+    #[marshal(with = "id_alox")]
+    pub id: usize,
+    pub name: String,
+    #[serde(with = "optional_path_serde")]
+    #[marshal(with = "optional_path_alox")]
+    pub animation1_name: Path,
+    pub animation1_hue: i32,
+    #[serde(with = "optional_path_serde")]
+    #[marshal(with = "optional_path_alox")]
+    pub animation2_name: Path,
+    pub animation2_hue: i32,
+    pub position: Position,
+    pub frame_max: usize,
+    pub frames: Vec<Frame>,
+    pub timings: Vec<Timing>,
+}
+
+/// Unlike RGSS1, RGSS2 animation timings have no hit/miss condition.
+#[derive(Debug, Clone, serde::Deserialize, serde::Serialize)]
+#[derive(alox_48::Deserialize, alox_48::Serialize)]
+#[marshal(class = "RPG::Animation::Timing")]
+pub struct Timing {
+    pub frame: usize,
+    pub se: SE,
+    pub flash_scope: Scope,
+    pub flash_color: Color,
+    pub flash_duration: usize,
+}
+
+impl Default for Timing {
+    fn default() -> Self {
+        Self {
+            frame: 0,
+            se: SE::default(),
+            flash_scope: Scope::default(),
+            flash_color: Color::default(),
+            flash_duration: 5,
+        }
+    }
+}
@@ -0,0 +1,181 @@
+// Copyright (C) 2024 Melody Madeline Lyons
+//
+// This file is part of Luminol.
+//
+// Luminol is free software: you can redistribute it and/or modify
+// it under the terms of the GNU General Public License as published by
+// the Free Software Foundation, either version 3 of the License, or
+// (at your option) any later version.
+//
+// Luminol is distributed in the hope that it will be useful,
+// but WITHOUT ANY WARRANTY; without even the implied warranty of
+// MERCHANTABILITY or FITNESS FOR A PARTICULAR PURPOSE.  See the
+// GNU General Public License for more details.
+//
+// You should have received a copy of the GNU General Public License
+// along with Luminol.  If not, see <http://www.gnu.org/licenses/>.
+use crate::{
+    id_alox, id_serde, optional_id_alox, optional_id_serde, optional_path_alox,
+    optional_path_serde,
+    rpg::{EventCommand, MoveRoute},
+    Path,
+};
+
+pub use crate::shared::{EventTrigger, MoveFreq, MoveSpeed, MoveType, SelfSwitch};
+
+#[derive(Debug, serde::Deserialize, serde::Serialize, Clone)]
+#[derive(alox_48::Deserialize, alox_48::Serialize)]
+#[marshal(class = "RPG::Event")]
+pub struct Event {
+    pub id: usize,
+    pub name: String,
+    pub x: i32,
+    pub y: i32,
+    pub pages: Vec<EventPage>,
+}
+
+impl Event {
+    #[must_use]
+    pub fn new(x: i32, y: i32, id: usize) -> Self {
+        Self {
+            id,
+            name: format!("EV{id:0>3}"),
+            x,
+            y,
+            pages: vec![EventPage::default()],
+        }
+    }
+}
+
+#[derive(Debug, serde::Deserialize, serde::Serialize, Clone)]
+#[derive(alox_48::Deserialize, alox_48::Serialize)]
+#[marshal(class = "RPG::Event::Page")]
+pub struct EventPage {
+    pub condition: EventCondition,
+    pub graphic: Graphic,
+    pub move_type: MoveType,
+    pub move_speed: MoveSpeed,
+    pub move_frequency: MoveFreq,
+    pub move_route: MoveRoute,
+    pub walk_anime: bool,
+    pub step_anime: bool,
+    pub direction_fix: bool,
+    pub through: bool,
+    pub priority_type: PriorityType,
+    pub trigger: EventTrigger,
+    pub list: Vec<EventCommand>,
+}
+
+impl Default for EventPage {
+    fn default() -> Self {
+        Self {
+            condition: EventCondition::default(),
+            graphic: Graphic::default(),
+            move_type: MoveType::Fixed,
+            move_speed: MoveSpeed::Slow,
+            move_frequency: MoveFreq::Low,
+            move_route: MoveRoute::default(),
+            walk_anime: true,
+            step_anime: false,
+            direction_fix: false,
+            through: false,
+            priority_type: PriorityType::Same,
+            trigger: EventTrigger::ActionButton,
+            list: vec![],
+        }
+    }
+}
+
+#[derive(serde::Deserialize, serde::Serialize)]
+#[derive(alox_48::Deserialize, alox_48::Serialize)]
+#[derive(Debug, Clone, Copy, PartialEq, Default)]
+#[derive(num_enum::TryFromPrimitive, num_enum::IntoPrimitive)]
+#[derive(strum::Display, strum::EnumIter)]
+#[serde(try_from = "u8", into = "u8")]
+#[marshal(try_from = "u8", into = "u8")]
+#[repr(u8)]
+pub enum PriorityType {
+    #[strum(to_string = "Below Characters")]
+    Below,
+    #[default]
+    #[strum(to_string = "Same As Characters")]
+    Same,
+    #[strum(to_string = "Above Characters")]
+    Above,
+}
+
+#[derive(Debug, serde::Deserialize, serde::Serialize, Clone)]
+#[derive(alox_48::Deserialize, alox_48::Serialize)]
+#[marshal(class = "RPG::Event::Page::Graphic")]
+pub struct Graphic {
+    #[serde(with = "optional_id_serde")]
+    #[marshal(with = "optional_id_alox")]
+    pub tile_id: Option<usize>,
+    #[serde(with = "optional_path_serde")]
+    #[marshal(with = "optional_path_alox")]
+    pub character_name: Path,
+    pub character_index: i32,
+    pub direction: i32,
+    pub pattern: i32,
+}
+
+impl Default for Graphic {
+    fn default() -> Self {
+        Self {
+            tile_id: None,
+            character_name: None,
+            character_index: 0,
+            direction: 2,
+            pattern: 0,
+        }
+    }
+}
+
+#[derive(Debug, serde::Deserialize, serde::Serialize, Clone)]
+#[derive(alox_48::Deserialize, alox_48::Serialize)]
+#[marshal(class = "RPG::Event::Page::Condition")]
+pub struct EventCondition {
+    pub switch1_valid: bool,
+    pub switch2_valid: bool,
+    pub variable_valid: bool,
+    pub self_switch_valid: bool,
+    pub item_valid: bool,
+    pub actor_valid: bool,
+    #[serde(with = "id_serde")]
+    #[marshal(with = "id_alox")]
+    pub switch1_id: usize,
+    #[serde(with = "id_serde")]
+    #[marshal(with = "id_alox")]
+    pub switch2_id: usize,
+    #[serde(with = "id_serde")]
+    #[marshal(with = "id_alox")]
+    pub variable_id: usize,
+    pub variable_value: i32,
+    pub self_switch_ch: SelfSwitch,
+    #[serde(with = "id_serde")]
+    #[marshal(with = "id_alox")]
+    pub item_id: usize,
+    #[serde(with = "id_serde")]
+    #[marshal(with = "id_alox")]
+    pub actor_id: usize,
+}
+
+impl Default for EventCondition {
+    fn default() -> Self {
+        Self {
+            switch1_valid: false,
+            switch2_valid: false,
+            variable_valid: false,
+            self_switch_valid: false,
+            item_valid: false,
+            actor_valid: false,
+            switch1_id: 0,
+            switch2_id: 0,
+            variable_id: 0,
+            variable_value: 0,
+            self_switch_ch: SelfSwitch::A,
+            item_id: 0,
+            actor_id: 0,
+        }
+    }
+}
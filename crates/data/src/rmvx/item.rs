@@ -0,0 +1,100 @@
+// Copyright (C) 2024 Melody Madeline Lyons
+//
+// This file is part of Luminol.
+//
+// Luminol is free software: you can redistribute it and/or modify
+// it under the terms of the GNU General Public License as published by
+// the Free Software Foundation, either version 3 of the License, or
+// (at your option) any later version.
+//
+// Luminol is distributed in the hope that it will be useful,
+// but WITHOUT ANY WARRANTY; without even the implied warranty of
+// MERCHANTABILITY or FITNESS FOR A PARTICULAR PURPOSE.  See the
+// GNU General Public License for more details.
+//
+// You should have received a copy of the GNU General Public License
+// along with Luminol.  If not, see <http://www.gnu.org/licenses/>.
+use crate::{
+    id_alox, id_serde, id_vec_alox, id_vec_serde, optional_id_alox, optional_id_serde,
+    rpg::Occasion,
+};
+
+use super::Scope;
+
+#[derive(Default, Debug, serde::Deserialize, serde::Serialize, Clone)]
+#[derive(alox_48::Deserialize, alox_48::Serialize)]
+#[marshal(class = "RPG::Item")]
+pub struct Item {
+    #[serde(with = "id_serde")]
+    #[marshal(with = "id_alox")]
+    pub id: usize,
+    pub name: String,
+    pub icon_index: i32,
+    pub description: String,
+    pub note: String,
+    pub scope: Scope,
+    pub occasion: Occasion,
+    pub speed: i32,
+    #[serde(with = "optional_id_serde")]
+    #[marshal(with = "optional_id_alox")]
+    pub animation_id: Option<usize>,
+    #[serde(with = "optional_id_serde")]
+    #[marshal(with = "optional_id_alox")]
+    pub common_event_id: Option<usize>,
+    pub base_damage: i32,
+    pub variance: i32,
+    pub atk_f: i32,
+    pub spi_f: i32,
+    pub physical_attack: bool,
+    pub damage_to_mp: bool,
+    pub absorb_damage: bool,
+    pub ignore_defense: bool,
+    #[serde(with = "id_vec_serde")]
+    #[marshal(with = "id_vec_alox")]
+    pub element_set: Vec<usize>,
+    #[serde(with = "id_vec_serde")]
+    #[marshal(with = "id_vec_alox")]
+    pub plus_state_set: Vec<usize>,
+    #[serde(with = "id_vec_serde")]
+    #[marshal(with = "id_vec_alox")]
+    pub minus_state_set: Vec<usize>,
+    pub price: i32,
+    pub consumable: bool,
+    pub hp_recovery_rate: i32,
+    pub hp_recovery: i32,
+    pub mp_recovery_rate: i32,
+    pub mp_recovery: i32,
+    pub parameter_type: ParameterType,
+    pub parameter_points: i32,
+}
+
+#[derive(Clone, Copy, Debug, Eq, PartialEq, Default)]
+#[derive(
+    num_enum::TryFromPrimitive,
+    num_enum::IntoPrimitive,
+    strum::Display,
+    strum::EnumIter
+)]
+#[derive(serde::Deserialize, serde::Serialize)]
+#[derive(alox_48::Deserialize, alox_48::Serialize)]
+#[repr(u8)]
+#[serde(into = "u8")]
+#[serde(try_from = "u8")]
+#[marshal(into = "u8")]
+#[marshal(try_from = "u8")]
+pub enum ParameterType {
+    #[default]
+    None = 0,
+    #[strum(to_string = "Max HP")]
+    MaxHP = 1,
+    #[strum(to_string = "Max MP")]
+    MaxMP = 2,
+    #[strum(to_string = "ATK")]
+    Atk = 3,
+    #[strum(to_string = "DEF")]
+    Def = 4,
+    #[strum(to_string = "SPI")]
+    Spi = 5,
+    #[strum(to_string = "AGI")]
+    Agi = 6,
+}
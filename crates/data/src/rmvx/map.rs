@@ -0,0 +1,82 @@
+// Copyright (C) 2024 Melody Madeline Lyons
+//
+// This file is part of Luminol.
+//
+// Luminol is free software: you can redistribute it and/or modify
+// it under the terms of the GNU General Public License as published by
+// the Free Software Foundation, either version 3 of the License, or
+// (at your option) any later version.
+//
+// Luminol is distributed in the hope that it will be useful,
+// but WITHOUT ANY WARRANTY; without even the implied warranty of
+// MERCHANTABILITY or FITNESS FOR A PARTICULAR PURPOSE.  See the
+// GNU General Public License for more details.
+//
+// You should have received a copy of the GNU General Public License
+// along with Luminol.  If not, see <http://www.gnu.org/licenses/>.
+use crate::{
+    id_vec_alox, id_vec_serde, option_vec, optional_path_alox, optional_path_serde,
+    rpg::{BGM, BGS},
+    Path, Table3,
+};
+
+use super::Event;
+
+#[derive(Default, Debug, serde::Deserialize, serde::Serialize)]
+#[derive(alox_48::Deserialize, alox_48::Serialize)]
+#[marshal(class = "RPG::Map")]
+pub struct Map {
+    pub width: usize,
+    pub height: usize,
+    pub scroll_type: ScrollType,
+    pub autoplay_bgm: bool,
+    pub bgm: BGM,
+    pub autoplay_bgs: bool,
+    pub bgs: BGS,
+    pub disable_dashing: bool,
+    #[serde(with = "id_vec_serde")]
+    #[marshal(with = "id_vec_alox")]
+    pub encounter_list: Vec<usize>,
+    pub encounter_step: i32,
+    #[serde(with = "optional_path_serde")]
+    #[marshal(with = "optional_path_alox")]
+    pub parallax_name: Path,
+    pub parallax_loop_x: bool,
+    pub parallax_loop_y: bool,
+    pub parallax_sx: i32,
+    pub parallax_sy: i32,
+    pub parallax_show: bool,
+    /// Tile ids for the three map layers.
+    pub data: Table3,
+    pub events: option_vec::OptionVec<Event>,
+
+    #[marshal(skip)]
+    #[serde(skip)]
+    pub modified: bool,
+}
+
+#[derive(Clone, Copy, Debug, Eq, PartialEq, Default)]
+#[derive(
+    num_enum::TryFromPrimitive,
+    num_enum::IntoPrimitive,
+    strum::Display,
+    strum::EnumIter
+)]
+#[derive(serde::Deserialize, serde::Serialize)]
+#[derive(alox_48::Deserialize, alox_48::Serialize)]
+#[repr(u8)]
+#[serde(into = "u8")]
+#[serde(try_from = "u8")]
+#[marshal(into = "u8")]
+#[marshal(try_from = "u8")]
+pub enum ScrollType {
+    #[default]
+    #[strum(to_string = "No Loop")]
+    NoLoop = 0,
+    #[strum(to_string = "Loop Vertically")]
+    LoopVertically = 1,
+    #[strum(to_string = "Loop Horizontally")]
+    LoopHorizontally = 2,
+    #[strum(to_string = "Loop Both")]
+    LoopBoth = 3,
+}
@@ -0,0 +1,130 @@
+// Copyright (C) 2024 Melody Madeline Lyons
+//
+// This file is part of Luminol.
+//
+// Luminol is free software: you can redistribute it and/or modify
+// it under the terms of the GNU General Public License as published by
+// the Free Software Foundation, either version 3 of the License, or
+// (at your option) any later version.
+//
+// Luminol is distributed in the hope that it will be useful,
+// but WITHOUT ANY WARRANTY; without even the implied warranty of
+// MERCHANTABILITY or FITNESS FOR A PARTICULAR PURPOSE.  See the
+// GNU General Public License for more details.
+//
+// You should have received a copy of the GNU General Public License
+// along with Luminol.  If not, see <http://www.gnu.org/licenses/>.
+
+//! Data structures for RPG Maker VX (RGSS2) projects.
+//!
+//! Classes that kept the same layout as RGSS1 (`RPG::MapInfo`, `RPG::CommonEvent`, `RPG::MoveRoute`, etc.) are shared with [`crate::rpg`].
+
+pub mod actor;
+pub mod animation;
+pub mod area;
+pub mod armor;
+pub mod class;
+pub mod enemy;
+pub mod event;
+pub mod item;
+pub mod map;
+pub mod skill;
+pub mod state;
+pub mod system;
+pub mod troop;
+pub mod weapon;
+
+pub use actor::Actor;
+pub use animation::Animation;
+pub use area::Area;
+pub use armor::Armor;
+pub use class::Class;
+pub use enemy::Enemy;
+pub use event::Event;
+pub use item::Item;
+pub use map::Map;
+pub use skill::Skill;
+pub use state::State;
+pub use system::System;
+pub use troop::Troop;
+pub use weapon::Weapon;
+
+pub use crate::rpg::{CommonEvents, MapInfos, Scripts};
+pub use crate::shared::{
+    CommonEvent, EventCommand, MapInfo, MoveCommand, MoveRoute, Script, BGM, BGS, ME, SE,
+};
+
+use crate::rpg::DatabaseEntry;
+
+macro_rules! basic_container {
+    ($($parent:ident, $child:ident),* $(,)?) => {
+        $(
+            #[derive(Debug, Default)]
+            pub struct $parent {
+                pub data: Vec<$child>,
+                pub modified: bool,
+            }
+
+            impl DatabaseEntry for $child {
+                fn default_with_id(id: usize) -> Self {
+                    Self { id, ..Default::default() }
+                }
+            }
+        )*
+    };
+}
+
+basic_container! {
+    Actors, Actor,
+    Animations, Animation,
+    Areas, Area,
+    Armors, Armor,
+    Classes, Class,
+    Enemies, Enemy,
+    Items, Item,
+    Skills, Skill,
+    States, State,
+    Troops, Troop,
+    Weapons, Weapon,
+}
+
+#[derive(Clone, Copy, Debug, Eq, PartialEq, Default)]
+#[derive(
+    num_enum::TryFromPrimitive,
+    num_enum::IntoPrimitive,
+    strum::Display,
+    strum::EnumIter
+)]
+#[derive(serde::Deserialize, serde::Serialize)]
+#[derive(alox_48::Deserialize, alox_48::Serialize)]
+#[repr(u8)]
+#[serde(into = "u8")]
+#[serde(try_from = "u8")]
+#[marshal(into = "u8")]
+#[marshal(try_from = "u8")]
+pub enum Scope {
+    #[default]
+    None = 0,
+    #[strum(to_string = "One Enemy")]
+    OneEnemy = 1,
+    #[strum(to_string = "All Enemies")]
+    AllEnemies = 2,
+    #[strum(to_string = "One Enemy Dual")]
+    OneEnemyDual = 3,
+    #[strum(to_string = "One Random Enemy")]
+    OneRandomEnemy = 4,
+    #[strum(to_string = "Two Random Enemies")]
+    TwoRandomEnemies = 5,
+    #[strum(to_string = "Three Random Enemies")]
+    ThreeRandomEnemies = 6,
+    #[strum(to_string = "One Ally")]
+    OneAlly = 7,
+    #[strum(to_string = "All Allies")]
+    AllAllies = 8,
+    #[strum(to_string = "One Ally (Dead)")]
+    OneAllyDead = 9,
+    #[strum(to_string = "All Allies (Dead)")]
+    AllAlliesDead = 10,
+    #[strum(to_string = "The User")]
+    User = 11,
+}
@@ -0,0 +1,78 @@
+// Copyright (C) 2024 Melody Madeline Lyons
+//
+// This file is part of Luminol.
+//
+// Luminol is free software: you can redistribute it and/or modify
+// it under the terms of the GNU General Public License as published by
+// the Free Software Foundation, either version 3 of the License, or
+// (at your option) any later version.
+//
+// Luminol is distributed in the hope that it will be useful,
+// but WITHOUT ANY WARRANTY; without even the implied warranty of
+// MERCHANTABILITY or FITNESS FOR A PARTICULAR PURPOSE.  See the
+// GNU General Public License for more details.
+//
+// You should have received a copy of the GNU General Public License
+// along with Luminol.  If not, see <http://www.gnu.org/licenses/>.
+use crate::{id_alox, id_serde, optional_path_alox, optional_path_serde, ParameterType, Path};
+
+use super::Feature;
+
+#[derive(Default, Debug, serde::Deserialize, serde::Serialize)]
+#[derive(alox_48::Deserialize, alox_48::Serialize)]
+#[marshal(class = "RPG::Enemy")]
+pub struct Enemy {
+    #[serde(with = "id_serde")]
+    #[marshal(with = "id_alox")]
+    pub id: usize,
+    pub name: String,
+    pub icon_index: i32,
+    pub description: String,
+    pub features: Vec<Feature>,
+    pub note: String,
+    #[serde(with = "optional_path_serde")]
+    #[marshal(with = "optional_path_alox")]
+    pub battler_name: Path,
+    pub battler_hue: i32,
+    /// Max HP, max MP, ATK, DEF, MAT, MDF, AGI and LUK.
+    pub params: Vec<i32>,
+    pub exp: i32,
+    pub gold: i32,
+    pub drop_items: Vec<DropItem>,
+    pub actions: Vec<Action>,
+}
+
+#[derive(Default, Debug, serde::Deserialize, serde::Serialize)]
+#[derive(alox_48::Deserialize, alox_48::Serialize)]
+#[marshal(class = "RPG::Enemy::DropItem")]
+pub struct DropItem {
+    pub kind: crate::rmvx::enemy::DropKind,
+    pub data_id: usize,
+    pub denominator: i32,
+}
+
+#[derive(Debug, serde::Deserialize, serde::Serialize)]
+#[derive(alox_48::Deserialize, alox_48::Serialize)]
+#[marshal(class = "RPG::Enemy::Action")]
+pub struct Action {
+    #[serde(with = "id_serde")]
+    #[marshal(with = "id_alox")]
+    pub skill_id: usize,
+    pub condition_type: i32,
+    /// Integers for turn and level conditions, floats for HP and MP rates.
+    pub condition_param1: ParameterType,
+    pub condition_param2: ParameterType,
+    pub rating: i32,
+}
+
+impl Default for Action {
+    fn default() -> Self {
+        Self {
+            skill_id: 0,
+            condition_type: 0,
+            condition_param1: ParameterType::Integer(0),
+            condition_param2: ParameterType::Integer(0),
+            rating: 5,
+        }
+    }
+}
@@ -0,0 +1,76 @@
+// Copyright (C) 2024 Melody Madeline Lyons
+//
+// This file is part of Luminol.
+//
+// Luminol is free software: you can redistribute it and/or modify
+// it under the terms of the GNU General Public License as published by
+// the Free Software Foundation, either version 3 of the License, or
+// (at your option) any later version.
+//
+// Luminol is distributed in the hope that it will be useful,
+// but WITHOUT ANY WARRANTY; without even the implied warranty of
+// MERCHANTABILITY or FITNESS FOR A PARTICULAR PURPOSE.  See the
+// GNU General Public License for more details.
+//
+// You should have received a copy of the GNU General Public License
+// along with Luminol.  If not, see <http://www.gnu.org/licenses/>.
+use crate::{
+    id_alox, id_serde, option_vec, optional_path_alox, optional_path_serde,
+    rmvx::{map::ScrollType, Event},
+    rpg::{BGM, BGS},
+    Path, Table3,
+};
+
+#[derive(Default, Debug, serde::Deserialize, serde::Serialize)]
+#[derive(alox_48::Deserialize, alox_48::Serialize)]
+#[marshal(class = "RPG::Map")]
+pub struct Map {
+    pub display_name: String,
+    #[serde(with = "id_serde")]
+    #[marshal(with = "id_alox")]
+    pub tileset_id: usize,
+    pub width: usize,
+    pub height: usize,
+    pub scroll_type: ScrollType,
+    pub specify_battleback: bool,
+    #[serde(with = "optional_path_serde")]
+    #[marshal(with = "optional_path_alox")]
+    pub battleback1_name: Path,
+    #[serde(with = "optional_path_serde")]
+    #[marshal(with = "optional_path_alox")]
+    pub battleback2_name: Path,
+    pub autoplay_bgm: bool,
+    pub bgm: BGM,
+    pub autoplay_bgs: bool,
+    pub bgs: BGS,
+    pub disable_dashing: bool,
+    pub encounter_list: Vec<Encounter>,
+    pub encounter_step: i32,
+    #[serde(with = "optional_path_serde")]
+    #[marshal(with = "optional_path_alox")]
+    pub parallax_name: Path,
+    pub parallax_loop_x: bool,
+    pub parallax_loop_y: bool,
+    pub parallax_sx: i32,
+    pub parallax_sy: i32,
+    pub parallax_show: bool,
+    pub note: String,
+    /// Tile ids for the three map layers, followed by the shadow and region layer.
+    pub data: Table3,
+    pub events: option_vec::OptionVec<Event>,
+
+    #[marshal(skip)]
+    #[serde(skip)]
+    pub modified: bool,
+}
+
+#[derive(Default, Debug, serde::Deserialize, serde::Serialize)]
+#[derive(alox_48::Deserialize, alox_48::Serialize)]
+#[marshal(class = "RPG::Map::Encounter")]
+pub struct Encounter {
+    #[serde(with = "id_serde")]
+    #[marshal(with = "id_alox")]
+    pub troop_id: usize,
+    pub weight: i32,
+    pub region_set: Vec<i32>,
+}
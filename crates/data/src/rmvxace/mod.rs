@@ -0,0 +1,107 @@
+// Copyright (C) 2024 Melody Madeline Lyons
+//
+// This file is part of Luminol.
+//
+// Luminol is free software: you can redistribute it and/or modify
+// it under the terms of the GNU General Public License as published by
+// the Free Software Foundation, either version 3 of the License, or
+// (at your option) any later version.
+//
+// Luminol is distributed in the hope that it will be useful,
+// but WITHOUT ANY WARRANTY; without even the implied warranty of
+// MERCHANTABILITY or FITNESS FOR A PARTICULAR PURPOSE.  See the
+// GNU General Public License for more details.
+//
+// You should have received a copy of the GNU General Public License
+// along with Luminol.  If not, see <http://www.gnu.org/licenses/>.
+
+//! Data structures for RPG Maker VX Ace (RGSS3) projects.
+//!
+//! Classes that kept the same layout as RGSS2 (`RPG::Event`, `RPG::Animation`, `RPG::Troop`, etc.) are shared with [`crate::rmvx`].
+
+pub mod actor;
+pub mod armor;
+pub mod base_item;
+pub mod class;
+pub mod enemy;
+pub mod item;
+pub mod map;
+pub mod skill;
+pub mod state;
+pub mod system;
+pub mod tileset;
+pub mod usable_item;
+pub mod weapon;
+
+pub use actor::Actor;
+pub use armor::Armor;
+pub use base_item::Feature;
+pub use class::Class;
+pub use enemy::Enemy;
+pub use item::Item;
+pub use map::Map;
+pub use skill::Skill;
+pub use state::State;
+pub use system::System;
+pub use tileset::Tileset;
+pub use usable_item::{Damage, Effect};
+pub use weapon::Weapon;
+
+pub use crate::rmvx::{Animation, Event, Troop};
+pub use crate::rpg::{CommonEvents, MapInfos, Scripts};
+pub use crate::shared::{
+    CommonEvent, EventCommand, MapInfo, MoveCommand, MoveRoute, Script, BGM, BGS, ME, SE,
+};
+
+use crate::rpg::DatabaseEntry;
+
+macro_rules! basic_container {
+    ($($parent:ident, $child:ident),* $(,)?) => {
+        $(
+            #[derive(Debug, Default)]
+            pub struct $parent {
+                pub data: Vec<$child>,
+                pub modified: bool,
+            }
+        )*
+    };
+}
+
+macro_rules! database_entry {
+    ($($type:ident),* $(,)?) => {
+        $(
+            impl DatabaseEntry for $type {
+                fn default_with_id(id: usize) -> Self {
+                    Self { id, ..Default::default() }
+                }
+            }
+        )*
+    };
+}
+
+basic_container! {
+    Actors, Actor,
+    Animations, Animation,
+    Armors, Armor,
+    Classes, Class,
+    Enemies, Enemy,
+    Items, Item,
+    Skills, Skill,
+    States, State,
+    Tilesets, Tileset,
+    Troops, Troop,
+    Weapons, Weapon,
+}
+
+// Animations and troops are already database entries through rmvx
+database_entry! {
+    Actor,
+    Armor,
+    Class,
+    Enemy,
+    Item,
+    Skill,
+    State,
+    Tileset,
+    Weapon,
+}
@@ -0,0 +1,122 @@
+// Copyright (C) 2024 Melody Madeline Lyons
+//
+// This file is part of Luminol.
+//
+// Luminol is free software: you can redistribute it and/or modify
+// it under the terms of the GNU General Public License as published by
+// the Free Software Foundation, either version 3 of the License, or
+// (at your option) any later version.
+//
+// Luminol is distributed in the hope that it will be useful,
+// but WITHOUT ANY WARRANTY; without even the implied warranty of
+// MERCHANTABILITY or FITNESS FOR A PARTICULAR PURPOSE.  See the
+// GNU General Public License for more details.
+//
+// You should have received a copy of the GNU General Public License
+// along with Luminol.  If not, see <http://www.gnu.org/licenses/>.
+pub use crate::{
+    id_alox, id_serde, id_vec_alox, id_vec_serde, nil_padded_alox, nil_padded_serde,
+    optional_id_alox, optional_id_serde, optional_path_alox, optional_path_serde,
+    rmvx::system::Vehicle,
+    rpg::{BGM, ME, SE},
+    Path, Tone,
+};
+
+#[derive(Default, Debug)]
+#[derive(serde::Deserialize, serde::Serialize)]
+#[derive(alox_48::Deserialize, alox_48::Serialize)]
+#[marshal(class = "RPG::System")]
+pub struct System {
+    pub game_title: String,
+    pub version_id: i32,
+    pub japanese: bool,
+    #[serde(with = "id_vec_serde")]
+    #[marshal(with = "id_vec_alox")]
+    pub party_members: Vec<usize>,
+    pub currency_unit: String,
+    pub elements: Vec<String>,
+    pub skill_types: Vec<String>,
+    pub weapon_types: Vec<String>,
+    pub armor_types: Vec<String>,
+    #[marshal(with = "nil_padded_alox")]
+    #[serde(with = "nil_padded_serde")]
+    pub switches: Vec<String>,
+    #[marshal(with = "nil_padded_alox")]
+    #[serde(with = "nil_padded_serde")]
+    pub variables: Vec<String>,
+    pub boat: Vehicle,
+    pub ship: Vehicle,
+    pub airship: Vehicle,
+    #[serde(with = "optional_path_serde")]
+    #[marshal(with = "optional_path_alox")]
+    pub title1_name: Path,
+    #[serde(with = "optional_path_serde")]
+    #[marshal(with = "optional_path_alox")]
+    pub title2_name: Path,
+    pub opt_draw_title: bool,
+    pub opt_use_midi: bool,
+    pub opt_transparent: bool,
+    pub opt_followers: bool,
+    pub opt_slip_death: bool,
+    pub opt_floor_death: bool,
+    pub opt_display_tp: bool,
+    pub opt_extra_exp: bool,
+    pub window_tone: Tone,
+    pub title_bgm: BGM,
+    pub battle_bgm: BGM,
+    pub battle_end_me: ME,
+    pub gameover_me: ME,
+    /// The 24 system sound effects, in the order they are listed in the database.
+    pub sounds: Vec<SE>,
+    pub test_battlers: Vec<TestBattler>,
+    #[serde(with = "optional_id_serde")]
+    #[marshal(with = "optional_id_alox")]
+    pub test_troop_id: Option<usize>,
+    #[serde(with = "id_serde")]
+    #[marshal(with = "id_alox")]
+    pub start_map_id: usize,
+    pub start_x: i32,
+    pub start_y: i32,
+    pub terms: Terms,
+    #[serde(with = "optional_path_serde")]
+    #[marshal(with = "optional_path_alox")]
+    pub battleback1_name: Path,
+    #[serde(with = "optional_path_serde")]
+    #[marshal(with = "optional_path_alox")]
+    pub battleback2_name: Path,
+    #[serde(with = "optional_path_serde")]
+    #[marshal(with = "optional_path_alox")]
+    pub battler_name: Path,
+    pub battler_hue: i32,
+    pub edit_map_id: usize,
+
+    #[marshal(skip)]
+    #[serde(skip)]
+    pub modified: bool,
+}
+
+/// RGSS3 stores terms as plain arrays instead of one field per term.
+#[derive(Default, Debug, serde::Deserialize, serde::Serialize)]
+#[derive(alox_48::Deserialize, alox_48::Serialize)]
+#[marshal(class = "RPG::System::Terms")]
+pub struct Terms {
+    /// Level, level (abbreviated), HP, HP (abbreviated), MP, MP (abbreviated), TP and TP (abbreviated).
+    pub basic: Vec<String>,
+    /// Max HP, max MP, ATK, DEF, MAT, MDF, AGI and LUK.
+    pub params: Vec<String>,
+    /// Weapon, shield, head, body and accessory.
+    pub etypes: Vec<String>,
+    pub commands: Vec<String>,
+}
+
+#[derive(Default, Debug, serde::Deserialize, serde::Serialize)]
+#[derive(alox_48::Deserialize, alox_48::Serialize)]
+#[marshal(class = "RPG::System::TestBattler")]
+pub struct TestBattler {
+    #[serde(with = "id_serde")]
+    #[marshal(with = "id_alox")]
+    pub actor_id: usize,
+    pub level: i32,
+    /// Equipment ids for each equipment slot (0 is empty).
+    pub equips: Vec<usize>,
+}
@@ -0,0 +1,88 @@
+// Copyright (C) 2024 Melody Madeline Lyons
+//
+// This file is part of Luminol.
+//
+// Luminol is free software: you can redistribute it and/or modify
+// it under the terms of the GNU General Public License as published by
+// the Free Software Foundation, either version 3 of the License, or
+// (at your option) any later version.
+//
+// Luminol is distributed in the hope that it will be useful,
+// but WITHOUT ANY WARRANTY; without even the implied warranty of
+// MERCHANTABILITY or FITNESS FOR A PARTICULAR PURPOSE.  See the
+// GNU General Public License for more details.
+//
+// You should have received a copy of the GNU General Public License
+// along with Luminol.  If not, see <http://www.gnu.org/licenses/>.
+use crate::{id_alox, id_serde, Table1};
+
+#[derive(Debug, serde::Deserialize, serde::Serialize)]
+#[derive(alox_48::Deserialize, alox_48::Serialize)]
+#[marshal(class = "RPG::Tileset")]
+pub struct Tileset {
+    #[serde(with = "id_serde")]
+    #[marshal(with = "id_alox")]
+    pub id: usize,
+    pub mode: Mode,
+    pub name: String,
+    /// The A1 to A5 and B to E tile sheets, in that order.
+    pub tileset_names: Vec<String>,
+    /// Passage, priority (star), bush, counter, damage floor, ladder and terrain tag flags for each tile id.
+    pub flags: Table1,
+    pub note: String,
+}
+
+impl Default for Tileset {
+    fn default() -> Self {
+        Self {
+            id: 0,
+            mode: Mode::default(),
+            name: String::new(),
+            tileset_names: vec![String::new(); 9],
+            flags: Table1::new(8192),
+            note: String::new(),
+        }
+    }
+}
+
+#[derive(Clone, Copy, Debug, Eq, PartialEq, Default)]
+#[derive(
+    num_enum::TryFromPrimitive,
+    num_enum::IntoPrimitive,
+    strum::Display,
+    strum::EnumIter
+)]
+#[derive(serde::Deserialize, serde::Serialize)]
+#[derive(alox_48::Deserialize, alox_48::Serialize)]
+#[repr(u8)]
+#[serde(into = "u8")]
+#[serde(try_from = "u8")]
+#[marshal(into = "u8")]
+#[marshal(try_from = "u8")]
+pub enum Mode {
+    #[strum(to_string = "World Type")]
+    World = 0,
+    #[default]
+    #[strum(to_string = "Field Type")]
+    Field = 1,
+    #[strum(to_string = "Area Type")]
+    Area = 2,
+}
+
+/// Bit flags stored in [`Tileset::flags`].
+pub mod flags {
+    pub const IMPASSABLE_DOWN: i16 = 0x0001;
+    pub const IMPASSABLE_LEFT: i16 = 0x0002;
+    pub const IMPASSABLE_RIGHT: i16 = 0x0004;
+    pub const IMPASSABLE_UP: i16 = 0x0008;
+    pub const STAR: i16 = 0x0010;
+    pub const LADDER: i16 = 0x0020;
+    pub const BUSH: i16 = 0x0040;
+    pub const COUNTER: i16 = 0x0080;
+    pub const DAMAGE_FLOOR: i16 = 0x0100;
+    pub const BOAT_IMPASSABLE: i16 = 0x0200;
+    pub const SHIP_IMPASSABLE: i16 = 0x0400;
+    pub const AIRSHIP_IMPASSABLE: i16 = 0x0800;
+    /// The terrain tag is stored in the upper 4 bits.
+    pub const TERRAIN_TAG_SHIFT: u32 = 12;
+}
@@ -0,0 +1,110 @@
+// Copyright (C) 2024 Melody Madeline Lyons
+//
+// This file is part of Luminol.
+//
+// Luminol is free software: you can redistribute it and/or modify
+// it under the terms of the GNU General Public License as published by
+// the Free Software Foundation, either version 3 of the License, or
+// (at your option) any later version.
+//
+// Luminol is distributed in the hope that it will be useful,
+// but WITHOUT ANY WARRANTY; without even the implied warranty of
+// MERCHANTABILITY or FITNESS FOR A PARTICULAR PURPOSE.  See the
+// GNU General Public License for more details.
+//
+// You should have received a copy of the GNU General Public License
+// along with Luminol.  If not, see <http://www.gnu.org/licenses/>.
+use crate::{optional_id_alox, optional_id_serde, ParameterType};
+
+#[derive(Debug, Clone, serde::Deserialize, serde::Serialize)]
+#[derive(alox_48::Deserialize, alox_48::Serialize)]
+#[marshal(class = "RPG::UsableItem::Damage")]
+pub struct Damage {
+    #[serde(rename = "type")]
+    #[marshal(rename = "type")]
+    pub kind: DamageKind,
+    #[serde(with = "optional_id_serde")]
+    #[marshal(with = "optional_id_alox")]
+    pub element_id: Option<usize>,
+    pub formula: String,
+    pub variance: i32,
+    pub critical: bool,
+}
+
+impl Default for Damage {
+    fn default() -> Self {
+        Self {
+            kind: DamageKind::None,
+            element_id: None,
+            formula: "0".to_string(),
+            variance: 20,
+            critical: false,
+        }
+    }
+}
+
+#[derive(Clone, Copy, Debug, Eq, PartialEq, Default)]
+#[derive(
+    num_enum::TryFromPrimitive,
+    num_enum::IntoPrimitive,
+    strum::Display,
+    strum::EnumIter
+)]
+#[derive(serde::Deserialize, serde::Serialize)]
+#[derive(alox_48::Deserialize, alox_48::Serialize)]
+#[repr(u8)]
+#[serde(into = "u8")]
+#[serde(try_from = "u8")]
+#[marshal(into = "u8")]
+#[marshal(try_from = "u8")]
+pub enum DamageKind {
+    #[default]
+    None = 0,
+    #[strum(to_string = "HP Damage")]
+    HPDamage = 1,
+    #[strum(to_string = "MP Damage")]
+    MPDamage = 2,
+    #[strum(to_string = "HP Recover")]
+    HPRecover = 3,
+    #[strum(to_string = "MP Recover")]
+    MPRecover = 4,
+    #[strum(to_string = "HP Drain")]
+    HPDrain = 5,
+    #[strum(to_string = "MP Drain")]
+    MPDrain = 6,
+}
+
+#[derive(Default, Debug, Clone, PartialEq, serde::Deserialize, serde::Serialize)]
+#[derive(alox_48::Deserialize, alox_48::Serialize)]
+#[marshal(class = "RPG::UsableItem::Effect")]
+pub struct Effect {
+    pub code: u16,
+    pub data_id: i32,
+    /// The meaning (and ruby type) of these values depends on the effect code.
+    pub value1: ParameterType,
+    pub value2: ParameterType,
+}
+
+#[derive(Clone, Copy, Debug, Eq, PartialEq, Default)]
+#[derive(
+    num_enum::TryFromPrimitive,
+    num_enum::IntoPrimitive,
+    strum::Display,
+    strum::EnumIter
+)]
+#[derive(serde::Deserialize, serde::Serialize)]
+#[derive(alox_48::Deserialize, alox_48::Serialize)]
+#[repr(u8)]
+#[serde(into = "u8")]
+#[serde(try_from = "u8")]
+#[marshal(into = "u8")]
+#[marshal(try_from = "u8")]
+pub enum HitType {
+    #[default]
+    #[strum(to_string = "Certain Hit")]
+    CertainHit = 0,
+    #[strum(to_string = "Physical Attack")]
+    PhysicalAttack = 1,
+    #[strum(to_string = "Magical Attack")]
+    MagicalAttack = 2,
+}
@@ -16,7 +16,7 @@
 // along with Luminol.  If not, see <http://www.gnu.org/licenses/>.
 use crate::{
     id_alox, id_serde, optional_id_alox, optional_id_serde, optional_path_alox,
-    optional_path_serde, Path, Table2,
+    optional_path_serde, ExtraIvars, Path, Table2,
 };
 
 #[derive(Default, Debug, serde::Deserialize, serde::Serialize)]
@@ -63,4 +63,8 @@ pub struct Actor {
     pub armor2_fix: bool,
     pub armor3_fix: bool,
     pub armor4_fix: bool,
+
+    #[serde(default, skip_serializing_if = "ExtraIvars::is_empty")]
+    #[marshal(skip)]
+    pub extra_ivars: ExtraIvars,
 }
@@ -15,7 +15,8 @@
 // You should have received a copy of the GNU General Public License
 // along with Luminol.  If not, see <http://www.gnu.org/licenses/>.
 use crate::{
-    id_alox, id_serde, optional_path_alox, optional_path_serde, rpg::AudioFile, Color, Path, Table2,
+    id_alox, id_serde, optional_path_alox, optional_path_serde, rpg::AudioFile, Color, ExtraIvars,
+    Path, Table2,
 };
 
 #[derive(Default, Debug, serde::Deserialize, serde::Serialize)]
@@ -34,6 +35,10 @@ pub struct Animation {
     pub frame_max: usize,
     pub frames: Vec<Frame>,
     pub timings: Vec<Timing>,
+
+    #[serde(default, skip_serializing_if = "ExtraIvars::is_empty")]
+    #[marshal(skip)]
+    pub extra_ivars: ExtraIvars,
 }
 
 #[derive(Debug, Clone, serde::Deserialize, serde::Serialize)]
@@ -46,6 +51,10 @@ pub struct Timing {
     pub flash_color: Color,
     pub flash_duration: usize,
     pub condition: Condition,
+
+    #[serde(default, skip_serializing_if = "ExtraIvars::is_empty")]
+    #[marshal(skip)]
+    pub extra_ivars: ExtraIvars,
 }
 
 impl Default for Timing {
@@ -57,6 +66,7 @@ impl Default for Timing {
             flash_color: Color::default(),
             flash_duration: 1,
             condition: Condition::default(),
+            extra_ivars: ExtraIvars::default(),
         }
     }
 }
@@ -67,6 +77,10 @@ impl Default for Timing {
 pub struct Frame {
     pub cell_max: usize,
     pub cell_data: Table2,
+
+    #[serde(default, skip_serializing_if = "ExtraIvars::is_empty")]
+    #[marshal(skip)]
+    pub extra_ivars: ExtraIvars,
 }
 
 impl Frame {
@@ -14,7 +14,7 @@
 //
 // You should have received a copy of the GNU General Public License
 // along with Luminol.  If not, see <http://www.gnu.org/licenses/>.
-pub use crate::{id_alox, id_serde, id_vec_alox, id_vec_serde, Table1};
+pub use crate::{id_alox, id_serde, id_vec_alox, id_vec_serde, ExtraIvars, Table1};
 
 #[derive(Default, Debug, serde::Deserialize, serde::Serialize)]
 #[derive(alox_48::Deserialize, alox_48::Serialize)]
@@ -34,6 +34,10 @@ pub struct Class {
     pub element_ranks: Table1,
     pub state_ranks: Table1,
     pub learnings: Vec<Learning>,
+
+    #[serde(default, skip_serializing_if = "ExtraIvars::is_empty")]
+    #[marshal(skip)]
+    pub extra_ivars: ExtraIvars,
 }
 
 #[derive(Default, Debug, serde::Deserialize, serde::Serialize)]
@@ -44,6 +48,10 @@ pub struct Learning {
     #[serde(with = "id_serde")]
     #[marshal(with = "id_alox")]
     pub skill_id: usize,
+
+    #[serde(default, skip_serializing_if = "ExtraIvars::is_empty")]
+    #[marshal(skip)]
+    pub extra_ivars: ExtraIvars,
 }
 
 #[derive(Clone, Copy, Debug, Eq, PartialEq, Default)]
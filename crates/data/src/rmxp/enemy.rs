@@ -16,7 +16,7 @@
 // along with Luminol.  If not, see <http://www.gnu.org/licenses/>.
 pub use crate::{
     id_alox, id_serde, optional_id_alox, optional_id_serde, optional_path_alox,
-    optional_path_serde, Path, Table1,
+    optional_path_serde, ExtraIvars, Path, Table1,
 };
 
 #[derive(Default, Debug, serde::Deserialize, serde::Serialize)]
@@ -63,6 +63,10 @@ pub struct Enemy {
     #[marshal(with = "optional_id_alox")]
     pub armor_id: Option<usize>,
     pub treasure_prob: i32,
+
+    #[serde(default, skip_serializing_if = "ExtraIvars::is_empty")]
+    #[marshal(skip)]
+    pub extra_ivars: ExtraIvars,
 }
 
 #[derive(Debug, serde::Deserialize, serde::Serialize)]
@@ -82,6 +86,10 @@ pub struct Action {
     #[marshal(with = "optional_id_alox")]
     pub condition_switch_id: Option<usize>,
     pub rating: i32,
+
+    #[serde(default, skip_serializing_if = "ExtraIvars::is_empty")]
+    #[marshal(skip)]
+    pub extra_ivars: ExtraIvars,
 }
 
 impl Default for Action {
@@ -96,6 +104,7 @@ impl Default for Action {
             condition_level: 1,
             condition_switch_id: None,
             rating: 5,
+            extra_ivars: ExtraIvars::default(),
         }
     }
 }
@@ -15,9 +15,9 @@
 // You should have received a copy of the GNU General Public License
 // along with Luminol.  If not, see <http://www.gnu.org/licenses/>.
 use crate::rpg::{AudioFile, Event};
-use crate::{id_alox, id_serde, option_vec, Table3};
+use crate::{id_alox, id_serde, option_vec, ExtraIvars, Table3};
 
-#[derive(Default, Debug, serde::Deserialize, serde::Serialize)]
+#[derive(Default, Debug, Clone, serde::Deserialize, serde::Serialize)]
 #[derive(alox_48::Deserialize, alox_48::Serialize)]
 #[marshal(class = "RPG::Map")]
 pub struct Map {
@@ -38,4 +38,8 @@ pub struct Map {
     #[marshal(skip)]
     #[serde(skip)]
     pub modified: bool,
+
+    #[serde(default, skip_serializing_if = "ExtraIvars::is_empty")]
+    #[marshal(skip)]
+    pub extra_ivars: ExtraIvars,
 }
@@ -16,7 +16,7 @@
 // along with Luminol.  If not, see <http://www.gnu.org/licenses/>.
 pub use crate::{
     id_alox, id_serde, id_vec_alox, id_vec_serde, optional_id_alox, optional_id_serde,
-    optional_path_alox, optional_path_serde, rpg::AudioFile, Path,
+    optional_path_alox, optional_path_serde, rpg::AudioFile, ExtraIvars, Path,
 };
 
 #[derive(Default, Debug, serde::Deserialize, serde::Serialize)]
@@ -64,4 +64,8 @@ pub struct Skill {
     #[serde(with = "id_vec_serde")]
     #[marshal(with = "id_vec_alox")]
     pub minus_state_set: Vec<usize>,
+
+    #[serde(default, skip_serializing_if = "ExtraIvars::is_empty")]
+    #[marshal(skip)]
+    pub extra_ivars: ExtraIvars,
 }
@@ -14,7 +14,9 @@
 //
 // You should have received a copy of the GNU General Public License
 // along with Luminol.  If not, see <http://www.gnu.org/licenses/>.
-use crate::{id_alox, id_serde, id_vec_alox, id_vec_serde, optional_id_alox, optional_id_serde};
+use crate::{
+    id_alox, id_serde, id_vec_alox, id_vec_serde, optional_id_alox, optional_id_serde, ExtraIvars,
+};
 
 #[derive(Default, Debug, serde::Deserialize, serde::Serialize)]
 #[derive(alox_48::Deserialize, alox_48::Serialize)]
@@ -58,6 +60,10 @@ pub struct State {
     #[serde(with = "id_vec_serde")]
     #[marshal(with = "id_vec_alox")]
     pub minus_state_set: Vec<usize>,
+
+    #[serde(default, skip_serializing_if = "ExtraIvars::is_empty")]
+    #[marshal(skip)]
+    pub extra_ivars: ExtraIvars,
 }
 
 #[derive(Clone, Copy, Debug, Eq, PartialEq, Default)]
@@ -17,7 +17,7 @@
 pub use crate::{
     id_alox, id_serde, id_vec_alox, id_vec_serde, nil_padded_alox, nil_padded_serde,
     optional_id_alox, optional_id_serde, optional_path_alox, optional_path_serde, rpg::AudioFile,
-    Path,
+    ExtraIvars, Path,
 };
 
 #[derive(Default, Debug)]
@@ -68,9 +68,7 @@ pub struct System {
     pub actor_collapse_se: AudioFile,
     pub enemy_collapse_se: AudioFile,
     pub words: Words,
-    #[serde(skip)]
-    // #[marshal(skip)]
-    pub test_battlers: alox_48::Value,
+    pub test_battlers: Vec<TestBattler>,
     #[serde(with = "optional_id_serde")]
     #[marshal(with = "optional_id_alox")]
     pub test_troop_id: Option<usize>,
@@ -91,6 +89,10 @@ pub struct System {
     #[marshal(skip)]
     #[serde(skip)]
     pub modified: bool,
+
+    #[serde(default, skip_serializing_if = "ExtraIvars::is_empty")]
+    #[marshal(skip)]
+    pub extra_ivars: ExtraIvars,
 }
 
 #[derive(Default, Debug, serde::Deserialize, serde::Serialize)]
@@ -98,50 +100,58 @@ pub struct System {
 #[marshal(class = "RPG::System::Words")]
 #[serde(default)]
 pub struct Words {
-    gold: String,
-    hp: String,
-    sp: String,
-    str: String,
-    dex: String,
-    agi: String,
-    int: String,
-    atk: String,
-    pdef: String,
-    mdef: String,
-    weapon: String,
-    armor1: String,
-    armor2: String,
-    armor3: String,
-    armor4: String,
-    attack: String,
-    skill: String,
-    guard: String,
-    item: String,
-    equip: String,
+    pub gold: String,
+    pub hp: String,
+    pub sp: String,
+    pub str: String,
+    pub dex: String,
+    pub agi: String,
+    pub int: String,
+    pub atk: String,
+    pub pdef: String,
+    pub mdef: String,
+    pub weapon: String,
+    pub armor1: String,
+    pub armor2: String,
+    pub armor3: String,
+    pub armor4: String,
+    pub attack: String,
+    pub skill: String,
+    pub guard: String,
+    pub item: String,
+    pub equip: String,
+
+    #[serde(default, skip_serializing_if = "ExtraIvars::is_empty")]
+    #[marshal(skip)]
+    pub extra_ivars: ExtraIvars,
 }
 
 #[derive(Default, Debug, serde::Deserialize, serde::Serialize)]
 #[derive(alox_48::Deserialize, alox_48::Serialize)]
 #[marshal(class = "RPG::System::TestBattler")]
 pub struct TestBattler {
-    level: i32,
+    pub level: i32,
 
     #[serde(with = "id_serde")]
     #[marshal(with = "id_alox")]
-    actor_id: usize,
+    pub actor_id: usize,
     #[serde(with = "optional_id_serde")]
     #[marshal(with = "optional_id_alox")]
-    weapon_id: Option<usize>,
+    pub weapon_id: Option<usize>,
     #[serde(with = "optional_id_serde")]
     #[marshal(with = "optional_id_alox")]
-    armor1_id: Option<usize>,
+    pub armor1_id: Option<usize>,
     #[serde(with = "optional_id_serde")]
     #[marshal(with = "optional_id_alox")]
-    armor2_id: Option<usize>,
+    pub armor2_id: Option<usize>,
     #[serde(with = "optional_id_serde")]
     #[marshal(with = "optional_id_alox")]
-    armor3_id: Option<usize>,
+    pub armor3_id: Option<usize>,
     #[serde(with = "optional_id_serde")]
     #[marshal(with = "optional_id_alox")]
-    armor4_id: Option<usize>,
+    pub armor4_id: Option<usize>,
+
+    #[serde(default, skip_serializing_if = "ExtraIvars::is_empty")]
+    #[marshal(skip)]
+    pub extra_ivars: ExtraIvars,
 }
@@ -15,7 +15,9 @@
 // You should have received a copy of the GNU General Public License
 // along with Luminol.  If not, see <http://www.gnu.org/licenses/>.
 
-use crate::{id_alox, id_serde, optional_path_alox, optional_path_serde, BlendMode, Path, Table1};
+use crate::{
+    id_alox, id_serde, optional_path_alox, optional_path_serde, BlendMode, ExtraIvars, Path, Table1,
+};
 
 #[derive(Default, Debug, serde::Deserialize, serde::Serialize)]
 #[derive(alox_48::Deserialize, alox_48::Serialize)]
@@ -48,4 +50,8 @@ pub struct Tileset {
     pub passages: Table1,
     pub priorities: Table1,
     pub terrain_tags: Table1,
+
+    #[serde(default, skip_serializing_if = "ExtraIvars::is_empty")]
+    #[marshal(skip)]
+    pub extra_ivars: ExtraIvars,
 }
@@ -14,7 +14,9 @@
 //
 // You should have received a copy of the GNU General Public License
 // along with Luminol.  If not, see <http://www.gnu.org/licenses/>.
-use crate::{id_alox, id_serde, optional_id_alox, optional_id_serde, rpg::EventCommand};
+use crate::{
+    id_alox, id_serde, optional_id_alox, optional_id_serde, rpg::EventCommand, ExtraIvars,
+};
 
 #[derive(Default, Debug, serde::Deserialize, serde::Serialize)]
 #[derive(alox_48::Deserialize, alox_48::Serialize)]
@@ -26,6 +28,10 @@ pub struct Troop {
     pub name: String,
     pub members: Vec<Member>,
     pub pages: Vec<Page>,
+
+    #[serde(default, skip_serializing_if = "ExtraIvars::is_empty")]
+    #[marshal(skip)]
+    pub extra_ivars: ExtraIvars,
 }
 
 #[derive(Default, Debug, serde::Deserialize, serde::Serialize)]
@@ -39,6 +45,10 @@ pub struct Member {
     pub y: i32,
     pub hidden: bool,
     pub immortal: bool,
+
+    #[serde(default, skip_serializing_if = "ExtraIvars::is_empty")]
+    #[marshal(skip)]
+    pub extra_ivars: ExtraIvars,
 }
 
 #[derive(Default, Debug, serde::Deserialize, serde::Serialize)]
@@ -48,6 +58,10 @@ pub struct Page {
     pub condition: Condition,
     pub span: i32,
     pub list: Vec<EventCommand>,
+
+    #[serde(default, skip_serializing_if = "ExtraIvars::is_empty")]
+    #[marshal(skip)]
+    pub extra_ivars: ExtraIvars,
 }
 
 #[derive(Default, Debug, serde::Deserialize, serde::Serialize)]
@@ -69,4 +83,8 @@ pub struct Condition {
     #[serde(with = "optional_id_serde")]
     #[marshal(with = "optional_id_alox")]
     pub switch_id: Option<usize>,
+
+    #[serde(default, skip_serializing_if = "ExtraIvars::is_empty")]
+    #[marshal(skip)]
+    pub extra_ivars: ExtraIvars,
 }
@@ -14,7 +14,7 @@
 //
 // You should have received a copy of the GNU General Public License
 // along with Luminol.  If not, see <http://www.gnu.org/licenses/>.
-use crate::{optional_path_alox, optional_path_serde, Path};
+use crate::{optional_path_alox, optional_path_serde, ExtraIvars, Path};
 
 #[derive(Debug, Clone, PartialEq)]
 #[derive(serde::Deserialize, serde::Serialize)]
@@ -26,6 +26,16 @@ pub struct AudioFile {
     pub name: Path,
     pub volume: u8,
     pub pitch: u8,
+
+    /// The class this audio file was stored as inside of event command parameters.
+    /// RGSS2 and RGSS3 store audio as subclasses of `RPG::AudioFile`.
+    #[serde(default, skip_serializing_if = "AudioKind::is_audio_file")]
+    #[marshal(skip)]
+    pub kind: AudioKind,
+
+    #[serde(default, skip_serializing_if = "ExtraIvars::is_empty")]
+    #[marshal(skip)]
+    pub extra_ivars: ExtraIvars,
 }
 
 impl Default for AudioFile {
@@ -34,6 +44,103 @@ impl Default for AudioFile {
             name: None,
             volume: 100,
             pitch: 100,
+            kind: AudioKind::AudioFile,
+            extra_ivars: ExtraIvars::default(),
         }
     }
 }
+
+#[derive(Debug, Default, Clone, Copy, PartialEq, Eq, Hash)]
+#[derive(serde::Deserialize, serde::Serialize)]
+#[allow(clippy::upper_case_acronyms)]
+pub enum AudioKind {
+    #[default]
+    AudioFile,
+    BGM,
+    BGS,
+    ME,
+    SE,
+}
+
+impl AudioKind {
+    pub fn is_audio_file(&self) -> bool {
+        matches!(self, Self::AudioFile)
+    }
+
+    /// Returns the kind of audio file stored with the given ruby class, if it is an audio file.
+    pub fn from_class(class: &str) -> Option<Self> {
+        match class {
+            "RPG::AudioFile" => Some(Self::AudioFile),
+            "RPG::BGM" => Some(Self::BGM),
+            "RPG::BGS" => Some(Self::BGS),
+            "RPG::ME" => Some(Self::ME),
+            "RPG::SE" => Some(Self::SE),
+            _ => None,
+        }
+    }
+}
+
+macro_rules! audio_subclass {
+    ($($type:ident, $class:literal),* $(,)?) => {
+        $(
+            #[derive(Debug, Clone, PartialEq)]
+            #[derive(serde::Deserialize, serde::Serialize)]
+            #[derive(alox_48::Deserialize, alox_48::Serialize)]
+            #[marshal(class = $class)]
+            #[allow(clippy::upper_case_acronyms)]
+            pub struct $type {
+                #[serde(with = "optional_path_serde")]
+                #[marshal(with = "optional_path_alox")]
+                pub name: Path,
+                pub volume: u8,
+                pub pitch: u8,
+
+                #[serde(default, skip_serializing_if = "ExtraIvars::is_empty")]
+                #[marshal(skip)]
+                pub extra_ivars: ExtraIvars,
+            }
+
+            impl Default for $type {
+                fn default() -> Self {
+                    Self {
+                        name: None,
+                        volume: 100,
+                        pitch: 100,
+                        extra_ivars: ExtraIvars::default(),
+                    }
+                }
+            }
+
+            impl From<$type> for AudioFile {
+                fn from(value: $type) -> Self {
+                    Self {
+                        name: value.name,
+                        volume: value.volume,
+                        pitch: value.pitch,
+                        kind: AudioKind::$type,
+                        extra_ivars: value.extra_ivars,
+                    }
+                }
+            }
+
+            impl From<AudioFile> for $type {
+                fn from(value: AudioFile) -> Self {
+                    Self {
+                        name: value.name,
+                        volume: value.volume,
+                        pitch: value.pitch,
+                        extra_ivars: value.extra_ivars,
+                    }
+                }
+            }
+        )*
+    };
+}
+
+// RGSS2 and RGSS3 split RPG::AudioFile into subclasses with the same fields.
+audio_subclass! {
+    BGM, "RPG::BGM",
+    BGS, "RPG::BGS",
+    ME, "RPG::ME",
+    SE, "RPG::SE",
+}
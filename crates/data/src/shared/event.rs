@@ -16,7 +16,7 @@
 // along with Luminol.  If not, see <http://www.gnu.org/licenses/>.
 use crate::{
     id_alox, id_serde, optional_id_alox, optional_id_serde, optional_path_alox,
-    optional_path_serde, rpg::MoveRoute, BlendMode, ParameterType, Path,
+    optional_path_serde, rpg::MoveRoute, BlendMode, ExtraIvars, ParameterType, Path,
 };
 
 #[derive(Debug, serde::Deserialize, serde::Serialize, Clone)]
@@ -34,6 +34,10 @@ pub struct Event {
     #[serde(skip)]
     #[marshal(skip)]
     pub extra_data: EventExtraData,
+
+    #[serde(default, skip_serializing_if = "ExtraIvars::is_empty")]
+    #[marshal(skip)]
+    pub extra_ivars: ExtraIvars,
 }
 
 #[derive(Debug, Default, Clone)]
@@ -54,6 +58,7 @@ impl Event {
             pages: vec![EventPage::default()],
 
             extra_data: EventExtraData::default(),
+            extra_ivars: ExtraIvars::default(),
         }
     }
 }
@@ -69,6 +74,10 @@ pub struct CommonEvent {
     pub trigger: usize,
     pub switch_id: usize,
     pub list: Vec<EventCommand>,
+
+    #[serde(default, skip_serializing_if = "ExtraIvars::is_empty")]
+    #[marshal(skip)]
+    pub extra_ivars: ExtraIvars,
 }
 
 #[derive(Debug, serde::Deserialize, serde::Serialize, Clone)]
@@ -88,6 +97,10 @@ pub struct EventPage {
     pub always_on_top: bool,
     pub trigger: EventTrigger,
     pub list: Vec<EventCommand>,
+
+    #[serde(default, skip_serializing_if = "ExtraIvars::is_empty")]
+    #[marshal(skip)]
+    pub extra_ivars: ExtraIvars,
 }
 
 #[derive(serde::Deserialize, serde::Serialize)]
@@ -176,6 +189,7 @@ impl Default for EventPage {
             always_on_top: false,
             trigger: EventTrigger::ActionButton,
             list: vec![],
+            extra_ivars: ExtraIvars::default(),
         }
     }
 }
@@ -195,6 +209,10 @@ pub struct Graphic {
     pub pattern: i32,
     pub opacity: i32,
     pub blend_type: BlendMode,
+
+    #[serde(default, skip_serializing_if = "ExtraIvars::is_empty")]
+    #[marshal(skip)]
+    pub extra_ivars: ExtraIvars,
 }
 
 impl Default for Graphic {
@@ -207,6 +225,7 @@ impl Default for Graphic {
             pattern: 0,
             opacity: 255,
             blend_type: BlendMode::Normal,
+            extra_ivars: ExtraIvars::default(),
         }
     }
 }
@@ -230,6 +249,10 @@ pub struct EventCondition {
     pub variable_id: usize,
     pub variable_value: i32,
     pub self_switch_ch: SelfSwitch,
+
+    #[serde(default, skip_serializing_if = "ExtraIvars::is_empty")]
+    #[marshal(skip)]
+    pub extra_ivars: ExtraIvars,
 }
 
 impl Default for EventCondition {
@@ -244,6 +267,7 @@ impl Default for EventCondition {
             variable_id: 0,
             variable_value: 0,
             self_switch_ch: SelfSwitch::A,
+            extra_ivars: ExtraIvars::default(),
         }
     }
 }
@@ -298,4 +322,8 @@ pub struct EventCommand {
     #[serde(default = "rand::random")]
     #[serde(skip)]
     pub guid: u16,
+
+    #[serde(default, skip_serializing_if = "ExtraIvars::is_empty")]
+    #[marshal(skip)]
+    pub extra_ivars: ExtraIvars,
 }
@@ -15,6 +15,8 @@
 // You should have received a copy of the GNU General Public License
 // along with Luminol.  If not, see <http://www.gnu.org/licenses/>.
 
+use crate::ExtraIvars;
+
 #[derive(Default, Debug, serde::Deserialize, serde::Serialize, PartialEq, Eq)]
 #[derive(alox_48::Deserialize, alox_48::Serialize)]
 #[marshal(class = "RPG::MapInfo")]
@@ -28,6 +30,10 @@ pub struct MapInfo {
     pub expanded: bool,
     pub scroll_x: i32,
     pub scroll_y: i32,
+
+    #[serde(default, skip_serializing_if = "ExtraIvars::is_empty")]
+    #[marshal(skip)]
+    pub extra_ivars: ExtraIvars,
 }
 
 impl PartialOrd for MapInfo {
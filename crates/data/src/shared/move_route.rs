@@ -14,7 +14,7 @@
 //
 // You should have received a copy of the GNU General Public License
 // along with Luminol.  If not, see <http://www.gnu.org/licenses/>.
-use crate::helpers::ParameterType;
+use crate::{helpers::ParameterType, ExtraIvars};
 
 #[derive(Default, Debug, serde::Deserialize, serde::Serialize, Clone, PartialEq)]
 #[derive(alox_48::Deserialize, alox_48::Serialize)]
@@ -23,6 +23,10 @@ pub struct MoveRoute {
     pub repeat: bool,
     pub skippable: bool,
     pub list: Vec<MoveCommand>,
+
+    #[serde(default, skip_serializing_if = "ExtraIvars::is_empty")]
+    #[marshal(skip)]
+    pub extra_ivars: ExtraIvars,
 }
 
 #[derive(Default, Debug, serde::Deserialize, serde::Serialize, Clone, PartialEq)]
@@ -38,4 +42,8 @@ pub struct MoveCommand {
     #[serde(default = "rand::random")]
     #[serde(skip)]
     pub guid: u16,
+
+    #[serde(default, skip_serializing_if = "ExtraIvars::is_empty")]
+    #[marshal(skip)]
+    pub extra_ivars: ExtraIvars,
 }
@@ -27,6 +27,8 @@ pub use trie::*;
 
 #[cfg(not(target_arch = "wasm32"))]
 pub mod native;
+#[cfg(not(target_arch = "wasm32"))]
+pub mod watcher;
 #[cfg(target_arch = "wasm32")]
 pub mod web;
 
@@ -227,6 +229,13 @@ pub trait FileSystem: Send + Sync {
 
     fn read_dir(&self, path: impl AsRef<camino::Utf8Path>) -> Result<Vec<DirEntry>>;
 
+    /// Starts watching this filesystem for changes made by other programs.
+    /// Returns `None` if this filesystem can't be watched.
+    #[cfg(not(target_arch = "wasm32"))]
+    fn watch(&self) -> Result<Option<watcher::Watcher>> {
+        Ok(None)
+    }
+
     /// Corresponds to [`std::fs::read()`].
     /// Will open a file at the path and read the entire file into a buffer.
     fn read(&self, path: impl AsRef<camino::Utf8Path>) -> Result<Vec<u8>> {
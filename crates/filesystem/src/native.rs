@@ -207,6 +207,12 @@ impl crate::FileSystem for FileSystem {
             .flatten()
             .try_collect()
     }
+
+    fn watch(&self) -> Result<Option<crate::watcher::Watcher>> {
+        crate::watcher::Watcher::new(&self.root_path)
+            .wrap_err_with(|| format!("While watching the host folder {:?}", self.root_path))
+            .map(Some)
+    }
 }
 
 impl File {
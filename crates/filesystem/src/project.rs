@@ -32,6 +32,9 @@ pub enum FileSystem {
         filesystem: path_cache::FileSystem<list::FileSystem>,
         host_filesystem: host::FileSystem,
         project_path: camino::Utf8PathBuf,
+        /// Watches the project folder for changes made by other programs.
+        #[cfg(not(target_arch = "wasm32"))]
+        watcher: Option<crate::watcher::Watcher>,
     },
 }
 
@@ -72,11 +75,102 @@ impl FileSystem {
         };
         filesystem.rebuild();
     }
+
+    /// Returns the paths in the project folder that other programs created, modified or removed
+    /// since the last call. Changes made by Luminol itself and changes in `.luminol` are left out.
+    ///
+    /// Changes are currently only detected on native.
+    pub fn changes(&self) -> Vec<camino::Utf8PathBuf> {
+        #[cfg(not(target_arch = "wasm32"))]
+        if let FileSystem::Loaded {
+            filesystem,
+            watcher: Some(watcher),
+            ..
+        } = self
+        {
+            let changes: Vec<_> = watcher
+                .changes()
+                .into_iter()
+                .filter(|path| !path.starts_with(".luminol"))
+                .collect();
+            if !changes.is_empty() {
+                // files may have been added or removed
+                filesystem.rebuild();
+            }
+            return changes;
+        }
+
+        Vec::new()
+    }
+
+    /// Stops `path` from being reported as changed by [`Self::changes`] for a short while.
+    fn ignore_changes(&self, path: impl AsRef<camino::Utf8Path>) {
+        #[cfg(not(target_arch = "wasm32"))]
+        if let FileSystem::Loaded {
+            watcher: Some(watcher),
+            ..
+        } = self
+        {
+            watcher.ignore(path);
+        }
+        #[cfg(target_arch = "wasm32")]
+        let _ = path;
+    }
+
+    /// Like [`Self::ignore_changes`], for a folder about to be created along with any of its
+    /// parent folders that don't exist yet.
+    fn ignore_created_dir(&self, path: &camino::Utf8Path) {
+        for ancestor in path.ancestors() {
+            if ancestor.as_str().is_empty() || self.exists(ancestor).unwrap_or(true) {
+                break;
+            }
+            self.ignore_changes(ancestor);
+        }
+    }
+
+    /// Like [`Self::ignore_changes`], for a folder about to be removed along with everything
+    /// inside of it.
+    fn ignore_removed_dir(&self, path: &camino::Utf8Path) {
+        #[cfg(not(target_arch = "wasm32"))]
+        if let FileSystem::Loaded {
+            filesystem,
+            watcher: Some(watcher),
+            ..
+        } = self
+        {
+            let mut dirs = vec![path.to_path_buf()];
+            while let Some(dir) = dirs.pop() {
+                for entry in filesystem.read_dir(&dir).unwrap_or_default() {
+                    if !entry.metadata.is_file {
+                        dirs.push(entry.path.clone());
+                    }
+                    watcher.ignore(entry.path);
+                }
+                watcher.ignore(dir);
+            }
+        }
+        #[cfg(target_arch = "wasm32")]
+        let _ = path;
+    }
 }
 
 // Not platform specific
 impl FileSystem {
     fn detect_rm_ver(&self) -> Option<luminol_config::RMVer> {
+        // The editor's project file is the most reliable indicator, since converted projects
+        // can still have data files from other versions lying around
+        if self.exists("Game.rxproj").ok()? {
+            return Some(luminol_config::RMVer::XP);
+        }
+
+        if self.exists("Game.rvproj").ok()? {
+            return Some(luminol_config::RMVer::VX);
+        }
+
+        if self.exists("Game.rvproj2").ok()? {
+            return Some(luminol_config::RMVer::Ace);
+        }
+
         if self.exists("Data/Actors.rxdata").ok()? {
             return Some(luminol_config::RMVer::XP);
         }
@@ -198,9 +292,24 @@ impl FileSystem {
             }
         };
 
+        // Unlike the command database, a broken terrain file isn't replaced with the default,
+        // since saving the project would then overwrite the user's terrains
+        let terrains = if self.exists(".luminol/terrains").wrap_err(c)? {
+            let terrains = self
+                .read_to_string(".luminol/terrains")
+                .wrap_err("While reading .luminol/terrains")
+                .wrap_err(c)?;
+            ron::from_str(&terrains)
+                .wrap_err("While reading .luminol/terrains")
+                .wrap_err(c)?
+        } else {
+            Default::default()
+        };
+
         Ok(luminol_config::project::Config {
             project,
             command_db,
+            terrains,
             game_ini,
         })
     }
@@ -448,10 +557,18 @@ impl FileSystem {
 
         let path_cache = path_cache::FileSystem::new(list)?;
 
+        let watcher = host_clone.watch().unwrap_or_else(|error| {
+            tracing::warn!(
+                "Changes made to the project by other programs won't be detected: {error:?}"
+            );
+            None
+        });
+
         *self = FileSystem::Loaded {
             filesystem: path_cache,
             host_filesystem: host_clone,
             project_path: project_path.to_path_buf(),
+            watcher,
         };
 
         // FIXME: handle
@@ -669,6 +786,9 @@ impl crate::FileSystem for FileSystem {
         path: impl AsRef<camino::Utf8Path>,
         flags: OpenFlags,
     ) -> Result<Self::File> {
+        if flags.intersects(OpenFlags::Write | OpenFlags::Truncate | OpenFlags::Create) {
+            self.ignore_changes(&path);
+        }
         match self {
             FileSystem::Unloaded => Err(Error::NotLoaded.into()),
             FileSystem::HostLoaded(f) => f.open_file(path, flags).map(File::Host),
@@ -689,6 +809,8 @@ impl crate::FileSystem for FileSystem {
         from: impl AsRef<camino::Utf8Path>,
         to: impl AsRef<camino::Utf8Path>,
     ) -> Result<()> {
+        self.ignore_changes(&from);
+        self.ignore_changes(&to);
         match self {
             FileSystem::Unloaded => Err(Error::NotLoaded.into()),
             FileSystem::HostLoaded(f) => f.rename(from, to),
@@ -705,6 +827,7 @@ impl crate::FileSystem for FileSystem {
     }
 
     fn create_dir(&self, path: impl AsRef<camino::Utf8Path>) -> Result<()> {
+        self.ignore_created_dir(path.as_ref());
         match self {
             FileSystem::Unloaded => Err(Error::NotLoaded.into()),
             FileSystem::HostLoaded(f) => f.create_dir(path),
@@ -713,6 +836,7 @@ impl crate::FileSystem for FileSystem {
     }
 
     fn remove_dir(&self, path: impl AsRef<camino::Utf8Path>) -> Result<()> {
+        self.ignore_removed_dir(path.as_ref());
         match self {
             FileSystem::Unloaded => Err(Error::NotLoaded.into()),
             FileSystem::HostLoaded(f) => f.remove_dir(path),
@@ -721,6 +845,7 @@ impl crate::FileSystem for FileSystem {
     }
 
     fn remove_file(&self, path: impl AsRef<camino::Utf8Path>) -> Result<()> {
+        self.ignore_changes(&path);
         match self {
             FileSystem::Unloaded => Err(Error::NotLoaded.into()),
             FileSystem::HostLoaded(f) => f.remove_file(path),
@@ -0,0 +1,126 @@
+// Copyright (C) 2024 Melody Madeline Lyons
+//
+// This file is part of Luminol.
+//
+// Luminol is free software: you can redistribute it and/or modify
+// it under the terms of the GNU General Public License as published by
+// the Free Software Foundation, either version 3 of the License, or
+// (at your option) any later version.
+//
+// Luminol is distributed in the hope that it will be useful,
+// but WITHOUT ANY WARRANTY; without even the implied warranty of
+// MERCHANTABILITY or FITNESS FOR A PARTICULAR PURPOSE.  See the
+// GNU General Public License for more details.
+//
+// You should have received a copy of the GNU General Public License
+// along with Luminol.  If not, see <http://www.gnu.org/licenses/>.
+
+//! Watching host folders for changes made by other programs.
+
+use std::collections::{BTreeSet, HashMap};
+
+/// How long changes to a path are ignored for after Luminol changes it itself.
+const IGNORE_DURATION: std::time::Duration = std::time::Duration::from_secs(2);
+
+/// Collects the paths changed in a host folder and its subfolders.
+pub struct Watcher {
+    root_path: camino::Utf8PathBuf,
+    /// Events along with when they were received, since `changes` may be called much later
+    receiver: parking_lot::Mutex<
+        std::sync::mpsc::Receiver<(std::time::Instant, notify::Result<notify::Event>)>,
+    >,
+    ignored: parking_lot::Mutex<HashMap<camino::Utf8PathBuf, std::time::Instant>>,
+    // dropping this stops watching
+    _watcher: notify::RecommendedWatcher,
+}
+
+impl std::fmt::Debug for Watcher {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        f.debug_struct("Watcher")
+            .field("root_path", &self.root_path)
+            .finish_non_exhaustive()
+    }
+}
+
+impl Watcher {
+    pub fn new(root_path: impl AsRef<camino::Utf8Path>) -> crate::Result<Self> {
+        use notify::Watcher;
+
+        let root_path = root_path.as_ref().to_path_buf();
+        let (sender, receiver) = std::sync::mpsc::channel();
+        let mut watcher = notify::recommended_watcher(move |event| {
+            // the receiver is only dropped along with the watcher
+            let _ = sender.send((std::time::Instant::now(), event));
+        })?;
+        watcher.watch(root_path.as_std_path(), notify::RecursiveMode::Recursive)?;
+
+        Ok(Self {
+            root_path,
+            receiver: parking_lot::Mutex::new(receiver),
+            ignored: Default::default(),
+            _watcher: watcher,
+        })
+    }
+
+    /// Ignores changes to `path` for a short while. Used so that changes Luminol makes itself
+    /// aren't reported. Changes to other files inside of `path` are still reported.
+    pub fn ignore(&self, path: impl AsRef<camino::Utf8Path>) {
+        self.ignored.lock().insert(
+            crate::path_cache::to_lowercase(path),
+            std::time::Instant::now(),
+        );
+    }
+
+    /// Returns the paths that were created, modified or removed since the last call, relative to
+    /// the root of the watched folder.
+    pub fn changes(&self) -> Vec<camino::Utf8PathBuf> {
+        let mut ignored = self.ignored.lock();
+
+        let mut changes = BTreeSet::new();
+        for (received, event) in self.receiver.lock().try_iter() {
+            let event = match event {
+                Ok(event) => event,
+                Err(error) => {
+                    tracing::warn!("Error watching {}: {error}", self.root_path);
+                    continue;
+                }
+            };
+            if !matches!(
+                event.kind,
+                notify::EventKind::Create(_)
+                    | notify::EventKind::Modify(_)
+                    | notify::EventKind::Remove(_)
+            ) {
+                continue;
+            }
+
+            for path in event.paths {
+                let Some(path) = camino::Utf8PathBuf::from_path_buf(path)
+                    .ok()
+                    .and_then(|path| path.strip_prefix(&self.root_path).ok().map(Into::into))
+                else {
+                    continue;
+                };
+
+                // i hate windows.
+                #[cfg(windows)]
+                let path: camino::Utf8PathBuf = path.into_string().replace('\\', "/").into();
+
+                let lowercase = crate::path_cache::to_lowercase(&path);
+                // compare against when the event happened rather than now, so that Luminol's
+                // own changes are still ignored if this isn't called for a while
+                let is_ignored = ignored.get(&lowercase).is_some_and(|time| {
+                    received.saturating_duration_since(*time) < IGNORE_DURATION
+                });
+                if !is_ignored {
+                    changes.insert(path);
+                }
+            }
+        }
+
+        // events received from now on are too late to be ignored by these anyway
+        ignored.retain(|_, time| time.elapsed() < IGNORE_DURATION);
+
+        changes.into_iter().collect()
+    }
+}
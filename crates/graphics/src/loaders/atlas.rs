@@ -115,4 +115,13 @@ impl Loader {
         self.atlases.clear();
         self.animation_atlases.clear();
     }
+
+    /// Returns the IDs of the tilesets that have an atlas loaded.
+    pub fn loaded_atlas_ids(&self) -> Vec<usize> {
+        self.atlases.iter().map(|atlas| *atlas.key()).collect()
+    }
+
+    pub fn clear_animation_atlases(&self) {
+        self.animation_atlases.clear();
+    }
 }
@@ -257,6 +257,17 @@ impl Loader {
         self.loaded_textures.clear();
     }
 
+    /// Removes the texture loaded from `path`, so that it is read again the next time it is loaded.
+    /// Textures are matched case-insensitively and regardless of their file extension.
+    pub fn invalidate(&self, path: impl AsRef<camino::Utf8Path>) {
+        fn key(path: &camino::Utf8Path) -> String {
+            path.with_extension("").as_str().to_lowercase()
+        }
+        let path = key(path.as_ref());
+        self.loaded_textures
+            .retain(|loaded_path, _| key(loaded_path) != path);
+    }
+
     pub fn placeholder_texture(&self) -> Arc<Texture> {
         self.placeholder_texture.clone()
     }
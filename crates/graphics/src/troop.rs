@@ -0,0 +1,200 @@
+// Copyright (C) 2024 Melody Madeline Lyons
+//
+// This file is part of Luminol.
+//
+// Luminol is free software: you can redistribute it and/or modify
+// it under the terms of the GNU General Public License as published by
+// the Free Software Foundation, either version 3 of the License, or
+// (at your option) any later version.
+//
+// Luminol is distributed in the hope that it will be useful,
+// but WITHOUT ANY WARRANTY; without even the implied warranty of
+// MERCHANTABILITY or FITNESS FOR A PARTICULAR PURPOSE.  See the
+// GNU General Public License for more details.
+//
+// You should have received a copy of the GNU General Public License
+// along with Luminol.  If not, see <http://www.gnu.org/licenses/>.
+
+use color_eyre::eyre::WrapErr;
+
+use crate::{Drawable, GraphicsState, Renderable, Sprite, Texture, Viewport};
+
+pub const TROOP_WIDTH: usize = 640;
+pub const TROOP_HEIGHT: usize = 320;
+
+/// Opacity used for troop members that are hidden at the start of battle.
+const HIDDEN_OPACITY: i32 = 128;
+
+pub struct Troop {
+    pub viewport: Viewport,
+
+    battleback_sprite: Option<Sprite>,
+    members: Vec<Option<Member>>,
+}
+
+pub struct Member {
+    pub sprite: Sprite,
+    pub rect: egui::Rect,
+}
+
+impl Troop {
+    pub fn new(graphics_state: &GraphicsState) -> Self {
+        let viewport = Viewport::new(
+            graphics_state,
+            glam::vec2(TROOP_WIDTH as f32, TROOP_HEIGHT as f32),
+        );
+
+        Self {
+            viewport,
+            battleback_sprite: None,
+            members: Vec::new(),
+        }
+    }
+
+    /// The sprites of the troop's members, in the same order as the members of the troop. Members
+    /// whose enemy has no battler graphic don't have a sprite.
+    #[inline]
+    pub fn members(&self) -> &[Option<Member>] {
+        &self.members
+    }
+
+    pub fn rebuild_battleback(
+        &mut self,
+        graphics_state: &GraphicsState,
+        filesystem: &impl luminol_filesystem::FileSystem,
+        battleback_name: Option<&camino::Utf8Path>,
+    ) {
+        self.battleback_sprite = battleback_name.map(|filename| {
+            let texture =
+                load_texture(graphics_state, filesystem, "Graphics/Battlebacks", filename);
+            Sprite::basic(graphics_state, &texture, &self.viewport)
+        });
+    }
+
+    pub fn rebuild_members(
+        &mut self,
+        graphics_state: &GraphicsState,
+        filesystem: &impl luminol_filesystem::FileSystem,
+        enemies: &[luminol_data::rpg::Enemy],
+        troop: &luminol_data::rpg::Troop,
+    ) {
+        self.members = troop
+            .members
+            .iter()
+            .map(|member| self.create_member(graphics_state, filesystem, enemies, member))
+            .collect();
+    }
+
+    /// Updates the position and opacity of a member's sprite without reloading its graphic.
+    pub fn update_member(
+        &mut self,
+        graphics_state: &GraphicsState,
+        troop: &luminol_data::rpg::Troop,
+        member_index: usize,
+    ) {
+        let (Some(Some(sprite)), Some(member)) = (
+            self.members.get_mut(member_index),
+            troop.members.get(member_index),
+        ) else {
+            return;
+        };
+
+        sprite.rect = member_rect(member, sprite.rect.size());
+        sprite.sprite.transform.set_position(
+            &graphics_state.render_state,
+            glam::vec2(sprite.rect.min.x, sprite.rect.min.y),
+        );
+        sprite.sprite.graphic.set_opacity(
+            &graphics_state.render_state,
+            if member.hidden { HIDDEN_OPACITY } else { 255 },
+        );
+    }
+
+    fn create_member(
+        &self,
+        graphics_state: &GraphicsState,
+        filesystem: &impl luminol_filesystem::FileSystem,
+        enemies: &[luminol_data::rpg::Enemy],
+        member: &luminol_data::rpg::troop::Member,
+    ) -> Option<Member> {
+        let enemy = enemies.get(member.enemy_id)?;
+        let filename = enemy.battler_name.as_ref()?;
+
+        let texture = load_texture(graphics_state, filesystem, "Graphics/Battlers", filename);
+        let rect = member_rect(member, texture.size_vec2());
+
+        let mut sprite =
+            Sprite::basic_hue(graphics_state, enemy.battler_hue, &texture, &self.viewport);
+        sprite.transform.set_position(
+            &graphics_state.render_state,
+            glam::vec2(rect.min.x, rect.min.y),
+        );
+        if member.hidden {
+            sprite
+                .graphic
+                .set_opacity(&graphics_state.render_state, HIDDEN_OPACITY);
+        }
+
+        Some(Member { sprite, rect })
+    }
+}
+
+/// Battlers are positioned by the center of their bottom edge.
+fn member_rect(member: &luminol_data::rpg::troop::Member, size: egui::Vec2) -> egui::Rect {
+    egui::Rect::from_min_size(
+        egui::pos2(member.x as f32 - size.x / 2., member.y as f32 - size.y),
+        size,
+    )
+}
+
+fn load_texture(
+    graphics_state: &GraphicsState,
+    filesystem: &impl luminol_filesystem::FileSystem,
+    directory: &str,
+    filename: &camino::Utf8Path,
+) -> std::sync::Arc<Texture> {
+    graphics_state
+        .texture_loader
+        .load_now_dir(filesystem, directory, filename)
+        .wrap_err_with(|| format!("Error loading troop graphic {directory}/{filename}"))
+        .unwrap_or_else(|e| {
+            graphics_state.send_texture_error(e);
+            graphics_state.texture_loader.placeholder_texture()
+        })
+}
+
+pub struct Prepared {
+    battleback_sprite: Option<<Sprite as Renderable>::Prepared>,
+    members: Vec<<Sprite as Renderable>::Prepared>,
+}
+
+impl Renderable for Troop {
+    type Prepared = Prepared;
+
+    fn prepare(&mut self, graphics_state: &std::sync::Arc<GraphicsState>) -> Self::Prepared {
+        Self::Prepared {
+            battleback_sprite: self
+                .battleback_sprite
+                .as_mut()
+                .map(|sprite| sprite.prepare(graphics_state)),
+
+            members: self
+                .members
+                .iter_mut()
+                .flatten()
+                .map(|member| member.sprite.prepare(graphics_state))
+                .collect(),
+        }
+    }
+}
+
+impl Drawable for Prepared {
+    fn draw<'rpass>(&'rpass self, render_pass: &mut wgpu::RenderPass<'rpass>) {
+        if let Some(sprite) = &self.battleback_sprite {
+            sprite.draw(render_pass);
+        }
+        for sprite in &self.members {
+            sprite.draw(render_pass);
+        }
+    }
+}
@@ -406,6 +406,19 @@ impl luminol_eframe::App for App {
         // if they want to save their changes.
         update_state.manage_projects(true);
 
+        // Show which databases couldn't be loaded if a project was opened without them.
+        if std::mem::take(&mut update_state.project_manager.show_load_errors) {
+            update_state
+                .edit_windows
+                .add_window(luminol_ui::windows::load_errors::Window::default());
+        }
+
+        // Autosave unsaved changes so they can be recovered if Luminol crashes.
+        update_state.autosave();
+
+        // Reload anything that was changed by other programs, like an external image editor.
+        update_state.handle_file_changes();
+
         // If we don't do this tabs added by windows won't be added.
         // It also cleans up code nicely.
         self.tabs
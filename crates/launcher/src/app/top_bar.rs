@@ -140,6 +140,12 @@ impl TopBar {
                         .add_window(luminol_ui::windows::config_window::Window::new(config));
                 }
 
+                if ui.button("Backups").clicked() {
+                    update_state
+                        .edit_windows
+                        .add_window(luminol_ui::windows::backups::Window::default());
+                }
+
                 if ui.button("Event Commands").clicked() {
                     // update_state.windows.add_window(
                     //     luminol_ui::windows::command_gen::CommandGeneratorWindow::default(),
@@ -160,30 +166,38 @@ impl TopBar {
                 ui.close_menu();
             }
 
+            // Only the RPG Maker XP editors are implemented so far.
+            let xp_loaded = update_state.data.editor_ver() == Some(luminol_config::RMVer::XP);
+
             ui.add_enabled_ui(update_state.filesystem.project_loaded(), |ui| {
-                if ui.button("Maps").clicked() {
-                    update_state
-                        .edit_windows
-                        .add_window(luminol_ui::windows::map_picker::Window::default());
-                }
+                ui.add_enabled_ui(xp_loaded, |ui| {
+                    if ui.button("Maps").clicked() {
+                        update_state
+                            .edit_windows
+                            .add_window(luminol_ui::windows::map_picker::Window::default());
+                    }
 
-                ui.add_enabled_ui(false, |ui| {
-                    if ui.button("Tilesets [TODO]").clicked() {
-                        todo!();
+                    if database_button(ui, update_state.data, "Tilesets", "Tilesets").clicked() {
+                        update_state
+                            .edit_windows
+                            .add_window(luminol_ui::windows::tilesets::Window::new(update_state));
                     }
-                });
 
-                if ui.button("Animations").clicked() {
-                    update_state
-                        .edit_windows
-                        .add_window(luminol_ui::windows::animations::Window::default());
-                }
+                    if database_button(ui, update_state.data, "Animations", "Animations").clicked()
+                    {
+                        update_state
+                            .edit_windows
+                            .add_window(luminol_ui::windows::animations::Window::default());
+                    }
 
-                if ui.button("Common Events").clicked() {
-                    update_state
-                        .edit_windows
-                        .add_window(luminol_ui::windows::common_event_edit::Window::default());
-                }
+                    if database_button(ui, update_state.data, "Common Events", "CommonEvents")
+                        .clicked()
+                    {
+                        update_state
+                            .edit_windows
+                            .add_window(luminol_ui::windows::common_event_edit::Window::default());
+                    }
+                });
 
                 if ui.button("Scripts").clicked() {
                     update_state
@@ -191,73 +205,83 @@ impl TopBar {
                         .add_window(luminol_ui::windows::script_edit::Window::default());
                 }
 
+                ui.add_enabled_ui(update_state.data.load_errors().next().is_some(), |ui| {
+                    if ui.button("Load Errors").clicked() {
+                        update_state
+                            .edit_windows
+                            .add_window(luminol_ui::windows::load_errors::Window::default());
+                    }
+                });
+
                 if ui.button("Sound Test").clicked() {
                     update_state.edit_windows.add_window(
                         luminol_ui::windows::sound_test::Window::new(update_state.filesystem),
                     );
                 }
 
-                ui.add_enabled_ui(false, |ui| {
-                    if ui.button("System [TODO]").clicked() {
-                        todo!();
+                ui.add_enabled_ui(xp_loaded, |ui| {
+                    if ui.button("System").clicked() {
+                        update_state
+                            .edit_windows
+                            .add_window(luminol_ui::windows::system::Window::new(update_state));
                     }
-                });
 
-                ui.separator();
+                    ui.separator();
 
-                if ui.button("Items").clicked() {
-                    update_state
-                        .edit_windows
-                        .add_window(luminol_ui::windows::items::Window::new(update_state));
-                }
+                    if database_button(ui, update_state.data, "Items", "Items").clicked() {
+                        update_state
+                            .edit_windows
+                            .add_window(luminol_ui::windows::items::Window::new(update_state));
+                    }
 
-                if ui.button("Skills").clicked() {
-                    update_state
-                        .edit_windows
-                        .add_window(luminol_ui::windows::skills::Window::new());
-                }
+                    if database_button(ui, update_state.data, "Skills", "Skills").clicked() {
+                        update_state
+                            .edit_windows
+                            .add_window(luminol_ui::windows::skills::Window::new());
+                    }
 
-                if ui.button("Weapons").clicked() {
-                    update_state
-                        .edit_windows
-                        .add_window(luminol_ui::windows::weapons::Window::new());
-                }
+                    if database_button(ui, update_state.data, "Weapons", "Weapons").clicked() {
+                        update_state
+                            .edit_windows
+                            .add_window(luminol_ui::windows::weapons::Window::new());
+                    }
 
-                if ui.button("Armor").clicked() {
-                    update_state
-                        .edit_windows
-                        .add_window(luminol_ui::windows::armor::Window::new());
-                }
+                    if database_button(ui, update_state.data, "Armor", "Armors").clicked() {
+                        update_state
+                            .edit_windows
+                            .add_window(luminol_ui::windows::armor::Window::new());
+                    }
 
-                if ui.button("States").clicked() {
-                    update_state
-                        .edit_windows
-                        .add_window(luminol_ui::windows::states::Window::new());
-                }
+                    if database_button(ui, update_state.data, "States", "States").clicked() {
+                        update_state
+                            .edit_windows
+                            .add_window(luminol_ui::windows::states::Window::new());
+                    }
 
-                ui.separator();
+                    ui.separator();
 
-                if ui.button("Actors").clicked() {
-                    update_state
-                        .edit_windows
-                        .add_window(luminol_ui::windows::actors::Window::new(update_state));
-                }
+                    if database_button(ui, update_state.data, "Actors", "Actors").clicked() {
+                        update_state
+                            .edit_windows
+                            .add_window(luminol_ui::windows::actors::Window::new(update_state));
+                    }
 
-                if ui.button("Classes").clicked() {
-                    update_state
-                        .edit_windows
-                        .add_window(luminol_ui::windows::classes::Window::new());
-                }
+                    if database_button(ui, update_state.data, "Classes", "Classes").clicked() {
+                        update_state
+                            .edit_windows
+                            .add_window(luminol_ui::windows::classes::Window::new());
+                    }
 
-                if ui.button("Enemies").clicked() {
-                    update_state
-                        .edit_windows
-                        .add_window(luminol_ui::windows::enemies::Window::new(update_state));
-                }
+                    if database_button(ui, update_state.data, "Enemies", "Enemies").clicked() {
+                        update_state
+                            .edit_windows
+                            .add_window(luminol_ui::windows::enemies::Window::new(update_state));
+                    }
 
-                ui.add_enabled_ui(false, |ui| {
-                    if ui.button("Troops [TODO]").clicked() {
-                        todo!();
+                    if database_button(ui, update_state.data, "Troops", "Troops").clicked() {
+                        update_state
+                            .edit_windows
+                            .add_window(luminol_ui::windows::troops::Window::new());
                     }
                 });
             });
@@ -472,3 +496,20 @@ impl TopBar {
         }
     }
 }
+
+/// A button that opens a database editor, disabled if the database couldn't be loaded.
+fn database_button(
+    ui: &mut egui::Ui,
+    data: &luminol_core::Data,
+    text: &str,
+    database: &str,
+) -> egui::Response {
+    if data.is_available(database) {
+        ui.button(text)
+    } else {
+        ui.add_enabled(false, egui::Button::new(text))
+            .on_disabled_hover_text(format!(
+                "{database} could not be loaded, see Data > Load Errors"
+            ))
+    }
+}
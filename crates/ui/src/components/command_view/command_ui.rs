@@ -22,16 +22,292 @@
 // terms of the Steamworks API by Valve Corporation, the licensors of this
 // Program grant you additional permission to convey the resulting work.
 
+use luminol_config::command_db::CommandDB;
+use luminol_data::commands::{CommandKind, Parameter, ParameterKind};
+use luminol_data::rpg;
+
+use super::{Action, WindowState};
+
 impl super::CommandView {
     pub fn command_ui<'i, I>(
         &mut self,
-        _ui: &mut egui::Ui,
-        _db: &luminol_config::command_db::CommandDB,
-        (_index, _command): (usize, &'i mut luminol_data::rpg::EventCommand),
-        _iter: &mut std::iter::Peekable<I>,
+        ui: &mut egui::Ui,
+        db: &CommandDB,
+        system: &rpg::System,
+        (index, command): (usize, &'i mut rpg::EventCommand),
+        iter: &mut std::iter::Peekable<I>,
     ) where
-        I: Iterator<Item = (usize, &'i mut luminol_data::rpg::EventCommand)>,
+        I: Iterator<Item = (usize, &'i mut rpg::EventCommand)>,
     {
-        todo!()
+        let indent = command.indent;
+
+        let weak_color = ui.visuals().weak_text_color();
+        let text_color = ui.visuals().text_color();
+        let strong_color = ui.visuals().strong_text_color();
+
+        if command.code == 0 {
+            self.command_label(ui, db, index, command, color_text!("@>", weak_color));
+            return;
+        }
+
+        let Some(desc) = db.get(command.code) else {
+            self.command_label(
+                ui,
+                db,
+                index,
+                command,
+                error!(format!(
+                    "@>❎ Command ({}) ???: {:?}",
+                    command.code, command.parameters
+                )),
+            );
+            return;
+        };
+
+        match desc.kind {
+            CommandKind::Branch { ref parameters, .. } => {
+                let text = format!("@>{}{}", desc.name, summary(parameters, command, system));
+                let header = egui::collapsing_header::CollapsingState::load_with_default_open(
+                    ui.ctx(),
+                    self.id.with(command.guid),
+                    true,
+                );
+                header
+                    .show_header(ui, |ui| {
+                        self.command_label(ui, db, index, command, color_text!(text, strong_color));
+                    })
+                    .body(|ui| {
+                        while let Some(child) = iter.next_if(|(_, c)| c.indent > indent) {
+                            self.command_ui(ui, db, system, child, iter);
+                        }
+                    });
+
+                // Skip over the contents of the branch if it was collapsed
+                while iter.next_if(|(_, c)| c.indent > indent).is_some() {}
+            }
+            CommandKind::Multi { code, highlight } => {
+                let color = if highlight {
+                    egui::Color32::LIGHT_GREEN
+                } else {
+                    text_color
+                };
+
+                let mut text = format!(
+                    "@>{}: {}",
+                    desc.name,
+                    command
+                        .parameters
+                        .first()
+                        .and_then(|p| p.as_string())
+                        .map_or("", String::as_str)
+                );
+                while let Some((_, line)) =
+                    iter.next_if(|(_, c)| c.code == code && c.indent == indent)
+                {
+                    text.push_str("\n  : ");
+                    text.push_str(
+                        line.parameters
+                            .first()
+                            .and_then(|p| p.as_string())
+                            .map_or("", String::as_str),
+                    );
+                }
+
+                self.command_label(ui, db, index, command, color_text!(text, color));
+            }
+            CommandKind::Single(_) if command.code == super::MOVE_ROUTE_CONTINUATION_CODE => {
+                let text = command
+                    .parameters
+                    .first()
+                    .and_then(|p| p.as_movecommand())
+                    .map_or_else(String::new, crate::modals::move_route::command_text);
+                let text = color_text!(format!("  : $>{text}"), weak_color);
+                self.command_label(ui, db, index, command, text);
+            }
+            CommandKind::Single(ref parameters) => {
+                let text = format!("@>{}{}", desc.name, summary(parameters, command, system));
+                let text = color_text!(text, if desc.hidden { weak_color } else { text_color });
+                self.command_label(ui, db, index, command, text);
+            }
+        }
+    }
+
+    /// Shows one line of the command list and handles selecting and editing it.
+    fn command_label(
+        &mut self,
+        ui: &mut egui::Ui,
+        db: &CommandDB,
+        index: usize,
+        command: &rpg::EventCommand,
+        text: egui::RichText,
+    ) {
+        let structural = super::is_structural(db, command);
+        let editable = !structural && db.get(command.code).is_some();
+        let can_insert_here = command.code == 0 || !structural;
+
+        let response = ui
+            .selectable_label(self.selected_index == index, text)
+            .interact(egui::Sense::click());
+
+        if response.clicked() {
+            self.selected_index = index;
+            response.request_focus();
+        }
+
+        if response.double_clicked() {
+            if editable {
+                self.window_state = WindowState::Edit { index };
+            } else if can_insert_here {
+                self.window_state = WindowState::Insert {
+                    index,
+                    search_text: String::new(),
+                };
+            }
+        }
+
+        if response.has_focus() {
+            if ui.input(|i| i.key_pressed(egui::Key::Delete)) && !structural {
+                self.action = Some(Action::Delete(index));
+            } else if ui.input(|i| i.key_pressed(egui::Key::Enter)) && can_insert_here {
+                self.window_state = WindowState::Insert {
+                    index,
+                    search_text: String::new(),
+                };
+            }
+        }
+
+        response.context_menu(|ui| {
+            self.selected_index = index;
+
+            if ui
+                .add_enabled(can_insert_here, egui::Button::new("Insert..."))
+                .clicked()
+            {
+                self.window_state = WindowState::Insert {
+                    index,
+                    search_text: String::new(),
+                };
+                ui.close_menu();
+            }
+
+            if ui
+                .add_enabled(editable, egui::Button::new("Edit..."))
+                .clicked()
+            {
+                self.window_state = WindowState::Edit { index };
+                ui.close_menu();
+            }
+
+            ui.add_enabled_ui(!structural, |ui| {
+                if ui.button("Move Up").clicked() {
+                    self.action = Some(Action::MoveUp(index));
+                    ui.close_menu();
+                }
+
+                if ui.button("Move Down").clicked() {
+                    self.action = Some(Action::MoveDown(index));
+                    ui.close_menu();
+                }
+
+                if ui.button("Delete").clicked() {
+                    self.action = Some(Action::Delete(index));
+                    ui.close_menu();
+                }
+            });
+        });
+    }
+}
+
+/// Formats the values of a command's parameters for display in the command list.
+fn summary(parameters: &[Parameter], command: &rpg::EventCommand, system: &rpg::System) -> String {
+    let mut values = Vec::new();
+    for parameter in parameters {
+        parameter_summary(parameter, command, system, &mut values);
+    }
+
+    if values.is_empty() {
+        String::new()
+    } else {
+        format!(": {}", values.join(", "))
+    }
+}
+
+fn parameter_summary(
+    parameter: &Parameter,
+    command: &rpg::EventCommand,
+    system: &rpg::System,
+    values: &mut Vec<String>,
+) {
+    match parameter {
+        Parameter::Single {
+            index, name, kind, ..
+        } => {
+            let Some(value) = command.parameters.get(index.as_usize()) else {
+                return;
+            };
+
+            let value = match kind {
+                ParameterKind::Switch => {
+                    let id = value.as_integer().copied().unwrap_or_default();
+                    let name = usize::try_from(id - 1)
+                        .ok()
+                        .and_then(|i| system.switches.get(i))
+                        .map_or("", String::as_str);
+                    format!("[{id:0>4}: {name}]")
+                }
+                ParameterKind::Variable => {
+                    let id = value.as_integer().copied().unwrap_or_default();
+                    let name = usize::try_from(id - 1)
+                        .ok()
+                        .and_then(|i| system.variables.get(i))
+                        .map_or("", String::as_str);
+                    format!("[{id:0>4}: {name}]")
+                }
+                ParameterKind::SelfSwitch | ParameterKind::String => {
+                    value.as_string().cloned().unwrap_or_default()
+                }
+                ParameterKind::Int => value.as_integer().copied().unwrap_or_default().to_string(),
+                // The commands of the route are shown by the continuation commands after this one
+                ParameterKind::MoveRoute => return,
+                ParameterKind::IntBool | ParameterKind::Bool => {
+                    if value.truthy() { "ON" } else { "OFF" }.to_string()
+                }
+                ParameterKind::Enum { variants } => {
+                    let v = value.as_integer().copied().unwrap_or_default();
+                    variants
+                        .iter()
+                        .find(|(_, variant)| *variant as i32 == v)
+                        .map_or_else(|| v.to_string(), |(name, _)| name.clone())
+                }
+            };
+
+            values.push(if name.is_empty() {
+                value
+            } else {
+                format!("{name} {value}")
+            });
+        }
+        Parameter::Group { parameters, .. } => {
+            for parameter in parameters {
+                parameter_summary(parameter, command, system, values);
+            }
+        }
+        Parameter::Selection {
+            index, parameters, ..
+        } => {
+            let selected = command
+                .parameters
+                .get(index.as_usize())
+                .and_then(|p| p.as_integer())
+                .copied();
+            if let Some((_, parameter)) = parameters
+                .iter()
+                .find(|(value, _)| Some(*value as i32) == selected)
+            {
+                parameter_summary(parameter, command, system, values);
+            }
+        }
+        Parameter::Label(label) => values.push(label.clone()),
+        Parameter::Dummy => {}
     }
 }
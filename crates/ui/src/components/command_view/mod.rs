@@ -30,24 +30,54 @@ mod ui;
 
 use std::collections::HashMap;
 
+use luminol_config::command_db::CommandDB;
+use luminol_data::commands::{CommandDescription, CommandKind, Parameter, ParameterKind};
+use luminol_data::rpg;
+use luminol_data::ParameterType;
+
+use crate::modals::database_modal::{SwitchModal, VariableModal};
+use crate::modals::move_route::Modal as MoveRouteModal;
+
+/// The code of the commands that follow a `Set Move Route` command, one for each command in its
+/// route. The interpreter ignores them; they only exist so the route can be shown in the list.
+const MOVE_ROUTE_CONTINUATION_CODE: u16 = 509;
+
 pub struct CommandView {
-    _selected_index: usize,
-    _window_state: WindowState,
-    _id: egui::Id,
-    _modals: HashMap<u64, bool>, // todo find a better way to handle modals
+    selected_index: usize,
+    window_state: WindowState,
+    action: Option<Action>,
+    id: egui::Id,
+    switch_modals: HashMap<egui::Id, SwitchModal>, // todo find a better way to handle modals
+    variable_modals: HashMap<egui::Id, VariableModal>,
+    move_route_modals: HashMap<egui::Id, MoveRouteModal>,
 }
 
 enum WindowState {
     None,
+    Insert { index: usize, search_text: String },
+    Edit { index: usize },
+}
+
+/// An edit to the command list. We only ever have one command borrowed at a time while drawing the
+/// list, so these are queued up and applied once the whole list has been drawn.
+#[derive(Clone, Copy)]
+enum Action {
+    Insert { index: usize, code: u16 },
+    Delete(usize),
+    MoveUp(usize),
+    MoveDown(usize),
 }
 
 impl Default for CommandView {
     fn default() -> Self {
         Self {
-            _selected_index: 0,
-            _window_state: WindowState::None,
-            _id: egui::Id::new("command_view"),
-            _modals: HashMap::new(),
+            selected_index: 0,
+            window_state: WindowState::None,
+            action: None,
+            id: egui::Id::new("command_view"),
+            switch_modals: HashMap::new(),
+            variable_modals: HashMap::new(),
+            move_route_modals: HashMap::new(),
         }
     }
 }
@@ -55,8 +85,218 @@ impl Default for CommandView {
 impl CommandView {
     pub fn new(id: impl std::hash::Hash) -> Self {
         Self {
-            _id: egui::Id::new(id),
+            id: egui::Id::new(id),
             ..Default::default()
         }
     }
 }
+
+/// Commands that exist only to give the list its structure (blank lines, `Else`, `Branch End`,
+/// continuation lines, etc.) and that can't be inserted, edited or deleted on their own.
+fn is_structural(db: &CommandDB, command: &rpg::EventCommand) -> bool {
+    command.code == 0 || db.get(command.code).is_some_and(|desc| desc.hidden)
+}
+
+/// Returns the index one past the last command that belongs to the command at `index`.
+///
+/// For branches this includes all nested commands, sub-branches like `Else` and the end marker.
+/// Text commands include their continuation lines, and other commands include any structural
+/// commands directly after them (e.g. the `If Win`/`If Lose` branches of `Battle Processing`).
+fn block_end(db: &CommandDB, commands: &[rpg::EventCommand], index: usize) -> usize {
+    let indent = commands[index].indent;
+    let mut end = index + 1;
+
+    match db.get(commands[index].code).map(|desc| &desc.kind) {
+        Some(CommandKind::Multi { code, .. }) => {
+            while commands
+                .get(end)
+                .is_some_and(|c| c.code == *code && c.indent == indent)
+            {
+                end += 1;
+            }
+        }
+        Some(CommandKind::Branch { end_code, .. }) => loop {
+            while commands.get(end).is_some_and(|c| c.indent > indent) {
+                end += 1;
+            }
+            let Some(next) = commands.get(end).filter(|c| c.indent == indent) else {
+                break;
+            };
+            if next.code == *end_code {
+                end += 1;
+                break;
+            }
+            let is_sub_branch = db.get(next.code).is_some_and(|desc| {
+                desc.hidden
+                    && matches!(desc.kind, CommandKind::Branch { end_code: e, .. } if e == *end_code)
+            });
+            if !is_sub_branch {
+                break;
+            }
+            end += 1;
+        },
+        Some(CommandKind::Single(_)) => {
+            while let Some(next) = commands
+                .get(end)
+                .filter(|c| c.indent == indent && c.code != 0 && is_structural(db, c))
+            {
+                end = if matches!(
+                    db.get(next.code).map(|desc| &desc.kind),
+                    Some(CommandKind::Branch { .. })
+                ) {
+                    block_end(db, commands, end)
+                } else {
+                    end + 1
+                };
+            }
+        }
+        None => {}
+    }
+
+    end
+}
+
+/// Returns the index of the first command of the block directly before the block starting at
+/// `index` that has the same indent, if there is one.
+fn previous_block(db: &CommandDB, commands: &[rpg::EventCommand], index: usize) -> Option<usize> {
+    let indent = commands[index].indent;
+    let mut i = index;
+    while i > 0 {
+        i -= 1;
+        let command = &commands[i];
+        if command.indent < indent {
+            return None;
+        }
+        if command.indent == indent && !is_structural(db, command) {
+            return (block_end(db, commands, i) == index).then_some(i);
+        }
+    }
+    None
+}
+
+/// Creates the commands that are inserted when the user adds a new command, including the blank
+/// line and end marker for branches.
+fn new_commands(desc: &CommandDescription, indent: usize) -> Vec<rpg::EventCommand> {
+    let mut parameters = Vec::new();
+    match desc.kind {
+        CommandKind::Branch {
+            parameters: ref p, ..
+        }
+        | CommandKind::Single(ref p) => {
+            for parameter in p {
+                fill_default_parameters(parameter, &mut parameters);
+            }
+        }
+        CommandKind::Multi { .. } => parameters.push(ParameterType::String(String::new())),
+    }
+
+    let command = rpg::EventCommand {
+        code: desc.code,
+        indent,
+        parameters,
+        guid: rand::random(),
+        extra_ivars: Default::default(),
+    };
+
+    match desc.kind {
+        CommandKind::Branch { end_code, .. } => vec![
+            command,
+            rpg::EventCommand {
+                code: 0,
+                indent: indent + 1,
+                parameters: vec![],
+                guid: rand::random(),
+                extra_ivars: Default::default(),
+            },
+            rpg::EventCommand {
+                code: end_code,
+                indent,
+                parameters: vec![],
+                guid: rand::random(),
+                extra_ivars: Default::default(),
+            },
+        ],
+        _ => vec![command],
+    }
+}
+
+/// Writes sensible default values for a parameter (and the first option of selections) into
+/// `parameters`, so that every described parameter has a value of the right type.
+fn fill_default_parameters(parameter: &Parameter, parameters: &mut Vec<ParameterType>) {
+    match parameter {
+        Parameter::Single { index, kind, .. } => {
+            *get_or_resize!(parameters, index.as_usize()) = match kind {
+                ParameterKind::Switch | ParameterKind::Variable => ParameterType::Integer(1),
+                ParameterKind::SelfSwitch => ParameterType::String("A".to_string()),
+                ParameterKind::String => ParameterType::String(String::new()),
+                ParameterKind::Int | ParameterKind::IntBool => ParameterType::Integer(0),
+                ParameterKind::Bool => ParameterType::Bool(false),
+                ParameterKind::MoveRoute => ParameterType::MoveRoute(rpg::MoveRoute {
+                    repeat: false,
+                    skippable: false,
+                    list: vec![rpg::MoveCommand {
+                        code: 0,
+                        parameters: vec![],
+                        guid: rand::random(),
+                        extra_ivars: Default::default(),
+                    }],
+                    extra_ivars: Default::default(),
+                }),
+                ParameterKind::Enum { variants } => {
+                    ParameterType::Integer(variants.first().map_or(0, |(_, v)| *v as i32))
+                }
+            };
+        }
+        Parameter::Group { parameters: p, .. } => {
+            for parameter in p {
+                fill_default_parameters(parameter, parameters);
+            }
+        }
+        Parameter::Selection {
+            index,
+            parameters: p,
+            ..
+        } => {
+            if let Some((value, parameter)) = p.first() {
+                *get_or_resize!(parameters, index.as_usize()) =
+                    ParameterType::Integer(*value as i32);
+                fill_default_parameters(parameter, parameters);
+            }
+        }
+        Parameter::Dummy | Parameter::Label(_) => {}
+    }
+}
+
+/// Replaces the continuation commands after the command at `index` with ones mirroring the
+/// commands of its move route, if it has one.
+fn sync_move_route_continuations(
+    db: &CommandDB,
+    commands: &mut Vec<rpg::EventCommand>,
+    index: usize,
+) {
+    let Some(route) = commands[index]
+        .parameters
+        .iter()
+        .find_map(ParameterType::as_moveroute)
+    else {
+        return;
+    };
+
+    let indent = commands[index].indent;
+    // The terminating blank command isn't mirrored
+    let continuation = route
+        .list
+        .iter()
+        .filter(|command| command.code != 0)
+        .map(|command| rpg::EventCommand {
+            code: MOVE_ROUTE_CONTINUATION_CODE,
+            indent,
+            parameters: vec![ParameterType::MoveCommand(command.clone())],
+            guid: rand::random(),
+            extra_ivars: Default::default(),
+        })
+        .collect::<Vec<_>>();
+
+    let end = block_end(db, commands, index);
+    commands.splice(index + 1..end, continuation);
+}
@@ -22,16 +22,218 @@
 // terms of the Steamworks API by Valve Corporation, the licensors of this
 // Program grant you additional permission to convey the resulting work.
 
+use luminol_core::Modal;
+use luminol_data::commands::{Parameter, ParameterKind};
+use luminol_data::{rpg, ParameterType};
+
 use super::CommandView;
+use crate::modals::database_modal::{SwitchModal, VariableModal};
+use crate::modals::move_route::Modal as MoveRouteModal;
 
 impl CommandView {
-    #[allow(clippy::only_used_in_recursion)]
+    /// Shows the editor for one parameter of a command, returning whether or not it was modified.
     pub fn parameter_ui(
         &mut self,
-        _ui: &mut egui::Ui,
-        _parameter: &luminol_data::commands::Parameter,
-        _command: &mut luminol_data::rpg::EventCommand,
-    ) {
-        todo!()
+        ui: &mut egui::Ui,
+        update_state: &mut luminol_core::UpdateState<'_>,
+        parameter: &Parameter,
+        command: &mut rpg::EventCommand,
+    ) -> bool {
+        let mut modified = false;
+
+        match parameter {
+            Parameter::Single {
+                index,
+                description,
+                name,
+                kind,
+                guid,
+            } => {
+                let id = self.id.with(command.guid).with(guid);
+                let value = get_or_resize!(command.parameters, index.as_usize());
+
+                ui.horizontal(|ui| {
+                    if !name.is_empty() {
+                        ui.label(format!("{name}:")).on_hover_text(description);
+                    }
+
+                    match kind {
+                        ParameterKind::Switch => {
+                            let value = value.into_integer();
+                            let mut switch_id = usize::try_from(*value - 1).unwrap_or_default();
+                            let modal = self
+                                .switch_modals
+                                .entry(id)
+                                .or_insert_with(|| SwitchModal::new(id));
+                            ui.add(modal.button(&mut switch_id, update_state));
+
+                            if *value != switch_id as i32 + 1 {
+                                *value = switch_id as i32 + 1;
+                                modified = true;
+                            }
+                        }
+                        ParameterKind::Variable => {
+                            let value = value.into_integer();
+                            let mut variable_id = usize::try_from(*value - 1).unwrap_or_default();
+                            let modal = self
+                                .variable_modals
+                                .entry(id)
+                                .or_insert_with(|| VariableModal::new(id));
+                            ui.add(modal.button(&mut variable_id, update_state));
+
+                            if *value != variable_id as i32 + 1 {
+                                *value = variable_id as i32 + 1;
+                                modified = true;
+                            }
+                        }
+                        ParameterKind::SelfSwitch => {
+                            let value = value.into_string_with("A".to_string());
+                            egui::ComboBox::from_id_source(id)
+                                .selected_text(value.as_str())
+                                .show_ui(ui, |ui| {
+                                    for self_switch in ["A", "B", "C", "D"] {
+                                        if ui
+                                            .selectable_label(value == self_switch, self_switch)
+                                            .clicked()
+                                        {
+                                            *value = self_switch.to_string();
+                                            modified = true;
+                                        }
+                                    }
+                                });
+                        }
+                        ParameterKind::String => {
+                            modified |= ui.text_edit_singleline(value.into_string()).changed();
+                        }
+                        ParameterKind::Int => {
+                            modified |=
+                                ui.add(egui::DragValue::new(value.into_integer())).changed();
+                        }
+                        ParameterKind::IntBool => {
+                            let value = value.into_integer();
+                            let mut checked = *value != 0;
+                            if ui.checkbox(&mut checked, "").changed() {
+                                *value = checked as i32;
+                                modified = true;
+                            }
+                        }
+                        ParameterKind::Bool => {
+                            modified |= ui.checkbox(value.into_bool(), "").changed();
+                        }
+                        ParameterKind::MoveRoute => {
+                            let modal = self
+                                .move_route_modals
+                                .entry(id)
+                                .or_insert_with(|| MoveRouteModal::new(id));
+                            modified |= ui
+                                .add(modal.button(value.into_moveroute(), update_state))
+                                .changed();
+                        }
+                        ParameterKind::Enum { variants } => {
+                            let value = value.into_integer();
+                            let selected_text = variants
+                                .iter()
+                                .find(|(_, variant)| *variant as i32 == *value)
+                                .map_or_else(|| value.to_string(), |(name, _)| name.clone());
+                            egui::ComboBox::from_id_source(id)
+                                .selected_text(selected_text)
+                                .show_ui(ui, |ui| {
+                                    for (name, variant) in variants {
+                                        modified |= ui
+                                            .selectable_value(value, *variant as i32, name)
+                                            .changed();
+                                    }
+                                });
+                        }
+                    }
+                });
+            }
+            Parameter::Group { parameters, .. } => {
+                ui.group(|ui| {
+                    for parameter in parameters {
+                        modified |= self.parameter_ui(ui, update_state, parameter, command);
+                    }
+                });
+            }
+            Parameter::Selection {
+                index, parameters, ..
+            } => {
+                let mut selected = command
+                    .parameters
+                    .get(index.as_usize())
+                    .and_then(|p| p.as_integer())
+                    .copied()
+                    .unwrap_or_default();
+
+                for (value, parameter) in parameters {
+                    let value = *value as i32;
+                    ui.horizontal(|ui| {
+                        if ui.radio_value(&mut selected, value, "").changed() {
+                            *get_or_resize!(command.parameters, index.as_usize()) =
+                                ParameterType::Integer(selected);
+                            // The parameters of the other options may use the same indices with
+                            // different types, so we need to reset them
+                            super::fill_default_parameters(parameter, &mut command.parameters);
+                            modified = true;
+                        }
+
+                        if selected == value {
+                            ui.vertical(|ui| {
+                                modified |= self.parameter_ui(ui, update_state, parameter, command);
+                            });
+                        } else {
+                            ui.add_enabled(false, egui::Label::new(parameter_name(parameter)));
+                        }
+                    });
+                }
+            }
+            Parameter::Label(label) => {
+                ui.label(label);
+            }
+            Parameter::Dummy => {}
+        }
+
+        modified
+    }
+}
+
+/// The name shown for an option of a selection that isn't currently selected.
+fn parameter_name(parameter: &Parameter) -> String {
+    match parameter {
+        Parameter::Single { name, .. } => name.clone(),
+        Parameter::Label(label) => label.clone(),
+        Parameter::Group { parameters, .. } => parameters
+            .iter()
+            .map(parameter_name)
+            .filter(|name| !name.is_empty())
+            .collect::<Vec<_>>()
+            .join(", "),
+        Parameter::Selection { .. } | Parameter::Dummy => String::new(),
+    }
+}
+
+/// Shows a basic editor for a parameter value that isn't described by the command database.
+pub(super) fn raw_parameter_ui(ui: &mut egui::Ui, parameter: &mut ParameterType) -> bool {
+    match parameter {
+        ParameterType::Integer(v) => ui.add(egui::DragValue::new(v)).changed(),
+        ParameterType::Float(v) => ui.add(egui::DragValue::new(v)).changed(),
+        ParameterType::String(v) => ui.text_edit_singleline(v).changed(),
+        ParameterType::Bool(v) => ui.checkbox(v, "").changed(),
+        ParameterType::Array(v) => {
+            ui.vertical(|ui| {
+                let mut modified = false;
+                for (i, parameter) in v.iter_mut().enumerate() {
+                    ui.push_id(i, |ui| {
+                        modified |= raw_parameter_ui(ui, parameter);
+                    });
+                }
+                modified
+            })
+            .inner
+        }
+        _ => {
+            ui.label(format!("{parameter:?}"));
+            false
+        }
     }
 }
@@ -22,14 +22,309 @@
 // terms of the Steamworks API by Valve Corporation, the licensors of this
 // Program grant you additional permission to convey the resulting work.
 
+use luminol_config::command_db::CommandDB;
+use luminol_data::commands::CommandKind;
+use luminol_data::{rpg, ParameterType};
+
+use super::{Action, WindowState};
+use crate::components::UiExt;
+
 impl super::CommandView {
-    #[allow(clippy::ptr_arg)]
     pub fn ui(
         &mut self,
-        _ui: &mut egui::Ui,
-        _db: &luminol_config::command_db::CommandDB,
-        _commands: &mut Vec<luminol_data::rpg::EventCommand>,
-    ) {
-        todo!()
+        ui: &mut egui::Ui,
+        update_state: &mut luminol_core::UpdateState<'_>,
+        commands: &mut Vec<rpg::EventCommand>,
+    ) -> egui::Response {
+        // The command database lives inside the project config, but the parameter editors need
+        // mutable access to the update state, so we temporarily take it out
+        let config = update_state
+            .project_config
+            .take()
+            .expect("project not loaded");
+        let db = &config.command_db;
+
+        // Command lists are always terminated by a blank command
+        if commands.is_empty() {
+            commands.push(rpg::EventCommand {
+                code: 0,
+                indent: 0,
+                parameters: vec![],
+                guid: rand::random(),
+                extra_ivars: Default::default(),
+            });
+        }
+
+        let mut modified = false;
+
+        let mut response = ui
+            .vertical(|ui| {
+                ui.style_mut().wrap_mode = Some(egui::TextWrapMode::Extend);
+
+                let system = update_state.data.system();
+                let mut iter = commands.iter_mut().enumerate().peekable();
+                while let Some(item) = iter.next() {
+                    self.command_ui(ui, db, &system, item, &mut iter);
+                }
+            })
+            .response;
+
+        if let Some(action) = self.action.take() {
+            modified |= self.apply_action(db, commands, action);
+        }
+
+        modified |= self.insert_window(ui.ctx(), db, commands);
+        modified |= self.edit_window(ui.ctx(), update_state, db, commands);
+
+        *update_state.project_config = Some(config);
+
+        if modified {
+            response.mark_changed();
+        }
+        response
+    }
+
+    fn apply_action(
+        &mut self,
+        db: &CommandDB,
+        commands: &mut Vec<rpg::EventCommand>,
+        action: Action,
+    ) -> bool {
+        match action {
+            Action::Insert { index, code } => {
+                let (Some(desc), Some(command)) = (db.get(code), commands.get(index)) else {
+                    return false;
+                };
+                if command.code != 0 && super::is_structural(db, command) {
+                    return false;
+                }
+
+                let new_commands = super::new_commands(desc, command.indent);
+                commands.splice(index..index, new_commands);
+                self.selected_index = index;
+
+                let has_parameters = match desc.kind {
+                    CommandKind::Branch { ref parameters, .. }
+                    | CommandKind::Single(ref parameters) => !parameters.is_empty(),
+                    CommandKind::Multi { .. } => true,
+                };
+                self.window_state = if has_parameters {
+                    WindowState::Edit { index }
+                } else {
+                    WindowState::None
+                };
+            }
+            Action::Delete(index) => {
+                let Some(command) = commands.get(index) else {
+                    return false;
+                };
+                if super::is_structural(db, command) {
+                    return false;
+                }
+
+                let end = super::block_end(db, commands, index);
+                commands.drain(index..end);
+                self.selected_index = index.min(commands.len().saturating_sub(1));
+                self.window_state = WindowState::None;
+            }
+            Action::MoveUp(index) => {
+                let Some(previous) = super::previous_block(db, commands, index) else {
+                    return false;
+                };
+
+                let end = super::block_end(db, commands, index);
+                commands[previous..end].rotate_left(index - previous);
+                self.selected_index = previous;
+                self.window_state = WindowState::None;
+            }
+            Action::MoveDown(index) => {
+                let end = super::block_end(db, commands, index);
+                let indent = commands[index].indent;
+                if !commands.get(end).is_some_and(|command| {
+                    command.indent == indent && !super::is_structural(db, command)
+                }) {
+                    return false;
+                }
+
+                let next_end = super::block_end(db, commands, end);
+                commands[index..next_end].rotate_left(end - index);
+                self.selected_index = index + next_end - end;
+                self.window_state = WindowState::None;
+            }
+        }
+
+        true
+    }
+
+    fn insert_window(
+        &mut self,
+        ctx: &egui::Context,
+        db: &CommandDB,
+        commands: &mut Vec<rpg::EventCommand>,
+    ) -> bool {
+        let WindowState::Insert {
+            index,
+            ref mut search_text,
+        } = self.window_state
+        else {
+            return false;
+        };
+
+        let mut open = true;
+        let mut selected_code = None;
+
+        egui::Window::new("Insert Command")
+            .id(self.id.with("insert_window"))
+            .resizable(true)
+            .open(&mut open)
+            .show(ctx, |ui| {
+                let matcher = fuzzy_matcher::skim::SkimMatcherV2::default();
+
+                ui.add(egui::TextEdit::singleline(search_text).hint_text("Search 🔎"));
+
+                ui.group(|ui| {
+                    egui::ScrollArea::vertical()
+                        .auto_shrink([false, false])
+                        .max_height(384.)
+                        .show(ui, |ui| {
+                            ui.with_cross_justify(|ui| {
+                                let descriptions = db.iter().filter(|desc| {
+                                    !desc.hidden
+                                        && matcher.fuzzy(&desc.name, search_text, false).is_some()
+                                });
+                                for desc in descriptions {
+                                    if ui
+                                        .selectable_label(
+                                            false,
+                                            format!("{}: {}", desc.code, desc.name),
+                                        )
+                                        .on_hover_text(&desc.description)
+                                        .clicked()
+                                    {
+                                        selected_code = Some(desc.code);
+                                    }
+                                }
+                            });
+                        });
+                });
+            });
+
+        if let Some(code) = selected_code {
+            self.window_state = WindowState::None;
+            return self.apply_action(db, commands, Action::Insert { index, code });
+        }
+        if !open {
+            self.window_state = WindowState::None;
+        }
+
+        false
+    }
+
+    fn edit_window(
+        &mut self,
+        ctx: &egui::Context,
+        update_state: &mut luminol_core::UpdateState<'_>,
+        db: &CommandDB,
+        commands: &mut Vec<rpg::EventCommand>,
+    ) -> bool {
+        let WindowState::Edit { index } = self.window_state else {
+            return false;
+        };
+        let Some(desc) = commands.get(index).and_then(|command| db.get(command.code)) else {
+            self.window_state = WindowState::None;
+            return false;
+        };
+
+        let mut open = true;
+        let mut modified = false;
+
+        egui::Window::new(format!("Edit {}", desc.name))
+            .id(self.id.with("edit_window"))
+            .resizable(true)
+            .open(&mut open)
+            .show(ctx, |ui| {
+                if !desc.description.is_empty() {
+                    ui.label(&desc.description);
+                    ui.separator();
+                }
+
+                match desc.kind {
+                    CommandKind::Multi { code, highlight } => {
+                        let end = super::block_end(db, commands, index);
+                        let mut text = commands[index..end]
+                            .iter()
+                            .map(|command| {
+                                command
+                                    .parameters
+                                    .first()
+                                    .and_then(|p| p.as_string())
+                                    .map_or("", String::as_str)
+                            })
+                            .collect::<Vec<_>>()
+                            .join("\n");
+
+                        let mut text_edit =
+                            egui::TextEdit::multiline(&mut text).desired_width(f32::INFINITY);
+                        if highlight {
+                            text_edit = text_edit.code_editor();
+                        }
+
+                        if ui.add(text_edit).changed() {
+                            let indent = commands[index].indent;
+                            let mut lines = text.split('\n');
+
+                            *get_or_resize!(commands[index].parameters, 0) =
+                                ParameterType::String(lines.next().unwrap_or_default().to_string());
+                            let continuation = lines
+                                .map(|line| rpg::EventCommand {
+                                    code,
+                                    indent,
+                                    parameters: vec![ParameterType::String(line.to_string())],
+                                    guid: rand::random(),
+                                    extra_ivars: Default::default(),
+                                })
+                                .collect::<Vec<_>>();
+                            commands.splice(index + 1..end, continuation);
+
+                            modified = true;
+                        }
+                    }
+                    CommandKind::Branch { ref parameters, .. }
+                    | CommandKind::Single(ref parameters) => {
+                        let mut parameters_modified = false;
+                        for parameter in parameters {
+                            parameters_modified |= self.parameter_ui(
+                                ui,
+                                update_state,
+                                parameter,
+                                &mut commands[index],
+                            );
+                        }
+                        if parameters_modified {
+                            super::sync_move_route_continuations(db, commands, index);
+                            modified = true;
+                        }
+                    }
+                }
+
+                ui.separator();
+
+                ui.collapsing("Raw Parameters", |ui| {
+                    for (i, parameter) in commands[index].parameters.iter_mut().enumerate() {
+                        ui.push_id(i, |ui| {
+                            ui.horizontal(|ui| {
+                                ui.label(format!("[{i}]"));
+                                modified |= super::parameter_ui::raw_parameter_ui(ui, parameter);
+                            });
+                        });
+                    }
+                });
+            });
+
+        if !open {
+            self.window_state = WindowState::None;
+        }
+
+        modified
     }
 }
@@ -0,0 +1,103 @@
+// Copyright (C) 2024 Melody Madeline Lyons
+//
+// This file is part of Luminol.
+//
+// Luminol is free software: you can redistribute it and/or modify
+// it under the terms of the GNU General Public License as published by
+// the Free Software Foundation, either version 3 of the License, or
+// (at your option) any later version.
+//
+// Luminol is distributed in the hope that it will be useful,
+// but WITHOUT ANY WARRANTY; without even the implied warranty of
+// MERCHANTABILITY or FITNESS FOR A PARTICULAR PURPOSE.  See the
+// GNU General Public License for more details.
+//
+// You should have received a copy of the GNU General Public License
+// along with Luminol.  If not, see <http://www.gnu.org/licenses/>.
+//
+//     Additional permission under GNU GPL version 3 section 7
+//
+// If you modify this Program, or any covered work, by linking or combining
+// it with Steamworks API by Valve Corporation, containing parts covered by
+// terms of the Steamworks API by Valve Corporation, the licensors of this
+// Program grant you additional permission to convey the resulting work.
+
+use alox_48::Value;
+use itertools::Itertools;
+
+/// Read-only list of the instance variables of an object that Luminol doesn't know about, which
+/// were usually added by scripts.
+pub struct ExtraIvarsView<'a> {
+    extra_ivars: &'a luminol_data::ExtraIvars,
+}
+
+impl<'a> ExtraIvarsView<'a> {
+    pub fn new(extra_ivars: &'a luminol_data::ExtraIvars) -> Self {
+        Self { extra_ivars }
+    }
+}
+
+impl<'a> egui::Widget for ExtraIvarsView<'a> {
+    fn ui(self, ui: &mut egui::Ui) -> egui::Response {
+        if self.extra_ivars.is_empty() {
+            return ui.allocate_response(egui::Vec2::ZERO, egui::Sense::hover());
+        }
+
+        egui::CollapsingHeader::new(format!(
+            "Other Instance Variables ({})",
+            self.extra_ivars.len()
+        ))
+        .id_source(ui.next_auto_id())
+        .show(ui, |ui| {
+            ui.label(
+                "These were added by scripts. Luminol can't edit them, but keeps them when saving.",
+            );
+
+            egui::Grid::new("luminol_extra_ivars_grid")
+                .num_columns(2)
+                .striped(true)
+                .show(ui, |ui| {
+                    for (name, value) in self.extra_ivars.iter() {
+                        ui.monospace(name);
+                        ui.add(
+                            egui::Label::new(egui::RichText::new(inspect(value)).monospace())
+                                .wrap(),
+                        );
+                        ui.end_row();
+                    }
+                });
+        })
+        .header_response
+    }
+}
+
+/// Formats a Ruby value roughly the way Ruby's `inspect` would.
+fn inspect(value: &Value) -> String {
+    match value {
+        Value::Nil => "nil".to_string(),
+        Value::Bool(v) => v.to_string(),
+        Value::Integer(v) => v.to_string(),
+        Value::Float(v) => format!("{v:?}"),
+        Value::String(v) => format!("{:?}", String::from_utf8_lossy(&v.data)),
+        Value::Symbol(v) => format!(":{}", v.as_str()),
+        Value::Array(v) => format!("[{}]", v.iter().map(inspect).join(", ")),
+        Value::Hash(v) => format!(
+            "{{{}}}",
+            v.iter()
+                .map(|(key, value)| format!("{} => {}", inspect(key), inspect(value)))
+                .join(", ")
+        ),
+        Value::Object(v) if v.fields.is_empty() => format!("#<{}>", v.class.as_str()),
+        Value::Object(v) => format!(
+            "#<{} {}>",
+            v.class.as_str(),
+            v.fields
+                .iter()
+                .map(|(name, value)| format!("{}={}", name.as_str(), inspect(value)))
+                .join(", ")
+        ),
+        Value::Instance(v) => inspect(&v.value),
+        Value::Userdata(v) => format!("#<{} ({} bytes)>", v.class.as_str(), v.data.len()),
+        value => format!("{value:?}"),
+    }
+}
@@ -54,6 +54,10 @@ pub struct MapView {
     /// Whether to display the tile IDs on the map
     pub display_tile_ids: bool,
 
+    /// A rectangle of tiles to outline instead of the brush pattern, in tile coordinates.
+    /// Used by the select tool for the selection and for where a paste will go.
+    pub selection: Option<egui::Rect>,
+
     pub scale: f32,
     previous_scale: f32,
 
@@ -62,6 +66,8 @@ pub struct MapView {
     pub event_rects: Vec<egui::Rect>,
 
     pub data_id: egui::Id,
+    /// Where the path of a move route being edited on this map is stored in egui's memory
+    move_route_preview_id: egui::Id,
 }
 
 struct PreviewEvent {
@@ -85,9 +91,15 @@ impl MapView {
             map_id,
             update_state.filesystem,
             update_state.project_config.as_ref().unwrap(),
-        );
+        )?;
         let tilesets = update_state.data.tilesets();
-        let tileset = &tilesets.data[map.tileset_id];
+        // the tilesets may be missing if they couldn't be loaded
+        let tileset = tilesets.data.get(map.tileset_id).wrap_err_with(|| {
+            format!(
+                "Map {map_id:0>3} uses tileset {}, which doesn't exist",
+                map.tileset_id
+            )
+        })?;
 
         let mut passages = luminol_data::Table2::new(map.data.xsize(), map.data.ysize());
         luminol_graphics::Collision::calculate_passages(
@@ -147,12 +159,15 @@ impl MapView {
 
             display_tile_ids: false,
 
+            selection: None,
+
             scale,
             previous_scale: scale,
 
             event_rects: Vec::new(),
 
             data_id,
+            move_route_preview_id: crate::modals::move_route::preview_id(map_id),
         })
     }
 
@@ -630,6 +645,35 @@ impl MapView {
             }
         }
 
+        // Draw the path of the move route that's being edited, if any. The path is only kept
+        // around for a frame after the move route editor stops updating it so that it disappears
+        // when the editor is closed.
+        let preview = ui
+            .ctx()
+            .data(|d| d.get_temp::<(u64, Vec<egui::Pos2>)>(self.move_route_preview_id));
+        if let Some((frame_nr, path)) = preview {
+            if frame_nr + 1 >= ui.ctx().frame_nr() {
+                let points = path
+                    .iter()
+                    .map(|pos| map_rect.min + (pos.to_vec2() + egui::Vec2::splat(0.5)) * tile_size)
+                    .collect::<Vec<_>>();
+                ui.painter().add(egui::Shape::line(
+                    points.clone(),
+                    egui::Stroke::new(2., egui::Color32::LIGHT_BLUE),
+                ));
+                for (i, point) in points.into_iter().enumerate() {
+                    ui.painter().circle_filled(
+                        point,
+                        if i == 0 { 4. } else { 2.5 },
+                        egui::Color32::LIGHT_BLUE,
+                    );
+                }
+            } else {
+                ui.ctx()
+                    .data_mut(|d| d.remove::<(u64, Vec<egui::Pos2>)>(self.move_route_preview_id));
+            }
+        }
+
         // Do we display the visible region?
         if self.visible_display {
             // Determine the visible region.
@@ -650,7 +694,7 @@ impl MapView {
             );
         }
 
-        // Draw the origin tile for the rectangle and circle brushes
+        // Draw the origin tile for the line, rectangle and circle brushes
         if drawing_shape {
             if let Some(drawing_shape_pos) = drawing_shape_pos {
                 let drawing_shape_rect = egui::Rect::from_min_size(
@@ -666,7 +710,17 @@ impl MapView {
         }
 
         // Display cursor.
-        if matches!(self.selected_layer, SelectedLayer::Tiles(_)) {
+        if let Some(selection) = self.selection {
+            let selection_rect = egui::Rect::from_min_max(
+                map_rect.min + (selection.min.to_vec2() * tile_size),
+                map_rect.min + (selection.max.to_vec2() * tile_size),
+            );
+            ui.painter().rect_stroke(
+                selection_rect,
+                5.,
+                egui::Stroke::new(2., egui::Color32::LIGHT_BLUE),
+            );
+        } else if matches!(self.selected_layer, SelectedLayer::Tiles(_)) {
             ui.painter().rect_stroke(
                 pattern_rect,
                 5.,
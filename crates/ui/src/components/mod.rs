@@ -51,12 +51,17 @@ pub use collapsing_view::CollapsingView;
 
 mod animation_frame_view;
 pub use animation_frame_view::AnimationFrameView;
+mod troop_view;
+pub use troop_view::TroopView;
 mod cellpicker;
 pub use cellpicker::Cellpicker;
 
 mod id_vec;
 pub use id_vec::{IdVecPlusMinusSelection, IdVecSelection, RankSelection};
 
+mod extra_ivars_view;
+pub use extra_ivars_view::ExtraIvarsView;
+
 mod ui_ext;
 pub use ui_ext::UiExt;
 
@@ -15,6 +15,7 @@
 // You should have received a copy of the GNU General Public License
 // along with Luminol.  If not, see <http://www.gnu.org/licenses/>.
 
+use color_eyre::eyre::ContextCompat;
 use luminol_graphics::Renderable;
 
 pub struct Tilepicker {
@@ -66,14 +67,19 @@ impl Tilepicker {
     pub fn new(
         update_state: &luminol_core::UpdateState<'_>,
         map_id: usize, // FIXME
-    ) -> Tilepicker {
+    ) -> color_eyre::Result<Tilepicker> {
         let map = update_state.data.get_or_load_map(
             map_id,
             update_state.filesystem,
             update_state.project_config.as_ref().unwrap(),
-        );
+        )?;
         let tilesets = update_state.data.tilesets();
-        let tileset = &tilesets.data[map.tileset_id];
+        let tileset = tilesets.data.get(map.tileset_id).wrap_err_with(|| {
+            format!(
+                "Map {map_id:0>3} uses tileset {}, which doesn't exist",
+                map.tileset_id
+            )
+        })?;
 
         let view = luminol_graphics::Tilepicker::new(
             &update_state.graphics,
@@ -94,7 +100,7 @@ impl Tilepicker {
         );
         brush_seed[8..16].copy_from_slice(&(map_id as u64).to_le_bytes());
 
-        Self {
+        Ok(Self {
             view,
 
             selected_tiles_left: 0,
@@ -105,7 +111,7 @@ impl Tilepicker {
             drag_origin: None,
             brush_seed,
             brush_random: false,
-        }
+        })
     }
 
     pub fn get_tile_from_offset(
@@ -147,6 +153,18 @@ impl Tilepicker {
         }
     }
 
+    /// Selects `tile`, and only `tile`, as the tile to draw with.
+    pub fn select_tile(&mut self, tile: SelectedTile) {
+        let (x, y) = match tile {
+            SelectedTile::Autotile(autotile) => (autotile, 0),
+            SelectedTile::Tile(id) => ((id - 384) % 8, (id - 384) / 8 + 1),
+        };
+        self.selected_tiles_left = x;
+        self.selected_tiles_right = x;
+        self.selected_tiles_top = y;
+        self.selected_tiles_bottom = y;
+    }
+
     pub fn ui(
         &mut self,
         update_state: &luminol_core::UpdateState<'_>,
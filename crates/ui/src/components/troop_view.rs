@@ -0,0 +1,170 @@
+// Copyright (C) 2024 Melody Madeline Lyons
+//
+// This file is part of Luminol.
+//
+// Luminol is free software: you can redistribute it and/or modify
+// it under the terms of the GNU General Public License as published by
+// the Free Software Foundation, either version 3 of the License, or
+// (at your option) any later version.
+//
+// Luminol is distributed in the hope that it will be useful,
+// but WITHOUT ANY WARRANTY; without even the implied warranty of
+// MERCHANTABILITY or FITNESS FOR A PARTICULAR PURPOSE.  See the
+// GNU General Public License for more details.
+//
+// You should have received a copy of the GNU General Public License
+// along with Luminol.  If not, see <http://www.gnu.org/licenses/>.
+
+use luminol_graphics::Renderable;
+
+use luminol_graphics::troop::{TROOP_HEIGHT, TROOP_WIDTH};
+
+/// Size of the box drawn for troop members whose enemy doesn't have a battler graphic.
+const EMPTY_MEMBER_SIZE: f32 = 32.;
+
+pub struct TroopView {
+    pub troop: luminol_graphics::Troop,
+    pub selected_member_index: Option<usize>,
+    pub hovered_member_index: Option<usize>,
+    pub hovered_member_drag_offset: Option<egui::Vec2>,
+}
+
+impl TroopView {
+    pub fn new(update_state: &luminol_core::UpdateState<'_>) -> Self {
+        Self {
+            troop: luminol_graphics::Troop::new(&update_state.graphics),
+            selected_member_index: None,
+            hovered_member_index: None,
+            hovered_member_drag_offset: None,
+        }
+    }
+
+    /// Shows the troop scaled to fit the available width. Members can be selected by clicking on
+    /// them and moved by dragging them; the inner response contains the index and new position of
+    /// the member being dragged.
+    pub fn ui(
+        &mut self,
+        ui: &mut egui::Ui,
+        update_state: &luminol_core::UpdateState<'_>,
+        troop: &luminol_data::rpg::Troop,
+    ) -> egui::InnerResponse<Option<(usize, i32, i32)>> {
+        let scale = ui.available_width() / TROOP_WIDTH as f32;
+        let (canvas_rect, response) = ui.allocate_exact_size(
+            egui::vec2(TROOP_WIDTH as f32, TROOP_HEIGHT as f32) * scale,
+            egui::Sense::click_and_drag(),
+        );
+
+        ui.painter()
+            .rect_filled(canvas_rect, egui::Rounding::ZERO, egui::Color32::BLACK);
+
+        // The paint callback can't extend past the edges of the screen, so if the canvas is only
+        // partially visible we shift the viewport to compensate
+        let visible_rect = ui.clip_rect().intersect(canvas_rect);
+        if visible_rect.is_positive() {
+            let translation = canvas_rect.min - visible_rect.min;
+            self.troop.viewport.set(
+                &update_state.graphics.render_state,
+                glam::vec2(visible_rect.width(), visible_rect.height()),
+                glam::vec2(translation.x, translation.y),
+                glam::Vec2::splat(scale),
+            );
+
+            let painter =
+                luminol_graphics::Painter::new(self.troop.prepare(&update_state.graphics));
+            ui.painter()
+                .add(luminol_egui_wgpu::Callback::new_paint_callback(
+                    visible_rect,
+                    painter,
+                ));
+        }
+
+        let offset = canvas_rect.min.to_vec2();
+        let member_rects = troop
+            .members
+            .iter()
+            .enumerate()
+            .map(|(i, member)| {
+                self.troop
+                    .members()
+                    .get(i)
+                    .and_then(|sprite| sprite.as_ref())
+                    .map_or_else(
+                        || {
+                            egui::Rect::from_min_size(
+                                egui::pos2(
+                                    member.x as f32 - EMPTY_MEMBER_SIZE / 2.,
+                                    member.y as f32 - EMPTY_MEMBER_SIZE,
+                                ),
+                                egui::Vec2::splat(EMPTY_MEMBER_SIZE),
+                            )
+                        },
+                        |sprite| sprite.rect,
+                    )
+            })
+            .map(|rect| (rect * scale).translate(offset))
+            .collect::<Vec<_>>();
+
+        // If multiple members are hovered we prioritize the one with the greatest index, since
+        // that's the one drawn on top
+        if self.hovered_member_drag_offset.is_none() {
+            self.hovered_member_index = response
+                .hover_pos()
+                .and_then(|pos| member_rects.iter().rposition(|rect| rect.contains(pos)));
+        }
+
+        if response.clicked() || response.drag_started_by(egui::PointerButton::Primary) {
+            self.selected_member_index = self.hovered_member_index;
+        }
+
+        let hover_pos_in_troop_coords = response.hover_pos().map(|pos| (pos - offset) / scale);
+
+        if !response.dragged_by(egui::PointerButton::Primary) {
+            self.hovered_member_drag_offset = None;
+        } else if let (Some(i), None, Some(pos)) = (
+            self.hovered_member_index,
+            self.hovered_member_drag_offset,
+            hover_pos_in_troop_coords,
+        ) {
+            if let Some(member) = troop.members.get(i) {
+                self.hovered_member_drag_offset =
+                    Some(egui::vec2(member.x as f32, member.y as f32) - pos.to_vec2());
+            }
+        }
+
+        let drag_pos = self
+            .hovered_member_index
+            .zip(self.hovered_member_drag_offset)
+            .zip(hover_pos_in_troop_coords)
+            .map(|((i, drag_offset), pos)| {
+                let pos = pos + drag_offset;
+                (
+                    i,
+                    pos.x.clamp(0., TROOP_WIDTH as f32).round() as i32,
+                    pos.y.clamp(0., TROOP_HEIGHT as f32).round() as i32,
+                )
+            });
+
+        // Draw a white rectangle on the border of every member
+        for rect in &member_rects {
+            ui.painter()
+                .rect_stroke(*rect, 5., egui::Stroke::new(1., egui::Color32::WHITE));
+        }
+
+        // Draw a yellow rectangle on the border of the hovered member
+        if let Some(rect) = self.hovered_member_index.and_then(|i| member_rects.get(i)) {
+            ui.painter()
+                .rect_stroke(*rect, 5., egui::Stroke::new(3., egui::Color32::YELLOW));
+        }
+
+        // Draw a magenta rectangle on the border of the selected member
+        if let Some(rect) = self.selected_member_index.and_then(|i| member_rects.get(i)) {
+            ui.painter().rect_stroke(
+                *rect,
+                5.,
+                egui::Stroke::new(3., egui::Color32::from_rgb(255, 0, 255)),
+            );
+        }
+
+        egui::InnerResponse::new(drag_pos, response)
+    }
+}
@@ -157,6 +157,7 @@ macro_rules! window_enum {
 tab_enum! {
     pub enum Tab {
         Map(tabs::map::Tab),
+        MapError(tabs::map_error::Tab),
         Started(tabs::started::Tab)
     }
 }
@@ -49,11 +49,11 @@ impl super::DatabaseModalHandler for Switch {
     }
 
     fn current_size(update_state: &luminol_core::UpdateState<'_>) -> Option<usize> {
-        Some(update_state.data.system().variables.len())
+        Some(update_state.data.system().switches.len())
     }
 
     fn resize(update_state: &mut luminol_core::UpdateState<'_>, new_size: usize) {
         let system = &mut update_state.data.system();
-        system.variables.resize_with(new_size, String::new);
+        system.switches.resize_with(new_size, String::new);
     }
 }
@@ -23,7 +23,7 @@
 // Program grant you additional permission to convey the resulting work.
 
 use crate::components::{EnumComboBox, UiExt};
-use color_eyre::eyre::WrapErr;
+use color_eyre::eyre::{ContextCompat, WrapErr};
 use egui::Widget;
 use luminol_core::prelude::*;
 
@@ -129,11 +129,15 @@ impl luminol_core::Modal for Modal {
 
             if response.clicked() && !is_open {
                 let selected = if let Some(tile_id) = data.tile_id {
-                    let tilepicker = Self::load_tilepicker(update_state, self.tileset_id);
-
-                    Selected::Tile {
-                        tile_id,
-                        tilepicker,
+                    match Self::load_tilepicker(update_state, self.tileset_id) {
+                        Ok(tilepicker) => Selected::Tile {
+                            tile_id,
+                            tilepicker,
+                        },
+                        Err(e) => {
+                            luminol_core::error!(update_state.toasts, e);
+                            Selected::None
+                        }
                     }
                 } else if let Some(path) = data.character_name.clone() {
                     let sprite = match Self::load_preview_sprite(
@@ -215,9 +219,15 @@ impl Modal {
         });
     }
 
-    fn load_tilepicker(update_state: &UpdateState<'_>, tileset_id: usize) -> Tilepicker {
+    fn load_tilepicker(
+        update_state: &UpdateState<'_>,
+        tileset_id: usize,
+    ) -> color_eyre::Result<Tilepicker> {
         let tilesets = update_state.data.tilesets();
-        let tileset = &tilesets.data[tileset_id];
+        let tileset = tilesets
+            .data
+            .get(tileset_id)
+            .wrap_err_with(|| format!("Tileset {tileset_id} doesn't exist"))?;
 
         let mut tilepicker = Tilepicker::new(
             &update_state.graphics,
@@ -227,7 +237,7 @@ impl Modal {
         );
         tilepicker.tiles.auto_opacity = false;
 
-        tilepicker
+        Ok(tilepicker)
     }
 
     fn load_preview_sprite(
@@ -356,14 +366,20 @@ impl Modal {
                                             let checked = matches!(selected, Selected::Tile { .. });
                                             let res = ui.selectable_label(checked, "(Tileset)");
                                             if res.clicked() && !checked {
-                                                let tilepicker = Self::load_tilepicker(
+                                                match Self::load_tilepicker(
                                                     update_state,
                                                     self.tileset_id,
-                                                );
-                                                *selected = Selected::Tile {
-                                                    tile_id: 384,
-                                                    tilepicker,
-                                                };
+                                                ) {
+                                                    Ok(tilepicker) => {
+                                                        *selected = Selected::Tile {
+                                                            tile_id: 384,
+                                                            tilepicker,
+                                                        };
+                                                    }
+                                                    Err(e) => {
+                                                        luminol_core::error!(update_state.toasts, e)
+                                                    }
+                                                }
                                             }
                                         });
                                     }
@@ -24,6 +24,8 @@
 
 pub mod sound_picker;
 
+pub mod move_route;
+
 pub mod graphic_picker;
 
 pub mod database_modal;
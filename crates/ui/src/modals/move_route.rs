@@ -21,3 +21,650 @@
 // it with Steamworks API by Valve Corporation, containing parts covered by
 // terms of the Steamworks API by Valve Corporation, the licensors of this
 // Program grant you additional permission to convey the resulting work.
+
+use luminol_core::Modal as _;
+use luminol_data::{rpg, ParameterType};
+
+use crate::components::UiExt;
+use crate::modals::{
+    database_modal::SwitchModal, graphic_picker::hue::Modal as HueGraphicPicker,
+    sound_picker::Modal as SoundPicker,
+};
+
+/// The name of every move command code, in the order they're shown in the palette.
+const MOVE_COMMANDS: [(u16, &str); 45] = [
+    (1, "Move Down"),
+    (2, "Move Left"),
+    (3, "Move Right"),
+    (4, "Move Up"),
+    (5, "Move Lower Left"),
+    (6, "Move Lower Right"),
+    (7, "Move Upper Left"),
+    (8, "Move Upper Right"),
+    (9, "Move at Random"),
+    (10, "Move toward Player"),
+    (11, "Move away from Player"),
+    (12, "1 Step Forward"),
+    (13, "1 Step Backward"),
+    (14, "Jump"),
+    (15, "Wait"),
+    (16, "Turn Down"),
+    (17, "Turn Left"),
+    (18, "Turn Right"),
+    (19, "Turn Up"),
+    (20, "Turn 90° Right"),
+    (21, "Turn 90° Left"),
+    (22, "Turn 180°"),
+    (23, "Turn 90° Right or Left"),
+    (24, "Turn at Random"),
+    (25, "Turn toward Player"),
+    (26, "Turn away from Player"),
+    (27, "Switch ON"),
+    (28, "Switch OFF"),
+    (29, "Change Speed"),
+    (30, "Change Frequency"),
+    (31, "Move Animation ON"),
+    (32, "Move Animation OFF"),
+    (33, "Stop Animation ON"),
+    (34, "Stop Animation OFF"),
+    (35, "Direction Fix ON"),
+    (36, "Direction Fix OFF"),
+    (37, "Through ON"),
+    (38, "Through OFF"),
+    (39, "Always on Top ON"),
+    (40, "Always on Top OFF"),
+    (41, "Change Graphic"),
+    (42, "Change Opacity"),
+    (43, "Change Blending"),
+    (44, "Play SE"),
+    (45, "Script"),
+];
+
+const BLEND_TYPES: [&str; 3] = ["Normal", "Add", "Subtract"];
+const DIRECTIONS: [(i32, &str); 4] = [(2, "Down"), (4, "Left"), (6, "Right"), (8, "Up")];
+
+/// Where the route starts on the map, used to preview the route on the map view.
+#[derive(Clone, Copy, Debug)]
+pub struct PreviewOrigin {
+    pub map_id: usize,
+    pub x: i32,
+    pub y: i32,
+    pub direction: i32,
+}
+
+/// The move route editor.
+pub struct Modal {
+    state: State,
+    id_source: egui::Id,
+    preview_origin: Option<PreviewOrigin>,
+}
+
+enum State {
+    Closed,
+    Open {
+        route: rpg::MoveRoute,
+        selected_index: usize,
+        switch_modal: SwitchModal,
+        graphic_picker: Option<HueGraphicPicker>,
+        se_picker: SoundPicker,
+    },
+}
+
+impl Modal {
+    pub fn new(id_source: impl Into<egui::Id>) -> Self {
+        Self {
+            state: State::Closed,
+            id_source: id_source.into(),
+            preview_origin: None,
+        }
+    }
+
+    /// Sets where the route is previewed on the map view while this modal is open. This should be
+    /// called every frame before showing the button since the origin can move.
+    pub fn set_preview_origin(&mut self, preview_origin: Option<PreviewOrigin>) {
+        self.preview_origin = preview_origin;
+    }
+}
+
+impl luminol_core::Modal for Modal {
+    type Data<'m> = &'m mut rpg::MoveRoute;
+
+    fn button<'m>(
+        &'m mut self,
+        data: Self::Data<'m>,
+        update_state: &'m mut luminol_core::UpdateState<'_>,
+    ) -> impl egui::Widget + 'm {
+        |ui: &mut egui::Ui| {
+            let is_open = matches!(self.state, State::Open { .. });
+            let mut response = ui.add(egui::Button::new("Move Route...").selected(is_open));
+
+            if response.clicked() && !is_open {
+                self.state = State::Open {
+                    route: data.clone(),
+                    selected_index: 0,
+                    switch_modal: SwitchModal::new(self.id_source.with("switch_modal")),
+                    graphic_picker: None,
+                    se_picker: SoundPicker::new(
+                        luminol_audio::Source::SE,
+                        self.id_source.with("se_picker"),
+                    ),
+                };
+            }
+            if self.show_window(update_state, ui.ctx(), data) {
+                response.mark_changed();
+            }
+
+            response
+        }
+    }
+
+    fn reset(&mut self, _update_state: &mut luminol_core::UpdateState<'_>, _data: Self::Data<'_>) {
+        self.state = State::Closed;
+    }
+}
+
+impl Modal {
+    fn show_window(
+        &mut self,
+        update_state: &mut luminol_core::UpdateState<'_>,
+        ctx: &egui::Context,
+        data: &mut rpg::MoveRoute,
+    ) -> bool {
+        let mut win_open = true;
+        let mut keep_open = true;
+        let mut needs_save = false;
+
+        let State::Open {
+            route,
+            selected_index,
+            switch_modal,
+            graphic_picker,
+            se_picker,
+        } = &mut self.state
+        else {
+            return false;
+        };
+
+        // Routes are always terminated by a blank command
+        if !route.list.last().is_some_and(|command| command.code == 0) {
+            route.list.push(rpg::MoveCommand {
+                code: 0,
+                parameters: vec![],
+                guid: rand::random(),
+                extra_ivars: Default::default(),
+            });
+        }
+        *selected_index = (*selected_index).min(route.list.len() - 1);
+        let previous_index = *selected_index;
+
+        egui::Window::new("Move Route")
+            .open(&mut win_open)
+            .id(self.id_source.with("window"))
+            .default_width(500.)
+            .show(ctx, |ui| {
+                egui::TopBottomPanel::bottom(self.id_source.with("bottom_panel")).show_inside(
+                    ui,
+                    |ui| {
+                        ui.add_space(1.0);
+                        ui.horizontal(|ui| {
+                            ui.checkbox(&mut route.repeat, "Repeat Action");
+                            ui.checkbox(&mut route.skippable, "Ignore If Can't Move");
+                        });
+                        crate::components::close_options_ui(ui, &mut keep_open, &mut needs_save);
+                    },
+                );
+
+                egui::SidePanel::right(self.id_source.with("palette")).show_inside(ui, |ui| {
+                    egui::ScrollArea::vertical().show(ui, |ui| {
+                        ui.with_cross_justify(|ui| {
+                            for (code, name) in MOVE_COMMANDS {
+                                if ui.button(name).clicked() {
+                                    route.list.insert(*selected_index, new_move_command(code));
+                                    *selected_index += 1;
+                                }
+                            }
+                        });
+                    });
+                });
+
+                egui::CentralPanel::default().show_inside(ui, |ui| {
+                    ui.horizontal(|ui| {
+                        let is_end = *selected_index + 1 == route.list.len();
+                        if ui
+                            .add_enabled(*selected_index > 0 && !is_end, egui::Button::new("Up"))
+                            .clicked()
+                        {
+                            route.list.swap(*selected_index, *selected_index - 1);
+                            *selected_index -= 1;
+                        }
+                        if ui
+                            .add_enabled(
+                                *selected_index + 2 < route.list.len(),
+                                egui::Button::new("Down"),
+                            )
+                            .clicked()
+                        {
+                            route.list.swap(*selected_index, *selected_index + 1);
+                            *selected_index += 1;
+                        }
+                        if ui
+                            .add_enabled(!is_end, egui::Button::new("Delete"))
+                            .clicked()
+                        {
+                            route.list.remove(*selected_index);
+                        }
+                    });
+
+                    ui.group(|ui| {
+                        egui::ScrollArea::vertical()
+                            .id_source(self.id_source.with("list"))
+                            .max_height(256.)
+                            .auto_shrink([false, true])
+                            .show(ui, |ui| {
+                                ui.with_cross_justify(|ui| {
+                                    for (i, command) in route.list.iter().enumerate() {
+                                        ui.selectable_value(
+                                            selected_index,
+                                            i,
+                                            format!("$>{}", command_text(command)),
+                                        );
+                                    }
+                                });
+                            });
+                    });
+
+                    ui.separator();
+
+                    // The list may have shrunk if a command was deleted
+                    let index = (*selected_index).min(route.list.len() - 1);
+                    if index != previous_index {
+                        *graphic_picker = None;
+                    }
+                    let command = &mut route.list[index];
+                    command_parameter_ui(
+                        ui,
+                        update_state,
+                        self.id_source,
+                        command,
+                        switch_modal,
+                        graphic_picker,
+                        se_picker,
+                    );
+                });
+            });
+
+        if let Some(origin) = self.preview_origin {
+            ctx.data_mut(|d| {
+                d.insert_temp(
+                    preview_id(origin.map_id),
+                    (
+                        ctx.frame_nr(),
+                        route_path(route, origin.x, origin.y, origin.direction),
+                    ),
+                )
+            });
+        }
+
+        if needs_save {
+            *data = route.clone();
+        }
+
+        if !(win_open && keep_open) {
+            self.state = State::Closed;
+        }
+        needs_save
+    }
+}
+
+/// Creates a move command with sensible default values for its parameters.
+fn new_move_command(code: u16) -> rpg::MoveCommand {
+    let parameters = match code {
+        14 => vec![ParameterType::Integer(0), ParameterType::Integer(0)],
+        15 | 27 | 28 => vec![ParameterType::Integer(1)],
+        29 | 30 => vec![ParameterType::Integer(3)],
+        41 => vec![
+            ParameterType::String(String::new()),
+            ParameterType::Integer(0),
+            ParameterType::Integer(2),
+            ParameterType::Integer(0),
+        ],
+        42 => vec![ParameterType::Integer(255)],
+        43 => vec![ParameterType::Integer(0)],
+        44 => vec![ParameterType::AudioFile(rpg::AudioFile {
+            name: None,
+            volume: 80,
+            ..Default::default()
+        })],
+        45 => vec![ParameterType::String(String::new())],
+        _ => vec![],
+    };
+
+    rpg::MoveCommand {
+        code,
+        parameters,
+        guid: rand::random(),
+        extra_ivars: Default::default(),
+    }
+}
+
+/// Shows the editor for the parameters of a move command, returning whether or not it was
+/// modified.
+fn command_parameter_ui(
+    ui: &mut egui::Ui,
+    update_state: &mut luminol_core::UpdateState<'_>,
+    id_source: egui::Id,
+    command: &mut rpg::MoveCommand,
+    switch_modal: &mut SwitchModal,
+    graphic_picker: &mut Option<HueGraphicPicker>,
+    se_picker: &mut SoundPicker,
+) -> bool {
+    let mut modified = false;
+    let parameters = &mut command.parameters;
+
+    match command.code {
+        14 => {
+            if parameters.len() < 2 {
+                parameters.resize_with(2, || ParameterType::Integer(0));
+            }
+            let [x, y, ..] = parameters.as_mut_slice() else {
+                unreachable!()
+            };
+            ui.horizontal(|ui| {
+                ui.label("X Offset");
+                modified |= ui.add(egui::DragValue::new(x.into_integer())).changed();
+                ui.label("Y Offset");
+                modified |= ui.add(egui::DragValue::new(y.into_integer())).changed();
+            });
+        }
+        15 => {
+            let frames = first_parameter(parameters).into_integer();
+            ui.horizontal(|ui| {
+                ui.label("Frames");
+                modified |= ui
+                    .add(egui::DragValue::new(frames).range(1..=999))
+                    .changed();
+            });
+        }
+        27 | 28 => {
+            let value = first_parameter(parameters).into_integer();
+            let mut switch_id = usize::try_from(*value - 1).unwrap_or_default();
+            ui.horizontal(|ui| {
+                ui.label("Switch");
+                ui.add(switch_modal.button(&mut switch_id, update_state));
+            });
+            if *value != switch_id as i32 + 1 {
+                *value = switch_id as i32 + 1;
+                modified = true;
+            }
+        }
+        29 | 30 => {
+            let value = first_parameter(parameters).into_integer();
+            ui.horizontal(|ui| {
+                ui.label(if command.code == 29 {
+                    "Speed"
+                } else {
+                    "Frequency"
+                });
+                modified |= ui.add(egui::Slider::new(value, 1..=6)).changed();
+            });
+        }
+        41 => {
+            if parameters.len() < 4 {
+                parameters.resize_with(4, || ParameterType::Integer(0));
+            }
+            let [name, hue, direction, pattern, ..] = parameters.as_mut_slice() else {
+                unreachable!()
+            };
+            let name = name.into_string();
+            let hue = hue.into_integer();
+            let direction = direction.into_integer();
+            let pattern = pattern.into_integer();
+
+            let mut path = (!name.is_empty()).then(|| camino::Utf8PathBuf::from(name.as_str()));
+            let picker = graphic_picker.get_or_insert_with(|| {
+                HueGraphicPicker::new(
+                    update_state,
+                    "Graphics/Characters".into(),
+                    path.as_deref(),
+                    *hue,
+                    egui::vec2(48., 48.),
+                    id_source.with("graphic_picker"),
+                )
+            });
+            ui.horizontal(|ui| {
+                if ui
+                    .add(picker.button((&mut path, hue), update_state))
+                    .changed()
+                {
+                    *name = path.map(String::from).unwrap_or_default();
+                    modified = true;
+                }
+
+                ui.vertical(|ui| {
+                    egui::ComboBox::from_id_source(id_source.with("direction"))
+                        .selected_text(
+                            DIRECTIONS
+                                .iter()
+                                .find(|(d, _)| *d == *direction)
+                                .map_or_else(|| direction.to_string(), |(_, n)| n.to_string()),
+                        )
+                        .show_ui(ui, |ui| {
+                            for (value, name) in DIRECTIONS {
+                                modified |= ui.selectable_value(direction, value, name).changed();
+                            }
+                        });
+                    ui.horizontal(|ui| {
+                        ui.label("Pattern");
+                        modified |= ui.add(egui::Slider::new(pattern, 0..=3)).changed();
+                    });
+                });
+            });
+        }
+        42 => {
+            let value = first_parameter(parameters).into_integer();
+            ui.horizontal(|ui| {
+                ui.label("Opacity");
+                modified |= ui.add(egui::Slider::new(value, 0..=255)).changed();
+            });
+        }
+        43 => {
+            let value = first_parameter(parameters).into_integer();
+            ui.horizontal(|ui| {
+                ui.label("Blending");
+                egui::ComboBox::from_id_source(id_source.with("blend_type"))
+                    .selected_text(
+                        usize::try_from(*value)
+                            .ok()
+                            .and_then(|i| BLEND_TYPES.get(i))
+                            .map_or_else(|| value.to_string(), |n| n.to_string()),
+                    )
+                    .show_ui(ui, |ui| {
+                        for (i, name) in BLEND_TYPES.into_iter().enumerate() {
+                            modified |= ui.selectable_value(value, i as i32, name).changed();
+                        }
+                    });
+            });
+        }
+        44 => {
+            let audio_file = first_parameter(parameters).into_audiofile();
+            ui.horizontal(|ui| {
+                ui.label("Sound Effect");
+                modified |= ui.add(se_picker.button(audio_file, update_state)).changed();
+            });
+        }
+        45 => {
+            let script = first_parameter(parameters).into_string();
+            modified |= ui
+                .add(
+                    egui::TextEdit::multiline(script)
+                        .code_editor()
+                        .desired_width(f32::INFINITY),
+                )
+                .changed();
+        }
+        _ => {
+            ui.weak("This command has no parameters.");
+        }
+    }
+
+    modified
+}
+
+fn first_parameter(parameters: &mut Vec<ParameterType>) -> &mut ParameterType {
+    if parameters.is_empty() {
+        parameters.push(ParameterType::None);
+    }
+    &mut parameters[0]
+}
+
+/// Formats a move command for display in a move route.
+pub fn command_text(command: &rpg::MoveCommand) -> String {
+    let Some((_, name)) = MOVE_COMMANDS.iter().find(|(code, _)| *code == command.code) else {
+        return if command.code == 0 {
+            String::new()
+        } else {
+            format!("??? ({})", command.code)
+        };
+    };
+
+    let int = |i: usize| {
+        command
+            .parameters
+            .get(i)
+            .and_then(ParameterType::as_integer)
+            .copied()
+            .unwrap_or_default()
+    };
+    let string = |i: usize| {
+        command
+            .parameters
+            .get(i)
+            .and_then(ParameterType::as_string)
+            .cloned()
+            .unwrap_or_default()
+    };
+
+    match command.code {
+        14 => format!("{name}: {:+}, {:+}", int(0), int(1)),
+        15 => format!("{name}: {} frame(s)", int(0)),
+        27 | 28 => format!("{name}: [{:0>4}]", int(0)),
+        29 | 30 | 42 => format!("{name}: {}", int(0)),
+        41 => format!(
+            "{name}: '{}', {}, {}, {}",
+            string(0),
+            int(1),
+            int(2),
+            int(3)
+        ),
+        43 => format!(
+            "{name}: {}",
+            usize::try_from(int(0))
+                .ok()
+                .and_then(|i| BLEND_TYPES.get(i))
+                .copied()
+                .unwrap_or_default()
+        ),
+        44 => {
+            let audio_file = command
+                .parameters
+                .first()
+                .and_then(ParameterType::as_audiofile);
+            format!(
+                "{name}: '{}', {}, {}",
+                audio_file
+                    .and_then(|a| a.name.as_ref())
+                    .map_or("", |n| n.as_str()),
+                audio_file.map_or(0, |a| a.volume),
+                audio_file.map_or(0, |a| a.pitch),
+            )
+        }
+        45 => format!("{name}: {}", string(0)),
+        _ => name.to_string(),
+    }
+}
+
+/// The ID under which the path of the move route being edited for a map is stored in egui's
+/// temporary memory, along with the frame number it was last updated.
+pub fn preview_id(map_id: usize) -> egui::Id {
+    egui::Id::new("luminol_move_route_preview").with(map_id)
+}
+
+/// Follows a move route from a starting position, returning the tile positions it passes through.
+/// Commands with outcomes that can't be known in advance, like random movement, are skipped.
+pub fn route_path(route: &rpg::MoveRoute, x: i32, y: i32, direction: i32) -> Vec<egui::Pos2> {
+    let (mut x, mut y, mut direction) = (x, y, direction);
+    let mut path = vec![egui::pos2(x as f32, y as f32)];
+
+    let offset = |direction: i32| match direction {
+        2 => (0, 1),
+        4 => (-1, 0),
+        6 => (1, 0),
+        8 => (0, -1),
+        _ => (0, 0),
+    };
+
+    for command in &route.list {
+        let int = |i: usize| {
+            command
+                .parameters
+                .get(i)
+                .and_then(ParameterType::as_integer)
+                .copied()
+                .unwrap_or_default()
+        };
+
+        let (dx, dy) = match command.code {
+            1..=4 => {
+                direction = [2, 4, 6, 8][command.code as usize - 1];
+                offset(direction)
+            }
+            5 => (-1, 1),
+            6 => (1, 1),
+            7 => (-1, -1),
+            8 => (1, -1),
+            12 => offset(direction),
+            13 => {
+                let (dx, dy) = offset(direction);
+                (-dx, -dy)
+            }
+            14 => (int(0), int(1)),
+            16..=19 => {
+                direction = [2, 4, 6, 8][command.code as usize - 16];
+                continue;
+            }
+            20 => {
+                direction = match direction {
+                    2 => 4,
+                    4 => 8,
+                    6 => 2,
+                    8 => 6,
+                    d => d,
+                };
+                continue;
+            }
+            21 => {
+                direction = match direction {
+                    2 => 6,
+                    4 => 2,
+                    6 => 8,
+                    8 => 4,
+                    d => d,
+                };
+                continue;
+            }
+            22 => {
+                direction = 10 - direction;
+                continue;
+            }
+            _ => continue,
+        };
+
+        if (dx, dy) != (0, 0) {
+            x += dx;
+            y += dy;
+            path.push(egui::pos2(x as f32, y as f32));
+        }
+    }
+
+    path
+}
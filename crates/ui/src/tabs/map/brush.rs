@@ -31,6 +31,7 @@ impl super::Tab {
         map_y: usize,
         tile_layer: usize,
         pencil: luminol_core::Pencil,
+        terrain: Option<&luminol_config::terrain::Terrain>,
         map: &mut luminol_data::rpg::Map,
     ) {
         let map_pos = egui::pos2(map_x as f32, map_y as f32);
@@ -139,42 +140,62 @@ impl super::Tab {
                 }
             }
 
-            luminol_core::Pencil::Rectangle => {
-                if !self.drawing_shape {
-                    // Save the current layer
-                    for x in 0..map.data.xsize() {
-                        for y in 0..map.data.ysize() {
-                            self.brush_layer_cache[x + y * map.data.xsize()] =
-                                map.data[(x, y, tile_layer)];
+            luminol_core::Pencil::Line => {
+                self.begin_shape(map, tile_layer);
+
+                // Use Bresenham's algorithm to draw the line.
+                if let Some(drawing_shape_pos) = self.drawing_shape_pos {
+                    let (x1, y1) = (map_x as i32, map_y as i32);
+                    let (mut x, mut y) = (drawing_shape_pos.x as i32, drawing_shape_pos.y as i32);
+                    let dx = (x1 - x).abs();
+                    let dy = -(y1 - y).abs();
+                    let step_x = if x < x1 { 1 } else { -1 };
+                    let step_y = if y < y1 { 1 } else { -1 };
+                    let mut error = dx + dy;
+
+                    loop {
+                        self.set_shape_tile(
+                            map,
+                            x as usize,
+                            y as usize,
+                            tile_layer,
+                            drawing_shape_pos,
+                        );
+                        if x == x1 && y == y1 {
+                            break;
                         }
-                    }
-                    self.drawing_shape = true;
-                } else {
-                    // Restore the previously stored state of the current layer
-                    for y in 0..map.data.ysize() {
-                        for x in 0..map.data.xsize() {
-                            map.data[(x, y, tile_layer)] =
-                                self.brush_layer_cache[x + y * map.data.xsize()];
+                        let doubled_error = 2 * error;
+                        if doubled_error >= dy {
+                            error += dy;
+                            x += step_x;
+                        }
+                        if doubled_error <= dx {
+                            error += dx;
+                            y += step_y;
                         }
                     }
+                } else {
+                    self.drawing_shape_pos = Some(map_pos);
                 }
+            }
+
+            luminol_core::Pencil::Rectangle | luminol_core::Pencil::FilledRectangle => {
+                self.begin_shape(map, tile_layer);
 
                 if let Some(drawing_shape_pos) = self.drawing_shape_pos {
+                    let filled = pencil == luminol_core::Pencil::FilledRectangle;
                     let bounding_rect = egui::Rect::from_two_pos(drawing_shape_pos, map_pos);
-                    for y in (bounding_rect.min.y as usize)..=(bounding_rect.max.y as usize) {
-                        for x in (bounding_rect.min.x as usize)..=(bounding_rect.max.x) as usize {
-                            let position = (x, y, tile_layer);
-                            self.set_tile(
-                                map,
-                                self.tilepicker.get_tile_from_offset(
-                                    x as i16,
-                                    y as i16,
-                                    tile_layer as i16,
-                                    x as i16 - drawing_shape_pos.x as i16,
-                                    y as i16 - drawing_shape_pos.y as i16,
-                                ),
-                                position,
-                            );
+                    let (left, right) =
+                        (bounding_rect.min.x as usize, bounding_rect.max.x as usize);
+                    let (top, bottom) =
+                        (bounding_rect.min.y as usize, bounding_rect.max.y as usize);
+                    for y in top..=bottom {
+                        for x in left..=right {
+                            // Outlines only have the tiles on the edges of the rectangle
+                            if !filled && x != left && x != right && y != top && y != bottom {
+                                continue;
+                            }
+                            self.set_shape_tile(map, x, y, tile_layer, drawing_shape_pos);
                         }
                     }
                 } else {
@@ -182,45 +203,19 @@ impl super::Tab {
                 }
             }
 
-            luminol_core::Pencil::Circle => {
-                if !self.drawing_shape {
-                    // Save the current layer
-                    for x in 0..map.data.xsize() {
-                        for y in 0..map.data.ysize() {
-                            self.brush_layer_cache[x + y * map.data.xsize()] =
-                                map.data[(x, y, tile_layer)];
-                        }
-                    }
-                    self.drawing_shape = true;
-                } else {
-                    // Restore the previously stored state of the current layer
-                    for y in 0..map.data.ysize() {
-                        for x in 0..map.data.xsize() {
-                            map.data[(x, y, tile_layer)] =
-                                self.brush_layer_cache[x + y * map.data.xsize()];
-                        }
-                    }
-                }
+            luminol_core::Pencil::Circle | luminol_core::Pencil::FilledCircle => {
+                self.begin_shape(map, tile_layer);
 
                 // Use Bresenham's algorithm to draw the ellipse.
                 // We consider (x, y) to be the top-left corner of the tile at
                 // (x, y).
                 if let Some(drawing_shape_pos) = self.drawing_shape_pos {
+                    let filled = pencil == luminol_core::Pencil::FilledCircle;
                     let bounding_rect = egui::Rect::from_two_pos(drawing_shape_pos, map_pos);
                     // Edge case: Bresenham's algorithm breaks down when drawing a
                     // 1x1 ellipse.
                     if drawing_shape_pos == map_pos {
-                        self.set_tile(
-                            map,
-                            self.tilepicker.get_tile_from_offset(
-                                map_x as i16,
-                                map_y as i16,
-                                tile_layer as i16,
-                                map_x as i16 - drawing_shape_pos.x as i16,
-                                map_y as i16 - drawing_shape_pos.y as i16,
-                            ),
-                            (map_x, map_y, tile_layer),
-                        );
+                        self.set_shape_tile(map, map_x, map_y, tile_layer, drawing_shape_pos);
                     } else {
                         let bounding_rect = bounding_rect.translate(egui::vec2(0.5, 0.5));
 
@@ -235,6 +230,25 @@ impl super::Tab {
                         let rx2 = rx * rx;
                         let ry2 = ry * ry;
 
+                        // Returns the offsets from the center to draw at for a point on the edge
+                        // of the ellipse that is `offset` away from the center: only the point
+                        // and its mirror image for outlines, and everything in between them for
+                        // filled ellipses.
+                        let span = |offset: f32| -> Vec<f32> {
+                            if !filled {
+                                return vec![-offset, offset];
+                            }
+                            (((-offset).floor() as i32)..=(offset.floor() as i32))
+                                .map(|i| {
+                                    if offset.floor() == offset {
+                                        i as f32
+                                    } else {
+                                        i as f32 + 0.5
+                                    }
+                                })
+                                .collect()
+                        };
+
                         // Let the "ellipse function" be defined as
                         // f(x, y) = b^2 x^2 + a^2 y^2 - a^2 b^2
                         // where a is the x-radius of an ellipse centered at (0, 0)
@@ -252,25 +266,16 @@ impl super::Tab {
 
                         // Keep looping until dy/dx < -1.
                         while rx2 * y >= ry2 * x {
-                            for i in ((-y).floor() as i32)..=(y.floor() as i32) {
-                                let i = if y.floor() == y {
-                                    i as f32
-                                } else {
-                                    i as f32 + 0.5
-                                };
+                            for i in span(y) {
                                 for j in [x, -x] {
                                     let x = (x0 + j).floor();
                                     let y = (y0 + i).floor();
-                                    self.set_tile(
+                                    self.set_shape_tile(
                                         map,
-                                        self.tilepicker.get_tile_from_offset(
-                                            x as i16,
-                                            y as i16,
-                                            tile_layer as i16,
-                                            x as i16 - drawing_shape_pos.x as i16,
-                                            y as i16 - drawing_shape_pos.y as i16,
-                                        ),
-                                        (x as usize, y as usize, tile_layer),
+                                        x as usize,
+                                        y as usize,
+                                        tile_layer,
+                                        drawing_shape_pos,
                                     );
                                 }
                             }
@@ -297,25 +302,16 @@ impl super::Tab {
 
                         // Keep looping until dy/dx > -1.
                         while rx2 * y <= ry2 * x {
-                            for i in ((-x).floor() as i32)..=(x.floor() as i32) {
-                                let i = if x.floor() == x {
-                                    i as f32
-                                } else {
-                                    i as f32 + 0.5
-                                };
+                            for i in span(x) {
                                 for j in [y, -y] {
                                     let x = (x0 + i).floor();
                                     let y = (y0 + j).floor();
-                                    self.set_tile(
+                                    self.set_shape_tile(
                                         map,
-                                        self.tilepicker.get_tile_from_offset(
-                                            x as i16,
-                                            y as i16,
-                                            tile_layer as i16,
-                                            x as i16 - drawing_shape_pos.x as i16,
-                                            y as i16 - drawing_shape_pos.y as i16,
-                                        ),
-                                        (x as usize, y as usize, tile_layer),
+                                        x as usize,
+                                        y as usize,
+                                        tile_layer,
+                                        drawing_shape_pos,
                                     );
                                 }
                             }
@@ -338,6 +334,67 @@ impl super::Tab {
                     self.drawing_shape_pos = Some(map_pos);
                 }
             }
+
+            luminol_core::Pencil::Eyedropper => {
+                self.tilepicker
+                    .select_tile(SelectedTile::from_id(map.data[(map_x, map_y, tile_layer)]));
+            }
+
+            luminol_core::Pencil::Terrain => {
+                if let Some(terrain) = terrain {
+                    self.paint_terrain(map_x, map_y, tile_layer, terrain, map);
+                }
+            }
+
+            // The select tool doesn't draw anything
+            luminol_core::Pencil::Select => {}
         };
     }
+
+    /// Saves the current layer when a shape starts being drawn, or restores it while the shape is
+    /// being drawn so that the shape can be redrawn from scratch every frame.
+    fn begin_shape(&mut self, map: &mut luminol_data::rpg::Map, tile_layer: usize) {
+        if !self.drawing_shape {
+            // Save the current layer
+            for x in 0..map.data.xsize() {
+                for y in 0..map.data.ysize() {
+                    self.brush_layer_cache[x + y * map.data.xsize()] = map.data[(x, y, tile_layer)];
+                }
+            }
+            self.drawing_shape = true;
+        } else {
+            // Restore the previously stored state of the current layer
+            for y in 0..map.data.ysize() {
+                for x in 0..map.data.xsize() {
+                    map.data[(x, y, tile_layer)] = self.brush_layer_cache[x + y * map.data.xsize()];
+                }
+            }
+        }
+    }
+
+    /// Draws the tile of a shape that starts at `drawing_shape_pos` at (`x`, `y`), skipping tiles
+    /// that are outside the map.
+    fn set_shape_tile(
+        &self,
+        map: &mut luminol_data::rpg::Map,
+        x: usize,
+        y: usize,
+        tile_layer: usize,
+        drawing_shape_pos: egui::Pos2,
+    ) {
+        if x >= map.data.xsize() || y >= map.data.ysize() {
+            return;
+        }
+        self.set_tile(
+            map,
+            self.tilepicker.get_tile_from_offset(
+                x as i16,
+                y as i16,
+                tile_layer as i16,
+                x as i16 - drawing_shape_pos.x as i16,
+                y as i16 - drawing_shape_pos.y as i16,
+            ),
+            (x, y, tile_layer),
+        );
+    }
 }
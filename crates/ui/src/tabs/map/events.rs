@@ -0,0 +1,278 @@
+// Copyright (C) 2024 Melody Madeline Lyons
+//
+// This file is part of Luminol.
+//
+// Luminol is free software: you can redistribute it and/or modify
+// it under the terms of the GNU General Public License as published by
+// the Free Software Foundation, either version 3 of the License, or
+// (at your option) any later version.
+//
+// Luminol is distributed in the hope that it will be useful,
+// but WITHOUT ANY WARRANTY; without even the implied warranty of
+// MERCHANTABILITY or FITNESS FOR A PARTICULAR PURPOSE.  See the
+// GNU General Public License for more details.
+//
+// You should have received a copy of the GNU General Public License
+// along with Luminol.  If not, see <http://www.gnu.org/licenses/>.
+//
+//     Additional permission under GNU GPL version 3 section 7
+//
+// If you modify this Program, or any covered work, by linking or combining
+// it with Steamworks API by Valve Corporation, containing parts covered by
+// terms of the Steamworks API by Valve Corporation, the licensors of this
+// Program grant you additional permission to convey the resulting work.
+
+use color_eyre::eyre::eyre;
+use luminol_data::{rpg, ParameterType};
+use std::collections::HashMap;
+
+/// Where the last copied event is kept in egui's memory, so that it can be pasted even if the OS
+/// clipboard can't be read (like in browsers that don't allow it).
+const CLIPBOARD_ID: &str = "luminol_event_clipboard";
+
+pub(super) enum EventAction {
+    Copy(usize),
+    Paste { event: rpg::Event, pos: egui::Pos2 },
+    Duplicate(usize),
+    CompactIds,
+}
+
+impl EventAction {
+    /// Returns the event action the keyboard shortcuts pressed this frame ask for, if any.
+    pub(super) fn from_input(
+        ctx: &egui::Context,
+        selected_event_id: Option<usize>,
+        cursor_pos: egui::Pos2,
+    ) -> Option<Self> {
+        // Don't take copy and paste away from text fields
+        if ctx.wants_keyboard_input() {
+            return None;
+        }
+
+        let (action, paste_pressed) = ctx.input(|i| {
+            let action = i.events.iter().find_map(|event| match event {
+                egui::Event::Copy => selected_event_id.map(Self::Copy),
+                egui::Event::Paste(text) => event_from_text(text).map(|event| Self::Paste {
+                    event,
+                    pos: cursor_pos,
+                }),
+                _ => None,
+            });
+            let action = action.or_else(|| {
+                (i.modifiers.command && i.key_pressed(egui::Key::D))
+                    .then_some(selected_event_id)
+                    .flatten()
+                    .map(Self::Duplicate)
+            });
+            let paste_pressed = !i
+                .events
+                .iter()
+                .any(|event| matches!(event, egui::Event::Paste(_)))
+                && i.modifiers.command
+                && i.key_pressed(egui::Key::V);
+            (action, paste_pressed)
+        });
+
+        // Fall back to the event we copied ourselves if the OS clipboard couldn't be read
+        action.or_else(|| {
+            paste_pressed
+                .then(|| event_from_memory(ctx))
+                .flatten()
+                .map(|event| Self::Paste {
+                    event,
+                    pos: cursor_pos,
+                })
+        })
+    }
+}
+
+/// Reads an event from the clipboard, returning `None` if it holds something else.
+fn event_from_text(text: &str) -> Option<rpg::Event> {
+    ron::from_str(text).ok()
+}
+
+/// The last event copied in this instance of Luminol.
+pub(super) fn event_from_memory(ctx: &egui::Context) -> Option<rpg::Event> {
+    ctx.data(|d| d.get_temp::<String>(egui::Id::new(CLIPBOARD_ID)))
+        .and_then(|text| event_from_text(&text))
+}
+
+/// Returns the indices of the parameters of `command` that hold the ID of an event on the map.
+/// IDs 0 (this event) and -1 (the player) are stored in the same parameters.
+fn event_id_parameters(command: &rpg::EventCommand) -> &'static [usize] {
+    let parameter = |index: usize| match command.parameters.get(index) {
+        Some(ParameterType::Integer(value)) => Some(*value),
+        _ => None,
+    };
+
+    match command.code {
+        // Set Event Location, swapping places with another event
+        202 if parameter(1) == Some(2) => &[0, 2],
+        // Set Event Location, Show Animation and Set Move Route
+        202 | 207 | 209 => &[0],
+        // Conditional Branch on a character's direction
+        111 if parameter(0) == Some(6) => &[1],
+        // Control Variables set to something about a character
+        122 if parameter(3) == Some(6) => &[4],
+        _ => &[],
+    }
+}
+
+/// Changes the event IDs that the commands of `event` refer to according to `ids`.
+fn remap_event_references(event: &mut rpg::Event, ids: &HashMap<usize, usize>) {
+    for command in event.pages.iter_mut().flat_map(|page| page.list.iter_mut()) {
+        for &index in event_id_parameters(command) {
+            let Some(ParameterType::Integer(id)) = command.parameters.get_mut(index) else {
+                continue;
+            };
+            if let Some(&new_id) = usize::try_from(*id).ok().and_then(|id| ids.get(&id)) {
+                *id = new_id as i32;
+            }
+        }
+    }
+}
+
+impl super::Tab {
+    pub(super) fn handle_event_action(
+        &mut self,
+        ctx: &egui::Context,
+        update_state: &luminol_core::UpdateState<'_>,
+        map: &mut rpg::Map,
+        action: EventAction,
+    ) -> color_eyre::Result<()> {
+        match action {
+            EventAction::Copy(id) => {
+                let event = map
+                    .events
+                    .get(id)
+                    .ok_or_else(|| eyre!("Event {id} doesn't exist"))?;
+                let text = ron::ser::to_string_pretty(
+                    event,
+                    ron::ser::PrettyConfig::new().struct_names(true),
+                )?;
+                ctx.data_mut(|d| d.insert_temp(egui::Id::new(CLIPBOARD_ID), text.clone()));
+                ctx.copy_text(text);
+            }
+
+            EventAction::Paste { event, pos } => {
+                self.paste_event(update_state, map, event, (pos.x as i32, pos.y as i32))?;
+            }
+
+            EventAction::Duplicate(id) => {
+                let event = map
+                    .events
+                    .get(id)
+                    .ok_or_else(|| eyre!("Event {id} doesn't exist"))?
+                    .clone();
+                let pos = Self::free_tile_near(map, event.x, event.y)
+                    .ok_or_else(|| eyre!("There is no room on the map for another event"))?;
+                let id = self.paste_event(update_state, map, event, pos)?;
+                self.view.selected_event_id = Some(id);
+                self.view.cursor_pos = egui::pos2(pos.0 as f32, pos.1 as f32);
+            }
+
+            EventAction::CompactIds => self.compact_event_ids(update_state, map),
+        }
+
+        Ok(())
+    }
+
+    /// Adds a copy of `event` (with a new ID) to the map at `pos`, returning its ID.
+    fn paste_event(
+        &mut self,
+        update_state: &luminol_core::UpdateState<'_>,
+        map: &mut rpg::Map,
+        mut event: rpg::Event,
+        pos: (i32, i32),
+    ) -> color_eyre::Result<usize> {
+        if map.events.iter().any(|(_, e)| e.x == pos.0 && e.y == pos.1) {
+            return Err(eyre!("There is already an event on this tile"));
+        }
+        let id = Self::new_event_id(map)
+            .ok_or_else(|| eyre!("This map has no more event IDs available"))?;
+
+        event.id = id;
+        event.x = pos.0;
+        event.y = pos.1;
+        event.extra_data = Default::default();
+        event.extra_data.graphic_modified.set(true);
+        map.events.insert(id, event);
+
+        self.push_to_history(update_state, map, super::HistoryEntry::EventCreated(id));
+        Ok(id)
+    }
+
+    /// Finds the closest tile to (`x`, `y`) that doesn't have an event on it, looking in
+    /// growing squares around it.
+    fn free_tile_near(map: &rpg::Map, x: i32, y: i32) -> Option<(i32, i32)> {
+        let (width, height) = (map.data.xsize() as i32, map.data.ysize() as i32);
+        let is_free = |(x, y): (i32, i32)| {
+            (0..width).contains(&x)
+                && (0..height).contains(&y)
+                && !map.events.iter().any(|(_, e)| e.x == x && e.y == y)
+        };
+
+        (1..width.max(height)).find_map(|distance| {
+            (-distance..=distance)
+                .flat_map(|dy| (-distance..=distance).map(move |dx| (dx, dy)))
+                .filter(|(dx, dy)| dx.abs() == distance || dy.abs() == distance)
+                .map(|(dx, dy)| (x + dx, y + dy))
+                .find(|&tile| is_free(tile))
+        })
+    }
+
+    /// Renumbers the events on the map so that their IDs go from 1 without gaps, keeping their
+    /// order. Commands of events on this map that refer to other events are updated too.
+    fn compact_event_ids(
+        &mut self,
+        update_state: &luminol_core::UpdateState<'_>,
+        map: &mut rpg::Map,
+    ) {
+        let ids: HashMap<usize, usize> = map
+            .events
+            .iter()
+            .enumerate()
+            .map(|(index, (id, _))| (id, index + 1))
+            .collect();
+        // Events are keyed by ID in the map, but the ID stored in an event can disagree with its
+        // key in hand-edited maps, so that gets fixed here too
+        if ids.iter().all(|(id, new_id)| id == new_id)
+            && map.events.iter().all(|(id, event)| event.id == id)
+        {
+            return;
+        }
+
+        let old_events = map.events.clone();
+        let mut events = luminol_data::OptionVec::new();
+        for (id, event) in old_events.iter() {
+            let mut event = event.clone();
+            event.id = ids[&id];
+            remap_event_references(&mut event, &ids);
+            events.insert(event.id, event);
+        }
+        map.events = events;
+
+        self.view.selected_event_id = self
+            .view
+            .selected_event_id
+            .and_then(|id| ids.get(&id).copied());
+        self.reload_events(map);
+
+        self.push_to_history(
+            update_state,
+            map,
+            super::HistoryEntry::EventIdsChanged { events: old_events },
+        );
+    }
+
+    /// Recreates the graphics of every event after their IDs have changed. Event editors are
+    /// closed, since they refer to events by ID.
+    pub(super) fn reload_events(&mut self, map: &rpg::Map) {
+        self.view.map.events.clear();
+        for (_, event) in map.events.iter() {
+            event.extra_data.graphic_modified.set(true);
+        }
+        self.event_windows.clean_windows(|_| false);
+        self.event_drag_info = None;
+    }
+}
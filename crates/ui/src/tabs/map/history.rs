@@ -21,3 +21,74 @@
 // it with Steamworks API by Valve Corporation, containing parts covered by
 // terms of the Steamworks API by Valve Corporation, the licensors of this
 // Program grant you additional permission to convey the resulting work.
+
+use super::HistoryEntry;
+
+impl super::Tab {
+    /// Undoes or redoes a history entry, returning the entry that reverses it.
+    pub(super) fn apply_history_entry(
+        &mut self,
+        update_state: &luminol_core::UpdateState<'_>,
+        map: &mut luminol_data::rpg::Map,
+        entry: HistoryEntry,
+    ) -> HistoryEntry {
+        match entry {
+            HistoryEntry::Tiles { layer, mut delta } => {
+                for d in delta.iter_mut() {
+                    let position = (d.0, d.1, layer);
+                    let new_id = d.2;
+                    *d = (d.0, d.1, map.data[position]);
+                    map.data[position] = new_id;
+                    self.view
+                        .map
+                        .set_tile(&update_state.graphics.render_state, new_id, position);
+                }
+                HistoryEntry::Tiles { layer, delta }
+            }
+
+            HistoryEntry::EventMoved { id, x, y } => {
+                let event = map.events.get_mut(id).unwrap();
+                let new_entry = HistoryEntry::EventMoved {
+                    id,
+                    x: event.x,
+                    y: event.y,
+                };
+                event.x = x;
+                event.y = y;
+                new_entry
+            }
+
+            HistoryEntry::EventCreated(id) => {
+                let event = map.events.remove(id);
+                let sprite = self.view.map.events.try_remove(id).ok();
+                HistoryEntry::EventDeleted { event, sprite }
+            }
+
+            HistoryEntry::EventDeleted { event, sprite } => {
+                let id = event.id;
+                map.events.insert(id, event);
+                if let Some(sprite) = sprite {
+                    self.view.map.events.insert(id, sprite);
+                }
+                HistoryEntry::EventCreated(id)
+            }
+
+            // The entries are reversed in the opposite order they were made in, and the reversed
+            // entries are stored in that order so that reversing them again also works
+            HistoryEntry::Multiple(entries) => HistoryEntry::Multiple(
+                entries
+                    .into_iter()
+                    .rev()
+                    .map(|entry| self.apply_history_entry(update_state, map, entry))
+                    .collect(),
+            ),
+
+            HistoryEntry::EventIdsChanged { events } => {
+                let events = std::mem::replace(&mut map.events, events);
+                self.view.selected_event_id = None;
+                self.reload_events(map);
+                HistoryEntry::EventIdsChanged { events }
+            }
+        }
+    }
+}
@@ -24,6 +24,7 @@
 
 #![allow(unused_imports)]
 use crate::components::{MapView, SelectedLayer, Tilepicker};
+use color_eyre::eyre::ContextCompat;
 use egui::Pos2;
 use std::{cell::RefMut, collections::HashMap, collections::VecDeque};
 
@@ -46,7 +47,10 @@ use crate::windows::event_edit;
 use itertools::Itertools;
 
 mod brush;
+mod events;
 mod history;
+mod selection;
+mod terrain;
 mod util;
 
 pub struct Tab {
@@ -93,12 +97,27 @@ pub struct Tab {
     /// Seed for the PRNG used for the brush when brush density is less than 1
     brush_seed: [u8; 16],
 
+    /// The tile that was right clicked on to open the event layer's context menu
+    context_menu_tile: Option<egui::Pos2>,
+
+    /// The rectangle of tiles selected with the select tool, in tile coordinates
+    selection: Option<egui::Rect>,
+    /// When dragging out a selection, this is set to the tile the drag started on
+    selection_start: Option<egui::Pos2>,
+    /// Which tile layers the select tool copies and cuts
+    select_layers: Vec<bool>,
+    /// Whether the select tool copies and cuts the events in the selection
+    select_events: bool,
+    /// A copied region that is waiting to be placed on the map
+    floating_paste: Option<selection::FloatingPaste>,
+
+    /// Index of the terrain in the project's terrains that the terrain brush paints
+    selected_terrain: Option<usize>,
+
     /// Asynchronous task used to save the map as an image file
     save_as_image_promise: Option<poll_promise::Promise<color_eyre::Result<()>>>,
 }
 
-// TODO: If we add support for changing event IDs, these need to be added as history entries
-// in order to not corrupt the EventMoved and EventCreated entries.
 enum HistoryEntry {
     /// Contains the (x, y, tile_id) delta for a changed map layer.
     Tiles {
@@ -114,6 +133,14 @@ enum HistoryEntry {
         event: luminol_data::rpg::Event,
         sprite: Option<luminol_graphics::Event>,
     },
+    /// Contains the entries of an action that changed several things at once, like pasting a
+    /// region of a map, so that they are undone together.
+    Multiple(Vec<HistoryEntry>),
+    /// Contains every event from before their IDs were changed. The entries before this one refer
+    /// to events by their old IDs, so restoring all of them keeps those entries valid.
+    EventIdsChanged {
+        events: luminol_data::OptionVec<luminol_data::rpg::Event>,
+    },
 }
 
 impl Tab {
@@ -125,15 +152,20 @@ impl Tab {
         // *sigh*
         // borrow checker.
         let view = MapView::new(update_state, id)?;
-        let tilepicker = Tilepicker::new(update_state, id);
+        let tilepicker = Tilepicker::new(update_state, id)?;
 
         let map = update_state.data.get_or_load_map(
             id,
             update_state.filesystem,
             update_state.project_config.as_ref().unwrap(),
-        );
+        )?;
         let tilesets = update_state.data.tilesets();
-        let tileset = &tilesets.data[map.tileset_id];
+        let tileset = tilesets.data.get(map.tileset_id).wrap_err_with(|| {
+            format!(
+                "Map {id:0>3} uses tileset {}, which doesn't exist",
+                map.tileset_id
+            )
+        })?;
 
         let mut passages = luminol_data::Table2::new(map.data.xsize(), map.data.ysize());
         luminol_graphics::Collision::calculate_passages(
@@ -185,11 +217,110 @@ impl Tab {
             brush_density: 1.,
             brush_seed,
 
+            context_menu_tile: None,
+
+            selection: None,
+            selection_start: None,
+            select_layers: vec![true; map.data.zsize()],
+            select_events: true,
+            floating_paste: None,
+
+            selected_terrain: None,
+
             save_as_image_promise: None,
         })
     }
 }
 
+impl Tab {
+    /// Rebuilds the map and tilepicker graphics if the map was resized or if the tileset atlas
+    /// this map uses has been reloaded since they were created, e.g. because the map's properties
+    /// or the tileset were edited.
+    fn refresh_map_graphics(
+        &mut self,
+        update_state: &luminol_core::UpdateState<'_>,
+    ) -> color_eyre::Result<()> {
+        let map = update_state.data.get_map(self.id);
+        let resized =
+            map.data.xsize() != self.passages.xsize() || map.data.ysize() != self.passages.ysize();
+        let atlas_changed = update_state
+            .graphics
+            .atlas_loader
+            .get_atlas(map.tileset_id)
+            .map_or(true, |atlas| {
+                !std::sync::Arc::ptr_eq(atlas.texture(), self.view.map.atlas.texture())
+            });
+        if !resized && !atlas_changed {
+            return Ok(());
+        }
+
+        let tilesets = update_state.data.tilesets();
+        let Some(tileset) = tilesets.data.get(map.tileset_id) else {
+            // The map view reports the missing tileset itself
+            return Ok(());
+        };
+
+        if resized {
+            // Everything that refers to tile positions is invalidated by resizing the map
+            let size = map.data.xsize() * map.data.ysize();
+            self.layer_cache = vec![0; size];
+            self.dfs_cache = vec![false; size];
+            self.brush_layer_cache = vec![0; size];
+            self.tilemap_undo_cache = vec![0; size];
+            self.history.clear();
+            self.redo_history.clear();
+            self.drawing_shape = false;
+            self.drawing_shape_pos = None;
+            self.event_drag_info = None;
+            self.context_menu_tile = None;
+            self.view.selected_event_id = None;
+            self.selection = None;
+            self.selection_start = None;
+            self.select_layers.resize(map.data.zsize(), true);
+            self.floating_paste = None;
+
+            self.passages = luminol_data::Table2::new(map.data.xsize(), map.data.ysize());
+            luminol_graphics::Collision::calculate_passages(
+                &tileset.passages,
+                &tileset.priorities,
+                &map.data,
+                Some(&map.events),
+                (0..map.data.zsize()).rev(),
+                |x, y, passage| self.passages[(x, y)] = passage,
+            );
+        }
+
+        let mut graphics_map = luminol_graphics::Map::new(
+            &update_state.graphics,
+            update_state.filesystem,
+            &map,
+            tileset,
+            &self.passages,
+        )?;
+        graphics_map.fog_enabled = self.view.map.fog_enabled;
+        graphics_map.pano_enabled = self.view.map.pano_enabled;
+        graphics_map.coll_enabled = self.view.map.coll_enabled;
+        graphics_map.grid_enabled = self.view.map.grid_enabled;
+        graphics_map.event_enabled = self.view.map.event_enabled;
+        graphics_map
+            .tiles
+            .enabled_layers
+            .clone_from(&self.view.map.tiles.enabled_layers);
+        self.view.map = graphics_map;
+
+        if atlas_changed {
+            self.tilepicker.view = luminol_graphics::Tilepicker::new(
+                &update_state.graphics,
+                tileset,
+                update_state.filesystem,
+                false,
+            );
+        }
+
+        Ok(())
+    }
+}
+
 impl luminol_core::Tab for Tab {
     fn name(&self, update_state: &luminol_core::UpdateState<'_>) -> String {
         let map_infos = update_state.data.map_infos();
@@ -201,7 +332,10 @@ impl luminol_core::Tab for Tab {
                 ""
             },
             self.id,
-            map_infos.data[&self.id].name,
+            map_infos
+                .data
+                .get(&self.id)
+                .map_or("", |info| info.name.as_str()),
         )
     }
 
@@ -221,6 +355,15 @@ impl luminol_core::Tab for Tab {
     ) {
         self.brush_density = update_state.toolbar.brush_density;
 
+        if let Err(e) = self.refresh_map_graphics(update_state) {
+            luminol_core::error!(
+                update_state.toasts,
+                e.wrap_err("Error refreshing map after it was modified")
+            );
+        }
+
+        let mut clipboard_action = None;
+
         // Display the toolbar.
         // FIXME: find a proper place for this toolbar! it looks very out of place right now.
         egui::TopBottomPanel::top(format!("map_{}_toolbar", self.id)).show_inside(ui, |ui| {
@@ -329,6 +472,58 @@ impl luminol_core::Tab for Tab {
 
                         ui.separator();
 
+                        if update_state.toolbar.pencil == luminol_core::Pencil::Select {
+                            ui.menu_button("Copy ⏷", |ui| {
+                                ui.style_mut().wrap_mode = Some(egui::TextWrapMode::Extend);
+
+                                for (index, copy) in self.select_layers.iter_mut().enumerate() {
+                                    ui.checkbox(copy, format!("Layer {}", index + 1));
+                                }
+                                ui.checkbox(&mut self.select_events, "Events");
+                            })
+                            .response
+                            .on_hover_text("Choose what the select tool copies and cuts");
+
+                            let has_selection = self.selection.is_some();
+                            if ui
+                                .add_enabled(has_selection, egui::Button::new("Copy"))
+                                .clicked()
+                            {
+                                clipboard_action = Some(selection::ClipboardAction::Copy);
+                            }
+                            if ui
+                                .add_enabled(has_selection, egui::Button::new("Cut"))
+                                .clicked()
+                            {
+                                clipboard_action = Some(selection::ClipboardAction::Cut);
+                            }
+                            if ui.button("Paste").clicked() {
+                                clipboard_action = selection::MapRegion::from_memory(ui.ctx())
+                                    .map(selection::ClipboardAction::Paste);
+                            }
+
+                            ui.separator();
+                        }
+
+                        if update_state.toolbar.pencil == luminol_core::Pencil::Terrain {
+                            self.terrain_menu(ui, update_state);
+
+                            ui.separator();
+                        }
+
+                        if ui.button("Map properties").clicked() {
+                            match crate::windows::map_properties::Window::new(update_state, self.id)
+                            {
+                                Ok(window) => update_state.edit_windows.add_window(window),
+                                Err(e) => luminol_core::error!(
+                                    update_state.toasts,
+                                    e.wrap_err("Error opening the map properties")
+                                ),
+                            }
+                        }
+
+                        ui.separator();
+
                         if ui.button("Save map preview").clicked()
                             && self.save_as_image_promise.is_none()
                         {
@@ -379,7 +574,15 @@ impl luminol_core::Tab for Tab {
                 // Get the map.
                 let mut map = update_state.data.get_map(self.id);
                 let tilesets = update_state.data.tilesets();
-                let tileset = &tilesets.data[map.tileset_id];
+                let Some(tileset) = tilesets.data.get(map.tileset_id) else {
+                    ui.centered_and_justified(|ui| {
+                        ui.label(format!(
+                            "This map uses tileset {}, which doesn't exist",
+                            map.tileset_id
+                        ));
+                    });
+                    return;
+                };
 
                 // Save the state of the selected layer into the cache
                 if let SelectedLayer::Tiles(tile_layer) = self.view.selected_layer {
@@ -387,6 +590,13 @@ impl luminol_core::Tab for Tab {
                         .copy_from_slice(map.data.layer_as_slice(tile_layer));
                 }
 
+                let is_selecting = update_state.toolbar.pencil == luminol_core::Pencil::Select
+                    && matches!(self.view.selected_layer, SelectedLayer::Tiles(_));
+                if !is_selecting {
+                    self.cancel_paste(update_state, &map);
+                }
+                self.view.selection = is_selecting.then(|| self.selection_outline());
+
                 let response = self.view.ui(
                     ui,
                     update_state,
@@ -465,7 +675,33 @@ impl luminol_core::Tab for Tab {
                     }
                 }
 
-                if let SelectedLayer::Tiles(tile_layer) = self.view.selected_layer {
+                if is_selecting {
+                    if is_focused && clipboard_action.is_none() {
+                        clipboard_action = selection::ClipboardAction::from_input(ui.ctx());
+                    }
+                    match self.handle_select_tool(
+                        ui,
+                        update_state,
+                        &mut map,
+                        &response,
+                        is_focused,
+                        clipboard_action.take(),
+                    ) {
+                        Ok(0) => {}
+                        Ok(skipped) => luminol_core::warn!(
+                            update_state.toasts,
+                            format!(
+                                "{skipped} events weren't pasted because their tiles already \
+                                have events or are outside the map, or the map has no more \
+                                event IDs"
+                            )
+                        ),
+                        Err(e) => luminol_core::error!(
+                            update_state.toasts,
+                            e.wrap_err("Error copying the selection")
+                        ),
+                    }
+                } else if let SelectedLayer::Tiles(tile_layer) = self.view.selected_layer {
                     // Tile drawing
                     if response.is_pointer_button_down_on()
                         && ui.input(|i| {
@@ -480,11 +716,17 @@ impl luminol_core::Tab for Tab {
                             self.tilemap_undo_cache.copy_from_slice(&self.layer_cache);
                         }
 
+                        let terrain = update_state
+                            .project_config
+                            .as_ref()
+                            .and_then(|config| config.terrains.terrains.get(self.selected_terrain?))
+                            .filter(|terrain| terrain.tileset_id == map.tileset_id);
                         self.handle_brush(
                             map_x as usize,
                             map_y as usize,
                             tile_layer,
                             update_state.toolbar.pencil,
+                            terrain,
                             &mut map,
                         );
                     }
@@ -582,6 +824,105 @@ impl luminol_core::Tab for Tab {
                     }
                 }
 
+                // Right click on the event layer to move the player's starting position
+                if matches!(self.view.selected_layer, SelectedLayer::Events) {
+                    if response.secondary_clicked() {
+                        self.context_menu_tile = self.view.hover_tile;
+                    }
+
+                    let mut event_action = None;
+
+                    if let Some(tile) = self.context_menu_tile {
+                        response.context_menu(|ui| {
+                            let tile_event_id = map
+                                .events
+                                .iter()
+                                .find(|(_, e)| e.x == tile.x as i32 && e.y == tile.y as i32)
+                                .map(|(id, _)| id);
+
+                            if ui.button("Set Player Starting Position").clicked() {
+                                let mut system = update_state.data.system();
+                                system.start_map_id = self.id - 1;
+                                system.start_x = tile.x as i32;
+                                system.start_y = tile.y as i32;
+                                system.modified = true;
+                                update_state.modified.set(true);
+                                ui.close_menu();
+                            }
+
+                            ui.separator();
+
+                            if ui
+                                .add_enabled(
+                                    tile_event_id.is_some(),
+                                    egui::Button::new("Copy Event"),
+                                )
+                                .clicked()
+                            {
+                                event_action = tile_event_id.map(events::EventAction::Copy);
+                                ui.close_menu();
+                            }
+
+                            if ui
+                                .add_enabled(
+                                    tile_event_id.is_some(),
+                                    egui::Button::new("Duplicate Event"),
+                                )
+                                .clicked()
+                            {
+                                event_action = tile_event_id.map(events::EventAction::Duplicate);
+                                ui.close_menu();
+                            }
+
+                            let copied_event = events::event_from_memory(ui.ctx());
+                            if ui
+                                .add_enabled(
+                                    tile_event_id.is_none() && copied_event.is_some(),
+                                    egui::Button::new("Paste Event"),
+                                )
+                                .clicked()
+                            {
+                                event_action = copied_event
+                                    .map(|event| events::EventAction::Paste { event, pos: tile });
+                                ui.close_menu();
+                            }
+
+                            ui.separator();
+
+                            if ui
+                                .button("Compact Event IDs")
+                                .on_hover_text(
+                                    "Renumbers the events on this map so their IDs start at 1 \
+                                    with no gaps, updating the commands that refer to them",
+                                )
+                                .clicked()
+                            {
+                                event_action = Some(events::EventAction::CompactIds);
+                                ui.close_menu();
+                            }
+                        });
+                    }
+
+                    if is_focused && event_action.is_none() {
+                        event_action = events::EventAction::from_input(
+                            ui.ctx(),
+                            self.view.selected_event_id,
+                            self.view.cursor_pos,
+                        );
+                    }
+
+                    if let Some(action) = event_action {
+                        if let Err(e) =
+                            self.handle_event_action(ui.ctx(), update_state, &mut map, action)
+                        {
+                            luminol_core::error!(
+                                update_state.toasts,
+                                e.wrap_err("Error changing the map's events")
+                            );
+                        }
+                    }
+                }
+
                 // Handle undo/redo keypresses
                 let is_dragged_by_primary = response.dragged_by(egui::PointerButton::Primary);
                 let is_undo_pressed = is_focused
@@ -595,55 +936,12 @@ impl luminol_core::Tab for Tab {
                             && (!i.modifiers.shift || i.key_pressed(egui::Key::Z))
                     });
                 if !is_dragged_by_primary && (is_undo_pressed || is_redo_pressed) {
-                    let new_entry = match if is_undo_pressed {
+                    let new_entry = if is_undo_pressed {
                         self.history.pop_back()
                     } else {
                         self.redo_history.pop()
-                    } {
-                        None => None,
-
-                        Some(HistoryEntry::Tiles { layer, mut delta }) => {
-                            for d in delta.iter_mut() {
-                                let position = (d.0, d.1, layer);
-                                let new_id = d.2;
-                                *d = (d.0, d.1, map.data[position]);
-                                map.data[position] = new_id;
-                                self.view.map.set_tile(
-                                    &update_state.graphics.render_state,
-                                    new_id,
-                                    position,
-                                );
-                            }
-                            Some(HistoryEntry::Tiles { layer, delta })
-                        }
-
-                        Some(HistoryEntry::EventMoved { id, x, y }) => {
-                            let event = map.events.get_mut(id).unwrap();
-                            let new_entry = Some(HistoryEntry::EventMoved {
-                                id,
-                                x: event.x,
-                                y: event.y,
-                            });
-                            event.x = x;
-                            event.y = y;
-                            new_entry
-                        }
-
-                        Some(HistoryEntry::EventCreated(id)) => {
-                            let event = map.events.remove(id);
-                            let sprite = self.view.map.events.try_remove(id).ok();
-                            Some(HistoryEntry::EventDeleted { event, sprite })
-                        }
-
-                        Some(HistoryEntry::EventDeleted { event, sprite }) => {
-                            let id = event.id;
-                            map.events.insert(id, event);
-                            if let Some(sprite) = sprite {
-                                self.view.map.events.insert(id, sprite);
-                            }
-                            Some(HistoryEntry::EventCreated(id))
-                        }
-                    };
+                    }
+                    .map(|entry| self.apply_history_entry(update_state, &mut map, entry));
 
                     if let Some(new_entry) = new_entry {
                         update_state.modified.set(true);
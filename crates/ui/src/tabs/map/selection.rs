@@ -0,0 +1,457 @@
+// Copyright (C) 2024 Melody Madeline Lyons
+//
+// This file is part of Luminol.
+//
+// Luminol is free software: you can redistribute it and/or modify
+// it under the terms of the GNU General Public License as published by
+// the Free Software Foundation, either version 3 of the License, or
+// (at your option) any later version.
+//
+// Luminol is distributed in the hope that it will be useful,
+// but WITHOUT ANY WARRANTY; without even the implied warranty of
+// MERCHANTABILITY or FITNESS FOR A PARTICULAR PURPOSE.  See the
+// GNU General Public License for more details.
+//
+// You should have received a copy of the GNU General Public License
+// along with Luminol.  If not, see <http://www.gnu.org/licenses/>.
+//
+//     Additional permission under GNU GPL version 3 section 7
+//
+// If you modify this Program, or any covered work, by linking or combining
+// it with Steamworks API by Valve Corporation, containing parts covered by
+// terms of the Steamworks API by Valve Corporation, the licensors of this
+// Program grant you additional permission to convey the resulting work.
+
+use itertools::Itertools;
+
+/// Where the last copied map region is kept in egui's memory, so that it can be pasted even if
+/// the OS clipboard can't be read (like in browsers that don't allow it).
+const CLIPBOARD_ID: &str = "luminol_map_clipboard";
+
+/// A rectangular region of a map, copied with the select tool.
+///
+/// This is put on the clipboard as RON so that it can be pasted into any map, including in
+/// another project or instance of Luminol.
+#[derive(Debug, Clone, serde::Serialize, serde::Deserialize)]
+pub(super) struct MapRegion {
+    width: usize,
+    height: usize,
+    /// The tiles of every layer in row-major order, or `None` for layers that weren't copied.
+    layers: Vec<Option<Vec<i16>>>,
+    /// The events inside the region, with positions relative to its top-left corner.
+    events: Vec<luminol_data::rpg::Event>,
+}
+
+/// A copied region that is being moved around the map before it is pasted.
+/// It is only drawn on the map's graphics until it is placed, the map data isn't changed.
+pub(super) struct FloatingPaste {
+    region: MapRegion,
+    /// The tile the top-left corner of the region is currently drawn at.
+    pos: Option<(usize, usize)>,
+}
+
+pub(super) enum ClipboardAction {
+    Copy,
+    Cut,
+    Paste(MapRegion),
+}
+
+impl MapRegion {
+    /// Copies the tiles of `layers` (and the events, if `events` is true) in `rect`, which is
+    /// in tile coordinates.
+    fn copy(map: &luminol_data::rpg::Map, rect: egui::Rect, layers: &[bool], events: bool) -> Self {
+        let (x0, y0) = (rect.min.x as usize, rect.min.y as usize);
+        let width = rect.width() as usize;
+        let height = rect.height() as usize;
+
+        let layers = (0..map.data.zsize())
+            .map(|layer| {
+                layers.get(layer).copied().unwrap_or(true).then(|| {
+                    (0..height)
+                        .cartesian_product(0..width)
+                        .map(|(y, x)| map.data[(x0 + x, y0 + y, layer)])
+                        .collect()
+                })
+            })
+            .collect();
+
+        let events = if events {
+            map.events
+                .iter()
+                .filter(|(_, event)| {
+                    rect.contains(egui::pos2(event.x as f32 + 0.5, event.y as f32 + 0.5))
+                })
+                .map(|(_, event)| {
+                    let mut event = event.clone();
+                    event.x -= x0 as i32;
+                    event.y -= y0 as i32;
+                    event.extra_data = Default::default();
+                    event
+                })
+                .collect()
+        } else {
+            Vec::new()
+        };
+
+        Self {
+            width,
+            height,
+            layers,
+            events,
+        }
+    }
+
+    fn to_text(&self) -> color_eyre::Result<String> {
+        Ok(ron::ser::to_string_pretty(
+            self,
+            ron::ser::PrettyConfig::new().struct_names(true),
+        )?)
+    }
+
+    /// Reads a region from the clipboard, returning `None` if it holds something else.
+    fn from_text(text: &str) -> Option<Self> {
+        let region: Self = ron::from_str(text).ok()?;
+        region
+            .layers
+            .iter()
+            .flatten()
+            .all(|tiles| tiles.len() == region.width * region.height)
+            .then_some(region)
+    }
+
+    /// The last region copied in this instance of Luminol.
+    pub(super) fn from_memory(ctx: &egui::Context) -> Option<Self> {
+        ctx.data(|d| d.get_temp::<String>(egui::Id::new(CLIPBOARD_ID)))
+            .and_then(|text| Self::from_text(&text))
+    }
+}
+
+impl ClipboardAction {
+    /// Returns the clipboard action the keyboard shortcuts pressed this frame ask for, if any.
+    pub(super) fn from_input(ctx: &egui::Context) -> Option<Self> {
+        // Don't take copy and paste away from text fields
+        if ctx.wants_keyboard_input() {
+            return None;
+        }
+
+        let (action, paste_pressed) = ctx.input(|i| {
+            let action = i.events.iter().find_map(|event| match event {
+                egui::Event::Copy => Some(Self::Copy),
+                egui::Event::Cut => Some(Self::Cut),
+                egui::Event::Paste(text) => MapRegion::from_text(text).map(Self::Paste),
+                _ => None,
+            });
+            let paste_pressed = !i
+                .events
+                .iter()
+                .any(|event| matches!(event, egui::Event::Paste(_)))
+                && i.modifiers.command
+                && i.key_pressed(egui::Key::V);
+            (action, paste_pressed)
+        });
+
+        // Fall back to the region we copied ourselves if the OS clipboard couldn't be read
+        action.or_else(|| {
+            paste_pressed
+                .then(|| MapRegion::from_memory(ctx))
+                .flatten()
+                .map(Self::Paste)
+        })
+    }
+}
+
+impl FloatingPaste {
+    /// The tiles the region covers when its top-left corner is at `pos`.
+    fn rect_at(&self, pos: egui::Pos2) -> egui::Rect {
+        egui::Rect::from_min_size(
+            pos,
+            egui::vec2(self.region.width as f32, self.region.height as f32),
+        )
+    }
+}
+
+impl super::Tab {
+    /// The outline the map view should draw while the select tool is in use.
+    pub(super) fn selection_outline(&self) -> egui::Rect {
+        if let Some(paste) = &self.floating_paste {
+            paste.rect_at(self.view.cursor_pos)
+        } else if let Some(selection) = self.selection {
+            selection
+        } else {
+            egui::Rect::from_min_size(self.view.cursor_pos, egui::Vec2::splat(1.))
+        }
+    }
+
+    /// Handles the select tool for this frame: dragging out a selection, clipboard actions and
+    /// placing pasted regions.
+    ///
+    /// Returns the number of pasted events that had to be left out, since their tile already had
+    /// an event or the map ran out of event IDs.
+    pub(super) fn handle_select_tool(
+        &mut self,
+        ui: &egui::Ui,
+        update_state: &luminol_core::UpdateState<'_>,
+        map: &mut luminol_data::rpg::Map,
+        response: &egui::Response,
+        is_focused: bool,
+        action: Option<ClipboardAction>,
+    ) -> color_eyre::Result<usize> {
+        match action {
+            Some(ClipboardAction::Copy) => self.copy_selection(ui.ctx(), map)?,
+            Some(ClipboardAction::Cut) => {
+                self.copy_selection(ui.ctx(), map)?;
+                self.clear_selection(update_state, map);
+            }
+            Some(ClipboardAction::Paste(region)) => {
+                self.cancel_paste(update_state, map);
+                self.selection = None;
+                self.floating_paste = Some(FloatingPaste { region, pos: None });
+            }
+            None => {}
+        }
+
+        let cancel_pressed = is_focused && ui.input(|i| i.key_pressed(egui::Key::Escape));
+
+        if let Some(mut paste) = self.floating_paste.take() {
+            if cancel_pressed || response.secondary_clicked() {
+                self.floating_paste = Some(paste);
+                self.cancel_paste(update_state, map);
+                return Ok(0);
+            }
+
+            let pos = (
+                self.view.cursor_pos.x as usize,
+                self.view.cursor_pos.y as usize,
+            );
+            if paste.pos != Some(pos) {
+                if let Some(old_pos) = paste.pos {
+                    self.preview_region(update_state, map, &paste.region, old_pos, false);
+                }
+                self.preview_region(update_state, map, &paste.region, pos, true);
+                paste.pos = Some(pos);
+            }
+
+            if response.clicked_by(egui::PointerButton::Primary)
+                && ui.input(|i| !i.modifiers.command)
+            {
+                let selection = paste.rect_at(self.view.cursor_pos);
+                let skipped = self.place_region(update_state, map, paste.region, pos);
+                self.selection = Some(selection.intersect(Self::map_rect(map)));
+                return Ok(skipped);
+            }
+
+            self.floating_paste = Some(paste);
+            return Ok(0);
+        }
+
+        if cancel_pressed {
+            self.selection = None;
+        }
+
+        if response.is_pointer_button_down_on()
+            && ui.input(|i| {
+                i.pointer.button_down(egui::PointerButton::Primary) && !i.modifiers.command
+            })
+        {
+            let start = *self.selection_start.get_or_insert(self.view.cursor_pos);
+            let mut selection = egui::Rect::from_two_pos(start, self.view.cursor_pos);
+            selection.max += egui::Vec2::splat(1.);
+            self.selection = Some(selection);
+        } else {
+            self.selection_start = None;
+        }
+
+        Ok(0)
+    }
+
+    /// Removes the floating paste, if there is one, and restores the tiles it was drawn over.
+    pub(super) fn cancel_paste(
+        &mut self,
+        update_state: &luminol_core::UpdateState<'_>,
+        map: &luminol_data::rpg::Map,
+    ) {
+        if let Some(FloatingPaste {
+            region,
+            pos: Some(pos),
+        }) = self.floating_paste.take()
+        {
+            self.preview_region(update_state, map, &region, pos, false);
+        }
+    }
+
+    fn map_rect(map: &luminol_data::rpg::Map) -> egui::Rect {
+        egui::Rect::from_min_size(
+            egui::Pos2::ZERO,
+            egui::vec2(map.data.xsize() as f32, map.data.ysize() as f32),
+        )
+    }
+
+    fn copy_selection(
+        &self,
+        ctx: &egui::Context,
+        map: &luminol_data::rpg::Map,
+    ) -> color_eyre::Result<()> {
+        let Some(selection) = self.selection else {
+            return Ok(());
+        };
+
+        let region = MapRegion::copy(map, selection, &self.select_layers, self.select_events);
+        let text = region.to_text()?;
+        ctx.data_mut(|d| d.insert_temp(egui::Id::new(CLIPBOARD_ID), text.clone()));
+        ctx.copy_text(text);
+        Ok(())
+    }
+
+    /// Erases the tiles of the copied layers in the selection, and the events inside it if events
+    /// are being copied.
+    fn clear_selection(
+        &mut self,
+        update_state: &luminol_core::UpdateState<'_>,
+        map: &mut luminol_data::rpg::Map,
+    ) {
+        let Some(selection) = self.selection else {
+            return;
+        };
+        let (x0, y0) = (selection.min.x as usize, selection.min.y as usize);
+        let (x1, y1) = (selection.max.x as usize, selection.max.y as usize);
+
+        let mut entries = Vec::new();
+        for layer in 0..map.data.zsize() {
+            if !self.select_layers.get(layer).copied().unwrap_or(true) {
+                continue;
+            }
+
+            let mut delta = Vec::new();
+            for (y, x) in (y0..y1).cartesian_product(x0..x1) {
+                let position = (x, y, layer);
+                if map.data[position] != 0 {
+                    delta.push((x, y, map.data[position]));
+                    map.data[position] = 0;
+                    self.view
+                        .map
+                        .set_tile(&update_state.graphics.render_state, 0, position);
+                }
+            }
+            if !delta.is_empty() {
+                entries.push(super::HistoryEntry::Tiles { layer, delta });
+            }
+        }
+
+        if self.select_events {
+            let ids = map
+                .events
+                .iter()
+                .filter(|(_, event)| {
+                    selection.contains(egui::pos2(event.x as f32 + 0.5, event.y as f32 + 0.5))
+                })
+                .map(|(id, _)| id)
+                .collect_vec();
+            for id in ids {
+                let event = map.events.remove(id);
+                let sprite = self.view.map.events.try_remove(id).ok();
+                entries.push(super::HistoryEntry::EventDeleted { event, sprite });
+            }
+        }
+
+        if !entries.is_empty() {
+            self.push_to_history(update_state, map, super::HistoryEntry::Multiple(entries));
+        }
+    }
+
+    /// Draws the tiles of `region` on the map's graphics with its top-left corner at `pos`, or
+    /// if `show` is false, draws the map's own tiles there again.
+    fn preview_region(
+        &mut self,
+        update_state: &luminol_core::UpdateState<'_>,
+        map: &luminol_data::rpg::Map,
+        region: &MapRegion,
+        pos: (usize, usize),
+        show: bool,
+    ) {
+        for (layer, tiles) in region.layers.iter().enumerate().take(map.data.zsize()) {
+            let Some(tiles) = tiles else {
+                continue;
+            };
+            for (y, x) in (0..region.height).cartesian_product(0..region.width) {
+                let position = (pos.0 + x, pos.1 + y, layer);
+                if position.0 >= map.data.xsize() || position.1 >= map.data.ysize() {
+                    continue;
+                }
+                let tile_id = if show {
+                    tiles[x + y * region.width]
+                } else {
+                    map.data[position]
+                };
+                self.view
+                    .map
+                    .set_tile(&update_state.graphics.render_state, tile_id, position);
+            }
+        }
+    }
+
+    /// Writes `region` into the map with its top-left corner at `pos`, as a single history entry.
+    /// Returns the number of events that couldn't be placed.
+    fn place_region(
+        &mut self,
+        update_state: &luminol_core::UpdateState<'_>,
+        map: &mut luminol_data::rpg::Map,
+        region: MapRegion,
+        pos: (usize, usize),
+    ) -> usize {
+        let mut entries = Vec::new();
+
+        for (layer, tiles) in region.layers.iter().enumerate().take(map.data.zsize()) {
+            let Some(tiles) = tiles else {
+                continue;
+            };
+
+            let mut delta = Vec::new();
+            for (y, x) in (0..region.height).cartesian_product(0..region.width) {
+                let position = (pos.0 + x, pos.1 + y, layer);
+                if position.0 >= map.data.xsize() || position.1 >= map.data.ysize() {
+                    continue;
+                }
+                let tile_id = tiles[x + y * region.width];
+                if map.data[position] != tile_id {
+                    delta.push((position.0, position.1, map.data[position]));
+                    map.data[position] = tile_id;
+                }
+                // The preview already drew this, but make sure the graphics match the data
+                self.view
+                    .map
+                    .set_tile(&update_state.graphics.render_state, tile_id, position);
+            }
+            if !delta.is_empty() {
+                entries.push(super::HistoryEntry::Tiles { layer, delta });
+            }
+        }
+
+        let mut skipped = 0;
+        for mut event in region.events {
+            event.x += pos.0 as i32;
+            event.y += pos.1 as i32;
+
+            let is_out_of_bounds =
+                event.x as usize >= map.data.xsize() || event.y as usize >= map.data.ysize();
+            let is_occupied = map
+                .events
+                .iter()
+                .any(|(_, e)| e.x == event.x && e.y == event.y);
+            let id = Self::new_event_id(map);
+            let Some(id) = id.filter(|_| !is_out_of_bounds && !is_occupied) else {
+                skipped += 1;
+                continue;
+            };
+
+            event.id = id;
+            event.extra_data.graphic_modified.set(true);
+            map.events.insert(id, event);
+            entries.push(super::HistoryEntry::EventCreated(id));
+        }
+
+        if !entries.is_empty() {
+            self.push_to_history(update_state, map, super::HistoryEntry::Multiple(entries));
+        }
+        skipped
+    }
+}
@@ -0,0 +1,204 @@
+// Copyright (C) 2024 Melody Madeline Lyons
+//
+// This file is part of Luminol.
+//
+// Luminol is free software: you can redistribute it and/or modify
+// it under the terms of the GNU General Public License as published by
+// the Free Software Foundation, either version 3 of the License, or
+// (at your option) any later version.
+//
+// Luminol is distributed in the hope that it will be useful,
+// but WITHOUT ANY WARRANTY; without even the implied warranty of
+// MERCHANTABILITY or FITNESS FOR A PARTICULAR PURPOSE.  See the
+// GNU General Public License for more details.
+//
+// You should have received a copy of the GNU General Public License
+// along with Luminol.  If not, see <http://www.gnu.org/licenses/>.
+//
+//     Additional permission under GNU GPL version 3 section 7
+//
+// If you modify this Program, or any covered work, by linking or combining
+// it with Steamworks API by Valve Corporation, containing parts covered by
+// terms of the Steamworks API by Valve Corporation, the licensors of this
+// Program grant you additional permission to convey the resulting work.
+
+use luminol_config::terrain::{Neighbors, Terrain, TerrainTiles};
+
+/// The size, in tiles, of the block of tiles a terrain is made from in the tilepicker.
+const TERRAIN_BLOCK_WIDTH: i16 = 5;
+const TERRAIN_BLOCK_HEIGHT: i16 = 3;
+
+impl super::Tab {
+    /// Paints `terrain` on the tile at (`map_x`, `map_y`) and picks the tiles of it and the
+    /// tiles around it that are part of the terrain based on their neighbors.
+    pub(super) fn paint_terrain(
+        &mut self,
+        map_x: usize,
+        map_y: usize,
+        tile_layer: usize,
+        terrain: &Terrain,
+        map: &mut luminol_data::rpg::Map,
+    ) {
+        if self.drawing_shape_pos.is_none() {
+            self.drawing_shape_pos = Some(egui::pos2(map_x as f32, map_y as f32));
+        }
+
+        let position = (map_x, map_y, tile_layer);
+        if self.is_skipped_by_brush_density(position) {
+            return;
+        }
+        map.data[position] = terrain.tiles.center;
+
+        for y in map_y.saturating_sub(1)..=(map_y + 1).min(map.data.ysize() - 1) {
+            for x in map_x.saturating_sub(1)..=(map_x + 1).min(map.data.xsize() - 1) {
+                if terrain.contains(map.data[(x, y, tile_layer)]) {
+                    let neighbors = Self::terrain_neighbors(map, terrain, (x, y, tile_layer));
+                    map.data[(x, y, tile_layer)] = terrain.tile_for(neighbors);
+                }
+            }
+        }
+    }
+
+    /// Returns which of the tiles around `position` are part of `terrain`. Like with autotiles,
+    /// out-of-bounds tiles always count as part of it.
+    fn terrain_neighbors(
+        map: &luminol_data::rpg::Map,
+        terrain: &Terrain,
+        position: (usize, usize, usize),
+    ) -> Neighbors {
+        let is_terrain = |x: isize, y: isize| {
+            let (Some(x), Some(y)) = (
+                position.0.checked_add_signed(x),
+                position.1.checked_add_signed(y),
+            ) else {
+                return true;
+            };
+            x >= map.data.xsize()
+                || y >= map.data.ysize()
+                || terrain.contains(map.data[(x, y, position.2)])
+        };
+
+        Neighbors {
+            top: is_terrain(0, -1),
+            bottom: is_terrain(0, 1),
+            left: is_terrain(-1, 0),
+            right: is_terrain(1, 0),
+            top_left: is_terrain(-1, -1),
+            top_right: is_terrain(1, -1),
+            bottom_left: is_terrain(-1, 1),
+            bottom_right: is_terrain(1, 1),
+        }
+    }
+
+    /// Reads a terrain from the tiles selected in the tilepicker, if they form a block of the
+    /// right size made of regular tiles.
+    fn terrain_tiles_from_selection(&self) -> Option<TerrainTiles> {
+        let tilepicker = &self.tilepicker;
+        let width = tilepicker.selected_tiles_right - tilepicker.selected_tiles_left + 1;
+        let height = tilepicker.selected_tiles_bottom - tilepicker.selected_tiles_top + 1;
+        // The top row of the tilepicker holds autotiles, which already have borders of their own
+        if width != TERRAIN_BLOCK_WIDTH
+            || height != TERRAIN_BLOCK_HEIGHT
+            || tilepicker.selected_tiles_top == 0
+        {
+            return None;
+        }
+
+        let block = std::array::from_fn(|row| {
+            std::array::from_fn(|column| {
+                let x = tilepicker.selected_tiles_left + column as i16;
+                let y = tilepicker.selected_tiles_top + row as i16;
+                x + (y - 1) * 8 + 384
+            })
+        });
+        Some(TerrainTiles::from_block(&block))
+    }
+
+    /// Shows the menu for choosing, creating and removing the terrains of this map's tileset.
+    pub(super) fn terrain_menu(
+        &mut self,
+        ui: &mut egui::Ui,
+        update_state: &mut luminol_core::UpdateState<'_>,
+    ) {
+        let tileset_id = update_state.data.get_map(self.id).tileset_id;
+        let Some(config) = update_state.project_config.as_mut() else {
+            return;
+        };
+        let terrains = &mut config.terrains;
+
+        let selected_name = self
+            .selected_terrain
+            .and_then(|index| terrains.terrains.get(index))
+            .filter(|terrain| terrain.tileset_id == tileset_id)
+            .map_or_else(|| "Terrain".to_string(), |terrain| terrain.name.clone());
+
+        ui.menu_button(format!("{selected_name} ⏷"), |ui| {
+            ui.style_mut().wrap_mode = Some(egui::TextWrapMode::Extend);
+
+            let mut has_terrains = false;
+            for (index, terrain) in terrains.for_tileset(tileset_id) {
+                has_terrains = true;
+                ui.selectable_value(
+                    &mut self.selected_terrain,
+                    Some(index),
+                    terrain.name.as_str(),
+                );
+            }
+            if !has_terrains {
+                ui.weak("This tileset has no terrains yet");
+            }
+
+            ui.separator();
+
+            if let Some(terrain) = self
+                .selected_terrain
+                .and_then(|index| terrains.terrains.get_mut(index))
+                .filter(|terrain| terrain.tileset_id == tileset_id)
+            {
+                ui.horizontal(|ui| {
+                    ui.label("Name");
+                    if ui.text_edit_singleline(&mut terrain.name).changed() {
+                        update_state.modified.set(true);
+                    }
+                });
+
+                if ui.button("Remove terrain").clicked() {
+                    if let Some(index) = self.selected_terrain.take() {
+                        terrains.terrains.remove(index);
+                    }
+                    update_state.modified.set(true);
+                }
+
+                ui.separator();
+            }
+
+            let tiles = self.terrain_tiles_from_selection();
+            if ui
+                .add_enabled(
+                    tiles.is_some(),
+                    egui::Button::new("New terrain from selection"),
+                )
+                .on_hover_text(
+                    "Select a block of 5x3 tiles in the tilepicker. The first three columns are \
+                    the edges and outer corners around the middle tile of the terrain, as they'd \
+                    look on a map. The top two rows of the last two columns are the inner \
+                    corners, each in the corner the terrain is missing from.",
+                )
+                .clicked()
+            {
+                if let Some(tiles) = tiles {
+                    let count = terrains.for_tileset(tileset_id).count();
+                    terrains.terrains.push(Terrain {
+                        name: format!("Terrain {}", count + 1),
+                        tileset_id,
+                        tiles,
+                    });
+                    self.selected_terrain = Some(terrains.terrains.len() - 1);
+                    update_state.modified.set(true);
+                }
+            }
+        })
+        .response
+        .on_hover_text("Choose the terrain the terrain brush paints");
+    }
+}
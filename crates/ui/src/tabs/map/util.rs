@@ -23,8 +23,8 @@
 // Program grant you additional permission to convey the resulting work.
 
 impl super::Tab {
-    pub(super) fn recompute_autotile(
-        &self,
+    /// Returns the autotile variant the tile at `position` should use given its neighbors.
+    pub(crate) fn recompute_autotile(
         map: &luminol_data::rpg::Map,
         position: (usize, usize, usize),
     ) -> i16 {
@@ -142,31 +142,38 @@ impl super::Tab {
             } as i16
     }
 
+    /// Returns true if the brush shouldn't draw on `position` because the brush density is less
+    /// than 1.
+    pub(super) fn is_skipped_by_brush_density(&self, position: (usize, usize, usize)) -> bool {
+        if self.brush_density == 1. {
+            return false;
+        }
+        if self.brush_density == 0. {
+            return true;
+        }
+
+        // Pick a pseudorandom normal f32 uniformly in the interval [0, 1)
+        let mut preimage = [0u8; 40];
+        preimage[0..16].copy_from_slice(&self.brush_seed);
+        preimage[16..24].copy_from_slice(&(position.0 as u64).to_le_bytes());
+        preimage[24..32].copy_from_slice(&(position.1 as u64).to_le_bytes());
+        preimage[32..40].copy_from_slice(&(position.2 as u64).to_le_bytes());
+        let image = (murmur3::murmur3_32(&mut std::io::Cursor::new(preimage), 1729).unwrap()
+            & 16777215) as f32
+            / 16777216f32;
+
+        // Draw on the tile only if that's less than the brush density
+        image >= self.brush_density
+    }
+
     pub(super) fn set_tile(
         &self,
         map: &mut luminol_data::rpg::Map,
         tile: crate::components::SelectedTile,
         position: (usize, usize, usize),
     ) {
-        if self.brush_density != 1. {
-            if self.brush_density == 0. {
-                return;
-            }
-
-            // Pick a pseudorandom normal f32 uniformly in the interval [0, 1)
-            let mut preimage = [0u8; 40];
-            preimage[0..16].copy_from_slice(&self.brush_seed);
-            preimage[16..24].copy_from_slice(&(position.0 as u64).to_le_bytes());
-            preimage[24..32].copy_from_slice(&(position.1 as u64).to_le_bytes());
-            preimage[32..40].copy_from_slice(&(position.2 as u64).to_le_bytes());
-            let image = (murmur3::murmur3_32(&mut std::io::Cursor::new(preimage), 1729).unwrap()
-                & 16777215) as f32
-                / 16777216f32;
-
-            // Set the tile only if that's less than the brush density
-            if image >= self.brush_density {
-                return;
-            }
+        if self.is_skipped_by_brush_density(position) {
+            return;
         }
 
         map.data[position] = tile.to_id();
@@ -193,7 +200,7 @@ impl super::Tab {
                     },
                     position.2,
                 );
-                let tile_id = self.recompute_autotile(map, position);
+                let tile_id = Self::recompute_autotile(map, position);
                 map.data[position] = tile_id;
             }
         }
@@ -204,32 +211,12 @@ impl super::Tab {
         update_state: &luminol_core::UpdateState<'_>,
         map: &mut luminol_data::rpg::Map,
     ) -> Option<usize> {
-        let mut first_vacant_id = 1;
-        let mut max_event_id = 0;
-
-        for (_, event) in map.events.iter() {
-            if event.id == first_vacant_id {
-                first_vacant_id += 1;
-            }
-            max_event_id = event.id;
-
-            if event.x == self.view.cursor_pos.x as i32 && event.y == self.view.cursor_pos.y as i32
-            {
-                return None;
-            }
-        }
-
-        // Try first to allocate the event number directly after the current highest one.
-        // However, valid event number range in RPG Maker XP and VX is 1-999.
-        let new_event_id = if max_event_id < 999 {
-            max_event_id + 1
-        }
-        // Otherwise, we'll try to use a non-allocated event ID that isn't zero.
-        else if first_vacant_id <= 999 {
-            first_vacant_id
-        } else {
+        if map.events.iter().any(|(_, event)| {
+            event.x == self.view.cursor_pos.x as i32 && event.y == self.view.cursor_pos.y as i32
+        }) {
             return None;
-        };
+        }
+        let new_event_id = Self::new_event_id(map)?;
 
         let event = luminol_data::rpg::Event::new(
             self.view.cursor_pos.x as i32,
@@ -249,6 +236,31 @@ impl super::Tab {
         Some(new_event_id)
     }
 
+    /// Picks the ID of a new event on `map`, or returns `None` if every valid ID is taken.
+    pub(super) fn new_event_id(map: &luminol_data::rpg::Map) -> Option<usize> {
+        let mut first_vacant_id = 1;
+        let mut max_event_id = 0;
+
+        for (_, event) in map.events.iter() {
+            if event.id == first_vacant_id {
+                first_vacant_id += 1;
+            }
+            max_event_id = event.id;
+        }
+
+        // Try first to allocate the event number directly after the current highest one.
+        // However, valid event number range in RPG Maker XP and VX is 1-999.
+        if max_event_id < 999 {
+            Some(max_event_id + 1)
+        }
+        // Otherwise, we'll try to use a non-allocated event ID that isn't zero.
+        else if first_vacant_id <= 999 {
+            Some(first_vacant_id)
+        } else {
+            None
+        }
+    }
+
     pub(super) fn push_to_history(
         &mut self,
         update_state: &luminol_core::UpdateState<'_>,
@@ -0,0 +1,165 @@
+// Copyright (C) 2024 Melody Madeline Lyons
+//
+// This file is part of Luminol.
+//
+// Luminol is free software: you can redistribute it and/or modify
+// it under the terms of the GNU General Public License as published by
+// the Free Software Foundation, either version 3 of the License, or
+// (at your option) any later version.
+//
+// Luminol is distributed in the hope that it will be useful,
+// but WITHOUT ANY WARRANTY; without even the implied warranty of
+// MERCHANTABILITY or FITNESS FOR A PARTICULAR PURPOSE.  See the
+// GNU General Public License for more details.
+//
+// You should have received a copy of the GNU General Public License
+// along with Luminol.  If not, see <http://www.gnu.org/licenses/>.
+//
+//     Additional permission under GNU GPL version 3 section 7
+//
+// If you modify this Program, or any covered work, by linking or combining
+// it with Steamworks API by Valve Corporation, containing parts covered by
+// terms of the Steamworks API by Valve Corporation, the licensors of this
+// Program grant you additional permission to convey the resulting work.
+
+use crate::windows::map_picker;
+
+/// Shown in place of the map editor when a map can't be loaded, with ways to fix the map.
+pub struct Tab {
+    /// ID of the map that failed to load.
+    id: usize,
+    /// Why the map failed to load.
+    error: String,
+
+    /// The size of the empty map that can replace the broken one.
+    width: usize,
+    height: usize,
+
+    force_close: bool,
+}
+
+impl Tab {
+    pub fn new(id: usize, error: &color_eyre::Report) -> Self {
+        Self {
+            id,
+            error: format!("{error:#}"),
+            width: 20,
+            height: 15,
+            force_close: false,
+        }
+    }
+
+    /// Opens the map in a map editor in place of this tab.
+    fn open_map(
+        &mut self,
+        update_state: &mut luminol_core::UpdateState<'_>,
+    ) -> color_eyre::Result<()> {
+        let tab = super::map::Tab::new(self.id, update_state)?;
+        update_state.edit_tabs.add_tab(tab);
+        self.force_close = true;
+        Ok(())
+    }
+
+    /// Replaces the map with an empty map and opens it.
+    /// The map's file is overwritten the next time the project is saved.
+    fn create_empty_map(
+        &mut self,
+        update_state: &mut luminol_core::UpdateState<'_>,
+    ) -> color_eyre::Result<()> {
+        update_state.data.insert_map(
+            self.id,
+            map_picker::Window::new_map(self.width, self.height),
+        );
+        update_state.modified.set(true);
+
+        self.open_map(update_state)
+    }
+}
+
+impl luminol_core::Tab for Tab {
+    fn name(&self, update_state: &luminol_core::UpdateState<'_>) -> String {
+        let map_infos = update_state.data.map_infos();
+        format!(
+            "Map {}: {} (failed to load)",
+            self.id,
+            map_infos
+                .data
+                .get(&self.id)
+                .map_or("", |map_info| map_info.name.as_str()),
+        )
+    }
+
+    fn id(&self) -> egui::Id {
+        egui::Id::new("luminol_map_error").with(self.id)
+    }
+
+    fn force_close(&mut self) -> bool {
+        self.force_close
+    }
+
+    fn show(
+        &mut self,
+        ui: &mut egui::Ui,
+        update_state: &mut luminol_core::UpdateState<'_>,
+        _is_focused: bool,
+    ) {
+        egui::ScrollArea::vertical().show(ui, |ui| {
+            ui.heading(format!("Map {:0>3} could not be loaded", self.id));
+            ui.label(egui::RichText::new(&self.error).color(ui.visuals().error_fg_color));
+
+            ui.separator();
+
+            if ui
+                .button("Try again")
+                .on_hover_text("Load the map again, e.g. after fixing its file in another program")
+                .clicked()
+            {
+                if let Err(e) = self.open_map(update_state) {
+                    self.error = format!("{e:#}");
+                }
+            }
+
+            ui.separator();
+
+            ui.label("Replace the map with an empty map of this size:");
+            ui.horizontal(|ui| {
+                ui.label("Width");
+                ui.add(egui::DragValue::new(&mut self.width).range(1..=500));
+                ui.label("Height");
+                ui.add(egui::DragValue::new(&mut self.height).range(1..=500));
+            });
+            if ui
+                .button(
+                    egui::RichText::new("Create empty map")
+                        .color(ui.style().visuals.error_fg_color),
+                )
+                .on_hover_text("The map's current file will be overwritten when the project is saved")
+                .clicked()
+            {
+                if let Err(e) = self.create_empty_map(update_state) {
+                    luminol_core::error!(
+                        update_state.toasts,
+                        e.wrap_err(format!("Error replacing map {:0>3}", self.id))
+                    );
+                }
+            }
+
+            ui.separator();
+
+            if ui
+                .button("Remove from map list")
+                .on_hover_text(
+                    "Removes the map from the map tree without deleting its file. The maps inside it are moved up in its place",
+                )
+                .clicked()
+            {
+                map_picker::Window::remove_map_info(self.id, update_state);
+                self.force_close = true;
+            }
+        });
+    }
+
+    fn requires_filesystem(&self) -> bool {
+        true
+    }
+}
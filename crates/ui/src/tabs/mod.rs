@@ -23,5 +23,7 @@
 // Program grant you additional permission to convey the resulting work.
 /// The map editor.
 pub mod map;
+/// Shown instead of the map editor when a map can't be loaded.
+pub mod map_error;
 /// The getting started screen.
 pub mod started;
@@ -22,7 +22,7 @@
 // terms of the Steamworks API by Valve Corporation, the licensors of this
 // Program grant you additional permission to convey the resulting work.
 
-use crate::components::{Field, OptionalIdComboBox, UiExt};
+use crate::components::{ExtraIvarsView, Field, OptionalIdComboBox, UiExt};
 use itertools::Itertools;
 
 use crate::modals::graphic_picker::actor::Modal as GraphicPicker;
@@ -700,6 +700,8 @@ impl luminol_core::Window for Window {
                             });
                         });
 
+                        ui.add(ExtraIvarsView::new(&actor.extra_ivars));
+
                         self.previous_actor = Some(actor.id);
                     },
                 )
@@ -22,7 +22,7 @@
 // terms of the Steamworks API by Valve Corporation, the licensors of this
 // Program grant you additional permission to convey the resulting work.
 
-use crate::components::{Cellpicker, Field, UiExt};
+use crate::components::{Cellpicker, ExtraIvarsView, Field, UiExt};
 use luminol_core::Modal;
 use strum::IntoEnumIterator;
 
@@ -439,6 +439,8 @@ impl luminol_core::Window for super::Window {
                                 );
                         }
 
+                        ui.add(ExtraIvarsView::new(&animation.extra_ivars));
+
                         self.previous_animation = Some(animation.id);
                     },
                 )
@@ -22,7 +22,9 @@
 // terms of the Steamworks API by Valve Corporation, the licensors of this
 // Program grant you additional permission to convey the resulting work.
 
-use crate::components::{EnumComboBox, Field, IdVecSelection, OptionalIdComboBox, UiExt};
+use crate::components::{
+    EnumComboBox, ExtraIvarsView, Field, IdVecSelection, OptionalIdComboBox, UiExt,
+};
 
 #[derive(Default)]
 pub struct Window {
@@ -235,6 +237,8 @@ impl luminol_core::Window for Window {
                             });
                         });
 
+                        ui.add(ExtraIvarsView::new(&armor.extra_ivars));
+
                         self.previous_armor = Some(armor.id);
                     },
                 )
@@ -0,0 +1,229 @@
+// Copyright (C) 2024 Melody Madeline Lyons
+//
+// This file is part of Luminol.
+//
+// Luminol is free software: you can redistribute it and/or modify
+// it under the terms of the GNU General Public License as published by
+// the Free Software Foundation, either version 3 of the License, or
+// (at your option) any later version.
+//
+// Luminol is distributed in the hope that it will be useful,
+// but WITHOUT ANY WARRANTY; without even the implied warranty of
+// MERCHANTABILITY or FITNESS FOR A PARTICULAR PURPOSE.  See the
+// GNU General Public License for more details.
+//
+// You should have received a copy of the GNU General Public License
+// along with Luminol.  If not, see <http://www.gnu.org/licenses/>.
+//
+//     Additional permission under GNU GPL version 3 section 7
+//
+// If you modify this Program, or any covered work, by linking or combining
+// it with Steamworks API by Valve Corporation, containing parts covered by
+// terms of the Steamworks API by Valve Corporation, the licensors of this
+// Program grant you additional permission to convey the resulting work.
+
+use luminol_core::backups::Backup;
+use luminol_core::Window as _;
+
+/// The backup browser.
+/// Lists the backups taken when saving, and restores or deletes them.
+#[derive(Default)]
+pub struct Window {
+    backups: Option<Vec<Backup>>,
+    pending_action: Option<(Backup, Action)>,
+}
+
+#[derive(Clone, Copy, PartialEq, Eq)]
+enum Action {
+    Restore,
+    Delete,
+}
+
+impl Window {
+    fn refresh(&mut self, update_state: &mut luminol_core::UpdateState<'_>) {
+        self.backups = match luminol_core::backups::list(update_state.filesystem) {
+            Ok(backups) => Some(backups),
+            Err(e) => {
+                luminol_core::error!(update_state.toasts, e.wrap_err("Error listing backups"));
+                Some(Vec::new())
+            }
+        };
+    }
+
+    fn restore(
+        &self,
+        backup: &Backup,
+        update_state: &mut luminol_core::UpdateState<'_>,
+    ) -> color_eyre::Result<()> {
+        let config = update_state
+            .project_config
+            .as_mut()
+            .expect("project not loaded");
+
+        luminol_core::backups::restore(update_state.filesystem, config, backup)?;
+
+        // everything open is showing data that no longer exists, so close it all and reload
+        let id = self.id();
+        update_state
+            .edit_windows
+            .clean(move |w| !w.requires_filesystem() || w.id() == id);
+        update_state.edit_tabs.clean(|t| !t.requires_filesystem());
+        update_state.graphics.atlas_loader.clear();
+        update_state
+            .data
+            .load(update_state.filesystem, update_state.toasts, config)?;
+        update_state.modified.set(false);
+
+        Ok(())
+    }
+}
+
+impl luminol_core::Window for Window {
+    fn id(&self) -> egui::Id {
+        egui::Id::new("luminol_backups_window")
+    }
+
+    fn show(
+        &mut self,
+        ctx: &egui::Context,
+        open: &mut bool,
+        update_state: &mut luminol_core::UpdateState<'_>,
+    ) {
+        if self.backups.is_none() {
+            self.refresh(update_state);
+        }
+        let backup_count = update_state
+            .project_config
+            .as_ref()
+            .map_or(0, |config| config.project.backup_count);
+
+        egui::Window::new("Backups")
+            .id(self.id())
+            .open(open)
+            .show(ctx, |ui| {
+                ui.label(if backup_count == 0 {
+                    "Backups are disabled. You can enable them in the project config.".to_string()
+                } else {
+                    format!(
+                        "A backup is taken every time you save. The last {backup_count} are kept."
+                    )
+                });
+
+                if ui.button("Refresh").clicked() {
+                    self.backups = None;
+                }
+
+                ui.separator();
+
+                let backups = self.backups.as_deref().unwrap_or_default();
+                if backups.is_empty() {
+                    ui.label("There are no backups yet.");
+                }
+
+                egui::ScrollArea::vertical().show(ui, |ui| {
+                    for backup in backups {
+                        ui.horizontal(|ui| {
+                            ui.label(format!("{} (UTC)", backup.name));
+
+                            ui.with_layout(
+                                egui::Layout::right_to_left(egui::Align::Center),
+                                |ui| {
+                                    if ui.button("Delete").clicked() {
+                                        self.pending_action =
+                                            Some((backup.clone(), Action::Delete));
+                                    }
+                                    if ui.button("Restore").clicked() {
+                                        self.pending_action =
+                                            Some((backup.clone(), Action::Restore));
+                                    }
+                                },
+                            );
+                        });
+                    }
+                });
+            });
+
+        let modal = egui_modal::Modal::new(ctx, "backups_confirm_modal");
+        if let Some((backup, action)) = self.pending_action.clone() {
+            let mut confirmed = false;
+            let mut should_close = false;
+
+            modal.open();
+            modal.show(|ui| {
+                match action {
+                    Action::Restore => {
+                        modal.title(ui, "Restore Backup");
+                        modal.frame(ui, |ui| {
+                            modal.body(
+                                ui,
+                                format!("Are you sure you want to restore the backup from {}?", backup.name),
+                            );
+                            ui.label("Any unsaved changes will be lost. Your current data will be backed up first.");
+                        });
+                    }
+                    Action::Delete => {
+                        modal.title(ui, "Delete Backup");
+                        modal.frame(ui, |ui| {
+                            modal.body(
+                                ui,
+                                format!("Are you sure you want to delete the backup from {}?", backup.name),
+                            );
+                            ui.label("This cannot be undone.");
+                        });
+                    }
+                }
+                modal.buttons(ui, |ui| {
+                    if modal.button(ui, "Cancel").clicked() {
+                        should_close = true;
+                    } else if modal
+                        .caution_button(
+                            ui,
+                            match action {
+                                Action::Restore => "Restore",
+                                Action::Delete => "Delete",
+                            },
+                        )
+                        .clicked()
+                    {
+                        confirmed = true;
+                        should_close = true;
+                    }
+                });
+            });
+
+            if confirmed {
+                match action {
+                    Action::Restore => match self.restore(&backup, update_state) {
+                        Ok(()) => luminol_core::info!(
+                            update_state.toasts,
+                            format!("Restored the backup from {}", backup.name)
+                        ),
+                        Err(e) => luminol_core::error!(
+                            update_state.toasts,
+                            e.wrap_err("Error restoring backup")
+                        ),
+                    },
+                    Action::Delete => {
+                        if let Err(e) =
+                            luminol_core::backups::delete(update_state.filesystem, &backup)
+                        {
+                            luminol_core::error!(
+                                update_state.toasts,
+                                e.wrap_err("Error deleting backup")
+                            );
+                        }
+                    }
+                }
+                self.backups = None;
+            }
+            if should_close {
+                self.pending_action = None;
+                modal.close();
+            }
+        }
+    }
+
+    fn requires_filesystem(&self) -> bool {
+        true
+    }
+}
@@ -23,8 +23,8 @@
 // Program grant you additional permission to convey the resulting work.
 
 use crate::components::{
-    CollapsingView, DatabaseView, EnumComboBox, Field, IdVecSelection, OptionalIdComboBox,
-    RankSelection, UiExt,
+    CollapsingView, DatabaseView, EnumComboBox, ExtraIvarsView, Field, IdVecSelection,
+    OptionalIdComboBox, RankSelection, UiExt,
 };
 
 #[derive(Default)]
@@ -283,6 +283,8 @@ impl luminol_core::Window for Window {
                             });
                         });
 
+                        ui.add(ExtraIvarsView::new(&class.extra_ivars));
+
                         self.previous_class = Some(class.id);
                     },
                 )
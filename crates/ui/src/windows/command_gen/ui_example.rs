@@ -114,9 +114,12 @@ impl UiExample {
                     ParameterKind::Int => {
                         ui.add(egui::DragValue::new(&mut 0i16));
                     }
-                    ParameterKind::IntBool => {
+                    ParameterKind::IntBool | ParameterKind::Bool => {
                         ui.checkbox(&mut false, "");
                     }
+                    ParameterKind::MoveRoute => {
+                        ui.button("Move Route...").clicked();
+                    }
                     ParameterKind::Enum { ref variants } => {
                         let (first_name, mut first_id) = variants.first().unwrap();
                         ui.menu_button(format!("{first_name} ⏷"), |ui| {
@@ -22,136 +22,187 @@
 // terms of the Steamworks API by Valve Corporation, the licensors of this
 // Program grant you additional permission to convey the resulting work.
 
-use crate::modals::database_modal;
+use crate::components::{CommandView, DatabaseView, ExtraIvarsView, Field, UiExt};
+use crate::modals::database_modal::SwitchModal;
 use luminol_core::Modal;
 
+const TRIGGER_TYPES: [&str; 3] = ["None", "Autorun", "Parallel"];
+
 /// The common event editor.
 pub struct Window {
-    tabs: luminol_core::Tabs,
-    _selected_id: usize,
+    selected_common_event_name: Option<String>,
+    previous_common_event: Option<usize>,
+
+    switch_modal: SwitchModal,
+    command_view: CommandView,
+
+    view: DatabaseView,
 }
 
 impl Default for Window {
     fn default() -> Self {
         Self {
-            tabs: luminol_core::Tabs::new("common_event_tabs", false),
-            _selected_id: 0,
+            selected_common_event_name: None,
+            previous_common_event: None,
+            switch_modal: SwitchModal::new(egui::Id::new("common_event_switch_modal")),
+            command_view: CommandView::new("common_event_commands"),
+            view: DatabaseView::default(),
         }
     }
 }
 
+impl Window {
+    pub fn new() -> Self {
+        Default::default()
+    }
+}
+
 impl luminol_core::Window for Window {
     fn id(&self) -> egui::Id {
-        egui::Id::new("Common Events")
+        egui::Id::new("common_event_editor")
+    }
+
+    fn requires_filesystem(&self) -> bool {
+        true
     }
 
     fn show(
         &mut self,
         ctx: &egui::Context,
         open: &mut bool,
-        _update_state: &mut luminol_core::UpdateState<'_>,
+        update_state: &mut luminol_core::UpdateState<'_>,
     ) {
-        let name = self
-            .tabs
-            .focused_name()
-            .map_or("Common Events".to_string(), |name| {
-                format!("Editing Common Event {name}")
-            });
-        egui::Window::new(name)
-            .default_width(500.)
-            .id(egui::Id::new("common_events_edit"))
-            .open(open)
-            .show(ctx, |_| {
-                // TODO
-            });
-    }
+        // The command list and the switch picker both need to read the system data, so instead of
+        // taking all of the data out of the update state we only take the common events
+        let mut common_events = std::mem::take(&mut update_state.data.common_events().data);
 
-    fn requires_filesystem(&self) -> bool {
-        true
-    }
-}
+        let mut modified = false;
 
-pub struct CommonEventTab {
-    event: luminol_data::rpg::CommonEvent,
-    force_close: bool,
-    switch_modal: database_modal::SwitchModal,
-    command_view: crate::components::CommandView,
-}
+        self.selected_common_event_name = None;
 
-impl luminol_core::Tab for CommonEventTab {
-    fn name(&self, _update_state: &luminol_core::UpdateState<'_>) -> String {
-        format!("{}: {}", self.event.name, self.event.id)
-    }
+        let name = if let Some(name) = &self.selected_common_event_name {
+            format!("Editing common event {:?}", name)
+        } else {
+            "Common Event Editor".into()
+        };
 
-    fn id(&self) -> egui::Id {
-        egui::Id::new("luminol_common_event").with(self.event.id)
-    }
-
-    fn show(
-        &mut self,
-        ui: &mut egui::Ui,
-        update_state: &mut luminol_core::UpdateState<'_>,
-        _is_focused: bool,
-    ) {
-        ui.horizontal(|ui| {
-            let trigger_types = ["None", "Autorun", "Parallel"];
-            egui::ComboBox::new(format!("common_event_{}_trigger", self.event.id), "Trigger")
-                .selected_text(trigger_types[self.event.trigger])
-                .show_ui(ui, |ui| {
-                    for (ele, trigger) in trigger_types.into_iter().enumerate() {
-                        ui.selectable_value(&mut self.event.trigger, ele, trigger);
-                    }
-                });
-
-            ui.add_enabled(
-                self.event.trigger > 0,
-                self.switch_modal
-                    .button(&mut self.event.switch_id, update_state),
-            );
-
-            let mut save_event = false;
-
-            if ui.button("Ok").clicked() {
-                save_event = true;
-                self.force_close = true;
-            }
-
-            if ui.button("Cancel").clicked() {
-                self.force_close = true;
-            }
-
-            if ui.button("Apply").clicked() {
-                save_event = true;
-            }
-
-            if save_event {
-                let mut common_events = update_state.data.common_events();
-
-                common_events.data[self.event.id - 1] = self.event.clone();
-            }
-
-            ui.label("Name");
-            ui.text_edit_singleline(&mut self.event.name);
-        });
-
-        ui.separator();
-
-        egui::ScrollArea::both()
-            .auto_shrink([false; 2])
-            .show(ui, |ui| {
-                self.command_view.ui(
+        let response = egui::Window::new(name)
+            .id(self.id())
+            .default_width(500.)
+            .open(open)
+            .show(ctx, |ui| {
+                self.view.show(
                     ui,
-                    &update_state.project_config.as_ref().unwrap().command_db,
-                    &mut self.event.list,
-                );
+                    update_state,
+                    "Common Events",
+                    &mut common_events,
+                    |common_event| format!("{:0>4}: {}", common_event.id + 1, common_event.name),
+                    |ui, common_events, id, update_state| {
+                        let common_event = &mut common_events[id];
+                        self.selected_common_event_name = Some(common_event.name.clone());
+
+                        if self.previous_common_event != Some(common_event.id) {
+                            self.command_view =
+                                CommandView::new(("common_event_commands", common_event.id));
+                        }
+
+                        ui.with_padded_stripe(false, |ui| {
+                            modified |= ui
+                                .add(Field::new(
+                                    "Name",
+                                    egui::TextEdit::singleline(&mut common_event.name)
+                                        .desired_width(f32::INFINITY),
+                                ))
+                                .changed();
+                        });
+
+                        ui.with_padded_stripe(true, |ui| {
+                            ui.columns(2, |columns| {
+                                modified |= columns[0]
+                                    .add(Field::new("Trigger", |ui: &mut egui::Ui| {
+                                        let mut changed = false;
+                                        let mut response = egui::ComboBox::from_id_source((
+                                            common_event.id,
+                                            "trigger",
+                                        ))
+                                        .selected_text(
+                                            TRIGGER_TYPES
+                                                .get(common_event.trigger)
+                                                .copied()
+                                                .unwrap_or("???"),
+                                        )
+                                        .width(ui.available_width())
+                                        .show_ui(ui, |ui| {
+                                            for (value, trigger) in
+                                                TRIGGER_TYPES.into_iter().enumerate()
+                                            {
+                                                changed |= ui
+                                                    .selectable_value(
+                                                        &mut common_event.trigger,
+                                                        value,
+                                                        trigger,
+                                                    )
+                                                    .changed();
+                                            }
+                                        })
+                                        .response;
+                                        if changed {
+                                            response.mark_changed();
+                                        }
+                                        response
+                                    }))
+                                    .changed();
+
+                                // Common event switch IDs are 1-based, unlike the switch picker
+                                let old_switch_id = common_event.switch_id.saturating_sub(1);
+                                let mut switch_id = old_switch_id;
+                                columns[1].add(Field::new(
+                                    "Condition Switch",
+                                    |ui: &mut egui::Ui| {
+                                        ui.add_enabled(
+                                            common_event.trigger > 0,
+                                            self.switch_modal.button(&mut switch_id, update_state),
+                                        )
+                                    },
+                                ));
+                                if switch_id != old_switch_id {
+                                    common_event.switch_id = switch_id + 1;
+                                    modified = true;
+                                }
+                            });
+                        });
+
+                        ui.with_padded_stripe(false, |ui| {
+                            ui.label("Commands");
+                            ui.group(|ui| {
+                                egui::ScrollArea::both()
+                                    .id_source((common_event.id, "commands"))
+                                    .max_height(512.)
+                                    .show(ui, |ui| {
+                                        modified |= self
+                                            .command_view
+                                            .ui(ui, update_state, &mut common_event.list)
+                                            .changed();
+                                    });
+                            });
+                        });
+
+                        ui.add(ExtraIvarsView::new(&common_event.extra_ivars));
+
+                        self.previous_common_event = Some(common_event.id);
+                    },
+                )
             });
-    }
 
-    fn requires_filesystem(&self) -> bool {
-        true
-    }
+        if response.is_some_and(|ir| ir.inner.is_some_and(|ir| ir.inner.modified)) {
+            modified = true;
+        }
 
-    fn force_close(&mut self) -> bool {
-        self.force_close
+        let mut container = update_state.data.common_events();
+        container.data = common_events;
+        if modified {
+            update_state.modified.set(true);
+            container.modified = true;
+        }
     }
 }
@@ -23,241 +23,27 @@
 // Program grant you additional permission to convey the resulting work.
 
 use egui::Widget;
-use futures_lite::{AsyncReadExt, AsyncWriteExt};
-use luminol_core::data_formats::Handler as FormatHandler;
-use luminol_data::rpg;
-use luminol_filesystem::{FileSystem, OpenFlags};
-use std::sync::atomic::{AtomicUsize, Ordering};
 use strum::IntoEnumIterator;
 
 pub struct Window {
     selected_data_format: luminol_config::DataFormat,
-    convert: Option<Convert>,
-}
-
-struct Convert {
-    promise: poll_promise::Promise<color_eyre::Result<()>>,
-    converting: Converting,
+    selected_encoding: luminol_config::StringEncoding,
+    old_files: luminol_core::convert::OldFiles,
+    conversion: Option<luminol_core::convert::Conversion<luminol_filesystem::project::FileSystem>>,
 }
 
 impl Window {
     pub fn new(config: &luminol_config::project::Config) -> Self {
         Self {
             selected_data_format: config.project.data_format,
-            convert: None,
+            selected_encoding: config.project.encoding,
+            old_files: Default::default(),
+            conversion: None,
         }
     }
 }
 
-type Converting = std::sync::Arc<(AtomicUsize, AtomicUsize)>;
-
-const CONVERTING_ACTORS: usize = 0;
-const CONVERTING_ANIMATIONS: usize = 1;
-const CONVERTING_ARMORS: usize = 2;
-const CONVERTING_CLASSES: usize = 3;
-const CONVERTING_COMMON_EVENTS: usize = 4;
-const CONVERTING_ENEMIES: usize = 5;
-const CONVERTING_ITEMS: usize = 6;
-const CONVERTING_SKILLS: usize = 7;
-const CONVERTING_STATES: usize = 8;
-const CONVERTING_TILESETS: usize = 9;
-const CONVERTING_TROOPS: usize = 10;
-const CONVERTING_WEAPONS: usize = 11;
-const CONVERTING_SCRIPTS: usize = 12;
-const CONVERTING_SYSTEM: usize = 13;
-const CONVERTING_MAPINFOS: usize = 14;
-
-fn converting_to_string(
-    converting: usize,
-    map_id: usize,
-    selected_data_format: luminol_config::DataFormat,
-) -> String {
-    let text = match converting {
-        CONVERTING_ACTORS => "Actors",
-        CONVERTING_ANIMATIONS => "Animations",
-        CONVERTING_ARMORS => "Armors",
-        CONVERTING_CLASSES => "Classes",
-        CONVERTING_COMMON_EVENTS => "CommonEvents",
-        CONVERTING_ENEMIES => "Enemies",
-        CONVERTING_ITEMS => "Items",
-        CONVERTING_SKILLS => "Skills",
-        CONVERTING_STATES => "States",
-        CONVERTING_TILESETS => "Tilesets",
-        CONVERTING_TROOPS => "Troops",
-        CONVERTING_WEAPONS => "Weapons",
-
-        CONVERTING_SCRIPTS => "Scripts",
-        CONVERTING_SYSTEM => "System",
-        CONVERTING_MAPINFOS => "MapInfos",
-        _ => {
-            return format!("Map{map_id:0>3}.{}", selected_data_format.extension());
-        }
-    };
-    format!("{}.{}", text, selected_data_format.extension())
-}
-
-const FORMAT_WARNING: &str = "Luminol will need to convert your project.\nThis is not 100% safe yet, make backups!\nPress OK to continue.";
-
-// Mostly async, opening files is not however.
-// We should probably provide async fns for that
-async fn convert_nil_padded<T>(
-    from: FormatHandler,
-    to: FormatHandler,
-    read_buf: &mut Vec<u8>,
-    write_buf: &mut Vec<u8>,
-    filename: &str,
-    host: &luminol_filesystem::host::FileSystem,
-) -> color_eyre::Result<Vec<T>>
-where
-    T: ::serde::de::DeserializeOwned + serde::Serialize,
-    T: for<'de> alox_48::Deserialize<'de> + alox_48::Serialize,
-{
-    read_buf.clear();
-    write_buf.clear();
-
-    let mut file = host.open_file(from.path_for(filename), OpenFlags::Read)?;
-    file.read_to_end(read_buf).await?;
-
-    let data = from.read_nil_padded_from::<T>(read_buf)?;
-
-    to.write_nil_padded_to(&data, write_buf)?;
-
-    let mut file = host.open_file(
-        to.path_for(filename),
-        OpenFlags::Write | OpenFlags::Truncate | OpenFlags::Create,
-    )?;
-    file.write_all(write_buf).await?;
-    file.flush().await?;
-
-    from.remove_file(host, filename)?;
-
-    Ok(data)
-}
-
-async fn convert_regular<T>(
-    from: FormatHandler,
-    to: FormatHandler,
-    read_buf: &mut Vec<u8>,
-    write_buf: &mut Vec<u8>,
-    filename: &str,
-    host: &luminol_filesystem::host::FileSystem,
-) -> color_eyre::Result<T>
-where
-    T: ::serde::de::DeserializeOwned + serde::Serialize,
-    T: for<'de> alox_48::Deserialize<'de> + alox_48::Serialize,
-{
-    read_buf.clear();
-    write_buf.clear();
-
-    let mut file = host.open_file(from.path_for(filename), OpenFlags::Read)?;
-    file.read_to_end(read_buf).await?;
-
-    let data = from.read_data_from::<T>(read_buf)?;
-
-    to.write_data_to(&data, write_buf)?;
-
-    let mut file = host.open_file(
-        to.path_for(filename),
-        OpenFlags::Write | OpenFlags::Truncate | OpenFlags::Create,
-    )?;
-    file.write_all(write_buf).await?;
-    file.flush().await?;
-
-    from.remove_file(host, filename)?;
-
-    Ok(data)
-}
-
-fn convert_project(
-    config: &mut luminol_config::project::Config,
-    selected_data_format: luminol_config::DataFormat,
-    filesystem: &luminol_filesystem::project::FileSystem,
-    converting: Converting,
-) -> impl std::future::Future<Output = color_eyre::Result<()>> {
-    let from = FormatHandler::new(config.project.data_format);
-    let to = FormatHandler::new(selected_data_format);
-
-    // TODO handle errors
-    let pretty_config = ron::ser::PrettyConfig::new()
-        .struct_names(true)
-        .enumerate_arrays(true);
-    config.project.data_format = selected_data_format;
-    let project_config = ron::ser::to_string_pretty(&config.project, pretty_config).unwrap();
-    filesystem.write(".luminol/config", project_config).unwrap();
-
-    let host = filesystem.host().unwrap(); // This bypasses the path cache (which is BAD!) so we will need to regen it later
-    let scripts_filename = config.project.scripts_path.clone();
-
-    async move {
-        let mut read_buf = Vec::new();
-        let mut write_buf = Vec::<u8>::new();
-
-        let host = &host;
-        let read_buf = &mut read_buf;
-        let write_buf = &mut write_buf;
-
-        let (converting_progress, converting_map_id) = &*converting;
-
-        // FIXME: have some kind of trait system to determine filenames rather than hardcoding them like this
-        converting_progress.store(CONVERTING_ACTORS, Ordering::Relaxed);
-        convert_nil_padded::<rpg::Actor>(from, to, read_buf, write_buf, "Actors", host).await?;
-
-        converting_progress.store(CONVERTING_ANIMATIONS, Ordering::Relaxed);
-        convert_nil_padded::<rpg::Animation>(from, to, read_buf, write_buf, "Animations", host)
-            .await?;
-
-        converting_progress.store(CONVERTING_ARMORS, Ordering::Relaxed);
-        convert_nil_padded::<rpg::Armor>(from, to, read_buf, write_buf, "Armors", host).await?;
-
-        converting_progress.store(CONVERTING_CLASSES, Ordering::Relaxed);
-        convert_nil_padded::<rpg::Class>(from, to, read_buf, write_buf, "Classes", host).await?;
-
-        converting_progress.store(CONVERTING_COMMON_EVENTS, Ordering::Relaxed);
-        convert_nil_padded::<rpg::CommonEvent>(from, to, read_buf, write_buf, "CommonEvents", host)
-            .await?;
-
-        converting_progress.store(CONVERTING_ENEMIES, Ordering::Relaxed);
-        convert_nil_padded::<rpg::Enemy>(from, to, read_buf, write_buf, "Enemies", host).await?;
-
-        converting_progress.store(CONVERTING_ITEMS, Ordering::Relaxed);
-        convert_nil_padded::<rpg::Item>(from, to, read_buf, write_buf, "Items", host).await?;
-
-        converting_progress.store(CONVERTING_SKILLS, Ordering::Relaxed);
-        convert_nil_padded::<rpg::Skill>(from, to, read_buf, write_buf, "Skills", host).await?;
-
-        converting_progress.store(CONVERTING_STATES, Ordering::Relaxed);
-        convert_nil_padded::<rpg::State>(from, to, read_buf, write_buf, "States", host).await?;
-
-        converting_progress.store(CONVERTING_TILESETS, Ordering::Relaxed);
-        convert_nil_padded::<rpg::Tileset>(from, to, read_buf, write_buf, "Tilesets", host).await?;
-
-        converting_progress.store(CONVERTING_TROOPS, Ordering::Relaxed);
-        convert_nil_padded::<rpg::Troop>(from, to, read_buf, write_buf, "Troops", host).await?;
-
-        converting_progress.store(CONVERTING_WEAPONS, Ordering::Relaxed);
-        convert_nil_padded::<rpg::Weapon>(from, to, read_buf, write_buf, "Weapons", host).await?;
-
-        converting_progress.store(CONVERTING_SCRIPTS, Ordering::Relaxed);
-        convert_regular::<Vec<rpg::Script>>(from, to, read_buf, write_buf, &scripts_filename, host)
-            .await?;
-
-        converting_progress.store(CONVERTING_SYSTEM, Ordering::Relaxed);
-        convert_regular::<rpg::System>(from, to, read_buf, write_buf, "System", host).await?;
-
-        converting_progress.store(CONVERTING_MAPINFOS, Ordering::Relaxed);
-        let mapinfos: std::collections::HashMap<usize, rpg::MapInfo> =
-            convert_regular(from, to, read_buf, write_buf, "MapInfos", host).await?;
-
-        for (index, map_id) in mapinfos.keys().copied().enumerate() {
-            converting_progress.store(CONVERTING_MAPINFOS + index, Ordering::Relaxed);
-            converting_map_id.store(map_id, Ordering::Relaxed);
-
-            let map_filename = format!("Map{map_id:0>3}");
-            convert_regular::<rpg::Map>(from, to, read_buf, write_buf, &map_filename, host).await?;
-        }
-        Ok(())
-    }
-}
+const FORMAT_WARNING: &str = "Luminol will need to convert your project.\nEvery file is checked after converting, and nothing is changed unless all of them convert successfully.\nPress OK to continue.";
 
 impl luminol_core::Window for Window {
     fn id(&self) -> egui::Id {
@@ -302,6 +88,28 @@ impl luminol_core::Window for Window {
                         .text_edit_singleline(&mut config.project.playtest_exe)
                         .changed();
 
+                    ui.horizontal(|ui| {
+                        ui.label("Backups to keep").on_hover_text(
+                            "A backup of your data is taken every time you save. Set this to 0 to disable backups",
+                        );
+                        modified |= ui
+                            .add(egui::DragValue::new(&mut config.project.backup_count).range(0..=100))
+                            .changed();
+                    });
+
+                    ui.horizontal(|ui| {
+                        ui.label("Autosave interval").on_hover_text(
+                            "How often unsaved changes are saved so they can be recovered if Luminol crashes. Set this to 0 to disable autosaving",
+                        );
+                        modified |= ui
+                            .add(
+                                egui::DragValue::new(&mut config.project.autosave_interval)
+                                    .range(0..=3600)
+                                    .suffix(" s"),
+                            )
+                            .changed();
+                    });
+
                     ui.separator();
 
                     egui::ComboBox::from_label("Data Format")
@@ -323,7 +131,26 @@ impl luminol_core::Window for Window {
                         ui.checkbox(pretty, "Pretty Print").on_hover_text("This will make the data files human-readable, but significantly larger!");
                     }
 
-                    if self.selected_data_format != config.project.data_format {
+                    if self.selected_data_format == luminol_config::DataFormat::Marshal {
+                        egui::ComboBox::from_label("Text Encoding")
+                            .selected_text(self.selected_encoding.to_string())
+                            .show_ui(ui, |ui| {
+                                for encoding in luminol_config::StringEncoding::iter() {
+                                    ui.selectable_value(
+                                        &mut self.selected_encoding,
+                                        encoding,
+                                        encoding.to_string(),
+                                    );
+                                }
+                            })
+                            .response
+                            .on_hover_text("The encoding text is stored in. Older projects often use the encoding of the system they were made on, like Shift-JIS for Japanese projects. Text that can't be stored in the selected encoding is saved as UTF-8");
+                    }
+
+                    let encoding_changed = self.selected_data_format
+                        == luminol_config::DataFormat::Marshal
+                        && self.selected_encoding != config.project.encoding;
+                    if self.selected_data_format != config.project.data_format || encoding_changed {
                         // add warning message about needing to edit every single data file
                         egui::Frame::none().show(ui, |ui| {
                             ui.style_mut()
@@ -346,27 +173,39 @@ impl luminol_core::Window for Window {
                                 });
                         });
 
-                        let clicked = ui
+                        egui::ComboBox::from_label("Old data files")
+                            .selected_text(self.old_files.to_string())
+                            .show_ui(ui, |ui| {
+                                for old_files in luminol_core::convert::OldFiles::iter() {
+                                    ui.selectable_value(
+                                        &mut self.old_files,
+                                        old_files,
+                                        old_files.to_string(),
+                                    );
+                                }
+                            });
+
+                        if ui
                             .button(
-                                egui::RichText::new("Ok").color(ui.style().visuals.error_fg_color),
+                                egui::RichText::new("Ok")
+                                    .color(ui.style().visuals.error_fg_color),
                             )
-                            .clicked();
-                        if clicked {
-                            let converting = Converting::default();
-                            let future = convert_project(
+                            .clicked()
+                        {
+                            match luminol_core::convert::Conversion::new(
+                                update_state.filesystem,
                                 config,
                                 self.selected_data_format,
-                                update_state.filesystem,
-                                converting.clone(),
-                            );
-                            #[cfg(not(target_arch = "wasm32"))]
-                            let promise = poll_promise::Promise::spawn_async(future);
-                            #[cfg(target_arch = "wasm32")]
-                            let promise = poll_promise::Promise::spawn_local(future);
-                            self.convert = Some(Convert {
-                                promise,
-                                converting,
-                            });
+                            ) {
+                                Ok(conversion) => {
+                                    self.conversion =
+                                        Some(conversion.with_encoding(self.selected_encoding))
+                                }
+                                Err(e) => luminol_core::error!(
+                                    update_state.toasts,
+                                    e.wrap_err("Error preparing to convert the project")
+                                ),
+                            }
                         }
                     }
 
@@ -440,47 +279,88 @@ impl luminol_core::Window for Window {
                 });
             });
 
-        if let Some(convert) = self.convert.take() {
+        if let Some(mut conversion) = self.conversion.take() {
             let modal = egui_modal::Modal::new(ctx, "converting_project_modal");
+            let mut cancelled = false;
             modal.show(|ui| {
                 modal.title(ui, "Converting Project...");
 
-                let map_infos = update_state.data.map_infos();
-                let map_count = map_infos.data.len();
-
-                let current_progress = convert.converting.0.load(Ordering::Relaxed);
-                let current_map_id = convert.converting.1.load(Ordering::Relaxed);
-
-                let total = CONVERTING_MAPINFOS + map_count + 1;
-                let progress = (current_progress + 2) as f32 / total as f32;
-
-                let current_text = converting_to_string(
-                    current_progress,
-                    current_map_id,
-                    self.selected_data_format,
-                );
+                let (staged, total) = conversion.progress();
+                if let Some(file) = conversion.current_file() {
+                    ui.label(format!("Converting {file} {}/{total}", staged + 1));
+                }
+                egui::ProgressBar::new(staged as f32 / total.max(1) as f32)
+                    .animate(true)
+                    .ui(ui);
 
-                ui.label(format!(
-                    "Converting {current_text} {}/{total}",
-                    current_progress + 2
-                ));
-                egui::ProgressBar::new(progress).animate(true).ui(ui);
+                modal.buttons(ui, |ui| {
+                    cancelled = modal.caution_button(ui, "Cancel").clicked();
+                });
             });
-            match convert.promise.try_take() {
-                Ok(Ok(())) => {
-                    // we've drastically edited the data folder, so the path cache needs to be rebuilt
-                    update_state.filesystem.rebuild_path_cache();
-                }
-                Ok(Err(err)) => {
-                    luminol_core::error!(update_state.toasts, err);
-                    luminol_core::warn!(
+
+            if cancelled {
+                match conversion.rollback(update_state.filesystem) {
+                    Ok(()) => luminol_core::info!(update_state.toasts, "Conversion cancelled"),
+                    Err(e) => luminol_core::error!(
                         update_state.toasts,
-                        "WARNING: Your project may be corrupted!"
-                    );
+                        e.wrap_err("Error cleaning up the cancelled conversion")
+                    ),
                 }
-                Err(promise) => {
-                    modal.open();
-                    self.convert = Some(Convert { promise, ..convert });
+            } else {
+                match conversion.step(update_state.filesystem) {
+                    Ok(false) => {
+                        modal.open();
+                        ctx.request_repaint();
+                        self.conversion = Some(conversion);
+                    }
+                    Ok(true) => {
+                        modal.close();
+                        let old_format = config.project.data_format;
+                        match conversion.commit(update_state.filesystem, config, self.old_files) {
+                            Ok(()) if old_format == self.selected_data_format => {
+                                luminol_core::info!(
+                                    update_state.toasts,
+                                    format!(
+                                        "Converted the project's text to {}",
+                                        self.selected_encoding
+                                    )
+                                )
+                            }
+                            Ok(()) => luminol_core::info!(
+                                update_state.toasts,
+                                format!("Converted the project to {}", self.selected_data_format)
+                            ),
+                            // committing only changes the config once every file is in place
+                            Err(e)
+                                if config.project.data_format == self.selected_data_format
+                                    && config.project.encoding == self.selected_encoding =>
+                            {
+                                luminol_core::error!(
+                                    update_state.toasts,
+                                    e.wrap_err(
+                                        "The project was converted, but the old data files could not be cleaned up"
+                                    )
+                                )
+                            }
+                            Err(e) => luminol_core::error!(
+                                update_state.toasts,
+                                e.wrap_err("Error converting the project, no changes were made")
+                            ),
+                        }
+                    }
+                    Err(e) => {
+                        modal.close();
+                        luminol_core::error!(
+                            update_state.toasts,
+                            e.wrap_err("Error converting the project, no changes were made")
+                        );
+                        if let Err(e) = conversion.rollback(update_state.filesystem) {
+                            luminol_core::error!(
+                                update_state.toasts,
+                                e.wrap_err("Error cleaning up the failed conversion")
+                            );
+                        }
+                    }
                 }
             }
         }
@@ -23,7 +23,8 @@
 // Program grant you additional permission to convey the resulting work.
 
 use crate::components::{
-    CollapsingView, DatabaseView, EnumComboBox, Field, OptionalIdComboBox, RankSelection, UiExt,
+    CollapsingView, DatabaseView, EnumComboBox, ExtraIvarsView, Field, OptionalIdComboBox,
+    RankSelection, UiExt,
 };
 use crate::modals::graphic_picker::hue::Modal as GraphicPicker;
 use luminol_core::Modal;
@@ -650,6 +651,8 @@ impl luminol_core::Window for Window {
                             });
                         });
 
+                        ui.add(ExtraIvarsView::new(&enemy.extra_ivars));
+
                         self.previous_enemy = Some(enemy.id);
                     },
                 )
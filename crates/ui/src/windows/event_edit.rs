@@ -22,10 +22,11 @@
 // terms of the Steamworks API by Valve Corporation, the licensors of this
 // Program grant you additional permission to convey the resulting work.
 
-use crate::components::{EnumComboBox, EnumMenuButton, EnumRadioList};
+use crate::components::{CommandView, EnumComboBox, EnumMenuButton, EnumRadioList, ExtraIvarsView};
 use crate::modals::{
     database_modal::{SwitchModal, VariableModal},
     graphic_picker::event::Modal as GraphicPicker,
+    move_route::{Modal as MoveRouteModal, PreviewOrigin},
 };
 use egui::Widget;
 use luminol_core::prelude::*;
@@ -40,6 +41,8 @@ pub struct Window {
     switch_2_modal: SwitchModal,
     variable_modal: VariableModal,
     graphic_modal: GraphicPicker,
+    move_route_modal: MoveRouteModal,
+    command_view: CommandView,
 }
 
 impl Window {
@@ -68,6 +71,8 @@ impl Window {
             switch_2_modal: SwitchModal::new(id_source.with("switch_2_modal")),
             variable_modal: VariableModal::new(id_source.with("variable_modal")),
             graphic_modal,
+            move_route_modal: MoveRouteModal::new(id_source.with("move_route_modal")),
+            command_view: CommandView::new(id_source.with("commands")),
         }
     }
 }
@@ -145,6 +150,10 @@ impl luminol_core::Window for Window {
                 if self.selected_page != previous_page {
                     // reset the modal if we've changed pages
                     self.graphic_modal.reset(update_state, &mut page.graphic);
+                    self.move_route_modal
+                        .reset(update_state, &mut page.move_route);
+                    self.command_view =
+                        CommandView::new(id_source.with("commands").with(self.selected_page));
                 }
 
                 egui::SidePanel::left(id_source.with("side_panel")).show_inside(ui, |ui| {
@@ -227,10 +236,20 @@ impl luminol_core::Window for Window {
                                     .ui(ui)
                                     .changed();
                                 });
-                                ui.add_enabled(
-                                    page.move_type == luminol_data::rpg::MoveType::Custom,
-                                    egui::Button::new("Move Route..."),
-                                ); // TODO
+                                self.move_route_modal
+                                    .set_preview_origin(Some(PreviewOrigin {
+                                        map_id: self.map_id,
+                                        x: event.x,
+                                        y: event.y,
+                                        direction: page.graphic.direction,
+                                    }));
+                                modified |= ui
+                                    .add_enabled(
+                                        page.move_type == luminol_data::rpg::MoveType::Custom,
+                                        self.move_route_modal
+                                            .button(&mut page.move_route, update_state),
+                                    )
+                                    .changed();
                                 ui.horizontal(|ui| {
                                     ui.label("Move Speed");
                                     modified |= EnumComboBox::new(
@@ -282,6 +301,20 @@ impl luminol_core::Window for Window {
                             modified |= EnumRadioList::new(&mut page.trigger).ui(ui).changed();
                         });
                     });
+
+                    ui.add(ExtraIvarsView::new(&event.extra_ivars));
+                    ui.add(ExtraIvarsView::new(&page.extra_ivars));
+                });
+
+                egui::CentralPanel::default().show_inside(ui, |ui| {
+                    egui::ScrollArea::both()
+                        .auto_shrink([false; 2])
+                        .show(ui, |ui| {
+                            modified |= self
+                                .command_view
+                                .ui(ui, update_state, &mut page.list)
+                                .changed();
+                        });
                 });
             });
 
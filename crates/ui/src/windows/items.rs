@@ -24,7 +24,7 @@
 
 use crate::{
     components::{
-        DatabaseView, EnumComboBox, Field, IdVecPlusMinusSelection, IdVecSelection,
+        DatabaseView, EnumComboBox, ExtraIvarsView, Field, IdVecPlusMinusSelection, IdVecSelection,
         OptionalIdComboBox, UiExt,
     },
     modals::{graphic_picker::basic::Modal as GraphicPicker, sound_picker::Modal as SoundPicker},
@@ -406,6 +406,8 @@ impl luminol_core::Window for Window {
                             });
                         });
 
+                        ui.add(ExtraIvarsView::new(&item.extra_ivars));
+
                         self.previous_item = Some(item.id);
                     },
                 )
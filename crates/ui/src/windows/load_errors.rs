@@ -0,0 +1,96 @@
+// Copyright (C) 2024 Melody Madeline Lyons
+//
+// This file is part of Luminol.
+//
+// Luminol is free software: you can redistribute it and/or modify
+// it under the terms of the GNU General Public License as published by
+// the Free Software Foundation, either version 3 of the License, or
+// (at your option) any later version.
+//
+// Luminol is distributed in the hope that it will be useful,
+// but WITHOUT ANY WARRANTY; without even the implied warranty of
+// MERCHANTABILITY or FITNESS FOR A PARTICULAR PURPOSE.  See the
+// GNU General Public License for more details.
+//
+// You should have received a copy of the GNU General Public License
+// along with Luminol.  If not, see <http://www.gnu.org/licenses/>.
+//
+//     Additional permission under GNU GPL version 3 section 7
+//
+// If you modify this Program, or any covered work, by linking or combining
+// it with Steamworks API by Valve Corporation, containing parts covered by
+// terms of the Steamworks API by Valve Corporation, the licensors of this
+// Program grant you additional permission to convey the resulting work.
+
+/// Lists the databases that couldn't be loaded when the project was opened, and why.
+#[derive(Default)]
+pub struct Window {}
+
+impl luminol_core::Window for Window {
+    fn id(&self) -> egui::Id {
+        egui::Id::new("luminol_load_errors_window")
+    }
+
+    fn show(
+        &mut self,
+        ctx: &egui::Context,
+        open: &mut bool,
+        update_state: &mut luminol_core::UpdateState<'_>,
+    ) {
+        let mut retry = None;
+
+        egui::Window::new("Load Errors").open(open).show(ctx, |ui| {
+            if update_state.data.load_errors().next().is_none() {
+                ui.label("Every database was loaded successfully.");
+                return;
+            }
+
+            ui.label(
+                "These databases could not be loaded. They are empty until they are fixed, and are never saved, so their files are left as they are.",
+            );
+            ui.label(
+                "Once a file has been fixed in another program, Luminol reloads it automatically, or you can try loading it again here.",
+            );
+
+            egui::ScrollArea::vertical().show(ui, |ui| {
+                for (name, error) in update_state.data.load_errors() {
+                    ui.separator();
+                    ui.horizontal(|ui| {
+                        ui.strong(name);
+                        if ui.button("Try again").clicked() {
+                            retry = Some(name.to_string());
+                        }
+                    });
+                    for cause in error.chain() {
+                        ui.label(
+                            egui::RichText::new(cause.to_string())
+                                .color(ui.visuals().error_fg_color),
+                        );
+                    }
+                }
+            });
+        });
+
+        if let Some(name) = retry {
+            let config = update_state
+                .project_config
+                .as_ref()
+                .expect("project not loaded");
+            let entry = luminol_core::Entry::Database(name);
+            match update_state
+                .data
+                .reload(&entry, update_state.filesystem, config)
+            {
+                Ok(()) => luminol_core::info!(update_state.toasts, format!("Loaded {entry}")),
+                Err(e) => luminol_core::error!(
+                    update_state.toasts,
+                    e.wrap_err(format!("Error loading {entry}"))
+                ),
+            }
+        }
+    }
+
+    fn requires_filesystem(&self) -> bool {
+        true
+    }
+}
@@ -22,20 +22,57 @@
 // terms of the Steamworks API by Valve Corporation, the licensors of this
 // Program grant you additional permission to convey the resulting work.
 
-use std::collections::{BTreeMap, BTreeSet};
+use std::collections::BTreeMap;
+
+use luminol_data::rpg;
 
 /// The map picker window.
 /// Displays a list of maps in a tree.
 /// Maps can be double clicked to open them in a map editor.
+/// Right clicking a map lets the user create, copy or delete maps, and maps can be dragged around
+/// by their handles to reorder or reparent them.
 #[derive(Default)]
-pub struct Window {}
+pub struct Window {
+    /// The map the user asked to delete, pending confirmation.
+    pending_delete: Option<usize>,
+}
+
+/// Drag and drop payload for a map in the tree.
+#[derive(Debug, Clone, Copy)]
+struct DraggedMap(usize);
+
+/// Where a dropped map should be placed relative to the map it was dropped on.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+enum Placement {
+    Before,
+    Into,
+    After,
+}
+
+/// Something the user asked for while the tree was being rendered.
+/// These are applied afterwards so that we don't need to hold onto the map infos while doing so.
+#[derive(Debug, Clone, Copy)]
+enum Action {
+    Open(usize),
+    Properties(usize),
+    Create {
+        parent_id: usize,
+    },
+    Copy(usize),
+    Delete(usize),
+    Move {
+        id: usize,
+        target: usize,
+        placement: Placement,
+    },
+}
 
 impl Window {
     fn render_submap(
         id: usize,
-        children_data: &BTreeMap<usize, BTreeSet<usize>>,
-        mapinfos: &mut luminol_data::rpg::MapInfos,
-        open_map_id: &mut Option<usize>,
+        children_data: &BTreeMap<usize, Vec<usize>>,
+        mapinfos: &mut rpg::MapInfos,
+        actions: &mut Vec<Action>,
         ui: &mut egui::Ui,
     ) {
         // We get the map name. It's assumed that there is in fact a map with this ID in mapinfos.
@@ -54,30 +91,386 @@ impl Window {
             map_info.expanded = header.openness(ui.ctx()) >= 1.;
 
             header
-                .show_header(ui, |ui| {
-                    // Has the user
-                    if ui.text_edit_singleline(&mut map_info.name).double_clicked() {
-                        *open_map_id = Some(id)
-                    }
-                })
+                .show_header(ui, |ui| Self::render_row(id, map_info, actions, ui))
                 .body(|ui| {
-                    for id in children_data.get(&id).unwrap() {
+                    for &id in children_data.get(&id).unwrap() {
                         // Render children.
-                        Self::render_submap(*id, children_data, mapinfos, open_map_id, ui);
+                        Self::render_submap(id, children_data, mapinfos, actions, ui);
                     }
                 });
         } else {
             // Just display a label otherwise.
             ui.horizontal(|ui| {
                 ui.add_space(ui.spacing().indent);
-                if ui.text_edit_singleline(&mut map_info.name).double_clicked() {
-                    *open_map_id = Some(id)
-                }
+                Self::render_row(id, map_info, actions, ui);
+            });
+        }
+    }
+
+    fn render_row(
+        id: usize,
+        map_info: &mut rpg::MapInfo,
+        actions: &mut Vec<Action>,
+        ui: &mut egui::Ui,
+    ) {
+        let drag_id = egui::Id::new("luminol_map_drag").with(id);
+        let handle = ui
+            .dnd_drag_source(drag_id, DraggedMap(id), |ui| ui.label("☰"))
+            .response
+            .on_hover_cursor(egui::CursorIcon::Grab);
+
+        let response = ui.text_edit_singleline(&mut map_info.name);
+        // Has the user double clicked this map?
+        if response.double_clicked() {
+            actions.push(Action::Open(id));
+        }
+        response.context_menu(|ui| {
+            if ui.button("Open").clicked() {
+                actions.push(Action::Open(id));
+                ui.close_menu();
+            }
+            if ui.button("Properties").clicked() {
+                actions.push(Action::Properties(id));
+                ui.close_menu();
+            }
+            ui.separator();
+            if ui.button("New Map").clicked() {
+                actions.push(Action::Create { parent_id: id });
+                ui.close_menu();
+            }
+            if ui.button("Copy Map").clicked() {
+                actions.push(Action::Copy(id));
+                ui.close_menu();
+            }
+            if ui.button("Delete Map").clicked() {
+                actions.push(Action::Delete(id));
+                ui.close_menu();
+            }
+        });
+
+        // Handle other maps being dropped onto this one.
+        let response = handle.union(response);
+        let Some(pointer_pos) = ui.ctx().pointer_interact_pos() else {
+            return;
+        };
+        if !response.rect.contains(pointer_pos) {
+            return;
+        }
+        let Some(dragged) = response.dnd_hover_payload::<DraggedMap>() else {
+            return;
+        };
+        if dragged.0 == id {
+            return;
+        }
+
+        // The top and bottom quarters of the row place the map next to this one, the rest of the
+        // row places it inside of this one.
+        let rect = response.rect;
+        let placement = if pointer_pos.y < rect.top() + rect.height() / 4. {
+            Placement::Before
+        } else if pointer_pos.y > rect.bottom() - rect.height() / 4. {
+            Placement::After
+        } else {
+            Placement::Into
+        };
+
+        let stroke = ui.visuals().selection.stroke;
+        match placement {
+            Placement::Before => ui.painter().hline(rect.x_range(), rect.top(), stroke),
+            Placement::After => ui.painter().hline(rect.x_range(), rect.bottom(), stroke),
+            Placement::Into => ui.painter().rect_stroke(rect, 2., stroke),
+        };
+
+        if let Some(dragged) = response.dnd_release_payload::<DraggedMap>() {
+            actions.push(Action::Move {
+                id: dragged.0,
+                target: id,
+                placement,
             });
         }
     }
-}
 
+    /// Returns the IDs of the children of every map, sorted by their order in the tree.
+    fn children_data(mapinfos: &rpg::MapInfos) -> BTreeMap<usize, Vec<usize>> {
+        let mut children_data: BTreeMap<_, Vec<_>> = BTreeMap::new();
+        for (&id, map) in mapinfos.data.iter() {
+            children_data.entry(map.parent_id).or_default().push(id);
+        }
+        for children in children_data.values_mut() {
+            children.sort_by_key(|id| (mapinfos.data[id].order, *id));
+        }
+        children_data
+    }
+
+    /// Returns `id` and the IDs of all of its descendants.
+    fn descendants(id: usize, children_data: &BTreeMap<usize, Vec<usize>>) -> Vec<usize> {
+        let mut ids = vec![id];
+        let mut i = 0;
+        while let Some(&id) = ids.get(i) {
+            ids.extend(children_data.get(&id).into_iter().flatten().copied());
+            i += 1;
+        }
+        ids
+    }
+
+    /// Rewrites the `parent_id` and `order` of every map from a list of children for each map.
+    /// Orders are assigned in the order the maps are displayed, like RPG Maker does.
+    fn apply_children_data(
+        mapinfos: &mut rpg::MapInfos,
+        children_data: &BTreeMap<usize, Vec<usize>>,
+    ) {
+        fn visit(
+            parent_id: usize,
+            children_data: &BTreeMap<usize, Vec<usize>>,
+            mapinfos: &mut rpg::MapInfos,
+            order: &mut i32,
+        ) {
+            for &id in children_data.get(&parent_id).into_iter().flatten() {
+                *order += 1;
+                let map_info = mapinfos.data.get_mut(&id).unwrap();
+                map_info.parent_id = parent_id;
+                map_info.order = *order;
+                visit(id, children_data, mapinfos, order);
+            }
+        }
+
+        visit(0, children_data, mapinfos, &mut 0);
+        mapinfos.modified = true;
+    }
+
+    /// Adds a map to the tree, placing it relative to `target`.
+    fn insert_into_tree(
+        mapinfos: &mut rpg::MapInfos,
+        id: usize,
+        map_info: rpg::MapInfo,
+        target: usize,
+        placement: Placement,
+    ) {
+        mapinfos.data.insert(id, map_info);
+
+        let mut children_data = Self::children_data(mapinfos);
+        Self::place(&mut children_data, mapinfos, id, target, placement);
+        Self::apply_children_data(mapinfos, &children_data);
+    }
+
+    /// Moves `id` in `children_data` so that it is placed relative to `target`.
+    fn place(
+        children_data: &mut BTreeMap<usize, Vec<usize>>,
+        mapinfos: &rpg::MapInfos,
+        id: usize,
+        target: usize,
+        placement: Placement,
+    ) {
+        let old_parent_id = mapinfos.data[&id].parent_id;
+        if let Some(siblings) = children_data.get_mut(&old_parent_id) {
+            siblings.retain(|&sibling| sibling != id);
+        }
+
+        match placement {
+            Placement::Into => children_data.entry(target).or_default().push(id),
+            Placement::Before | Placement::After => {
+                let parent_id = mapinfos.data.get(&target).map_or(0, |m| m.parent_id);
+                let siblings = children_data.entry(parent_id).or_default();
+                let index = siblings
+                    .iter()
+                    .position(|&sibling| sibling == target)
+                    .map_or(siblings.len(), |index| {
+                        index + (placement == Placement::After) as usize
+                    });
+                siblings.insert(index, id);
+            }
+        }
+    }
+
+    /// Returns the lowest map ID that isn't in use.
+    fn free_id(mapinfos: &rpg::MapInfos) -> usize {
+        (1..).find(|id| !mapinfos.data.contains_key(id)).unwrap()
+    }
+
+    /// Creates the map RPG Maker XP creates for new maps, which is 20x15 tiles by default.
+    pub(crate) fn new_map(width: usize, height: usize) -> rpg::Map {
+        rpg::Map {
+            width,
+            height,
+            encounter_step: 30,
+            data: luminol_data::Table3::new(width, height, 3),
+            ..Default::default()
+        }
+    }
+
+    fn apply_action(
+        &mut self,
+        action: Action,
+        update_state: &mut luminol_core::UpdateState<'_>,
+    ) -> color_eyre::Result<()> {
+        let config = update_state
+            .project_config
+            .as_ref()
+            .expect("project not loaded");
+
+        match action {
+            Action::Open(id) => {
+                match crate::tabs::map::Tab::new(id, update_state) {
+                    Ok(tab) => update_state.edit_tabs.add_tab(tab),
+                    Err(e) => {
+                        let tab = crate::tabs::map_error::Tab::new(id, &e);
+                        update_state.edit_tabs.add_tab(tab);
+                        luminol_core::error!(
+                            update_state.toasts,
+                            e.wrap_err(format!("Error opening map {id:0>3}"))
+                        );
+                    }
+                }
+                return Ok(());
+            }
+
+            Action::Properties(id) => {
+                let window = super::map_properties::Window::new(update_state, id)?;
+                update_state.edit_windows.add_window(window);
+                return Ok(());
+            }
+
+            Action::Create { parent_id } => {
+                let mut mapinfos = update_state.data.map_infos();
+                let id = Self::free_id(&mapinfos);
+
+                update_state.data.insert_map(id, Self::new_map(20, 15));
+
+                let map_info = rpg::MapInfo {
+                    name: format!("MAP{id:0>3}"),
+                    parent_id,
+                    ..Default::default()
+                };
+                Self::insert_into_tree(&mut mapinfos, id, map_info, parent_id, Placement::Into);
+            }
+
+            Action::Copy(source_id) => {
+                let map = update_state
+                    .data
+                    .get_or_load_map(source_id, update_state.filesystem, config)?
+                    .clone();
+
+                let mut mapinfos = update_state.data.map_infos();
+                let id = Self::free_id(&mapinfos);
+
+                update_state.data.insert_map(id, map);
+
+                let source_info = &mapinfos.data[&source_id];
+                let map_info = rpg::MapInfo {
+                    name: format!("{} (copy)", source_info.name),
+                    parent_id: source_info.parent_id,
+                    scroll_x: source_info.scroll_x,
+                    scroll_y: source_info.scroll_y,
+                    ..Default::default()
+                };
+                Self::insert_into_tree(&mut mapinfos, id, map_info, source_id, Placement::After);
+            }
+
+            Action::Delete(id) => {
+                self.pending_delete = Some(id);
+                return Ok(());
+            }
+
+            Action::Move {
+                id,
+                target,
+                placement,
+            } => {
+                let mut mapinfos = update_state.data.map_infos();
+                let mut children_data = Self::children_data(&mapinfos);
+
+                // Don't allow a map to become its own descendant.
+                let new_parent_id = match placement {
+                    Placement::Into => target,
+                    Placement::Before | Placement::After => mapinfos.data[&target].parent_id,
+                };
+                if Self::descendants(id, &children_data).contains(&new_parent_id) {
+                    return Ok(());
+                }
+
+                Self::place(&mut children_data, &mapinfos, id, target, placement);
+                Self::apply_children_data(&mut mapinfos, &children_data);
+                if placement == Placement::Into {
+                    mapinfos.data.get_mut(&target).unwrap().expanded = true;
+                }
+            }
+        }
+
+        update_state.modified.set(true);
+        Ok(())
+    }
+
+    /// Deletes a map and all of its descendants, closing any tabs they are open in.
+    fn delete_map(id: usize, update_state: &mut luminol_core::UpdateState<'_>) {
+        let mut mapinfos = update_state.data.map_infos();
+        let mut children_data = Self::children_data(&mapinfos);
+        let ids = Self::descendants(id, &children_data);
+
+        let tab_ids: std::collections::HashSet<_> = ids
+            .iter()
+            .flat_map(|&id| {
+                [
+                    egui::Id::new("luminol_map").with(id),
+                    egui::Id::new("luminol_map_error").with(id),
+                ]
+            })
+            .collect();
+        let window_ids: std::collections::HashSet<_> = ids
+            .iter()
+            .map(|&id| egui::Id::new("luminol_map_properties").with(id))
+            .collect();
+        update_state
+            .edit_tabs
+            .clean(move |tab| !tab_ids.contains(&tab.id()));
+        update_state
+            .edit_windows
+            .clean(move |window| !window_ids.contains(&window.id()));
+
+        for &id in &ids {
+            update_state.data.delete_map(id);
+            mapinfos.data.remove(&id);
+            children_data.remove(&id);
+        }
+        for children in children_data.values_mut() {
+            children.retain(|id| !ids.contains(id));
+        }
+
+        Self::apply_children_data(&mut mapinfos, &children_data);
+
+        update_state.modified.set(true);
+    }
+
+    /// Removes a map from the map infos without touching its file, moving its children up into
+    /// its place. This is how maps whose file is missing or broken are gotten rid of.
+    pub(crate) fn remove_map_info(id: usize, update_state: &mut luminol_core::UpdateState<'_>) {
+        let mut mapinfos = update_state.data.map_infos();
+        let Some(parent_id) = mapinfos.data.get(&id).map(|map_info| map_info.parent_id) else {
+            return;
+        };
+
+        let mut children_data = Self::children_data(&mapinfos);
+        let children = children_data.remove(&id).unwrap_or_default();
+        let siblings = children_data.entry(parent_id).or_default();
+        match siblings.iter().position(|&sibling| sibling == id) {
+            Some(index) => {
+                siblings.splice(index..=index, children);
+            }
+            None => siblings.extend(children),
+        }
+
+        mapinfos.data.remove(&id);
+        Self::apply_children_data(&mut mapinfos, &children_data);
+        drop(mapinfos);
+
+        update_state.data.remove_map(id);
+        let window_id = egui::Id::new("luminol_map_properties").with(id);
+        update_state
+            .edit_windows
+            .clean(move |window| window.id() != window_id);
+
+        update_state.modified.set(true);
+    }
+}
 impl luminol_core::Window for Window {
     fn id(&self) -> egui::Id {
         egui::Id::new("Map Picker")
@@ -109,47 +502,102 @@ impl luminol_core::Window for Window {
 
                         // We preprocess maps to figure out what has nodes and what doesn't.
                         // This should result in an ordered hashmap of all the maps and their children.
-                        let mut children_data: BTreeMap<_, BTreeSet<_>> = BTreeMap::new();
-                        for (&id, map) in mapinfos.data.iter() {
-                            // Is there an entry for our parent?
-                            // If not, then just add a blank vector to it.
-                            let children = children_data.entry(map.parent_id).or_default(); // FIXME: this doesn't handle sorting properly
-                            children.insert(id);
-                        }
+                        let mut children_data = Self::children_data(&mapinfos);
                         children_data.entry(0).or_default(); // If there is no `0` entry (i.e. there are no maps) then add one.
 
-                        let mut open_map_id = None;
+                        let mut actions = Vec::new();
 
                         // Now we can actually render all maps.
-                        egui::CollapsingHeader::new("root")
-                            .default_open(true)
-                            .show(ui, |ui| {
-                                // There will always be a map `0`.
-                                // `0` is assumed to be the root map.
-                                for &id in children_data.get(&0).unwrap() {
-                                    Self::render_submap(
-                                        id,
-                                        &children_data,
-                                        &mut mapinfos,
-                                        &mut open_map_id,
-                                        ui,
-                                    );
-                                }
+                        let root =
+                            egui::CollapsingHeader::new("root")
+                                .default_open(true)
+                                .show(ui, |ui| {
+                                    // There will always be a map `0`.
+                                    // `0` is assumed to be the root map.
+                                    for &id in children_data.get(&0).unwrap() {
+                                        Self::render_submap(
+                                            id,
+                                            &children_data,
+                                            &mut mapinfos,
+                                            &mut actions,
+                                            ui,
+                                        );
+                                    }
+                                });
+                        root.header_response.context_menu(|ui| {
+                            if ui.button("New Map").clicked() {
+                                actions.push(Action::Create { parent_id: 0 });
+                                ui.close_menu();
+                            }
+                        });
+                        if let Some(dragged) =
+                            root.header_response.dnd_release_payload::<DraggedMap>()
+                        {
+                            actions.push(Action::Move {
+                                id: dragged.0,
+                                target: 0,
+                                placement: Placement::Into,
                             });
+                        }
 
                         drop(mapinfos);
 
-                        if let Some(id) = open_map_id {
-                            match crate::tabs::map::Tab::new(id, update_state) {
-                                Ok(tab) => update_state.edit_tabs.add_tab(tab),
-                                Err(e) => luminol_core::error!(
+                        for action in actions {
+                            if let Err(e) = self.apply_action(action, update_state) {
+                                luminol_core::error!(
                                     update_state.toasts,
-                                    e.wrap_err("Error enumerating maps")
-                                ),
+                                    e.wrap_err("Error editing maps")
+                                );
                             }
                         }
                     })
             });
+
+        let modal = egui_modal::Modal::new(ctx, "map_picker_delete_modal");
+        if let Some(id) = self.pending_delete {
+            let mut should_delete = false;
+            let mut should_close = false;
+
+            modal.open();
+            modal.show(|ui| {
+                let mapinfos = update_state.data.map_infos();
+                let children_data = Self::children_data(&mapinfos);
+                let descendant_count = Self::descendants(id, &children_data).len() - 1;
+                let name = mapinfos.data.get(&id).map_or("", |m| m.name.as_str());
+
+                modal.title(ui, "Delete Map");
+                modal.frame(ui, |ui| {
+                    modal.body(
+                        ui,
+                        if descendant_count == 0 {
+                            format!("Are you sure you want to delete {name}?")
+                        } else {
+                            format!(
+                                "Are you sure you want to delete {name} and the {descendant_count} map(s) inside of it?"
+                            )
+                        },
+                    );
+                    ui.label("This cannot be undone.");
+                });
+                modal.buttons(ui, |ui| {
+                    if modal.button(ui, "Cancel").clicked() {
+                        should_close = true;
+                    } else if modal.caution_button(ui, "Delete").clicked() {
+                        should_delete = true;
+                        should_close = true;
+                    }
+                });
+            });
+
+            if should_delete {
+                Self::delete_map(id, update_state);
+            }
+            if should_close {
+                self.pending_delete = None;
+                modal.close();
+            }
+        }
+
         *open = window_open;
     }
 
@@ -0,0 +1,358 @@
+// Copyright (C) 2024 Melody Madeline Lyons
+//
+// This file is part of Luminol.
+//
+// Luminol is free software: you can redistribute it and/or modify
+// it under the terms of the GNU General Public License as published by
+// the Free Software Foundation, either version 3 of the License, or
+// (at your option) any later version.
+//
+// Luminol is distributed in the hope that it will be useful,
+// but WITHOUT ANY WARRANTY; without even the implied warranty of
+// MERCHANTABILITY or FITNESS FOR A PARTICULAR PURPOSE.  See the
+// GNU General Public License for more details.
+//
+// You should have received a copy of the GNU General Public License
+// along with Luminol.  If not, see <http://www.gnu.org/licenses/>.
+//
+//     Additional permission under GNU GPL version 3 section 7
+//
+// If you modify this Program, or any covered work, by linking or combining
+// it with Steamworks API by Valve Corporation, containing parts covered by
+// terms of the Steamworks API by Valve Corporation, the licensors of this
+// Program grant you additional permission to convey the resulting work.
+
+use luminol_core::Modal;
+use luminol_data::rpg;
+
+use crate::components::{ExtraIvarsView, Field, FieldWithCheckbox, OptionalIdComboBox, UiExt};
+use crate::modals::sound_picker::Modal as SoundPicker;
+
+/// The largest width or height RPG Maker XP allows maps to have.
+const MAX_MAP_SIZE: usize = 500;
+
+/// The map properties window.
+/// Edits a map's name, tileset, audio and encounters, and resizes it.
+pub struct Window {
+    map_id: usize,
+
+    bgm_picker: SoundPicker,
+    bgs_picker: SoundPicker,
+
+    /// The size the map will be resized to when the user clicks "Resize".
+    width: usize,
+    height: usize,
+    /// The part of the map that stays in place when resizing.
+    anchor: egui::Align2,
+}
+
+impl Window {
+    pub fn new(
+        update_state: &luminol_core::UpdateState<'_>,
+        map_id: usize,
+    ) -> color_eyre::Result<Self> {
+        let map = update_state.data.get_or_load_map(
+            map_id,
+            update_state.filesystem,
+            update_state
+                .project_config
+                .as_ref()
+                .expect("project not loaded"),
+        )?;
+
+        Ok(Self {
+            map_id,
+
+            bgm_picker: SoundPicker::new(
+                luminol_audio::Source::BGM,
+                egui::Id::new("map_properties_bgm_picker").with(map_id),
+            ),
+            bgs_picker: SoundPicker::new(
+                luminol_audio::Source::BGS,
+                egui::Id::new("map_properties_bgs_picker").with(map_id),
+            ),
+
+            width: map.width,
+            height: map.height,
+            anchor: egui::Align2::LEFT_TOP,
+        })
+    }
+}
+
+impl luminol_core::Window for Window {
+    fn id(&self) -> egui::Id {
+        egui::Id::new("luminol_map_properties").with(self.map_id)
+    }
+
+    fn requires_filesystem(&self) -> bool {
+        true
+    }
+
+    fn show(
+        &mut self,
+        ctx: &egui::Context,
+        open: &mut bool,
+        update_state: &mut luminol_core::UpdateState<'_>,
+    ) {
+        if let Err(e) = update_state.data.load_map(
+            self.map_id,
+            update_state.filesystem,
+            update_state
+                .project_config
+                .as_ref()
+                .expect("project not loaded"),
+        ) {
+            luminol_core::error!(
+                update_state.toasts,
+                e.wrap_err("Error opening the map properties")
+            );
+            *open = false;
+            return;
+        }
+
+        let data = std::mem::take(update_state.data); // take data to avoid borrow checker issues
+        let mut map = data.get_map(self.map_id);
+        let mut map_infos = data.map_infos();
+        let tilesets = data.tilesets();
+        let troops = data.troops();
+
+        let mut modified = false;
+        let mut map_infos_modified = false;
+
+        egui::Window::new(format!("Map {:0>3} Properties", self.map_id))
+            .id(self.id())
+            .open(open)
+            .show(ctx, |ui| {
+                let map = &mut *map;
+
+                ui.with_padded_stripe(false, |ui| {
+                    if let Some(map_info) = map_infos.data.get_mut(&self.map_id) {
+                        map_infos_modified |= ui
+                            .add(Field::new(
+                                "Name",
+                                egui::TextEdit::singleline(&mut map_info.name)
+                                    .desired_width(f32::INFINITY),
+                            ))
+                            .changed();
+                    }
+
+                    modified |= ui
+                        .add(Field::new(
+                            "Tileset",
+                            OptionalIdComboBox::new(
+                                update_state,
+                                ("map_properties_tileset", self.map_id),
+                                &mut map.tileset_id,
+                                0..tilesets.data.len(),
+                                |id| {
+                                    tilesets.data.get(id).map_or_else(
+                                        || "".into(),
+                                        |t| format!("{:0>3}: {}", id + 1, t.name),
+                                    )
+                                },
+                            ),
+                        ))
+                        .changed();
+                });
+
+                ui.with_padded_stripe(true, |ui| {
+                    ui.columns(2, |columns| {
+                        let autoplay_bgm = map.autoplay_bgm;
+                        modified |= columns[0]
+                            .add(FieldWithCheckbox::new(
+                                "Auto-Change BGM",
+                                &mut map.autoplay_bgm,
+                                self.bgm_picker.button(&mut map.bgm, update_state),
+                            ))
+                            .changed();
+                        modified |= autoplay_bgm != map.autoplay_bgm;
+
+                        let autoplay_bgs = map.autoplay_bgs;
+                        modified |= columns[1]
+                            .add(FieldWithCheckbox::new(
+                                "Auto-Change BGS",
+                                &mut map.autoplay_bgs,
+                                self.bgs_picker.button(&mut map.bgs, update_state),
+                            ))
+                            .changed();
+                        modified |= autoplay_bgs != map.autoplay_bgs;
+                    });
+                });
+
+                ui.with_padded_stripe(false, |ui| {
+                    ui.label("Encounters");
+
+                    let mut deleted_encounter = None;
+                    for (i, troop_id) in map.encounter_list.iter_mut().enumerate() {
+                        ui.horizontal(|ui| {
+                            if ui.button("-").clicked() {
+                                deleted_encounter = Some(i);
+                            }
+
+                            // Troop IDs in the encounter list are 1-indexed
+                            let mut id = (*troop_id - 1).max(0) as usize;
+                            if ui
+                                .add(OptionalIdComboBox::new(
+                                    update_state,
+                                    ("map_properties_encounter", self.map_id, i),
+                                    &mut id,
+                                    0..troops.data.len(),
+                                    |id| {
+                                        troops.data.get(id).map_or_else(
+                                            || "".into(),
+                                            |t| format!("{:0>4}: {}", id + 1, t.name),
+                                        )
+                                    },
+                                ))
+                                .changed()
+                            {
+                                *troop_id = id as i32 + 1;
+                                modified = true;
+                            }
+                        });
+                    }
+                    if let Some(i) = deleted_encounter {
+                        map.encounter_list.remove(i);
+                        modified = true;
+                    }
+                    if ui.button("Add Encounter").clicked() {
+                        map.encounter_list.push(1);
+                        modified = true;
+                    }
+
+                    modified |= ui
+                        .add(Field::new(
+                            "Steps Average",
+                            egui::DragValue::new(&mut map.encounter_step).range(1..=999),
+                        ))
+                        .changed();
+                });
+
+                ui.with_padded_stripe(true, |ui| {
+                    ui.label(format!("Size: {}x{}", map.width, map.height));
+
+                    ui.columns(2, |columns| {
+                        columns[0].add(Field::new(
+                            "Width",
+                            egui::DragValue::new(&mut self.width).range(1..=MAX_MAP_SIZE),
+                        ));
+                        columns[1].add(Field::new(
+                            "Height",
+                            egui::DragValue::new(&mut self.height).range(1..=MAX_MAP_SIZE),
+                        ));
+                    });
+
+                    ui.label("Anchor");
+                    egui::Grid::new(self.id().with("anchor")).show(ui, |ui| {
+                        for y in [egui::Align::Min, egui::Align::Center, egui::Align::Max] {
+                            for x in [egui::Align::Min, egui::Align::Center, egui::Align::Max] {
+                                let anchor = egui::Align2([x, y]);
+                                let text = if anchor == self.anchor { "⏺" } else { "○" };
+                                ui.selectable_value(&mut self.anchor, anchor, text);
+                            }
+                            ui.end_row();
+                        }
+                    });
+
+                    let size_changed = self.width != map.width || self.height != map.height;
+                    if ui
+                        .add_enabled(size_changed, egui::Button::new("Resize"))
+                        .on_hover_text(
+                            "Tiles and events that end up outside of the map will be deleted",
+                        )
+                        .clicked()
+                    {
+                        let (x_offset, y_offset) =
+                            resize_map(map, self.width, self.height, self.anchor);
+
+                        // Keep the player's starting position on the same tile
+                        let mut system = data.system();
+                        if system.start_map_id + 1 == self.map_id {
+                            system.start_x =
+                                (system.start_x + x_offset as i32).clamp(0, self.width as i32 - 1);
+                            system.start_y =
+                                (system.start_y + y_offset as i32).clamp(0, self.height as i32 - 1);
+                            system.modified = true;
+                        }
+
+                        modified = true;
+                    }
+                });
+
+                ui.add(ExtraIvarsView::new(&map.extra_ivars));
+            });
+
+        if modified {
+            update_state.modified.set(true);
+            map.modified = true;
+        }
+        if map_infos_modified {
+            update_state.modified.set(true);
+            map_infos.modified = true;
+        }
+
+        drop(map);
+        drop(map_infos);
+        drop(tilesets);
+        drop(troops);
+
+        *update_state.data = data; // restore data
+    }
+}
+
+/// Resizes a map, keeping the part of the map at `anchor` in place.
+/// Events that end up outside of the map are deleted.
+///
+/// Returns how far the contents of the map were moved along the x and y axes.
+fn resize_map(
+    map: &mut rpg::Map,
+    width: usize,
+    height: usize,
+    anchor: egui::Align2,
+) -> (isize, isize) {
+    let offset = |align: egui::Align, old_size: usize, new_size: usize| {
+        let difference = new_size as isize - old_size as isize;
+        match align {
+            egui::Align::Min => 0,
+            egui::Align::Center => difference / 2,
+            egui::Align::Max => difference,
+        }
+    };
+    let x_offset = offset(anchor.x(), map.data.xsize(), width);
+    let y_offset = offset(anchor.y(), map.data.ysize(), height);
+
+    let zsize = map.data.zsize();
+    map.data
+        .resize_with_offset(width, height, zsize, x_offset, y_offset);
+    map.width = width;
+    map.height = height;
+
+    let deleted_events: Vec<_> = map
+        .events
+        .iter_mut()
+        .filter_map(|(id, event)| {
+            event.x += x_offset as i32;
+            event.y += y_offset as i32;
+            let in_bounds =
+                (0..width as i32).contains(&event.x) && (0..height as i32).contains(&event.y);
+            (!in_bounds).then_some(id)
+        })
+        .collect();
+    for id in deleted_events {
+        map.events.remove(id);
+    }
+
+    // Autotiles on the edges of the map treat out-of-bounds tiles as neighbors, so the autotiles
+    // along both the old and the new edges of the map need to be recomputed.
+    // Autotiles in the middle of the map are unaffected, so we can just recompute all of them.
+    for z in 0..zsize {
+        for y in 0..height {
+            for x in 0..width {
+                let tile_id = crate::tabs::map::Tab::recompute_autotile(map, (x, y, z));
+                map.data[(x, y, z)] = tile_id;
+            }
+        }
+    }
+
+    (x_offset, y_offset)
+}
@@ -32,6 +32,8 @@ pub mod animations;
 pub mod archive_manager;
 /// The armor editor.
 pub mod armor;
+/// The backup browser.
+pub mod backups;
 /// The class editor.
 pub mod classes;
 /// The common event editor.
@@ -47,8 +49,12 @@ pub mod enemies;
 pub mod event_edit;
 /// The item editor.
 pub mod items;
+/// Lists the databases that couldn't be loaded.
+pub mod load_errors;
 /// The map picker.
 pub mod map_picker;
+/// The map properties editor.
+pub mod map_properties;
 /// Misc windows.
 pub mod misc;
 /// New project window
@@ -66,5 +72,11 @@ pub mod skills;
 pub mod sound_test;
 /// The state editor.
 pub mod states;
+/// The system editor.
+pub mod system;
+/// The tileset editor.
+pub mod tilesets;
+/// The troop editor.
+pub mod troops;
 /// The weapon editor.
 pub mod weapons;
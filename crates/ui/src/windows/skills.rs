@@ -23,8 +23,8 @@
 // Program grant you additional permission to convey the resulting work.
 
 use crate::components::{
-    DatabaseView, EnumComboBox, Field, IdVecPlusMinusSelection, IdVecSelection, OptionalIdComboBox,
-    UiExt,
+    DatabaseView, EnumComboBox, ExtraIvarsView, Field, IdVecPlusMinusSelection, IdVecSelection,
+    OptionalIdComboBox, UiExt,
 };
 use luminol_core::Modal;
 
@@ -368,6 +368,8 @@ impl luminol_core::Window for Window {
                             });
                         });
 
+                        ui.add(ExtraIvarsView::new(&skill.extra_ivars));
+
                         self.previous_skill = Some(skill.id);
                     },
                 )
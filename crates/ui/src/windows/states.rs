@@ -23,7 +23,8 @@
 // Program grant you additional permission to convey the resulting work.
 
 use crate::components::{
-    EnumComboBox, Field, IdVecPlusMinusSelection, IdVecSelection, OptionalIdComboBox, UiExt,
+    EnumComboBox, ExtraIvarsView, Field, IdVecPlusMinusSelection, IdVecSelection,
+    OptionalIdComboBox, UiExt,
 };
 
 #[derive(Default)]
@@ -365,6 +366,9 @@ impl luminol_core::Window for Window {
                                     .changed();
                             });
                         });
+
+                        ui.add(ExtraIvarsView::new(&state.extra_ivars));
+
                         states[id] = state;
 
                         self.previous_state = Some(id);
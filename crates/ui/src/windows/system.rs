@@ -0,0 +1,724 @@
+// Copyright (C) 2024 Melody Madeline Lyons
+//
+// This file is part of Luminol.
+//
+// Luminol is free software: you can redistribute it and/or modify
+// it under the terms of the GNU General Public License as published by
+// the Free Software Foundation, either version 3 of the License, or
+// (at your option) any later version.
+//
+// Luminol is distributed in the hope that it will be useful,
+// but WITHOUT ANY WARRANTY; without even the implied warranty of
+// MERCHANTABILITY or FITNESS FOR A PARTICULAR PURPOSE.  See the
+// GNU General Public License for more details.
+//
+// You should have received a copy of the GNU General Public License
+// along with Luminol.  If not, see <http://www.gnu.org/licenses/>.
+//
+//     Additional permission under GNU GPL version 3 section 7
+//
+// If you modify this Program, or any covered work, by linking or combining
+// it with Steamworks API by Valve Corporation, containing parts covered by
+// terms of the Steamworks API by Valve Corporation, the licensors of this
+// Program grant you additional permission to convey the resulting work.
+
+use luminol_core::Modal;
+use luminol_data::rpg;
+use strum::IntoEnumIterator;
+
+use crate::components::{ExtraIvarsView, Field, OptionalIdComboBox, UiExt};
+use crate::modals::{
+    graphic_picker::{basic::Modal as GraphicPicker, hue::Modal as HueGraphicPicker},
+    sound_picker::Modal as SoundPicker,
+};
+
+/// The maximum number of actors that can be in the party at once.
+const MAX_PARTY_MEMBERS: usize = 4;
+
+#[derive(Clone, Copy, Debug, PartialEq, Eq, Default)]
+#[derive(strum::Display, strum::EnumIter)]
+enum Tab {
+    #[default]
+    General,
+    Graphics,
+    Audio,
+    Terms,
+    Elements,
+    Switches,
+    Variables,
+}
+
+/// Database - System management window.
+pub struct Window {
+    tab: Tab,
+
+    windowskin_picker: GraphicPicker,
+    title_picker: GraphicPicker,
+    gameover_picker: GraphicPicker,
+    transition_picker: GraphicPicker,
+    battleback_picker: GraphicPicker,
+    battler_picker: HueGraphicPicker,
+
+    sound_pickers: [SoundPicker; 16],
+
+    /// The sizes that the element, switch and variable lists will be resized to when the user
+    /// clicks "Set Maximum".
+    new_elements_size: Option<usize>,
+    new_switches_size: Option<usize>,
+    new_variables_size: Option<usize>,
+}
+
+impl Window {
+    pub fn new(update_state: &luminol_core::UpdateState<'_>) -> Self {
+        let system = update_state.data.system();
+
+        Self {
+            tab: Tab::default(),
+
+            windowskin_picker: GraphicPicker::new(
+                update_state,
+                "Graphics/Windowskins".into(),
+                system.windowskin_name.as_deref(),
+                egui::vec2(96., 64.),
+                "system_windowskin_picker",
+            ),
+            title_picker: GraphicPicker::new(
+                update_state,
+                "Graphics/Titles".into(),
+                system.title_name.as_deref(),
+                egui::vec2(128., 96.),
+                "system_title_picker",
+            ),
+            gameover_picker: GraphicPicker::new(
+                update_state,
+                "Graphics/Gameovers".into(),
+                system.gameover_name.as_deref(),
+                egui::vec2(128., 96.),
+                "system_gameover_picker",
+            ),
+            transition_picker: GraphicPicker::new(
+                update_state,
+                "Graphics/Transitions".into(),
+                system.battle_transition.as_deref(),
+                egui::vec2(128., 96.),
+                "system_transition_picker",
+            ),
+            battleback_picker: GraphicPicker::new(
+                update_state,
+                "Graphics/Battlebacks".into(),
+                system.battleback_name.as_deref(),
+                egui::vec2(128., 64.),
+                "system_battleback_picker",
+            ),
+            battler_picker: HueGraphicPicker::new(
+                update_state,
+                "Graphics/Battlers".into(),
+                system.battler_name.as_deref(),
+                system.battler_hue,
+                egui::vec2(64., 64.),
+                "system_battler_picker",
+            ),
+
+            sound_pickers: std::array::from_fn(|i| {
+                SoundPicker::new(
+                    SOUND_SOURCES[i],
+                    egui::Id::new("system_sound_picker").with(i),
+                )
+            }),
+
+            new_elements_size: None,
+            new_switches_size: None,
+            new_variables_size: None,
+        }
+    }
+}
+
+/// The audio sources of each of the sounds in the system, in the same order as in `sounds_mut`.
+const SOUND_SOURCES: [luminol_audio::Source; 16] = {
+    use luminol_audio::Source::*;
+    [
+        BGM, BGM, ME, ME, SE, SE, SE, SE, SE, SE, SE, SE, SE, SE, SE, SE,
+    ]
+};
+
+fn sounds_mut(system: &mut rpg::System) -> [(&'static str, &mut rpg::AudioFile); 16] {
+    [
+        ("Title BGM", &mut system.title_bgm),
+        ("Battle BGM", &mut system.battle_bgm),
+        ("Battle End ME", &mut system.battle_end_me),
+        ("Gameover ME", &mut system.gameover_me),
+        ("Cursor SE", &mut system.cursor_se),
+        ("Decision SE", &mut system.decision_se),
+        ("Cancel SE", &mut system.cancel_se),
+        ("Buzzer SE", &mut system.buzzer_se),
+        ("Equip SE", &mut system.equip_se),
+        ("Shop SE", &mut system.shop_se),
+        ("Save SE", &mut system.save_se),
+        ("Load SE", &mut system.load_se),
+        ("Battle Start SE", &mut system.battle_start_se),
+        ("Escape SE", &mut system.escape_se),
+        ("Actor Collapse SE", &mut system.actor_collapse_se),
+        ("Enemy Collapse SE", &mut system.enemy_collapse_se),
+    ]
+}
+
+fn words_mut(words: &mut rpg::system::Words) -> [(&'static str, &mut String); 20] {
+    [
+        ("Currency", &mut words.gold),
+        ("HP", &mut words.hp),
+        ("SP", &mut words.sp),
+        ("STR", &mut words.str),
+        ("DEX", &mut words.dex),
+        ("AGI", &mut words.agi),
+        ("INT", &mut words.int),
+        ("Attack Power", &mut words.atk),
+        ("Physical Defense", &mut words.pdef),
+        ("Magic Defense", &mut words.mdef),
+        ("Weapon", &mut words.weapon),
+        ("Shield", &mut words.armor1),
+        ("Helmet", &mut words.armor2),
+        ("Body Armor", &mut words.armor3),
+        ("Accessory", &mut words.armor4),
+        ("Attack", &mut words.attack),
+        ("Skill", &mut words.skill),
+        ("Defend", &mut words.guard),
+        ("Item", &mut words.item),
+        ("Equip", &mut words.equip),
+    ]
+}
+
+/// Plays an audio file from the system so the user can hear it without opening the sound picker.
+fn play_audio(
+    update_state: &mut luminol_core::UpdateState<'_>,
+    source: luminol_audio::Source,
+    audio_file: &rpg::AudioFile,
+) {
+    let Some(track) = &audio_file.name else {
+        update_state.audio.stop(source);
+        return;
+    };
+    let path = camino::Utf8Path::new("Audio")
+        .join(source.as_path())
+        .join(track);
+
+    if let Err(e) = update_state.audio.play(
+        path,
+        update_state.filesystem,
+        audio_file.volume,
+        audio_file.pitch,
+        Some(source),
+        update_state
+            .project_config
+            .as_ref()
+            .expect("project not loaded")
+            .project
+            .volume_scale,
+    ) {
+        luminol_core::error!(
+            update_state.toasts,
+            e.wrap_err("Error playing from audio file")
+        );
+    }
+}
+
+/// Shows an editable list of names along with a control for resizing the list. Entries before
+/// `first` are reserved and aren't shown. Returns whether or not the list was modified.
+fn names_ui(
+    ui: &mut egui::Ui,
+    id_source: &str,
+    names: &mut Vec<String>,
+    first: usize,
+    new_size: &mut Option<usize>,
+) -> bool {
+    let mut modified = false;
+
+    let size = new_size.get_or_insert(names.len().saturating_sub(first));
+    ui.horizontal(|ui| {
+        ui.add(egui::DragValue::new(size).range(1..=9999));
+        if ui.button("Set Maximum").clicked() {
+            names.resize_with(*size + first, String::new);
+            modified = true;
+        }
+    });
+
+    if *size > 999 && names.len() <= 999 + first {
+        ui.label(
+            egui::RichText::new(
+                "Setting the maximum above 999 may introduce performance issues and instability",
+            )
+            .color(ui.style().visuals.warn_fg_color),
+        );
+    }
+
+    ui.group(|ui| {
+        egui::ScrollArea::vertical().id_source(id_source).show_rows(
+            ui,
+            ui.spacing().interact_size.y + ui.spacing().item_spacing.y,
+            names.len().saturating_sub(first),
+            |ui, range| {
+                ui.set_width(ui.available_width());
+                for i in range {
+                    let id = i + first;
+                    ui.with_padded_stripe(i % 2 != 0, |ui| {
+                        ui.horizontal(|ui| {
+                            ui.label(format!("{:0>3}:", i + 1));
+                            modified |= ui
+                                .add(
+                                    egui::TextEdit::singleline(&mut names[id])
+                                        .desired_width(f32::INFINITY),
+                                )
+                                .changed();
+                        });
+                    });
+                }
+            },
+        );
+    });
+
+    modified
+}
+
+impl luminol_core::Window for Window {
+    fn id(&self) -> egui::Id {
+        egui::Id::new("system_editor")
+    }
+
+    fn requires_filesystem(&self) -> bool {
+        true
+    }
+
+    fn show(
+        &mut self,
+        ctx: &egui::Context,
+        open: &mut bool,
+        update_state: &mut luminol_core::UpdateState<'_>,
+    ) {
+        let data = std::mem::take(update_state.data); // take data to avoid borrow checker issues
+        let mut system = data.system();
+        let actors = data.actors();
+        let weapons = data.weapons();
+        let armors = data.armors();
+        let troops = data.troops();
+        let map_infos = data.map_infos();
+
+        let mut modified = false;
+
+        egui::Window::new("System Editor")
+            .id(self.id())
+            .default_width(500.)
+            .open(open)
+            .show(ctx, |ui| {
+                ui.horizontal_wrapped(|ui| {
+                    for tab in Tab::iter() {
+                        ui.selectable_value(&mut self.tab, tab, tab.to_string());
+                    }
+                });
+                ui.separator();
+
+                egui::ScrollArea::vertical()
+                    .id_source(self.tab.to_string())
+                    .show(ui, |ui| match self.tab {
+                        Tab::General => {
+                            ui.with_padded_stripe(false, |ui| {
+                                ui.label("Initial Party");
+                                let mut deleted_member = None;
+                                for (i, actor_id) in system.party_members.iter_mut().enumerate() {
+                                    ui.horizontal(|ui| {
+                                        if ui.button("-").clicked() {
+                                            deleted_member = Some(i);
+                                        }
+                                        modified |= ui
+                                            .add(OptionalIdComboBox::new(
+                                                update_state,
+                                                ("system_party_member", i),
+                                                actor_id,
+                                                0..actors.data.len(),
+                                                |id| {
+                                                    actors.data.get(id).map_or_else(
+                                                        || "".into(),
+                                                        |a| format!("{:0>4}: {}", id + 1, a.name),
+                                                    )
+                                                },
+                                            ))
+                                            .changed();
+                                    });
+                                }
+                                if let Some(i) = deleted_member {
+                                    system.party_members.remove(i);
+                                    modified = true;
+                                }
+                                if system.party_members.len() < MAX_PARTY_MEMBERS
+                                    && ui.button("Add Party Member").clicked()
+                                {
+                                    system.party_members.push(0);
+                                    modified = true;
+                                }
+                            });
+
+                            ui.with_padded_stripe(true, |ui| {
+                                ui.label("Player Starting Position");
+
+                                let old_map_id = system.start_map_id;
+                                modified |= ui
+                                    .add(Field::new(
+                                        "Map",
+                                        OptionalIdComboBox::new(
+                                            update_state,
+                                            "system_start_map",
+                                            &mut system.start_map_id,
+                                            map_infos.data.keys().map(|id| id - 1),
+                                            |id| {
+                                                map_infos.data.get(&(id + 1)).map_or_else(
+                                                    || "".into(),
+                                                    |m| format!("{:0>3}: {}", id + 1, m.name),
+                                                )
+                                            },
+                                        ),
+                                    ))
+                                    .changed();
+
+                                // Keep the starting position inside of the map
+                                let map_size = map_infos
+                                    .data
+                                    .contains_key(&(system.start_map_id + 1))
+                                    .then(|| {
+                                        data.get_or_load_map(
+                                            system.start_map_id + 1,
+                                            update_state.filesystem,
+                                            update_state.project_config.as_ref().unwrap(),
+                                        )
+                                    })
+                                    // maps that can't be loaded are reported when they're opened
+                                    .and_then(Result::ok)
+                                    .map(|map| (map.width as i32, map.height as i32));
+                                let (max_x, max_y) = map_size
+                                    .map_or((i32::MAX, i32::MAX), |(w, h)| {
+                                        ((w - 1).max(0), (h - 1).max(0))
+                                    });
+                                if old_map_id != system.start_map_id {
+                                    system.start_x = system.start_x.clamp(0, max_x);
+                                    system.start_y = system.start_y.clamp(0, max_y);
+                                }
+
+                                ui.columns(2, |columns| {
+                                    modified |= columns[0]
+                                        .add(Field::new(
+                                            "X",
+                                            egui::DragValue::new(&mut system.start_x)
+                                                .range(0..=max_x),
+                                        ))
+                                        .changed();
+                                    modified |= columns[1]
+                                        .add(Field::new(
+                                            "Y",
+                                            egui::DragValue::new(&mut system.start_y)
+                                                .range(0..=max_y),
+                                        ))
+                                        .changed();
+                                });
+
+                                ui.weak(
+                                    "You can also right click on a map while the event layer is \
+                                    selected to move the starting position there.",
+                                );
+                            });
+
+                            ui.with_padded_stripe(false, |ui| {
+                                modified |= ui
+                                    .add(Field::new(
+                                        "Test Troop",
+                                        OptionalIdComboBox::new(
+                                            update_state,
+                                            "system_test_troop",
+                                            &mut system.test_troop_id,
+                                            0..troops.data.len(),
+                                            |id| {
+                                                troops.data.get(id).map_or_else(
+                                                    || "".into(),
+                                                    |t| format!("{:0>4}: {}", id + 1, t.name),
+                                                )
+                                            },
+                                        ),
+                                    ))
+                                    .changed();
+                            });
+
+                            ui.with_padded_stripe(true, |ui| {
+                                ui.label("Test Battlers");
+                                let mut deleted_battler = None;
+                                for (i, battler) in system.test_battlers.iter_mut().enumerate() {
+                                    ui.group(|ui| {
+                                        ui.horizontal(|ui| {
+                                            if ui.button("-").clicked() {
+                                                deleted_battler = Some(i);
+                                            }
+                                            modified |= ui
+                                                .add(
+                                                    egui::DragValue::new(&mut battler.level)
+                                                        .range(1..=99)
+                                                        .prefix("Lv. "),
+                                                )
+                                                .changed();
+                                            modified |= ui
+                                                .add(OptionalIdComboBox::new(
+                                                    update_state,
+                                                    ("system_test_battler_actor", i),
+                                                    &mut battler.actor_id,
+                                                    0..actors.data.len(),
+                                                    |id| {
+                                                        actors.data.get(id).map_or_else(
+                                                            || "".into(),
+                                                            |a| {
+                                                                format!(
+                                                                    "{:0>4}: {}",
+                                                                    id + 1,
+                                                                    a.name
+                                                                )
+                                                            },
+                                                        )
+                                                    },
+                                                ))
+                                                .changed();
+                                        });
+
+                                        modified |= ui
+                                            .add(Field::new(
+                                                "Weapon",
+                                                OptionalIdComboBox::new(
+                                                    update_state,
+                                                    ("system_test_battler_weapon", i),
+                                                    &mut battler.weapon_id,
+                                                    0..weapons.data.len(),
+                                                    |id| {
+                                                        weapons.data.get(id).map_or_else(
+                                                            || "".into(),
+                                                            |w| {
+                                                                format!(
+                                                                    "{:0>4}: {}",
+                                                                    id + 1,
+                                                                    w.name
+                                                                )
+                                                            },
+                                                        )
+                                                    },
+                                                ),
+                                            ))
+                                            .changed();
+
+                                        for (kind, (name, armor_id)) in [
+                                            ("Shield", &mut battler.armor1_id),
+                                            ("Helmet", &mut battler.armor2_id),
+                                            ("Body Armor", &mut battler.armor3_id),
+                                            ("Accessory", &mut battler.armor4_id),
+                                        ]
+                                        .into_iter()
+                                        .enumerate()
+                                        {
+                                            modified |= ui
+                                                .add(Field::new(
+                                                    name,
+                                                    OptionalIdComboBox::new(
+                                                        update_state,
+                                                        ("system_test_battler_armor", i, kind),
+                                                        armor_id,
+                                                        (0..armors.data.len()).filter(|&id| {
+                                                            armors.data[id].kind as usize == kind
+                                                        }),
+                                                        |id| {
+                                                            armors.data.get(id).map_or_else(
+                                                                || "".into(),
+                                                                |a| {
+                                                                    format!(
+                                                                        "{:0>4}: {}",
+                                                                        id + 1,
+                                                                        a.name
+                                                                    )
+                                                                },
+                                                            )
+                                                        },
+                                                    ),
+                                                ))
+                                                .changed();
+                                        }
+                                    });
+                                }
+                                if let Some(i) = deleted_battler {
+                                    system.test_battlers.remove(i);
+                                    modified = true;
+                                }
+                                if system.test_battlers.len() < MAX_PARTY_MEMBERS
+                                    && ui.button("Add Test Battler").clicked()
+                                {
+                                    system.test_battlers.push(rpg::system::TestBattler {
+                                        level: 1,
+                                        ..Default::default()
+                                    });
+                                    modified = true;
+                                }
+                            });
+
+                            ui.add(ExtraIvarsView::new(&system.extra_ivars));
+                        }
+
+                        Tab::Graphics => {
+                            ui.with_padded_stripe(false, |ui| {
+                                ui.columns(2, |columns| {
+                                    modified |= columns[0]
+                                        .add(Field::new(
+                                            "Windowskin",
+                                            self.windowskin_picker
+                                                .button(&mut system.windowskin_name, update_state),
+                                        ))
+                                        .changed();
+                                    modified |= columns[1]
+                                        .add(Field::new(
+                                            "Battle Transition",
+                                            self.transition_picker.button(
+                                                &mut system.battle_transition,
+                                                update_state,
+                                            ),
+                                        ))
+                                        .changed();
+                                });
+                            });
+
+                            ui.with_padded_stripe(true, |ui| {
+                                ui.columns(2, |columns| {
+                                    modified |= columns[0]
+                                        .add(Field::new(
+                                            "Title Screen",
+                                            self.title_picker
+                                                .button(&mut system.title_name, update_state),
+                                        ))
+                                        .changed();
+                                    modified |= columns[1]
+                                        .add(Field::new(
+                                            "Gameover Screen",
+                                            self.gameover_picker
+                                                .button(&mut system.gameover_name, update_state),
+                                        ))
+                                        .changed();
+                                });
+                            });
+
+                            ui.with_padded_stripe(false, |ui| {
+                                ui.label("Battle Test Graphics");
+                                ui.columns(2, |columns| {
+                                    modified |= columns[0]
+                                        .add(Field::new(
+                                            "Battleback",
+                                            self.battleback_picker
+                                                .button(&mut system.battleback_name, update_state),
+                                        ))
+                                        .changed();
+                                    modified |= columns[1]
+                                        .add(Field::new(
+                                            "Battler",
+                                            self.battler_picker.button(
+                                                {
+                                                    // both fields have to be borrowed through the
+                                                    // same reference
+                                                    let system = &mut *system;
+                                                    (
+                                                        &mut system.battler_name,
+                                                        &mut system.battler_hue,
+                                                    )
+                                                },
+                                                update_state,
+                                            ),
+                                        ))
+                                        .changed();
+                                });
+                            });
+                        }
+
+                        Tab::Audio => {
+                            for (i, ((name, audio_file), picker)) in sounds_mut(&mut system)
+                                .into_iter()
+                                .zip(self.sound_pickers.iter_mut())
+                                .enumerate()
+                            {
+                                let source = SOUND_SOURCES[i];
+                                ui.with_padded_stripe(i % 2 != 0, |ui| {
+                                    ui.horizontal(|ui| {
+                                        if ui.button("▶").on_hover_text("Play").clicked() {
+                                            play_audio(update_state, source, audio_file);
+                                        }
+                                        if ui.button("⏹").on_hover_text("Stop").clicked() {
+                                            update_state.audio.stop(source);
+                                        }
+                                        modified |= ui
+                                            .add(Field::new(
+                                                name,
+                                                picker.button(audio_file, update_state),
+                                            ))
+                                            .changed();
+                                    });
+                                });
+                            }
+                        }
+
+                        Tab::Terms => {
+                            for (i, (name, word)) in
+                                words_mut(&mut system.words).into_iter().enumerate()
+                            {
+                                ui.with_padded_stripe(i % 2 != 0, |ui| {
+                                    modified |= ui
+                                        .add(Field::new(
+                                            name,
+                                            egui::TextEdit::singleline(word)
+                                                .desired_width(f32::INFINITY),
+                                        ))
+                                        .changed();
+                                });
+                            }
+                        }
+
+                        // The first element is always blank and can't be used by the game
+                        Tab::Elements => {
+                            modified |= names_ui(
+                                ui,
+                                "system_elements",
+                                &mut system.elements,
+                                1,
+                                &mut self.new_elements_size,
+                            );
+                        }
+
+                        Tab::Switches => {
+                            modified |= names_ui(
+                                ui,
+                                "system_switches",
+                                &mut system.switches,
+                                0,
+                                &mut self.new_switches_size,
+                            );
+                        }
+
+                        Tab::Variables => {
+                            modified |= names_ui(
+                                ui,
+                                "system_variables",
+                                &mut system.variables,
+                                0,
+                                &mut self.new_variables_size,
+                            );
+                        }
+                    });
+            });
+
+        if modified {
+            update_state.modified.set(true);
+            system.modified = true;
+        }
+
+        drop(system);
+        drop(actors);
+        drop(weapons);
+        drop(armors);
+        drop(troops);
+        drop(map_infos);
+
+        *update_state.data = data; // restore data
+    }
+}
@@ -0,0 +1,649 @@
+// Copyright (C) 2024 Melody Madeline Lyons
+//
+// This file is part of Luminol.
+//
+// Luminol is free software: you can redistribute it and/or modify
+// it under the terms of the GNU General Public License as published by
+// the Free Software Foundation, either version 3 of the License, or
+// (at your option) any later version.
+//
+// Luminol is distributed in the hope that it will be useful,
+// but WITHOUT ANY WARRANTY; without even the implied warranty of
+// MERCHANTABILITY or FITNESS FOR A PARTICULAR PURPOSE.  See the
+// GNU General Public License for more details.
+//
+// You should have received a copy of the GNU General Public License
+// along with Luminol.  If not, see <http://www.gnu.org/licenses/>.
+//
+//     Additional permission under GNU GPL version 3 section 7
+//
+// If you modify this Program, or any covered work, by linking or combining
+// it with Steamworks API by Valve Corporation, containing parts covered by
+// terms of the Steamworks API by Valve Corporation, the licensors of this
+// Program grant you additional permission to convey the resulting work.
+
+use luminol_core::Modal;
+use luminol_graphics::Renderable;
+use strum::IntoEnumIterator;
+
+use crate::components::{EnumComboBox, ExtraIvarsView, Field, UiExt};
+use crate::modals::graphic_picker::{
+    basic::Modal as GraphicPicker, hue::Modal as HueGraphicPicker,
+};
+
+/// The number of autotiles a tileset has (not counting the blank tile in the first slot).
+const AUTOTILE_AMOUNT: usize = 7;
+
+/// Passage flags that block movement in each of the four directions.
+const PASSAGE_DIRECTIONS: i16 = 0x0f;
+const PASSAGE_BUSH: i16 = 0x40;
+const PASSAGE_COUNTER: i16 = 0x80;
+
+const MAX_PRIORITY: i16 = 5;
+const MAX_TERRAIN_TAG: i16 = 7;
+
+#[derive(Clone, Copy, Debug, PartialEq, Eq, Default)]
+#[derive(strum::Display, strum::EnumIter)]
+enum Mode {
+    #[default]
+    Passage,
+    #[strum(to_string = "Passage (4 dir)")]
+    Passage4Dir,
+    Priority,
+    #[strum(to_string = "Bush Flag")]
+    Bush,
+    #[strum(to_string = "Counter Flag")]
+    Counter,
+    #[strum(to_string = "Terrain Tag")]
+    TerrainTag,
+}
+
+pub struct Window {
+    selected_tileset_name: Option<String>,
+    previous_tileset: Option<usize>,
+
+    mode: Mode,
+    /// The mask and value that are written to every tile the cursor passes over while painting.
+    paint: Option<(i16, i16)>,
+    /// Set when the tileset's graphics have been changed; the atlas is rebuilt once the user stops
+    /// interacting with the window so that we aren't rebuilding it every frame while dragging.
+    needs_atlas_reload: bool,
+
+    tilepicker: Option<luminol_graphics::Tilepicker>,
+
+    tileset_picker: GraphicPicker,
+    autotile_pickers: [GraphicPicker; AUTOTILE_AMOUNT],
+    battleback_picker: GraphicPicker,
+    panorama_picker: HueGraphicPicker,
+    fog_picker: HueGraphicPicker,
+
+    view: crate::components::DatabaseView,
+}
+
+impl Window {
+    pub fn new(update_state: &luminol_core::UpdateState<'_>) -> Self {
+        let tilesets = update_state.data.tilesets();
+        let tileset = &tilesets.data[0];
+
+        Self {
+            selected_tileset_name: None,
+            previous_tileset: None,
+
+            mode: Mode::default(),
+            paint: None,
+            needs_atlas_reload: false,
+
+            tilepicker: None,
+
+            tileset_picker: GraphicPicker::new(
+                update_state,
+                "Graphics/Tilesets".into(),
+                tileset.tileset_name.as_deref(),
+                egui::vec2(128., 128.),
+                "tileset_tileset_picker",
+            ),
+            autotile_pickers: std::array::from_fn(|i| {
+                let path = autotile_path(tileset, i);
+                GraphicPicker::new(
+                    update_state,
+                    "Graphics/Autotiles".into(),
+                    path.as_deref(),
+                    egui::vec2(32., 32.),
+                    egui::Id::new("tileset_autotile_picker").with(i),
+                )
+            }),
+            battleback_picker: GraphicPicker::new(
+                update_state,
+                "Graphics/Battlebacks".into(),
+                tileset.battleback_name.as_deref(),
+                egui::vec2(128., 64.),
+                "tileset_battleback_picker",
+            ),
+            panorama_picker: HueGraphicPicker::new(
+                update_state,
+                "Graphics/Panoramas".into(),
+                tileset.panorama_name.as_deref(),
+                tileset.panorama_hue,
+                egui::vec2(128., 96.),
+                "tileset_panorama_picker",
+            ),
+            fog_picker: HueGraphicPicker::new(
+                update_state,
+                "Graphics/Fogs".into(),
+                tileset.fog_name.as_deref(),
+                tileset.fog_hue,
+                egui::vec2(128., 96.),
+                "tileset_fog_picker",
+            ),
+
+            view: crate::components::DatabaseView::new(),
+        }
+    }
+}
+
+impl luminol_core::Window for Window {
+    fn id(&self) -> egui::Id {
+        egui::Id::new("tileset_editor")
+    }
+
+    fn requires_filesystem(&self) -> bool {
+        true
+    }
+
+    fn show(
+        &mut self,
+        ctx: &egui::Context,
+        open: &mut bool,
+        update_state: &mut luminol_core::UpdateState<'_>,
+    ) {
+        let data = std::mem::take(update_state.data); // take data to avoid borrow checker issues
+        let mut tilesets = data.tilesets();
+
+        let mut modified = false;
+
+        self.selected_tileset_name = None;
+
+        let name = if let Some(name) = &self.selected_tileset_name {
+            format!("Editing tileset {:?}", name)
+        } else {
+            "Tileset Editor".into()
+        };
+
+        let response = egui::Window::new(name)
+            .id(self.id())
+            .default_width(500.)
+            .open(open)
+            .show(ctx, |ui| {
+                self.view.show(
+                    ui,
+                    update_state,
+                    "Tilesets",
+                    &mut tilesets.data,
+                    |tileset| format!("{:0>4}: {}", tileset.id + 1, tileset.name),
+                    |ui, tilesets, id, update_state| {
+                        let tileset = &mut tilesets[id];
+                        self.selected_tileset_name = Some(tileset.name.clone());
+
+                        if self.previous_tileset != Some(tileset.id) {
+                            // avoid desyncs by resetting the modals if the tileset has changed
+                            self.tileset_picker
+                                .reset(update_state, &mut tileset.tileset_name);
+                            for (i, picker) in self.autotile_pickers.iter_mut().enumerate() {
+                                picker.reset(update_state, &mut autotile_path(tileset, i));
+                            }
+                            self.battleback_picker
+                                .reset(update_state, &mut tileset.battleback_name);
+                            self.panorama_picker.reset(
+                                update_state,
+                                (&mut tileset.panorama_name, &mut tileset.panorama_hue),
+                            );
+                            self.fog_picker
+                                .reset(update_state, (&mut tileset.fog_name, &mut tileset.fog_hue));
+
+                            self.tilepicker = None;
+                            self.paint = None;
+                        }
+
+                        let mut graphics_modified = false;
+
+                        ui.with_padded_stripe(false, |ui| {
+                            modified |= ui
+                                .add(Field::new(
+                                    "Name",
+                                    egui::TextEdit::singleline(&mut tileset.name)
+                                        .desired_width(f32::INFINITY),
+                                ))
+                                .changed();
+                        });
+
+                        ui.with_padded_stripe(true, |ui| {
+                            ui.columns(2, |columns| {
+                                graphics_modified |= columns[0]
+                                    .add(Field::new(
+                                        "Tileset Graphic",
+                                        self.tileset_picker
+                                            .button(&mut tileset.tileset_name, update_state),
+                                    ))
+                                    .changed();
+
+                                modified |= columns[1]
+                                    .add(Field::new(
+                                        "Battleback Graphic",
+                                        self.battleback_picker
+                                            .button(&mut tileset.battleback_name, update_state),
+                                    ))
+                                    .changed();
+                            });
+                        });
+
+                        ui.with_padded_stripe(false, |ui| {
+                            ui.label("Autotiles:");
+                            ui.horizontal_wrapped(|ui| {
+                                for (i, picker) in self.autotile_pickers.iter_mut().enumerate() {
+                                    let mut path = autotile_path(tileset, i);
+                                    if ui.add(picker.button(&mut path, update_state)).changed() {
+                                        set_autotile_path(tileset, i, path);
+                                        graphics_modified = true;
+                                    }
+                                }
+                            });
+                        });
+
+                        ui.with_padded_stripe(true, |ui| {
+                            graphics_modified |= ui
+                                .add(Field::new(
+                                    "Panorama Graphic",
+                                    self.panorama_picker.button(
+                                        (&mut tileset.panorama_name, &mut tileset.panorama_hue),
+                                        update_state,
+                                    ),
+                                ))
+                                .changed();
+                        });
+
+                        ui.with_padded_stripe(false, |ui| {
+                            graphics_modified |= ui
+                                .add(Field::new(
+                                    "Fog Graphic",
+                                    self.fog_picker.button(
+                                        (&mut tileset.fog_name, &mut tileset.fog_hue),
+                                        update_state,
+                                    ),
+                                ))
+                                .changed();
+
+                            ui.columns(2, |columns| {
+                                graphics_modified |= columns[0]
+                                    .add(Field::new(
+                                        "Fog Opacity",
+                                        egui::Slider::new(&mut tileset.fog_opacity, 0..=255),
+                                    ))
+                                    .changed();
+
+                                graphics_modified |= columns[1]
+                                    .add(Field::new(
+                                        "Fog Blending",
+                                        EnumComboBox::new(
+                                            (tileset.id, "fog_blend_type"),
+                                            &mut tileset.fog_blend_type,
+                                        ),
+                                    ))
+                                    .changed();
+                            });
+
+                            ui.columns(3, |columns| {
+                                graphics_modified |= columns[0]
+                                    .add(Field::new(
+                                        "Fog Zoom",
+                                        egui::DragValue::new(&mut tileset.fog_zoom)
+                                            .range(100..=800)
+                                            .suffix("%"),
+                                    ))
+                                    .changed();
+
+                                graphics_modified |= columns[1]
+                                    .add(Field::new(
+                                        "Fog Scroll X",
+                                        egui::DragValue::new(&mut tileset.fog_sx).range(-256..=256),
+                                    ))
+                                    .changed();
+
+                                graphics_modified |= columns[2]
+                                    .add(Field::new(
+                                        "Fog Scroll Y",
+                                        egui::DragValue::new(&mut tileset.fog_sy).range(-256..=256),
+                                    ))
+                                    .changed();
+                            });
+                        });
+
+                        if graphics_modified {
+                            self.needs_atlas_reload = true;
+                            modified = true;
+                        }
+
+                        // Wait until the user has stopped dragging sliders and such before
+                        // reloading the atlas, which also causes open maps to be reloaded
+                        if self.needs_atlas_reload && !ui.input(|i| i.pointer.any_down()) {
+                            update_state.graphics.atlas_loader.reload_atlas(
+                                &update_state.graphics,
+                                update_state.filesystem,
+                                tileset,
+                            );
+                            self.tilepicker = None;
+                            self.needs_atlas_reload = false;
+                        }
+
+                        ui.with_padded_stripe(true, |ui| {
+                            ui.horizontal_wrapped(|ui| {
+                                for mode in Mode::iter() {
+                                    ui.selectable_value(&mut self.mode, mode, mode.to_string());
+                                }
+                            });
+
+                            ui.weak(match self.mode {
+                                Mode::Passage4Dir => {
+                                    "Click near the edge of a tile to toggle passage in that \
+                                    direction."
+                                }
+                                Mode::Priority | Mode::TerrainTag => {
+                                    "Left click to increase and right click to decrease."
+                                }
+                                _ => "Click or drag over tiles to toggle them.",
+                            });
+
+                            let tilepicker = self.tilepicker.get_or_insert_with(|| {
+                                let mut tilepicker = luminol_graphics::Tilepicker::new(
+                                    &update_state.graphics,
+                                    tileset,
+                                    update_state.filesystem,
+                                    false,
+                                );
+                                // We draw our own overlays instead of the collision layer
+                                tilepicker.coll_enabled = false;
+                                tilepicker
+                            });
+
+                            ui.group(|ui| {
+                                egui::ScrollArea::vertical()
+                                    .id_source((tileset.id, "tiles"))
+                                    .max_height(512.)
+                                    .show_viewport(ui, |ui, scroll_rect| {
+                                        modified |= tiles_ui(
+                                            ui,
+                                            update_state,
+                                            tilepicker,
+                                            tileset,
+                                            self.mode,
+                                            &mut self.paint,
+                                            scroll_rect,
+                                        );
+                                    });
+                            });
+                        });
+
+                        ui.add(ExtraIvarsView::new(&tileset.extra_ivars));
+
+                        self.previous_tileset = Some(tileset.id);
+                    },
+                )
+            });
+
+        if response.is_some_and(|ir| ir.inner.is_some_and(|ir| ir.inner.modified)) {
+            modified = true;
+        }
+
+        if modified {
+            update_state.modified.set(true);
+            tilesets.modified = true;
+        }
+
+        drop(tilesets);
+
+        *update_state.data = data; // restore data
+    }
+}
+
+fn autotile_path(tileset: &luminol_data::rpg::Tileset, index: usize) -> luminol_data::Path {
+    tileset
+        .autotile_names
+        .get(index)
+        .filter(|name| !name.is_empty())
+        .map(camino::Utf8PathBuf::from)
+}
+
+fn set_autotile_path(
+    tileset: &mut luminol_data::rpg::Tileset,
+    index: usize,
+    path: luminol_data::Path,
+) {
+    if tileset.autotile_names.len() < AUTOTILE_AMOUNT {
+        tileset
+            .autotile_names
+            .resize_with(AUTOTILE_AMOUNT, Default::default);
+    }
+    tileset.autotile_names[index] = path.map(String::from).unwrap_or_default();
+}
+
+/// Returns the IDs of the tiles at a position in the tilepicker. The first row contains the
+/// autotiles, which each span 48 tile IDs.
+fn tile_ids(x: usize, y: usize) -> std::ops::Range<usize> {
+    if y == 0 {
+        x * 48..(x + 1) * 48
+    } else {
+        let id = 384 + (y - 1) * 8 + x;
+        id..id + 1
+    }
+}
+
+/// Shows the tileset with an overlay for the selected mode, and lets the user edit the values
+/// of the tiles by clicking on them. Returns whether or not the tileset was modified.
+fn tiles_ui(
+    ui: &mut egui::Ui,
+    update_state: &luminol_core::UpdateState<'_>,
+    tilepicker: &mut luminol_graphics::Tilepicker,
+    tileset: &mut luminol_data::rpg::Tileset,
+    mode: Mode,
+    paint: &mut Option<(i16, i16)>,
+    scroll_rect: egui::Rect,
+) -> bool {
+    let rows = tilepicker.atlas.tileset_height() as usize / 32 + 1;
+    let (canvas_rect, response) = ui.allocate_exact_size(
+        egui::vec2(256., rows as f32 * 32.),
+        egui::Sense::click_and_drag(),
+    );
+
+    let absolute_scroll_rect = ui
+        .clip_rect()
+        .intersect(scroll_rect.translate(canvas_rect.min.to_vec2()));
+    let scroll_rect = absolute_scroll_rect.translate(-canvas_rect.min.to_vec2());
+
+    tilepicker.grid.display.set_pixels_per_point(
+        &update_state.graphics.render_state,
+        ui.ctx().pixels_per_point(),
+    );
+    tilepicker.set_position(
+        &update_state.graphics.render_state,
+        glam::vec2(0.0, -scroll_rect.top()),
+    );
+    tilepicker.viewport.set(
+        &update_state.graphics.render_state,
+        glam::vec2(scroll_rect.width(), scroll_rect.height()),
+        glam::Vec2::ZERO,
+        glam::Vec2::ONE,
+    );
+    tilepicker.update_animation(&update_state.graphics.render_state, ui.input(|i| i.time));
+
+    if absolute_scroll_rect.is_positive() {
+        let painter = luminol_graphics::Painter::new(tilepicker.prepare(&update_state.graphics));
+        ui.painter()
+            .add(luminol_egui_wgpu::Callback::new_paint_callback(
+                absolute_scroll_rect,
+                painter,
+            ));
+    }
+
+    let mut modified = false;
+
+    let button = if ui.input(|i| i.pointer.button_down(egui::PointerButton::Secondary)) {
+        Some(egui::PointerButton::Secondary)
+    } else if ui.input(|i| i.pointer.button_down(egui::PointerButton::Primary)) {
+        Some(egui::PointerButton::Primary)
+    } else {
+        None
+    };
+
+    if let (Some(button), Some(pos), true) = (
+        button,
+        response.interact_pointer_pos(),
+        response.is_pointer_button_down_on(),
+    ) {
+        let pos = pos - canvas_rect.min;
+        let (x, y) = ((pos.x / 32.) as usize, (pos.y / 32.) as usize);
+
+        if pos.x >= 0. && pos.y >= 0. && x < 8 && y < rows {
+            let ids = tile_ids(x, y);
+            let table = match mode {
+                Mode::Priority => &mut tileset.priorities,
+                Mode::TerrainTag => &mut tileset.terrain_tags,
+                _ => &mut tileset.passages,
+            };
+            let required_len = 384 + (rows - 1) * 8;
+            if table.len() < required_len {
+                table.resize(required_len);
+            }
+            let current = table[ids.start];
+
+            let mut stroke_started = true;
+            if paint.is_none() {
+                let increment = if button == egui::PointerButton::Secondary {
+                    -1
+                } else {
+                    1
+                };
+                *paint = Some(match mode {
+                    Mode::Passage => (
+                        PASSAGE_DIRECTIONS,
+                        if current & PASSAGE_DIRECTIONS == 0 {
+                            PASSAGE_DIRECTIONS
+                        } else {
+                            0
+                        },
+                    ),
+                    Mode::Passage4Dir => {
+                        // Toggle whichever direction is closest to where the tile was clicked
+                        let offset = egui::vec2(pos.x % 32., pos.y % 32.) - egui::Vec2::splat(16.);
+                        let direction = if offset.x.abs() > offset.y.abs() {
+                            if offset.x < 0. {
+                                0x02
+                            } else {
+                                0x04
+                            }
+                        } else if offset.y < 0. {
+                            0x08
+                        } else {
+                            0x01
+                        };
+                        (direction, current ^ direction)
+                    }
+                    Mode::Bush => (PASSAGE_BUSH, current ^ PASSAGE_BUSH),
+                    Mode::Counter => (PASSAGE_COUNTER, current ^ PASSAGE_COUNTER),
+                    Mode::Priority => (!0, (current + increment).rem_euclid(MAX_PRIORITY + 1)),
+                    Mode::TerrainTag => (!0, (current + increment).rem_euclid(MAX_TERRAIN_TAG + 1)),
+                });
+            } else if mode == Mode::Passage4Dir {
+                // Painting a single direction doesn't make much sense when dragging across
+                // tiles, so only the tile that was initially clicked on gets changed
+                stroke_started = false;
+            }
+
+            if let Some((mask, value)) = paint.filter(|_| stroke_started) {
+                for id in ids {
+                    let new_value = (table[id] & !mask) | (value & mask);
+                    if table[id] != new_value {
+                        table[id] = new_value;
+                        modified = true;
+                    }
+                }
+            }
+        }
+    } else {
+        *paint = None;
+    }
+
+    // Draw the overlay for every visible tile
+    let first_row = (scroll_rect.top() / 32.).max(0.) as usize;
+    let last_row = ((scroll_rect.bottom() / 32.).ceil() as usize).min(rows);
+    let font_id = egui::FontId::monospace(14.);
+    for y in first_row..last_row {
+        for x in 0..8 {
+            let id = tile_ids(x, y).start;
+            let center = canvas_rect.min + egui::vec2(x as f32 * 32. + 16., y as f32 * 32. + 16.);
+            let value = |table: &luminol_data::Table1| table.as_slice().get(id).copied();
+
+            let text = match mode {
+                Mode::Passage => {
+                    let passage = value(&tileset.passages).unwrap_or_default();
+                    if passage & PASSAGE_DIRECTIONS == PASSAGE_DIRECTIONS {
+                        "×".to_string()
+                    } else {
+                        "○".to_string()
+                    }
+                }
+                Mode::Passage4Dir => {
+                    let passage = value(&tileset.passages).unwrap_or_default();
+                    for (flag, offset, arrow) in [
+                        (0x01, egui::vec2(0., 9.), "↓"),
+                        (0x02, egui::vec2(-9., 0.), "←"),
+                        (0x04, egui::vec2(9., 0.), "→"),
+                        (0x08, egui::vec2(0., -9.), "↑"),
+                    ] {
+                        let text = if passage & flag == 0 { arrow } else { "·" };
+                        overlay_text(ui.painter(), center + offset, text, font_id.clone());
+                    }
+                    continue;
+                }
+                Mode::Bush => {
+                    if value(&tileset.passages).unwrap_or_default() & PASSAGE_BUSH != 0 {
+                        "●".to_string()
+                    } else {
+                        "·".to_string()
+                    }
+                }
+                Mode::Counter => {
+                    if value(&tileset.passages).unwrap_or_default() & PASSAGE_COUNTER != 0 {
+                        "●".to_string()
+                    } else {
+                        "·".to_string()
+                    }
+                }
+                Mode::Priority => match value(&tileset.priorities).unwrap_or_default() {
+                    0 => "○".to_string(),
+                    priority => priority.to_string(),
+                },
+                Mode::TerrainTag => value(&tileset.terrain_tags).unwrap_or_default().to_string(),
+            };
+            overlay_text(ui.painter(), center, &text, font_id.clone());
+        }
+    }
+
+    modified
+}
+
+/// Draws white text with a black shadow so that it's readable on top of any tile.
+fn overlay_text(painter: &egui::Painter, pos: egui::Pos2, text: &str, font_id: egui::FontId) {
+    painter.text(
+        pos + egui::vec2(1., 1.),
+        egui::Align2::CENTER_CENTER,
+        text,
+        font_id.clone(),
+        egui::Color32::BLACK,
+    );
+    painter.text(
+        pos,
+        egui::Align2::CENTER_CENTER,
+        text,
+        font_id,
+        egui::Color32::WHITE,
+    );
+}
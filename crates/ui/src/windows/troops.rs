@@ -0,0 +1,675 @@
+// Copyright (C) 2024 Melody Madeline Lyons
+//
+// This file is part of Luminol.
+//
+// Luminol is free software: you can redistribute it and/or modify
+// it under the terms of the GNU General Public License as published by
+// the Free Software Foundation, either version 3 of the License, or
+// (at your option) any later version.
+//
+// Luminol is distributed in the hope that it will be useful,
+// but WITHOUT ANY WARRANTY; without even the implied warranty of
+// MERCHANTABILITY or FITNESS FOR A PARTICULAR PURPOSE.  See the
+// GNU General Public License for more details.
+//
+// You should have received a copy of the GNU General Public License
+// along with Luminol.  If not, see <http://www.gnu.org/licenses/>.
+//
+//     Additional permission under GNU GPL version 3 section 7
+//
+// If you modify this Program, or any covered work, by linking or combining
+// it with Steamworks API by Valve Corporation, containing parts covered by
+// terms of the Steamworks API by Valve Corporation, the licensors of this
+// Program grant you additional permission to convey the resulting work.
+
+use crate::components::{
+    CommandView, DatabaseView, ExtraIvarsView, Field, FieldWithCheckbox, OptionalIdComboBox,
+    TroopView, UiExt,
+};
+
+/// The maximum number of members a troop can have.
+const MAX_MEMBERS: usize = 8;
+
+const SPAN_TYPES: [&str; 3] = ["Battle", "Turn", "Moment"];
+
+pub struct Window {
+    selected_troop_name: Option<String>,
+    previous_troop: Option<usize>,
+    selected_page: usize,
+    previous_page: Option<usize>,
+
+    /// The tileset whose battleback is shown behind the troop, or `None` to use the battleback
+    /// from the system data.
+    battleback_tileset_id: Option<usize>,
+    new_member_enemy_id: usize,
+
+    troop_view: Option<TroopView>,
+    command_view: CommandView,
+    view: DatabaseView,
+}
+
+impl Default for Window {
+    fn default() -> Self {
+        Self {
+            selected_troop_name: None,
+            previous_troop: None,
+            selected_page: 0,
+            previous_page: None,
+            battleback_tileset_id: None,
+            new_member_enemy_id: 0,
+            troop_view: None,
+            command_view: CommandView::new("troop_commands"),
+            view: DatabaseView::new(),
+        }
+    }
+}
+
+impl Window {
+    pub fn new() -> Self {
+        Default::default()
+    }
+}
+
+impl luminol_core::Window for Window {
+    fn id(&self) -> egui::Id {
+        egui::Id::new("troop_editor")
+    }
+
+    fn requires_filesystem(&self) -> bool {
+        true
+    }
+
+    fn show(
+        &mut self,
+        ctx: &egui::Context,
+        open: &mut bool,
+        update_state: &mut luminol_core::UpdateState<'_>,
+    ) {
+        // The command list needs to read the system data, so instead of taking all of the data
+        // out of the update state we only take the troops
+        let mut troops = std::mem::take(&mut update_state.data.troops().data);
+
+        let mut modified = false;
+
+        self.selected_troop_name = None;
+
+        let name = if let Some(name) = &self.selected_troop_name {
+            format!("Editing troop {:?}", name)
+        } else {
+            "Troop Editor".into()
+        };
+
+        let response = egui::Window::new(name)
+            .id(self.id())
+            .default_width(640.)
+            .open(open)
+            .show(ctx, |ui| {
+                self.view.show(
+                    ui,
+                    update_state,
+                    "Troops",
+                    &mut troops,
+                    |troop| format!("{:0>4}: {}", troop.id + 1, troop.name),
+                    |ui, troops, id, update_state| {
+                        let troop = &mut troops[id];
+                        self.selected_troop_name = Some(troop.name.clone());
+
+                        let troop_changed = self.previous_troop != Some(troop.id);
+                        if troop_changed {
+                            self.selected_page = 0;
+                        }
+                        self.selected_page =
+                            self.selected_page.min(troop.pages.len().saturating_sub(1));
+
+                        let mut rebuild_battleback = self.troop_view.is_none();
+                        let mut rebuild_members = self.troop_view.is_none() || troop_changed;
+                        let troop_view = self
+                            .troop_view
+                            .get_or_insert_with(|| TroopView::new(update_state));
+                        if troop_changed {
+                            troop_view.selected_member_index = None;
+                        }
+
+                        let enemies = update_state.data.enemies();
+                        let actors = update_state.data.actors();
+                        let tilesets = update_state.data.tilesets();
+                        let system = update_state.data.system();
+
+                        let enemy_name = |id: usize| {
+                            enemies.data.get(id).map_or_else(
+                                || "".into(),
+                                |e| format!("{:0>4}: {}", id + 1, e.name),
+                            )
+                        };
+
+                        ui.with_padded_stripe(false, |ui| {
+                            modified |= ui
+                                .add(Field::new(
+                                    "Name",
+                                    egui::TextEdit::singleline(&mut troop.name)
+                                        .desired_width(f32::INFINITY),
+                                ))
+                                .changed();
+
+                            rebuild_battleback |= ui
+                                .add(Field::new(
+                                    "Battleback",
+                                    OptionalIdComboBox::new(
+                                        update_state,
+                                        "troop_battleback_tileset_id",
+                                        &mut self.battleback_tileset_id,
+                                        0..tilesets.data.len(),
+                                        |id| {
+                                            tilesets.data.get(id).map_or_else(
+                                                || "".into(),
+                                                |t| format!("{:0>4}: {}", id + 1, t.name),
+                                            )
+                                        },
+                                    ),
+                                ))
+                                .on_hover_text(
+                                    "The tileset whose battleback is shown behind the troop. \
+                                    If none is selected, the battleback from the system data is \
+                                    used instead.",
+                                )
+                                .changed();
+                        });
+
+                        ui.with_padded_stripe(true, |ui| {
+                            ui.horizontal(|ui| {
+                                ui.add(Field::new(
+                                    "Enemy",
+                                    OptionalIdComboBox::new(
+                                        update_state,
+                                        "troop_new_member_enemy_id",
+                                        &mut self.new_member_enemy_id,
+                                        0..enemies.data.len(),
+                                        enemy_name,
+                                    ),
+                                ));
+
+                                ui.vertical(|ui| {
+                                    ui.add_space(ui.spacing().interact_size.y);
+                                    ui.horizontal(|ui| {
+                                        if ui
+                                            .add_enabled(
+                                                troop.members.len() < MAX_MEMBERS
+                                                    && self.new_member_enemy_id
+                                                        < enemies.data.len(),
+                                                egui::Button::new("Add Member"),
+                                            )
+                                            .clicked()
+                                        {
+                                            troop.members.push(luminol_data::rpg::troop::Member {
+                                                enemy_id: self.new_member_enemy_id,
+                                                x: (luminol_graphics::troop::TROOP_WIDTH / 2)
+                                                    as i32,
+                                                y: (luminol_graphics::troop::TROOP_HEIGHT * 3 / 4)
+                                                    as i32,
+                                                hidden: false,
+                                                immortal: false,
+                                                extra_ivars: Default::default(),
+                                            });
+                                            troop_view.selected_member_index =
+                                                Some(troop.members.len() - 1);
+                                            rebuild_members = true;
+                                            modified = true;
+                                        }
+
+                                        if ui
+                                            .add_enabled(
+                                                troop_view.selected_member_index.is_some(),
+                                                egui::Button::new("Remove Member"),
+                                            )
+                                            .clicked()
+                                        {
+                                            if let Some(i) = troop_view.selected_member_index.take()
+                                            {
+                                                troop.members.remove(i);
+                                                rebuild_members = true;
+                                                modified = true;
+                                            }
+                                        }
+
+                                        if ui
+                                            .add_enabled(
+                                                !troop.members.is_empty(),
+                                                egui::Button::new("Clear"),
+                                            )
+                                            .clicked()
+                                        {
+                                            troop.members.clear();
+                                            troop_view.selected_member_index = None;
+                                            rebuild_members = true;
+                                            modified = true;
+                                        }
+                                    });
+                                });
+                            });
+
+                            if rebuild_battleback {
+                                let battleback_name = match self.battleback_tileset_id {
+                                    Some(id) => tilesets
+                                        .data
+                                        .get(id)
+                                        .and_then(|t| t.battleback_name.as_deref()),
+                                    None => system.battleback_name.as_deref(),
+                                };
+                                troop_view.troop.rebuild_battleback(
+                                    &update_state.graphics,
+                                    update_state.filesystem,
+                                    battleback_name,
+                                );
+                            }
+                            if rebuild_members {
+                                troop_view.troop.rebuild_members(
+                                    &update_state.graphics,
+                                    update_state.filesystem,
+                                    &enemies.data,
+                                    troop,
+                                );
+                            }
+
+                            let response = troop_view.ui(ui, update_state, troop);
+                            if let Some((i, x, y)) = response.inner {
+                                let member = &mut troop.members[i];
+                                if member.x != x || member.y != y {
+                                    member.x = x;
+                                    member.y = y;
+                                    troop_view.troop.update_member(
+                                        &update_state.graphics,
+                                        troop,
+                                        i,
+                                    );
+                                    modified = true;
+                                }
+                            }
+
+                            let Some(member_index) = troop_view
+                                .selected_member_index
+                                .filter(|i| *i < troop.members.len())
+                            else {
+                                ui.weak("Click on a member to select it, or drag it to move it.");
+                                return;
+                            };
+                            let member = &mut troop.members[member_index];
+
+                            let mut member_modified = false;
+                            let mut enemy_modified = false;
+                            ui.columns(5, |columns| {
+                                enemy_modified |= columns[0]
+                                    .add(Field::new(
+                                        "Enemy",
+                                        OptionalIdComboBox::new(
+                                            update_state,
+                                            (troop.id, member_index, "enemy_id"),
+                                            &mut member.enemy_id,
+                                            0..enemies.data.len(),
+                                            enemy_name,
+                                        ),
+                                    ))
+                                    .changed();
+
+                                member_modified |= columns[1]
+                                    .add(Field::new(
+                                        "X",
+                                        egui::DragValue::new(&mut member.x)
+                                            .range(0..=luminol_graphics::troop::TROOP_WIDTH as i32),
+                                    ))
+                                    .changed();
+
+                                member_modified |= columns[2]
+                                    .add(Field::new(
+                                        "Y",
+                                        egui::DragValue::new(&mut member.y).range(
+                                            0..=luminol_graphics::troop::TROOP_HEIGHT as i32,
+                                        ),
+                                    ))
+                                    .changed();
+
+                                member_modified |= columns[3]
+                                    .add(Field::new(
+                                        "Hidden",
+                                        egui::Checkbox::without_text(&mut member.hidden),
+                                    ))
+                                    .changed();
+
+                                modified |= columns[4]
+                                    .add(Field::new(
+                                        "Immortal",
+                                        egui::Checkbox::without_text(&mut member.immortal),
+                                    ))
+                                    .changed();
+                            });
+
+                            if enemy_modified {
+                                troop_view.troop.rebuild_members(
+                                    &update_state.graphics,
+                                    update_state.filesystem,
+                                    &enemies.data,
+                                    troop,
+                                );
+                            } else if member_modified {
+                                troop_view.troop.update_member(
+                                    &update_state.graphics,
+                                    troop,
+                                    member_index,
+                                );
+                            }
+                            modified |= enemy_modified || member_modified;
+                        });
+
+                        ui.with_padded_stripe(false, |ui| {
+                            ui.horizontal(|ui| {
+                                ui.label("Page:");
+                                for i in 0..troop.pages.len() {
+                                    ui.selectable_value(
+                                        &mut self.selected_page,
+                                        i,
+                                        format!("{}", i + 1),
+                                    );
+                                }
+
+                                if ui
+                                    .button(
+                                        egui::RichText::new("Add")
+                                            .color(egui::Color32::LIGHT_GREEN),
+                                    )
+                                    .clicked()
+                                {
+                                    troop.pages.push(Default::default());
+                                    self.selected_page = troop.pages.len() - 1;
+                                    modified = true;
+                                }
+
+                                let button = egui::Button::new(
+                                    egui::RichText::new("Delete").color(egui::Color32::LIGHT_RED),
+                                );
+                                if ui.add_enabled(troop.pages.len() > 1, button).clicked() {
+                                    troop.pages.remove(self.selected_page);
+                                    self.selected_page = self.selected_page.saturating_sub(1);
+                                    modified = true;
+                                }
+
+                                if ui
+                                    .add_enabled(
+                                        !troop.pages.is_empty(),
+                                        egui::Button::new("Clear"),
+                                    )
+                                    .clicked()
+                                {
+                                    troop.pages[self.selected_page] = Default::default();
+                                    modified = true;
+                                }
+                            });
+
+                            let Some(page) = troop.pages.get_mut(self.selected_page) else {
+                                return;
+                            };
+                            let condition = &mut page.condition;
+                            let valid = (
+                                condition.turn_valid,
+                                condition.enemy_valid,
+                                condition.actor_valid,
+                                condition.switch_valid,
+                            );
+
+                            ui.columns(2, |columns| {
+                                modified |= columns[0]
+                                    .add(FieldWithCheckbox::new(
+                                        "Turn",
+                                        &mut condition.turn_valid,
+                                        |ui: &mut egui::Ui| {
+                                            let mut changed = false;
+                                            let mut response = ui
+                                                .horizontal(|ui| {
+                                                    changed |= ui
+                                                        .add(
+                                                            egui::DragValue::new(
+                                                                &mut condition.turn_a,
+                                                            )
+                                                            .range(0..=i32::MAX),
+                                                        )
+                                                        .changed();
+                                                    ui.label("+");
+                                                    changed |= ui
+                                                        .add(
+                                                            egui::DragValue::new(
+                                                                &mut condition.turn_b,
+                                                            )
+                                                            .range(0..=i32::MAX),
+                                                        )
+                                                        .changed();
+                                                    ui.label("× X");
+                                                })
+                                                .response;
+                                            if changed {
+                                                response.mark_changed();
+                                            }
+                                            response
+                                        },
+                                    ))
+                                    .changed();
+
+                                modified |= columns[1]
+                                    .add(FieldWithCheckbox::new(
+                                        "Switch",
+                                        &mut condition.switch_valid,
+                                        OptionalIdComboBox::new(
+                                            update_state,
+                                            (troop.id, self.selected_page, "switch_id"),
+                                            &mut condition.switch_id,
+                                            0..system.switches.len(),
+                                            |id| {
+                                                system.switches.get(id).map_or_else(
+                                                    || "".into(),
+                                                    |s| format!("{:0>4}: {}", id + 1, s),
+                                                )
+                                            },
+                                        ),
+                                    ))
+                                    .changed();
+                            });
+
+                            ui.columns(2, |columns| {
+                                modified |= columns[0]
+                                    .add(FieldWithCheckbox::new(
+                                        "Enemy HP",
+                                        &mut condition.enemy_valid,
+                                        |ui: &mut egui::Ui| {
+                                            let mut changed = false;
+                                            let mut response = ui
+                                                .horizontal(|ui| {
+                                                    changed |= ui
+                                                        .add(OptionalIdComboBox::new(
+                                                            update_state,
+                                                            (
+                                                                troop.id,
+                                                                self.selected_page,
+                                                                "enemy_index",
+                                                            ),
+                                                            &mut condition.enemy_index,
+                                                            0..troop.members.len(),
+                                                            |i| {
+                                                                let name =
+                                                                    troop.members[i].enemy_id;
+                                                                format!(
+                                                                    "#{}: {}",
+                                                                    i + 1,
+                                                                    enemies
+                                                                        .data
+                                                                        .get(name)
+                                                                        .map_or("", |e| e
+                                                                            .name
+                                                                            .as_str())
+                                                                )
+                                                            },
+                                                        ))
+                                                        .changed();
+                                                    changed |= ui
+                                                        .add(
+                                                            egui::DragValue::new(
+                                                                &mut condition.enemy_hp,
+                                                            )
+                                                            .range(0..=100)
+                                                            .suffix("%"),
+                                                        )
+                                                        .changed();
+                                                    ui.label("or below");
+                                                })
+                                                .response;
+                                            if changed {
+                                                response.mark_changed();
+                                            }
+                                            response
+                                        },
+                                    ))
+                                    .changed();
+
+                                modified |= columns[1]
+                                    .add(FieldWithCheckbox::new(
+                                        "Actor HP",
+                                        &mut condition.actor_valid,
+                                        |ui: &mut egui::Ui| {
+                                            let mut changed = false;
+                                            let mut response = ui
+                                                .horizontal(|ui| {
+                                                    changed |= ui
+                                                        .add(OptionalIdComboBox::new(
+                                                            update_state,
+                                                            (
+                                                                troop.id,
+                                                                self.selected_page,
+                                                                "actor_id",
+                                                            ),
+                                                            &mut condition.actor_id,
+                                                            0..actors.data.len(),
+                                                            |id| {
+                                                                actors.data.get(id).map_or_else(
+                                                                    || "".into(),
+                                                                    |a| {
+                                                                        format!(
+                                                                            "{:0>4}: {}",
+                                                                            id + 1,
+                                                                            a.name
+                                                                        )
+                                                                    },
+                                                                )
+                                                            },
+                                                        ))
+                                                        .changed();
+                                                    changed |= ui
+                                                        .add(
+                                                            egui::DragValue::new(
+                                                                &mut condition.actor_hp,
+                                                            )
+                                                            .range(0..=100)
+                                                            .suffix("%"),
+                                                        )
+                                                        .changed();
+                                                    ui.label("or below");
+                                                })
+                                                .response;
+                                            if changed {
+                                                response.mark_changed();
+                                            }
+                                            response
+                                        },
+                                    ))
+                                    .changed();
+                            });
+
+                            // The checkboxes of these fields don't mark the fields as changed
+                            modified |= valid
+                                != (
+                                    condition.turn_valid,
+                                    condition.enemy_valid,
+                                    condition.actor_valid,
+                                    condition.switch_valid,
+                                );
+
+                            modified |= ui
+                                .add(Field::new("Span", |ui: &mut egui::Ui| {
+                                    let mut changed = false;
+                                    let mut response = egui::ComboBox::from_id_source((
+                                        troop.id,
+                                        self.selected_page,
+                                        "span",
+                                    ))
+                                    .selected_text(
+                                        usize::try_from(page.span)
+                                            .ok()
+                                            .and_then(|span| SPAN_TYPES.get(span))
+                                            .copied()
+                                            .unwrap_or("???"),
+                                    )
+                                    .show_ui(ui, |ui| {
+                                        for (value, span) in SPAN_TYPES.into_iter().enumerate() {
+                                            changed |= ui
+                                                .selectable_value(
+                                                    &mut page.span,
+                                                    value as i32,
+                                                    span,
+                                                )
+                                                .changed();
+                                        }
+                                    })
+                                    .response;
+                                    if changed {
+                                        response.mark_changed();
+                                    }
+                                    response
+                                }))
+                                .changed();
+                        });
+
+                        drop(enemies);
+                        drop(actors);
+                        drop(tilesets);
+                        drop(system);
+
+                        if let Some(page) = troop.pages.get_mut(self.selected_page) {
+                            if troop_changed || self.previous_page != Some(self.selected_page) {
+                                self.command_view = CommandView::new((
+                                    "troop_commands",
+                                    troop.id,
+                                    self.selected_page,
+                                ));
+                            }
+
+                            ui.with_padded_stripe(true, |ui| {
+                                ui.label("Commands");
+                                ui.group(|ui| {
+                                    egui::ScrollArea::both()
+                                        .id_source((troop.id, self.selected_page, "commands"))
+                                        .max_height(384.)
+                                        .show(ui, |ui| {
+                                            modified |= self
+                                                .command_view
+                                                .ui(ui, update_state, &mut page.list)
+                                                .changed();
+                                        });
+                                });
+                            });
+                        }
+
+                        ui.add(ExtraIvarsView::new(&troop.extra_ivars));
+
+                        self.previous_troop = Some(troop.id);
+                        self.previous_page = Some(self.selected_page);
+                    },
+                )
+            });
+
+        if response.is_some_and(|ir| ir.inner.is_some_and(|ir| ir.inner.modified)) {
+            modified = true;
+        }
+
+        let mut container = update_state.data.troops();
+        container.data = troops;
+        if modified {
+            update_state.modified.set(true);
+            container.modified = true;
+        }
+    }
+}
@@ -23,7 +23,7 @@
 // Program grant you additional permission to convey the resulting work.
 
 use crate::components::{
-    Field, IdVecPlusMinusSelection, IdVecSelection, OptionalIdComboBox, UiExt,
+    ExtraIvarsView, Field, IdVecPlusMinusSelection, IdVecSelection, OptionalIdComboBox, UiExt,
 };
 
 #[derive(Default)]
@@ -250,6 +250,8 @@ impl luminol_core::Window for Window {
                             });
                         });
 
+                        ui.add(ExtraIvarsView::new(&weapon.extra_ivars));
+
                         self.previous_weapon = Some(weapon.id);
                     },
                 )